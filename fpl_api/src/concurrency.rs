@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+pub const MAX_IN_FLIGHT_REQUESTS: usize = 20;
+const RESERVED_INTERACTIVE_REQUESTS: usize = 5;
+// Hosts not listed in requests_per_second()
+const DEFAULT_REQUESTS_PER_SECOND: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RequestPriority {
+    /// Someone is waiting on the result, e.g. a bot command.
+    Interactive,
+    #[default]
+    Background,
+}
+
+/// The most requests a second FPL's hosts get from us, across every process. Comfortably under
+/// what they take before answering 429s, since the bot and the scraper share it.
+pub fn requests_per_second(host: &str) -> u32 {
+    match host {
+        // The API, plus the shirts served from /dist
+        "fantasy.premierleague.com" => 20,
+        // Player photos and club badges
+        "resources.premierleague.com" => 10,
+        // Logging in, which is also where FPL is quickest to block
+        "users.premierleague.com" => 1,
+        _ => DEFAULT_REQUESTS_PER_SECOND,
+    }
+}
+
+/// How much of `requests_per_second` background requests can use, the rest is kept for
+/// interactive ones.
+pub fn background_requests_per_second(requests_per_second: u32) -> u32 {
+    (requests_per_second - requests_per_second / 4).max(1)
+}
+
+/// A rate limit kept somewhere every process can see, so the bot's fetches and the scraper's
+/// count against the same quota. See `fpl_db::rate_limit::PgRateLimiter`.
+#[async_trait]
+pub trait SharedRateLimiter: Send + Sync {
+    /// Waits until `host` has room for another request under `requests_per_second`, keeping
+    /// part of it free for interactive requests.
+    async fn until_ready(&self, host: &str, requests_per_second: u32, priority: RequestPriority);
+}
+
+/// Rate limits requests per host using `requests_per_second`. Always limited in process, and
+/// through the `SharedRateLimiter` too if there is one.
+pub struct HostRateLimiter {
+    limiters: Mutex<HashMap<String, Arc<DefaultDirectRateLimiter>>>,
+    shared: Option<Arc<dyn SharedRateLimiter>>,
+}
+
+impl HostRateLimiter {
+    pub fn new(shared: Option<Arc<dyn SharedRateLimiter>>) -> Self {
+        Self {
+            limiters: Mutex::new(HashMap::new()),
+            shared,
+        }
+    }
+
+    fn limiter(&self, host: &str) -> Arc<DefaultDirectRateLimiter> {
+        let mut limiters = self.limiters.lock().unwrap();
+        let limiter = limiters.entry(host.to_string()).or_insert_with(|| {
+            let requests_per_second = NonZeroU32::new(requests_per_second(host))
+                .expect("Every host allows at least one request a second");
+            Arc::new(RateLimiter::direct(Quota::per_second(requests_per_second)))
+        });
+        Arc::clone(limiter)
+    }
+
+    pub async fn until_ready(&self, host: &str, priority: RequestPriority) {
+        self.limiter(host).until_ready().await;
+        if let Some(shared) = &self.shared {
+            shared
+                .until_ready(host, requests_per_second(host), priority)
+                .await;
+        }
+    }
+
+    pub fn is_shared(&self) -> bool {
+        self.shared.is_some()
+    }
+}
+
+/// Caps the requests in flight across every clone of a client. Background requests can only
+/// hold part of the budget, the rest is kept free so interactive requests never queue behind a
+/// scrape. This is per process, it's the `SharedRateLimiter` that stops the bot and the scraper
+/// going over FPL's limits between them.
+#[derive(Debug)]
+pub struct ConcurrencyBudget {
+    max_in_flight: usize,
+    in_flight: Semaphore,
+    background: Semaphore,
+}
+
+pub struct BudgetPermit<'a> {
+    _in_flight: SemaphorePermit<'a>,
+    _background: Option<SemaphorePermit<'a>>,
+}
+
+impl Default for ConcurrencyBudget {
+    fn default() -> Self {
+        Self::new(MAX_IN_FLIGHT_REQUESTS, RESERVED_INTERACTIVE_REQUESTS)
+    }
+}
+
+impl ConcurrencyBudget {
+    pub fn new(max_in_flight: usize, reserved_interactive: usize) -> Self {
+        assert!(
+            reserved_interactive < max_in_flight,
+            "Reserved interactive requests must leave room for background requests"
+        );
+
+        Self {
+            max_in_flight,
+            in_flight: Semaphore::new(max_in_flight),
+            background: Semaphore::new(max_in_flight - reserved_interactive),
+        }
+    }
+
+    pub async fn acquire(&self, priority: RequestPriority) -> BudgetPermit<'_> {
+        let background = match priority {
+            RequestPriority::Interactive => None,
+            RequestPriority::Background => Some(
+                self.background
+                    .acquire()
+                    .await
+                    .expect("ConcurrencyBudget semaphores are never closed"),
+            ),
+        };
+
+        let in_flight = self
+            .in_flight
+            .acquire()
+            .await
+            .expect("ConcurrencyBudget semaphores are never closed");
+
+        BudgetPermit {
+            _in_flight: in_flight,
+            _background: background,
+        }
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.max_in_flight - self.in_flight.available_permits()
+    }
+
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    #[tokio::test]
+    async fn test_interactive_skips_background_queue() {
+        // Arrange
+        let budget = ConcurrencyBudget::new(2, 1);
+        let _background = budget.acquire(RequestPriority::Background).await;
+
+        // Act
+        let second_background = timeout(
+            Duration::from_millis(50),
+            budget.acquire(RequestPriority::Background),
+        )
+        .await;
+        let interactive = timeout(
+            Duration::from_millis(50),
+            budget.acquire(RequestPriority::Interactive),
+        )
+        .await;
+
+        // Assert
+        assert!(second_background.is_err());
+        assert!(interactive.is_ok());
+        assert_eq!(budget.in_flight(), 2);
+    }
+
+    #[test]
+    fn test_hosts_get_their_own_quota() {
+        assert_eq!(requests_per_second("fantasy.premierleague.com"), 20);
+        assert_eq!(requests_per_second("users.premierleague.com"), 1);
+        assert_eq!(
+            requests_per_second("example.com"),
+            DEFAULT_REQUESTS_PER_SECOND
+        );
+
+        assert_eq!(background_requests_per_second(20), 15);
+        assert_eq!(background_requests_per_second(1), 1);
+    }
+
+    #[test]
+    fn test_host_rate_limiter_is_keyed_by_host() {
+        let limiter = HostRateLimiter::new(None);
+
+        let api = limiter.limiter("fantasy.premierleague.com");
+        assert!(Arc::ptr_eq(
+            &api,
+            &limiter.limiter("fantasy.premierleague.com")
+        ));
+        assert!(!Arc::ptr_eq(
+            &api,
+            &limiter.limiter("resources.premierleague.com")
+        ));
+    }
+}
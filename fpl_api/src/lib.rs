@@ -1,21 +1,27 @@
+pub mod concurrency;
 pub mod requests;
 pub mod responses;
+pub mod retry;
+pub mod session;
 
-use governor::{
-    clock::DefaultClock,
-    state::{InMemoryState, NotKeyed},
-    Quota, RateLimiter,
+use concurrency::{
+    requests_per_second, ConcurrencyBudget, HostRateLimiter, RequestPriority, SharedRateLimiter,
 };
 use requests::{FplRequest, FplResponseType};
-use reqwest::Client;
+use reqwest::header::{COOKIE, RETRY_AFTER};
+use reqwest::{redirect::Policy, Client, StatusCode, Url};
+use retry::{CircuitBreaker, RetryClass, RetryPolicy};
 use serde_json::Value;
-use std::num::NonZeroU32;
+use session::FplSession;
 use std::{sync::Arc, time::Duration};
 use thiserror::Error;
-use tracing::debug;
+use tracing::{debug, warn};
 
 pub const REQ_TIMEOUT_SECONDS: u64 = 30;
-const REQUESTS_PER_SECOND: u32 = 250;
+const GAME_UPDATING_MESSAGE: &str = "The game is being updated";
+const LOGIN_URL: &str = "https://users.premierleague.com/accounts/login/";
+const LOGIN_REDIRECT_URI: &str = "https://fantasy.premierleague.com/a/login";
+const LOGIN_APP: &str = "plfpl-web";
 
 #[derive(Error, Debug)]
 pub enum FplClientError {
@@ -29,6 +35,23 @@ pub enum FplClientError {
     JsonError(reqwest::StatusCode, String, serde_json::Error),
     #[error("Binary processing error (status: {0}, url: {1}): {2}")]
     BinaryError(reqwest::StatusCode, String, std::io::Error),
+    #[error("Connection to {url} failed: {message}")]
+    ConnectionError { url: String, message: String },
+    #[error("Rate limited by {url}, retry after {retry_after:?}")]
+    RateLimited {
+        url: String,
+        retry_after: Option<Duration>,
+    },
+    #[error("The game is being updated ({url})")]
+    GameUpdating { url: String },
+    #[error("Not found: {url}")]
+    NotFound { url: String },
+    #[error("Game week not started yet: {url}")]
+    NotStarted { url: String },
+    #[error("FPL is down for maintenance ({url})")]
+    Maintenance { url: String },
+    #[error("Not logged in or session expired: {url}")]
+    Unauthorized { url: String },
     #[error("Response body missing extra detail that should have been added in process_response.")]
     MissingExtraDetailError,
 }
@@ -41,6 +64,13 @@ impl From<(reqwest::StatusCode, &String, serde_json::Error)> for FplClientError
 
 impl From<reqwest::Error> for FplClientError {
     fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() || e.is_connect() {
+            return Self::ConnectionError {
+                url: e.url().map_or("unknown".to_string(), |u| u.to_string()),
+                message: e.to_string(),
+            };
+        }
+
         Self::RequestError {
             status: e.status().unwrap_or_default(),
             url: e.url().map_or("unknown".to_string(), |u| u.to_string()),
@@ -52,8 +82,13 @@ impl From<reqwest::Error> for FplClientError {
 #[derive(Clone)]
 pub struct FplClient {
     client: Client,
+    login_client: Client,
     base_url: String,
-    rate_limiter: Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>,
+    rate_limiter: Arc<HostRateLimiter>,
+    budget: Arc<ConcurrencyBudget>,
+    priority: RequestPriority,
+    retry_policy: RetryPolicy,
+    circuit_breaker: Arc<CircuitBreaker>,
 }
 
 impl Default for FplClient {
@@ -69,29 +104,135 @@ impl FplClient {
             .build()
             .expect("Failed to build FplClient. Reqwest client cant build");
 
-        let rate_limiter = Arc::new(RateLimiter::direct(Quota::per_second(
-            NonZeroU32::new(REQUESTS_PER_SECOND).unwrap(),
-        )));
+        // The login response redirects on success and failure, the cookies are on the redirect
+        let login_client = Client::builder()
+            .timeout(Duration::from_secs(REQ_TIMEOUT_SECONDS))
+            .redirect(Policy::none())
+            .build()
+            .expect("Failed to build FplClient. Reqwest login client cant build");
 
         Self {
             client,
+            login_client,
             base_url: "https://fantasy.premierleague.com/api".to_string(),
-            rate_limiter,
+            // Keyed by host so player photos don't eat into the API's budget
+            rate_limiter: Arc::new(HostRateLimiter::new(None)),
+            budget: Arc::new(ConcurrencyBudget::default()),
+            priority: RequestPriority::default(),
+            retry_policy: RetryPolicy::default(),
+            circuit_breaker: Arc::new(CircuitBreaker::default()),
         }
     }
 
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Counts every request against `shared` as well, so other processes using the same limiter
+    /// (the bot and the scraper) stay under FPL's limits between them.
+    pub fn with_shared_rate_limiter(mut self, shared: Arc<dyn SharedRateLimiter>) -> Self {
+        self.rate_limiter = Arc::new(HostRateLimiter::new(Some(shared)));
+        self
+    }
+
+    /// Clones share the rate limits and concurrency budget, so a clone with a different priority
+    /// competes for the same capacity.
+    pub fn with_priority(mut self, priority: RequestPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn circuit_breaker(&self) -> Arc<CircuitBreaker> {
+        Arc::clone(&self.circuit_breaker)
+    }
+
     pub async fn get<T: FplRequest + std::fmt::Debug>(
         &self,
         request: T,
     ) -> Result<T::Response, FplClientError> {
-        self.rate_limiter.until_ready().await;
+        self.send(request, None).await
+    }
 
+    /// `get` with the cookies of a logged in account, needed for requests like `MyTeamRequest`.
+    pub async fn get_with_session<T: FplRequest + std::fmt::Debug>(
+        &self,
+        request: T,
+        session: &FplSession,
+    ) -> Result<T::Response, FplClientError> {
+        self.send(request, Some(session)).await
+    }
+
+    /// Log in to an FPL account. The password is only sent to FPL, never stored.
+    pub async fn login(&self, email: &str, password: &str) -> Result<FplSession, FplClientError> {
+        let _permit = self.budget.acquire(self.priority).await;
+        self.rate_limiter
+            .until_ready(&host_of(LOGIN_URL), self.priority)
+            .await;
+
+        debug!("Logging in to FPL");
+        let response = self
+            .login_client
+            .post(LOGIN_URL)
+            .form(&[
+                ("login", email),
+                ("password", password),
+                ("app", LOGIN_APP),
+                ("redirect_uri", LOGIN_REDIRECT_URI),
+            ])
+            .send()
+            .await?;
+
+        FplSession::from_login_response(response.headers()).ok_or_else(|| {
+            FplClientError::Unauthorized {
+                url: LOGIN_URL.to_string(),
+            }
+        })
+    }
+
+    async fn send<T: FplRequest + std::fmt::Debug>(
+        &self,
+        request: T,
+        session: Option<&FplSession>,
+    ) -> Result<T::Response, FplClientError> {
         let url = request.to_url(&self.base_url);
+        if self.circuit_breaker.is_open() {
+            return Err(FplClientError::GameUpdating { url });
+        }
+
+        if request.requires_auth() && session.is_none() {
+            return Err(FplClientError::Unauthorized { url });
+        }
+
+        let _permit = self.budget.acquire(self.priority).await;
+        self.rate_limiter
+            .until_ready(&host_of(&url), self.priority)
+            .await;
+
         debug!("Making {:?} with URL {}", request, url);
-        let response = self.client.get(&url).send().await?;
+        let mut builder = self.client.get(&url);
+        if let Some(session) = session {
+            builder = builder.header(COOKIE, session.cookie());
+        }
+        let response = builder.send().await?;
         let status = response.status();
 
-        if request.is_binary() {
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+            return Err(FplClientError::RateLimited { url, retry_after });
+        }
+
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(self.response_error(&request, status, url, &body));
+        }
+
+        let result = if request.is_binary() {
             let bytes = response
                 .bytes()
                 .await
@@ -101,43 +242,120 @@ impl FplClient {
                     message: e.to_string(),
                 })?;
 
-            request
-                .process_response(FplResponseType::Binary(bytes.to_vec()))
-                .map_err(|e| FplClientError::RequestError {
-                    status,
-                    url,
-                    message: e.to_string(),
-                })
+            request.process_response(FplResponseType::Binary(bytes.to_vec()))
         } else {
             let body = response.text().await?;
             let value: Value = serde_json::from_str(&body).map_err(|e| (status, &url, e))?;
 
-            request
-                .process_response(FplResponseType::Json(value))
-                .map_err(|e| FplClientError::RequestError {
-                    status,
-                    url,
-                    message: e.to_string(),
-                })
+            // FPL sometimes answers with a bare message string in place of the response body
+            if let Some(message) = value.as_str() {
+                return Err(self.response_error(&request, status, url, message));
+            }
+
+            request.process_response(FplResponseType::Json(value))
+        };
+
+        result.map_err(|e| match e.downcast::<serde_json::Error>() {
+            Ok(e) => FplClientError::JsonError(status, url, *e),
+            Err(e) => FplClientError::RequestError {
+                status,
+                url,
+                message: e.to_string(),
+            },
+        })
+    }
+
+    fn response_error<T: FplRequest>(
+        &self,
+        request: &T,
+        status: StatusCode,
+        url: String,
+        body: &str,
+    ) -> FplClientError {
+        if body.contains(GAME_UPDATING_MESSAGE) {
+            self.circuit_breaker.trip();
+            return FplClientError::GameUpdating { url };
+        }
+
+        match status {
+            StatusCode::NOT_FOUND => request.not_found_error(url),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                FplClientError::Unauthorized { url }
+            }
+            StatusCode::SERVICE_UNAVAILABLE => FplClientError::Maintenance { url },
+            _ => FplClientError::RequestError {
+                status,
+                url,
+                message: body.to_string(),
+            },
+        }
+    }
+
+    /// `get`, retrying according to the client's `RetryPolicy`. While the game is being updated
+    /// this waits for the circuit breaker to close rather than failing straight away.
+    pub async fn get_with_retry<T: FplRequest + Clone + std::fmt::Debug>(
+        &self,
+        request: T,
+    ) -> Result<T::Response, FplClientError> {
+        let mut attempt = 0;
+        loop {
+            self.circuit_breaker.wait_until_closed().await;
+
+            let error = match self.get(request.clone()).await {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            };
+
+            let Some(delay) = self.retry_policy.delay_for(&error, attempt) else {
+                return Err(error);
+            };
+
+            // Waiting out a game update doesn't use up a retry
+            if RetryClass::classify(&error) != RetryClass::GameUpdating {
+                warn!(
+                    "{}. Retrying after {:?} (retry {}/{})",
+                    error,
+                    delay,
+                    attempt + 1,
+                    self.retry_policy.max_retries
+                );
+                attempt += 1;
+            }
+            tokio::time::sleep(delay).await;
         }
     }
 
     pub fn get_rate_limit_state(&self) -> String {
+        let host = host_of(&self.base_url);
         format!(
-            "Rate limit: {:?}/{} requests remaining this second",
-            self.rate_limiter.check(),
-            REQUESTS_PER_SECOND
+            "Rate limit: {} requests/s for {} ({}), {}/{} requests in flight",
+            requests_per_second(&host),
+            host,
+            if self.rate_limiter.is_shared() {
+                "shared between processes"
+            } else {
+                "this process only"
+            },
+            self.budget.in_flight(),
+            self.budget.max_in_flight()
         )
     }
 }
 
+fn host_of(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
 
     use fpl_common::types::{GameWeekId, LeagueId, PlayerId, TeamId};
     use requests::{
-        FixtureRequest, GameStateRequest, GameWeekPlayersRequest, MiniLeagueRequest, PlayerRequest,
-        TransfersRequest,
+        CupRequest, FixtureRequest, GameStateRequest, GameWeekPlayersRequest, MiniLeagueRequest,
+        PlayerRequest, TransfersRequest,
     };
 
     use super::*;
@@ -145,6 +363,72 @@ mod tests {
     use crate::requests::TeamGameWeekRequest;
     use crate::requests::TeamRequest;
 
+    fn team_request() -> TeamRequest {
+        TeamRequest::new(TeamId::new(1))
+    }
+
+    #[test]
+    fn test_response_error_maps_status() {
+        let client = FplClient::new();
+        let error = |status: StatusCode| {
+            client.response_error(&team_request(), status, "url".to_string(), "body")
+        };
+
+        assert!(matches!(
+            error(StatusCode::NOT_FOUND),
+            FplClientError::NotFound { .. }
+        ));
+        assert!(matches!(
+            error(StatusCode::UNAUTHORIZED),
+            FplClientError::Unauthorized { .. }
+        ));
+        assert!(matches!(
+            error(StatusCode::FORBIDDEN),
+            FplClientError::Unauthorized { .. }
+        ));
+        assert!(matches!(
+            error(StatusCode::SERVICE_UNAVAILABLE),
+            FplClientError::Maintenance { .. }
+        ));
+        match error(StatusCode::BAD_GATEWAY) {
+            FplClientError::RequestError {
+                status, message, ..
+            } => {
+                assert_eq!(status, StatusCode::BAD_GATEWAY);
+                assert_eq!(message, "body");
+            }
+            other => panic!("Expected a RequestError, got {:?}", other),
+        }
+        assert!(!client.circuit_breaker.is_open());
+    }
+
+    #[test]
+    fn test_response_error_game_updating_trips_breaker() {
+        let client = FplClient::new();
+        let error = client.response_error(
+            &team_request(),
+            StatusCode::SERVICE_UNAVAILABLE,
+            "url".to_string(),
+            "\"The game is being updated.\"",
+        );
+
+        assert!(matches!(error, FplClientError::GameUpdating { .. }));
+        assert!(client.circuit_breaker.is_open());
+    }
+
+    #[test]
+    fn test_not_found_error() {
+        let game_week = GameWeekId::new(1).unwrap();
+        assert!(matches!(
+            TeamGameWeekRequest::new(TeamId::new(1), game_week).not_found_error("url".to_string()),
+            FplClientError::NotStarted { .. }
+        ));
+        assert!(matches!(
+            team_request().not_found_error("url".to_string()),
+            FplClientError::NotFound { .. }
+        ));
+    }
+
     fn setup_tracing() {
         tracing_subscriber::fmt()
             .with_max_level(tracing::Level::DEBUG)
@@ -160,6 +444,15 @@ mod tests {
         println!("Response: {:#?}", response);
     }
 
+    #[tokio::test]
+    async fn test_cup_request() {
+        let client = FplClient::new();
+        let request = CupRequest::new(TeamId::new(1871038));
+
+        let response = client.get(request).await.unwrap();
+        println!("Response: {:#?}", response);
+    }
+
     #[tokio::test]
     async fn test_team_game_week_request() {
         // Arrange
@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde::de::Error;
+
+use super::{FplRequest, FplResponseType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClubImageKind {
+    Shirt,
+    GoalkeeperShirt,
+    Badge,
+}
+
+impl ClubImageKind {
+    pub const ALL: [ClubImageKind; 3] = [
+        ClubImageKind::Shirt,
+        ClubImageKind::GoalkeeperShirt,
+        ClubImageKind::Badge,
+    ];
+}
+
+/// A club's shirt or badge, saved to `output_path`. Clubs are identified by `clubs.code`,
+/// which unlike `clubs.id` stays the same between seasons.
+#[derive(Debug, Clone)]
+pub struct ClubImageRequest {
+    pub club_code: u32,
+    pub kind: ClubImageKind,
+    pub output_path: PathBuf,
+}
+
+impl ClubImageRequest {
+    pub fn new(club_code: u32, kind: ClubImageKind, output_path: impl Into<PathBuf>) -> Self {
+        Self {
+            club_code,
+            kind,
+            output_path: output_path.into(),
+        }
+    }
+}
+
+impl FplRequest for ClubImageRequest {
+    type Response = (); // No data to return, just saving the file
+
+    fn to_url(&self, _base_url: &str) -> String {
+        match self.kind {
+            ClubImageKind::Shirt => format!(
+                "https://fantasy.premierleague.com/dist/img/shirts/standard/shirt_{}-110.png",
+                self.club_code
+            ),
+            ClubImageKind::GoalkeeperShirt => format!(
+                "https://fantasy.premierleague.com/dist/img/shirts/standard/shirt_{}_1-110.png",
+                self.club_code
+            ),
+            ClubImageKind::Badge => format!(
+                "https://resources.premierleague.com/premierleague/badges/100/t{}.png",
+                self.club_code
+            ),
+        }
+    }
+
+    fn is_binary(&self) -> bool {
+        true
+    }
+
+    fn process_response(
+        &self,
+        response: FplResponseType,
+    ) -> Result<Self::Response, Box<dyn std::error::Error>> {
+        match response {
+            FplResponseType::Binary(bytes) => {
+                if let Some(parent) = self.output_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&self.output_path, bytes)?;
+                Ok(())
+            }
+            FplResponseType::Json(_) => Err(Box::new(serde_json::Error::custom(
+                "Expected binary response, got JSON",
+            ))),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use super::{FplRequest, FplResponseType};
+use crate::responses::cup::CupResponse;
+use fpl_common::types::TeamId;
+
+#[derive(Debug, Clone)]
+pub struct CupRequest {
+    team_id: TeamId,
+}
+
+impl CupRequest {
+    pub fn new(team_id: TeamId) -> Self {
+        Self { team_id }
+    }
+}
+
+impl FplRequest for CupRequest {
+    type Response = CupResponse;
+
+    fn to_url(&self, base_url: &str) -> String {
+        format!("{}/entry/{}/cup/", base_url, self.team_id)
+    }
+
+    fn process_response(
+        &self,
+        response: FplResponseType,
+    ) -> Result<Self::Response, Box<dyn std::error::Error>> {
+        match response {
+            FplResponseType::Json(value) => Ok(serde_json::from_value(value)?),
+            FplResponseType::Binary(_) => Err("Expected JSON response, got binary".into()),
+        }
+    }
+}
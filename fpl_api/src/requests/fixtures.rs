@@ -1,7 +1,7 @@
 use super::{FplRequest, FplResponseType};
 use crate::responses::fixtures::FixturesResponse;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FixtureRequest {}
 
 impl Default for FixtureRequest {
@@ -1,7 +1,7 @@
 use super::{FplRequest, FplResponseType};
 use crate::responses::game_state::GameStateResponse;
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct GameStateRequest {}
 
 impl FplRequest for GameStateRequest {
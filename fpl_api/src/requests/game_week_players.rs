@@ -5,7 +5,7 @@ use crate::responses::game_week_players::{
 use fpl_common::types::GameWeekId;
 use serde::de::Error;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct GameWeekPlayersRequest {
     pub game_week: GameWeekId,
 }
@@ -0,0 +1,34 @@
+use super::{FplRequest, FplResponseType};
+use crate::responses::me::MeResponse;
+
+/// Details of the account a session is logged in as.
+#[derive(Debug, Clone, Default)]
+pub struct MeRequest;
+
+impl MeRequest {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl FplRequest for MeRequest {
+    type Response = MeResponse;
+
+    fn to_url(&self, base_url: &str) -> String {
+        format!("{}/me/", base_url)
+    }
+
+    fn process_response(
+        &self,
+        response: FplResponseType,
+    ) -> Result<Self::Response, Box<dyn std::error::Error>> {
+        match response {
+            FplResponseType::Json(value) => Ok(serde_json::from_value(value)?),
+            FplResponseType::Binary(_) => Err("Expected JSON response, got binary".into()),
+        }
+    }
+
+    fn requires_auth(&self) -> bool {
+        true
+    }
+}
@@ -1,8 +1,8 @@
 use super::{FplRequest, FplResponseType};
-use crate::responses::mini_league::{MiniLeagueResponse, MiniLeagueResponseWrapper};
+use crate::responses::mini_league::MiniLeagueResponse;
 use fpl_common::types::LeagueId;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MiniLeagueRequest {
     pub league_id: LeagueId,
     pub page: u8,
@@ -29,13 +29,7 @@ impl FplRequest for MiniLeagueRequest {
         response: FplResponseType,
     ) -> Result<Self::Response, Box<dyn std::error::Error>> {
         match response {
-            FplResponseType::Json(value) => {
-                let wrapper: MiniLeagueResponseWrapper = serde_json::from_value(value)?;
-                match wrapper {
-                    MiniLeagueResponseWrapper::Success(response) => Ok(response),
-                    MiniLeagueResponseWrapper::PlainText(message) => Err(message.into()),
-                }
-            }
+            FplResponseType::Json(value) => Ok(serde_json::from_value(value)?),
             FplResponseType::Binary(_) => Err("Expected JSON response, got binary".into()),
         }
     }
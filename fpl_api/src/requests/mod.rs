@@ -1,5 +1,7 @@
 use serde_json::Value;
 
+use crate::FplClientError;
+
 #[derive(Debug)]
 pub enum FplResponseType {
     Json(Value),
@@ -17,22 +19,40 @@ pub trait FplRequest {
     fn is_binary(&self) -> bool {
         false
     }
+
+    /// Requests that only work with a logged in `FplSession`
+    fn requires_auth(&self) -> bool {
+        false
+    }
+
+    /// The error for a 404, for requests where a 404 means something more specific.
+    fn not_found_error(&self, url: String) -> FplClientError {
+        FplClientError::NotFound { url }
+    }
 }
 
+pub mod club_image;
+pub mod cup;
 pub mod fixtures;
 pub mod game_state;
 pub mod game_week_players;
+pub mod me;
 pub mod mini_league;
+pub mod my_team;
 pub mod player;
 pub mod player_image;
 pub mod team;
 pub mod team_game_week;
 pub mod transfers;
 
+pub use club_image::*;
+pub use cup::*;
 pub use fixtures::*;
 pub use game_state::*;
 pub use game_week_players::*;
+pub use me::*;
 pub use mini_league::*;
+pub use my_team::*;
 pub use player::*;
 pub use player_image::*;
 pub use team::*;
@@ -0,0 +1,37 @@
+use super::{FplRequest, FplResponseType};
+use crate::responses::my_team::MyTeamResponse;
+use fpl_common::types::TeamId;
+
+/// The logged in user's own team, including picks for the next game week before the deadline.
+#[derive(Debug, Clone)]
+pub struct MyTeamRequest {
+    pub team_id: TeamId,
+}
+
+impl MyTeamRequest {
+    pub fn new(team_id: TeamId) -> Self {
+        Self { team_id }
+    }
+}
+
+impl FplRequest for MyTeamRequest {
+    type Response = MyTeamResponse;
+
+    fn to_url(&self, base_url: &str) -> String {
+        format!("{}/my-team/{}/", base_url, self.team_id)
+    }
+
+    fn process_response(
+        &self,
+        response: FplResponseType,
+    ) -> Result<Self::Response, Box<dyn std::error::Error>> {
+        match response {
+            FplResponseType::Json(value) => Ok(serde_json::from_value(value)?),
+            FplResponseType::Binary(_) => Err("Expected JSON response, got binary".into()),
+        }
+    }
+
+    fn requires_auth(&self) -> bool {
+        true
+    }
+}
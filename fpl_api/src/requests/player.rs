@@ -2,7 +2,7 @@ use super::{FplRequest, FplResponseType};
 use crate::responses::player::PlayerResponse;
 use fpl_common::types::PlayerId;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PlayerRequest {
     pub player_id: PlayerId,
 }
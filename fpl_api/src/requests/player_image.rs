@@ -5,7 +5,7 @@ use serde::de::Error;
 
 use super::{FplRequest, FplResponseType};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PlayerPhotoRequest {
     pub player_code: u32,
     pub output_path: PathBuf,
@@ -1,9 +1,8 @@
 use super::{FplRequest, FplResponseType};
 use crate::responses::team::TeamResponse;
 use fpl_common::types::TeamId;
-use serde::de::Error;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TeamRequest {
     pub team_id: TeamId,
 }
@@ -26,13 +25,7 @@ impl FplRequest for TeamRequest {
         response: FplResponseType,
     ) -> Result<Self::Response, Box<dyn std::error::Error>> {
         match response {
-            FplResponseType::Json(value) => {
-                if let Some(message) = value.as_str() {
-                    return Err(Box::new(serde_json::Error::custom(message)));
-                }
-
-                Ok(serde_json::from_value(value)?)
-            }
+            FplResponseType::Json(value) => Ok(serde_json::from_value(value)?),
             FplResponseType::Binary(_) => Err("Expected JSON response, got binary".into()),
         }
     }
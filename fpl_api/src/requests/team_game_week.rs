@@ -1,9 +1,9 @@
 use super::{FplRequest, FplResponseType};
-use crate::responses::team_game_week::{ErrorResponse, TeamGameWeekResponse};
+use crate::responses::team_game_week::TeamGameWeekResponse;
+use crate::FplClientError;
 use fpl_common::types::{GameWeekId, TeamId};
-use serde::de::Error;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TeamGameWeekRequest {
     pub team_id: TeamId,
     pub game_week: GameWeekId,
@@ -31,14 +31,6 @@ impl FplRequest for TeamGameWeekRequest {
     ) -> Result<Self::Response, Box<dyn std::error::Error>> {
         match response {
             FplResponseType::Json(value) => {
-                if let Some(message) = value.as_str() {
-                    return Err(Box::new(serde_json::Error::custom(message)));
-                }
-
-                if let Ok(error) = serde_json::from_value::<ErrorResponse>(value.clone()) {
-                    return Err(Box::new(serde_json::Error::custom(error.detail)));
-                }
-
                 let mut success: TeamGameWeekResponse = serde_json::from_value(value)?;
                 success.team_id = Some(self.team_id);
                 success.game_week_id = Some(self.game_week);
@@ -47,4 +39,9 @@ impl FplRequest for TeamGameWeekRequest {
             FplResponseType::Binary(_) => Err("Expected JSON response, got binary".into()),
         }
     }
+
+    /// Picks 404 for game weeks the team hasn't played, normally because the deadline hasn't passed
+    fn not_found_error(&self, url: String) -> FplClientError {
+        FplClientError::NotStarted { url }
+    }
 }
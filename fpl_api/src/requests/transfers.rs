@@ -2,7 +2,7 @@ use super::{FplRequest, FplResponseType};
 use crate::responses::transfers::TransfersResponse;
 use fpl_common::types::TeamId;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TransfersRequest {
     team_id: TeamId,
 }
@@ -0,0 +1,39 @@
+use fpl_common::types::{GameWeekId, TeamId};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct CupResponse {
+    pub cup_matches: Vec<CupMatchResponse>,
+    pub cup_status: CupStatus,
+}
+
+/// One tie in a team's cup run. `entry_2_*` is empty when `entry_1` got a bye.
+#[derive(Debug, Deserialize)]
+pub struct CupMatchResponse {
+    pub id: i32,
+    pub entry_1_entry: TeamId,
+    pub entry_1_name: String,
+    pub entry_1_player_name: String,
+    pub entry_1_points: i16,
+    pub entry_2_entry: Option<TeamId>,
+    pub entry_2_name: Option<String>,
+    pub entry_2_player_name: Option<String>,
+    pub entry_2_points: i16,
+    pub is_knockout: bool,
+    pub league: i32,
+    /// Team ID of the winner, set once the game week is over
+    pub winner: Option<TeamId>,
+    pub event: GameWeekId,
+    pub tiebreak: Option<String>,
+    pub is_bye: bool,
+    pub knockout_name: String,
+}
+
+/// How the team did in the qualifying game week, empty until the cup is drawn.
+#[derive(Debug, Deserialize)]
+pub struct CupStatus {
+    pub qualification_event: Option<GameWeekId>,
+    pub qualification_numbers: Option<i32>,
+    pub qualification_rank: Option<i32>,
+    pub qualification_state: Option<String>,
+}
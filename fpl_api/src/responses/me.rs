@@ -0,0 +1,17 @@
+use fpl_common::types::TeamId;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct MeResponse {
+    /// `None` when the session isn't logged in
+    pub player: Option<MePlayer>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MePlayer {
+    pub id: i32,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub entry: Option<TeamId>,
+}
@@ -2,13 +2,6 @@ use chrono::{DateTime, Utc};
 use fpl_common::types::{GameWeekId, LeagueId, TeamId};
 use serde::Deserialize;
 
-#[derive(Debug, Deserialize)]
-#[serde(untagged)]
-pub enum MiniLeagueResponseWrapper {
-    Success(MiniLeagueResponse),
-    PlainText(String),
-}
-
 #[derive(Debug, Deserialize)]
 pub struct MiniLeagueResponse {
     pub last_updated_data: DateTime<Utc>,
@@ -20,10 +20,13 @@ where
     }
 }
 
+pub mod cup;
 pub mod fixtures;
 pub mod game_state;
 pub mod game_week_players;
+pub mod me;
 pub mod mini_league;
+pub mod my_team;
 pub mod player;
 pub mod team;
 pub mod team_game_week;
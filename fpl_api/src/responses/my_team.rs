@@ -0,0 +1,48 @@
+use fpl_common::types::{GameWeekId, PlayerId, PlayerPosition};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct MyTeamResponse {
+    pub picks: Vec<MyTeamPick>,
+    pub chips: Vec<MyTeamChip>,
+    pub transfers: MyTeamTransfers,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MyTeamPick {
+    pub element: PlayerId,
+    pub position: i16,
+    pub multiplier: i16,
+    pub is_captain: bool,
+    pub is_vice_captain: bool,
+    pub element_type: PlayerPosition,
+    pub selling_price: i16,
+    pub purchase_price: i16,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MyTeamChip {
+    pub name: String,
+    pub status_for_entry: String,
+    pub played_by_entry: Vec<GameWeekId>,
+    pub number: i16,
+    pub start_event: GameWeekId,
+    pub stop_event: GameWeekId,
+    pub chip_type: String,
+}
+
+impl MyTeamChip {
+    pub fn is_available(&self) -> bool {
+        self.status_for_entry == "available"
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MyTeamTransfers {
+    pub cost: i16,
+    pub status: String,
+    pub limit: Option<i16>,
+    pub made: i16,
+    pub bank: i16,
+    pub value: i16,
+}
@@ -1,19 +1,6 @@
 use fpl_common::types::{Chip, GameWeekId, PlayerId, PlayerPosition, TeamId};
 use serde::Deserialize;
 
-#[derive(Debug, Deserialize)]
-#[serde(untagged)]
-pub enum TeamGameWeekResponseWrapper {
-    Success(TeamGameWeekResponse),
-    Error(ErrorResponse),
-    PlainText(String),
-}
-
-#[derive(Debug, Deserialize)]
-pub struct ErrorResponse {
-    pub detail: String,
-}
-
 #[derive(Debug, Deserialize)]
 pub struct TeamGameWeekResponse {
     pub active_chip: Option<Chip>,
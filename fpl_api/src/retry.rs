@@ -0,0 +1,240 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use tracing::warn;
+
+use crate::FplClientError;
+
+pub const DEFAULT_MAX_RETRIES: usize = 5;
+const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(500);
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(60);
+const DEFAULT_CIRCUIT_BREAKER_COOLDOWN: Duration = Duration::from_secs(120);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RetryClass {
+    /// Timeouts, connection failures, maintenance and 5xx responses.
+    Transient,
+    /// 429, optionally with the server's `Retry-After`.
+    RateLimited(Option<Duration>),
+    /// FPL is mid update, nothing will succeed until it's finished.
+    GameUpdating,
+    /// Retrying won't change the outcome.
+    Permanent,
+}
+
+impl RetryClass {
+    pub fn classify(error: &FplClientError) -> Self {
+        match error {
+            FplClientError::RateLimited { retry_after, .. } => Self::RateLimited(*retry_after),
+            FplClientError::GameUpdating { .. } => Self::GameUpdating,
+            FplClientError::ConnectionError { .. } | FplClientError::Maintenance { .. } => {
+                Self::Transient
+            }
+            FplClientError::RequestError { status, .. }
+            | FplClientError::JsonError(status, _, _)
+            | FplClientError::BinaryError(status, _, _)
+                if status.is_server_error() =>
+            {
+                Self::Transient
+            }
+            _ => Self::Permanent,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: usize,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay: DEFAULT_BASE_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: usize, base_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            max_retries,
+            base_delay,
+            max_delay,
+        }
+    }
+
+    /// Full jitter exponential backoff, a random delay between zero and the capped exponential.
+    pub fn backoff(&self, attempt: usize) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt as u32))
+            .min(self.max_delay);
+
+        rand::thread_rng().gen_range(Duration::ZERO..=exponential)
+    }
+
+    /// How long to wait before the next attempt, or `None` if the error shouldn't be retried.
+    /// A `Retry-After` is capped at `max_delay` like everything else.
+    ///
+    /// Game updates don't count as an attempt, the circuit breaker does the waiting for them
+    /// however long the update takes.
+    pub fn delay_for(&self, error: &FplClientError, attempt: usize) -> Option<Duration> {
+        match RetryClass::classify(error) {
+            RetryClass::GameUpdating => Some(Duration::ZERO),
+            _ if attempt >= self.max_retries => None,
+            RetryClass::Transient | RetryClass::RateLimited(None) => Some(self.backoff(attempt)),
+            RetryClass::RateLimited(Some(retry_after)) => Some(retry_after.min(self.max_delay)),
+            RetryClass::Permanent => None,
+        }
+    }
+}
+
+/// Shared between every clone of an `FplClient`, so when FPL reports the game is being
+/// updated every scraper using that client backs off together.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    cooldown: Duration,
+    open_until: Mutex<Option<Instant>>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(DEFAULT_CIRCUIT_BREAKER_COOLDOWN)
+    }
+}
+
+impl CircuitBreaker {
+    pub fn new(cooldown: Duration) -> Self {
+        Self {
+            cooldown,
+            open_until: Mutex::new(None),
+        }
+    }
+
+    pub fn trip(&self) {
+        let mut open_until = self.open_until.lock().unwrap();
+        if open_until.is_none() {
+            warn!(
+                "FPL game is being updated, pausing requests for {:?}",
+                self.cooldown
+            );
+        }
+        *open_until = Some(Instant::now() + self.cooldown);
+    }
+
+    /// Time left until requests are allowed again, `None` when closed.
+    pub fn remaining(&self) -> Option<Duration> {
+        let mut open_until = self.open_until.lock().unwrap();
+        match *open_until {
+            Some(until) if until > Instant::now() => Some(until - Instant::now()),
+            Some(_) => {
+                *open_until = None;
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.remaining().is_some()
+    }
+
+    pub async fn wait_until_closed(&self) {
+        while let Some(remaining) = self.remaining() {
+            tokio::time::sleep(remaining).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    fn request_error(status: StatusCode) -> FplClientError {
+        FplClientError::RequestError {
+            status,
+            url: "url".to_string(),
+            message: "message".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(
+            RetryClass::classify(&request_error(StatusCode::BAD_GATEWAY)),
+            RetryClass::Transient
+        );
+        assert_eq!(
+            RetryClass::classify(&request_error(StatusCode::NOT_FOUND)),
+            RetryClass::Permanent
+        );
+        assert_eq!(
+            RetryClass::classify(&FplClientError::RateLimited {
+                url: "url".to_string(),
+                retry_after: Some(Duration::from_secs(3)),
+            }),
+            RetryClass::RateLimited(Some(Duration::from_secs(3)))
+        );
+        assert_eq!(
+            RetryClass::classify(&FplClientError::GameUpdating {
+                url: "url".to_string()
+            }),
+            RetryClass::GameUpdating
+        );
+    }
+
+    #[test]
+    fn test_delay_for() {
+        // Arrange
+        let policy = RetryPolicy::new(3, Duration::from_millis(100), Duration::from_secs(1));
+        let rate_limited = FplClientError::RateLimited {
+            url: "url".to_string(),
+            retry_after: Some(Duration::from_secs(30)),
+        };
+
+        // Act + Assert
+        assert!(policy.backoff(10) <= Duration::from_secs(1));
+        // Retry-After is capped at max_delay
+        assert_eq!(
+            policy.delay_for(&rate_limited, 0),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(policy.delay_for(&rate_limited, 3), None);
+        assert_eq!(
+            policy.delay_for(&request_error(StatusCode::NOT_FOUND), 0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_game_updating_does_not_use_up_retries() {
+        let policy = RetryPolicy::new(3, Duration::from_millis(100), Duration::from_secs(1));
+        let game_updating = FplClientError::GameUpdating {
+            url: "url".to_string(),
+        };
+
+        assert_eq!(policy.delay_for(&game_updating, 0), Some(Duration::ZERO));
+        assert_eq!(policy.delay_for(&game_updating, 3), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_circuit_breaker() {
+        let breaker = CircuitBreaker::new(Duration::from_secs(60));
+        assert!(!breaker.is_open());
+
+        breaker.trip();
+        assert!(breaker.is_open());
+        assert!(breaker.remaining().unwrap() <= Duration::from_secs(60));
+
+        let breaker = CircuitBreaker::new(Duration::ZERO);
+        breaker.trip();
+        assert!(!breaker.is_open());
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt::{Debug, Formatter};
+
+use reqwest::header::{HeaderMap, LOCATION, SET_COOKIE};
+
+/// Cookies for a logged in FPL account, sent with requests that need authentication.
+#[derive(Clone)]
+pub struct FplSession {
+    cookie: String,
+}
+
+impl FplSession {
+    pub fn from_cookie(cookie: impl Into<String>) -> Self {
+        Self {
+            cookie: cookie.into(),
+        }
+    }
+
+    pub fn cookie(&self) -> &str {
+        &self.cookie
+    }
+
+    /// The session from the login redirect's cookies, or None if FPL redirected anywhere but
+    /// back with `state=success`.
+    pub(crate) fn from_login_response(headers: &HeaderMap) -> Option<Self> {
+        let succeeded = headers
+            .get(LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .is_some_and(|location| location.contains("state=success"));
+        if !succeeded {
+            return None;
+        }
+
+        let cookie = headers
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|value| value.split(';').next())
+            .collect::<Vec<_>>()
+            .join("; ");
+        Some(Self::from_cookie(cookie))
+    }
+}
+
+// Never log the cookie, it's as good as the user's password
+impl Debug for FplSession {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FplSession")
+            .field("cookie", &"<redacted>")
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    fn headers(location: &str, cookies: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(LOCATION, HeaderValue::from_str(location).unwrap());
+        for cookie in cookies {
+            headers.append(SET_COOKIE, HeaderValue::from_str(cookie).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_login_response_keeps_cookie_pairs() {
+        let session = FplSession::from_login_response(&headers(
+            "https://fantasy.premierleague.com/a/login?state=success",
+            &[
+                "pl_profile=abc123; Domain=.premierleague.com; Path=/; Secure",
+                "sessionid=xyz; HttpOnly; Path=/",
+            ],
+        ))
+        .unwrap();
+        assert_eq!(session.cookie(), "pl_profile=abc123; sessionid=xyz");
+    }
+
+    #[test]
+    fn test_failed_login_has_no_session() {
+        let failed = headers(
+            "https://fantasy.premierleague.com/a/login?state=fail&reason=credentials",
+            &["csrftoken=abc; Path=/"],
+        );
+        assert!(FplSession::from_login_response(&failed).is_none());
+        assert!(FplSession::from_login_response(&HeaderMap::new()).is_none());
+    }
+
+    #[test]
+    fn test_debug_redacts_cookie() {
+        let session = FplSession::from_cookie("sessionid=xyz");
+        assert!(!format!("{:?}", session).contains("xyz"));
+    }
+}
@@ -1,7 +1,6 @@
-use std::collections::HashSet;
-
 use crate::Context;
-use ::serenity::all::Member;
+use ::serenity::all::GuildId;
+use fpl_db::queries::guild::get_registered_guild_members;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
 use once_cell::sync::Lazy;
@@ -43,19 +42,42 @@ where
         })
 }
 
+/// Leagues the caller can pick from. In a server that's the server's home league plus any league
+/// a member of the server is in, in DMs it's just the caller's own leagues.
 pub(crate) async fn get_mini_league_name_autocompletes<'a>(
     ctx: Context<'_>,
     partial: &'a str,
     as_string: bool,
 ) -> impl Iterator<Item = serenity::AutocompleteChoice> + 'a {
-    let mini_league_names = (sqlx::query!(
-        "SELECT name, id FROM discord_user_mini_leagues WHERE discord_id = $1 AND name IS NOT NULL",
-        ctx.author().id.get() as i64
-    )
-    .map(|row| (row.name.unwrap(), row.id.unwrap()))
-    .fetch_all(&*ctx.data().pool)
-    .await)
-        .unwrap_or_default();
+    let mini_league_names = match ctx.guild_id() {
+        Some(guild_id) => sqlx::query!(
+            r#"
+            SELECT DISTINCT dum.name as "name!", dum.id as "id!"
+            FROM discord_user_mini_leagues dum
+            JOIN guild_members gm ON gm.discord_id = dum.discord_id
+            WHERE gm.guild_id = $1 AND dum.name IS NOT NULL
+            AND dum.id NOT IN (SELECT league_id FROM guild_home_leagues WHERE guild_id = $1)
+            UNION ALL
+            SELECT '🏠 ' || ml.name, ml.id
+            FROM guild_home_leagues ghl
+            JOIN mini_leagues ml ON ml.id = ghl.league_id
+            WHERE ghl.guild_id = $1
+            "#,
+            guild_id.get() as i64
+        )
+        .map(|row| (row.name, row.id))
+        .fetch_all(&*ctx.data().pool)
+        .await
+        .unwrap_or_default(),
+        None => sqlx::query!(
+            "SELECT name, id FROM discord_user_mini_leagues WHERE discord_id = $1 AND name IS NOT NULL",
+            ctx.author().id.get() as i64
+        )
+        .map(|row| (row.name.unwrap(), row.id.unwrap()))
+        .fetch_all(&*ctx.data().pool)
+        .await
+        .unwrap_or_default(),
+    };
 
     get_fuzzy_matches(partial, mini_league_names, as_string)
 }
@@ -95,30 +117,95 @@ pub(crate) async fn get_player_name_autocompletes<'a>(
     get_fuzzy_matches(partial, player_names, as_string)
 }
 
-// TODO: Can easily have a cache here for the registered_discord_ids.
-// Can even probably just have one for server id: [registered members]
+pub(crate) async fn get_linked_team_autocompletes<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+    as_string: bool,
+) -> impl Iterator<Item = serenity::AutocompleteChoice> + 'a {
+    let team_names = (sqlx::query!(
+        r#"
+        SELECT t.name, t.id
+        FROM discord_user_teams dut
+        JOIN teams t ON t.id = dut.team_id
+        WHERE dut.discord_id = $1
+        "#,
+        ctx.author().id.get() as i64
+    )
+    .map(|row| (format!("{} ({})", row.name, row.id), row.id))
+    .fetch_all(&*ctx.data().pool)
+    .await)
+        .unwrap_or_default();
+
+    get_fuzzy_matches(partial, team_names, as_string)
+}
+
+/// Teams we already know about, from `teams` or the standings of tracked leagues, searched by
+/// team or manager name. A number is offered as a team ID as is.
+pub(crate) async fn get_team_search_autocompletes<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = serenity::AutocompleteChoice> + 'a {
+    let mut choices = vec![];
+    if let Ok(team_id) = partial.trim().parse::<i32>() {
+        choices.push(serenity::AutocompleteChoice::new(
+            format!("Team ID {}", team_id),
+            team_id,
+        ));
+    }
+    if partial.trim().len() < 3 {
+        return choices.into_iter();
+    }
+
+    let team_names = (sqlx::query!(
+        r#"
+        SELECT DISTINCT ON (id) id as "id!", name as "name!", manager as "manager!"
+        FROM (
+            SELECT id, name, player_first_name || ' ' || player_last_name as manager
+            FROM teams
+            UNION ALL
+            SELECT team_id, entry_name, player_name
+            FROM mini_league_standings
+        ) known_teams
+        WHERE name ILIKE '%' || $1 || '%' OR manager ILIKE '%' || $1 || '%'
+        ORDER BY id
+        LIMIT 100
+        "#,
+        escape_like(partial.trim())
+    )
+    .map(|row| (format!("{} - {} ({})", row.name, row.manager, row.id), row.id))
+    .fetch_all(&*ctx.data().pool)
+    .await)
+        .unwrap_or_default();
+
+    choices.extend(get_fuzzy_matches(partial, team_names, false));
+    choices.into_iter()
+}
+
+/// `partial` with the ILIKE wildcards escaped so they match literally, `\` is the default escape.
+fn escape_like(partial: &str) -> String {
+    partial
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
 pub(crate) async fn get_registered_users_autocompletes<'a>(
     ctx: Context<'_>,
-    members: &[Member],
+    guild_id: GuildId,
     partial: &'a str,
 ) -> impl Iterator<Item = serenity::AutocompleteChoice> + 'a {
-    let registered_discord_ids: HashSet<i64> = sqlx::query!("SELECT discord_id FROM discord_users")
-        .fetch_all(&*ctx.data().pool)
+    let members = get_registered_guild_members(&ctx.data().pool, guild_id.get() as i64)
         .await
-        .unwrap_or_default()
-        .into_iter()
-        .map(|row| row.discord_id)
-        .collect();
+        .unwrap_or_default();
 
     let filtered_members = members
-        .iter()
-        .filter(|member| registered_discord_ids.contains(&(member.user.id.get() as i64)))
+        .into_iter()
         .filter_map(|member| {
-            let name = format!("{} ({})", member.display_name(), member.user.name);
+            let name = format!("{} ({})", member.display_name, member.username);
             if name.to_lowercase().contains(&partial.to_lowercase()) {
                 Some(serenity::AutocompleteChoice::new(
                     name,
-                    member.user.id.to_string(),
+                    member.discord_id.to_string(),
                 ))
             } else {
                 None
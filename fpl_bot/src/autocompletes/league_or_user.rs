@@ -1,5 +1,4 @@
 use poise::serenity_prelude as serenity;
-use tracing::error;
 
 use crate::autocompletes::get_mini_league_name_autocompletes;
 use crate::Context;
@@ -46,20 +45,9 @@ pub async fn autocomplete_league_or_user_value<'a>(
         "League" => get_mini_league_name_autocompletes(ctx, partial, true)
             .await
             .collect::<Vec<_>>(),
-        "User" => {
-            match guild_id
-                .members(&ctx.serenity_context().http, None, None)
-                .await
-            {
-                Ok(members) => get_registered_users_autocompletes(ctx, &members, partial)
-                    .await
-                    .collect::<Vec<_>>(),
-                Err(err) => {
-                    error!("{:#?}", err);
-                    vec![]
-                }
-            }
-        }
+        "User" => get_registered_users_autocompletes(ctx, guild_id, partial)
+            .await
+            .collect::<Vec<_>>(),
         _ => vec![],
     };
 
@@ -0,0 +1,11 @@
+use crate::Context;
+use poise::serenity_prelude as serenity;
+
+use crate::autocompletes::helpers::get_linked_team_autocompletes;
+
+pub async fn autocomplete_linked_team<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = serenity::AutocompleteChoice> + 'a {
+    get_linked_team_autocompletes(ctx, partial, false).await
+}
@@ -1,15 +1,19 @@
 pub mod helpers;
 pub mod league_or_user;
+pub mod linked_team;
 pub mod mini_league;
 pub mod overall_or_week;
 pub mod player;
 pub mod player_or_club;
+pub mod team;
 pub mod user;
 
 use helpers::*;
 pub use league_or_user::*;
+pub use linked_team::*;
 pub use mini_league::*;
 pub use overall_or_week::*;
 pub use player::*;
 pub use player_or_club::*;
+pub use team::*;
 pub use user::*;
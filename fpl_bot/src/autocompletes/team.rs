@@ -0,0 +1,11 @@
+use crate::Context;
+use poise::serenity_prelude as serenity;
+
+use super::get_team_search_autocompletes;
+
+pub async fn autocomplete_team<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = serenity::AutocompleteChoice> + 'a {
+    get_team_search_autocompletes(ctx, partial).await
+}
@@ -5,8 +5,9 @@ use fpl_db::queries::mini_league::get_league_name;
 use std::time::Instant;
 use tracing::{debug, info};
 
-use fpl_common::types::{LeagueId, PlayerId};
+use fpl_common::types::LeagueId;
 use fpl_db::queries::game_week::get_current_game_week;
+use fpl_services::captains::get_captains;
 
 const COMMAND: &str = "/captains";
 
@@ -21,42 +22,12 @@ pub async fn captains(
     let timer: Instant = start_timer!();
 
     let current_game_week = get_current_game_week(&ctx.data().pool).await?;
-    let captains = sqlx::query!(
-        r#"
-        SELECT
-            mls.player_name,
-            p.id as "id!",
-            p.web_name
-        FROM mini_league_standings mls
-        JOIN mini_leagues ml ON mls.league_id = ml.id
-        JOIN team_game_week_picks tgwp ON tgwp.team_id = mls.team_id
-        JOIN players p ON p.id = tgwp.player_id
-        WHERE tgwp.game_week_id = $1
-        AND ml.id = $2
-        AND tgwp.is_captain = true
-        ORDER BY p.id ASC
-        "#,
-        i16::from(current_game_week.id),
-        i32::from(league_id)
-    )
-    .fetch_all(&*ctx.data().pool)
-    .await
-    .map(|rows| {
-        rows.into_iter()
-            .map(|row| (row.player_name, PlayerId::from(row.id), row.web_name))
-            .collect::<Vec<(String, PlayerId, String)>>()
-    })?;
-
+    let captains_rows = get_captains(&ctx.data().pool, league_id, current_game_week.id).await?;
     log_timer!(timer, COMMAND, ctx, "fetched captains");
 
     let league_name = get_league_name(&ctx.data().pool, league_id).await?;
     log_timer!(timer, COMMAND, ctx, "fetched league_name");
 
-    let captains_rows = captains
-        .into_iter()
-        .map(|(player_name, _, web_name)| format!("**{}** captained **{}**", player_name, web_name))
-        .collect::<Vec<String>>();
-
     Embed::from_ctx(ctx)?
         .success()
         .title(format!("Captains for {league_name}"))
@@ -0,0 +1,88 @@
+use fpl_db::models::CupTie;
+use fpl_db::queries::cup::get_cup_ties;
+use std::time::Instant;
+use tracing::debug;
+
+use crate::utils::embed::Embed;
+use crate::{handle_async_fallible, log_call, log_timer, start_timer, Context, Error};
+
+const COMMAND: &str = "/cup";
+
+/// Show everyone's current FPL cup tie, the live score and who's been knocked out
+#[poise::command(slash_command)]
+pub async fn cup(ctx: Context<'_>) -> Result<(), Error> {
+    log_call!(COMMAND, ctx);
+    let timer: Instant = start_timer!();
+
+    let guild_id = ctx.guild_id().map(|guild_id| guild_id.get() as i64);
+    let mut ties = handle_async_fallible!(
+        ctx,
+        get_cup_ties(&ctx.data().pool, guild_id),
+        "Error calling get_cup_ties"
+    );
+    log_timer!(timer, COMMAND, ctx, "fetched cup ties");
+
+    // Outside a server only show the caller's own tie
+    if guild_id.is_none() {
+        let discord_id = ctx.author().id.get() as i64;
+        ties.retain(|tie| tie.discord_id == discord_id);
+    }
+
+    if ties.is_empty() {
+        Embed::from_ctx(ctx)?
+            .error()
+            .title("No cup ties")
+            .body("Either the cup hasn't been drawn yet or nobody registered has qualified.")
+            .send()
+            .await?;
+        return Ok(());
+    }
+
+    // Still in first, latest round first
+    ties.sort_by_key(|tie| (tie.is_eliminated(), -i16::from(tie.game_week_id)));
+    let rows: Vec<String> = ties.iter().map(format_tie).collect();
+
+    Embed::from_ctx(ctx)?
+        .success()
+        .title(format!(
+            "Cup ties ({} of {} still in)",
+            ties.iter().filter(|tie| !tie.is_eliminated()).count(),
+            ties.len()
+        ))
+        .add_pages_from_strings(rows, None)
+        .send()
+        .await?;
+    Ok(())
+}
+
+fn format_tie(tie: &CupTie) -> String {
+    let round = format!("{}, GW{}", tie.knockout_name, tie.game_week_id);
+    if tie.is_bye {
+        return format!("➡️ **{}** has a bye ({round})", tie.team_name);
+    }
+
+    let status = match tie.winner {
+        None => "⚔️",
+        Some(_) if tie.is_eliminated() => "❌",
+        Some(_) => "✅",
+    };
+    let opponent = match &tie.opponent_player_name {
+        Some(player_name) => format!(
+            "{} ({player_name})",
+            tie.opponent_name.as_deref().unwrap_or_default()
+        ),
+        None => tie.opponent_name.clone().unwrap_or_default(),
+    };
+    // Their picks aren't scraped so the points are from the last cup update
+    let not_live = if tie.opponent_points_live {
+        ""
+    } else {
+        " · opponent's points aren't live"
+    };
+    format!(
+        "{status} **{}** {} - {} {opponent}\n> {round}{not_live}",
+        tie.team_name,
+        tie.points,
+        tie.opponent_points.unwrap_or_default()
+    )
+}
@@ -3,6 +3,7 @@ use std::time::Instant;
 use crate::{
     handle_async_fallible, handle_parse_value,
     images::{DifferentialKey, Differentials, DifferentialsRenderer},
+    render,
 };
 use fpl_common::types::{GameWeekId, LeagueId};
 use fpl_db::queries::{
@@ -14,9 +15,11 @@ use tracing::debug;
 
 use crate::{
     autocompletes::{autocomplete_league_or_user, autocomplete_league_or_user_value},
-    commands::get_image_file_path,
     log_call, log_timer, start_timer,
-    utils::embed::{Embed, EmbedPage},
+    utils::{
+        embed::{Embed, EmbedPage},
+        theme::get_caller_theme,
+    },
     Context, Error,
 };
 
@@ -131,9 +134,14 @@ pub async fn differentials(
         "Error calling get_league_name"
     );
 
-    let file_name = get_image_file_path(COMMAND, &ctx);
-    let renderer = DifferentialsRenderer::default();
-    renderer.render(differentials, &file_name).await?;
+    let renderer = DifferentialsRenderer::default().with_theme(get_caller_theme(ctx).await);
+    let image = render!(
+        ctx,
+        embed,
+        renderer,
+        differentials,
+        "Failed to render differentials"
+    );
     log_timer!(timer, COMMAND, ctx, "rendered differentials image");
 
     embed
@@ -142,7 +150,7 @@ pub async fn differentials(
             "Differentials for {} in GW{}",
             user_or_league_name, game_week_id
         ))
-        .add_page(EmbedPage::new().with_image(file_name))
+        .add_page(EmbedPage::new().with_rendered_image(image))
         .send()
         .await?;
 
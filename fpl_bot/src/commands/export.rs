@@ -0,0 +1,194 @@
+use std::time::Instant;
+
+use fpl_common::types::{GameWeekId, LeagueId};
+use fpl_db::queries::discord::get_discord_user;
+use fpl_services::export::{ExportDataset, ExportFilter, ExportFormat};
+use fpl_services::ServiceError;
+use serenity::all::{CreateAttachment, EditInteractionResponse, User};
+use tracing::debug;
+
+use crate::autocompletes::autocomplete_mini_league;
+use crate::utils::common::get_not_registered_title_and_message;
+use crate::utils::embed::Embed;
+use crate::{handle_async_fallible, log_call, log_timer, start_timer, Context, Error};
+
+const COMMAND: &str = "/export";
+
+/// Discord's upload limit for servers without boosts
+const MAX_ATTACHMENT_BYTES: usize = 8 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum DatasetChoice {
+    #[name = "Standings"]
+    Standings,
+    #[name = "Picks"]
+    Picks,
+    #[name = "Transfers"]
+    Transfers,
+    #[name = "Chips"]
+    Chips,
+    #[name = "Player stats"]
+    PlayerStats,
+}
+
+impl From<DatasetChoice> for ExportDataset {
+    fn from(choice: DatasetChoice) -> Self {
+        match choice {
+            DatasetChoice::Standings => ExportDataset::Standings,
+            DatasetChoice::Picks => ExportDataset::Picks,
+            DatasetChoice::Transfers => ExportDataset::Transfers,
+            DatasetChoice::Chips => ExportDataset::Chips,
+            DatasetChoice::PlayerStats => ExportDataset::PlayerStats,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum FormatChoice {
+    #[name = "CSV"]
+    Csv,
+    #[name = "JSON"]
+    Json,
+    #[name = "Parquet"]
+    Parquet,
+}
+
+impl From<FormatChoice> for ExportFormat {
+    fn from(choice: FormatChoice) -> Self {
+        match choice {
+            FormatChoice::Csv => ExportFormat::Csv,
+            FormatChoice::Json => ExportFormat::Json,
+            FormatChoice::Parquet => ExportFormat::Parquet,
+        }
+    }
+}
+
+/// Download a league's standings, picks, transfers, chips or player stats as a file
+#[poise::command(slash_command, guild_only, user_cooldown = 10)]
+#[allow(clippy::too_many_arguments)]
+pub async fn export(
+    ctx: Context<'_>,
+    #[description = "What to export"] dataset: DatasetChoice,
+    #[description = "Mini League"]
+    #[autocomplete = "autocomplete_mini_league"]
+    league_id: LeagueId,
+    #[description = "File format (default CSV)"] format: Option<FormatChoice>,
+    #[description = "Only this user's team"] user: Option<User>,
+    #[description = "First Game Week"] from_game_week: Option<GameWeekId>,
+    #[description = "Last Game Week"] to_game_week: Option<GameWeekId>,
+) -> Result<(), Error> {
+    log_call!(
+        COMMAND,
+        ctx,
+        "dataset",
+        dataset,
+        "league_id",
+        league_id,
+        "format",
+        format,
+        "user",
+        user,
+        "from_game_week",
+        from_game_week,
+        "to_game_week",
+        to_game_week
+    );
+    let timer: Instant = start_timer!();
+    let embed = Embed::from_ctx(ctx)?
+        .processing()
+        .title("Processing export request")
+        .send()
+        .await?;
+
+    let team_id = match user {
+        Some(user) => {
+            let user_id = i64::from(user.id);
+            let discord_user = handle_async_fallible!(
+                ctx,
+                embed,
+                get_discord_user(&ctx.data().pool, user_id),
+                "Error calling get_discord_user"
+            );
+            let Some(discord_user) = discord_user else {
+                let (title, message) = get_not_registered_title_and_message(user_id);
+                embed.error().title(title).body(message).send().await?;
+                return Ok(());
+            };
+            Some(discord_user.team_id)
+        }
+        None => None,
+    };
+
+    let filter = ExportFilter {
+        league_id: Some(league_id),
+        team_id,
+        from_game_week,
+        to_game_week,
+    };
+    let format = format.map_or(ExportFormat::Csv, ExportFormat::from);
+    let export = match fpl_services::export::export(
+        &ctx.data().pool,
+        ExportDataset::from(dataset),
+        &filter,
+        format,
+    )
+    .await
+    {
+        Ok(export) => export,
+        Err(ServiceError::Invalid(message)) => {
+            embed
+                .error()
+                .title("Couldn't export")
+                .body(message)
+                .send()
+                .await?;
+            return Ok(());
+        }
+        Err(e) => {
+            embed
+                .error()
+                .body(format!("Error when calling {}", COMMAND))
+                .send()
+                .await?;
+            return Err(format!("Error calling export: {}", e).into());
+        }
+    };
+    log_timer!(timer, COMMAND, ctx, "exported");
+
+    if export.rows == 0 {
+        embed
+            .error()
+            .title("Nothing to export")
+            .body("No rows match those filters.")
+            .send()
+            .await?;
+        return Ok(());
+    }
+
+    if export.bytes.len() > MAX_ATTACHMENT_BYTES {
+        embed
+            .error()
+            .title("Export too large")
+            .body("Try a smaller gameweek range, a single user or Parquet.")
+            .send()
+            .await?;
+        return Ok(());
+    }
+
+    // Embeds can only attach images, so the file replaces the processing embed instead
+    let Context::Application(app_ctx) = ctx else {
+        return Err("Couldn't fetch interaction from ctx".into());
+    };
+    let content = format!("**{}** ({} rows)", export.file_name, export.rows);
+    app_ctx
+        .interaction
+        .edit_response(
+            ctx.http(),
+            EditInteractionResponse::new()
+                .content(content)
+                .embeds(vec![])
+                .new_attachment(CreateAttachment::bytes(export.bytes, export.file_name)),
+        )
+        .await?;
+    Ok(())
+}
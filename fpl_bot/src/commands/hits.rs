@@ -1,15 +1,16 @@
-use std::collections::BTreeMap;
 use std::time::Instant;
 
 use crate::autocompletes::{autocomplete_league_or_user, autocomplete_league_or_user_value};
-use crate::utils::common::{check_discord_user_registered, get_not_registered_title_and_message};
+use crate::utils::common::get_not_registered_title_and_message;
 use crate::utils::embed::Embed;
 use crate::{handle_async_fallible, handle_parse_value, log_call, log_timer, start_timer};
 use crate::{Context, Error};
 
 use fpl_common::types::LeagueId;
+use fpl_db::queries::discord::get_discord_user;
 use fpl_db::queries::mini_league::get_league_name;
 use fpl_db::queries::team::get_team_name_from_discord_id;
+use fpl_services::hits::{get_league_hits, get_team_hits};
 use tracing::{debug, info};
 
 const COMMAND: &str = "/hits";
@@ -42,17 +43,17 @@ pub async fn hits(
     );
 
     let rows = match league_or_user.as_str() {
-        "User" => match check_discord_user_registered(&ctx.data().pool, value).await {
-            Ok(true) => {
+        "User" => match get_discord_user(&ctx.data().pool, value).await {
+            Ok(Some(user)) => {
                 let user_chips = handle_async_fallible!(
                     ctx,
-                    get_user_hits(ctx, &timer, value),
-                    "Error calling get_user_hits"
+                    get_team_hits(&ctx.data().pool, user.team_id),
+                    "Error calling get_team_hits"
                 );
                 log_timer!(timer, COMMAND, ctx, "got user chips");
                 user_chips
             }
-            Ok(false) => {
+            Ok(None) => {
                 let (title, message) = get_not_registered_title_and_message(value);
                 Embed::from_ctx(ctx)?
                     .error()
@@ -68,17 +69,13 @@ pub async fn hits(
                     .body(format!("Error when calling {}", COMMAND))
                     .send()
                     .await?;
-                return Err(format!(
-                    "Unknown error when calling check_discord_user_registered: {}",
-                    e
-                )
-                .into());
+                return Err(format!("Unknown error when calling get_discord_user: {}", e).into());
             }
         },
         "League" => {
             let league_chips = handle_async_fallible!(
                 ctx,
-                get_league_hits(ctx, &timer, LeagueId::from(value as i32)),
+                get_league_hits(&ctx.data().pool, LeagueId::from(value as i32)),
                 "Error calling get_league_hits"
             );
             log_timer!(timer, COMMAND, ctx, "got league chips");
@@ -123,125 +120,3 @@ pub async fn hits(
 
     Ok(())
 }
-
-pub async fn get_user_hits(
-    ctx: Context<'_>,
-    timer: &Instant,
-    discord_id: i64,
-) -> Result<Vec<String>, Error> {
-    let user_hits = sqlx::query!(
-        "
-        SELECT tgw.game_week_id, tgw.event_transfers_cost 
-        FROM discord_users du 
-        JOIN teams t ON du.team_id = t.id 
-        JOIN team_game_weeks tgw ON tgw.team_id = t.id 
-        WHERE event_transfers_cost > 0 and du.discord_id = $1
-        ORDER BY tgw.game_week_id ASC;
-        ",
-        discord_id
-    )
-    .fetch_all(&*ctx.data().pool)
-    .await
-    .map(|rows| {
-        // First collect all rows
-        rows.into_iter()
-            .map(|row| (row.game_week_id, row.event_transfers_cost))
-            .collect::<Vec<(i16, i16)>>()
-    })?;
-
-    log_timer!(timer, COMMAND, ctx, "fetched hits team");
-
-    let hit_rows = match user_hits.len() {
-        0 => {
-            vec!["Has not taken a hit".to_string()]
-        }
-        _ => {
-            let mut result = Vec::new();
-            result.push("**__Hits__**\n".to_string());
-            let mut total_hits = 0;
-
-            result.extend(user_hits.into_iter().map(|(game_week, hits_cost)| {
-                let hits = hits_cost / 4;
-                total_hits += hits;
-                format!("**GW{}** - {}", game_week, hits)
-            }));
-
-            result.push(format!(
-                "\n**Total:** {total_hits} (-{} points)",
-                total_hits * 4
-            ));
-
-            result
-        }
-    };
-    log_timer!(timer, COMMAND, ctx, "grouped and formatted rows");
-    Ok(hit_rows)
-}
-
-pub async fn get_league_hits(
-    ctx: Context<'_>,
-    timer: &Instant,
-    league_id: LeagueId,
-) -> Result<Vec<String>, Error> {
-    let league_hits = sqlx::query!(
-        "
-        SELECT tgw.game_week_id, mls.player_name, mls.entry_name, tgw.event_transfers_cost FROM team_game_weeks tgw 
-        JOIN mini_league_standings mls ON mls.team_id = tgw.team_id 
-        WHERE tgw.event_transfers_cost > 0 AND mls.league_id = $1;
-        ",
-        i32::from(league_id)
-    )
-    .fetch_all(&*ctx.data().pool)
-    .await
-    .map(|rows| {
-        // First collect all rows
-        rows.into_iter()
-            .map(|row| {
-                (
-                    row.game_week_id,
-                    row.player_name,
-                    row.entry_name,
-                    row.event_transfers_cost,
-                )
-            })
-            .collect::<Vec<(i16, String, String, i16)>>()
-    })?;
-
-    log_timer!(timer, COMMAND, ctx, "fetched hits league");
-
-    let hit_rows = match league_hits.len() {
-        0 => {
-            vec!["No one has taken a hit".to_string()]
-        }
-        _ => {
-            // Group by game week
-            type TeamRows = Vec<(String, String, i16)>;
-            let mut hits_game_week: BTreeMap<i16, TeamRows> = BTreeMap::new();
-            for row in league_hits {
-                hits_game_week
-                    .entry(row.0)
-                    .or_default()
-                    .push((row.1, row.2, row.3));
-            }
-
-            let mut result = Vec::new();
-            result.push("**__Hits__**\n".to_string());
-
-            result.extend(hits_game_week.into_iter().map(|(game_week, users)| {
-                let users_formatted = users
-                    .into_iter()
-                    .map(|(user_name, team_name, hits_cost)| {
-                        format!("{user_name} ({team_name}) **{}**", hits_cost / 4)
-                    })
-                    .collect::<Vec<_>>()
-                    .join(", ");
-
-                format!("**GW{}** - {}", game_week, users_formatted)
-            }));
-
-            result
-        }
-    };
-    log_timer!(timer, COMMAND, ctx, "grouped and formatted rows");
-    Ok(hit_rows)
-}
@@ -0,0 +1,95 @@
+use fpl_common::types::LeagueId;
+use fpl_db::queries::guild::{clear_guild_home_league, set_guild_home_league};
+use fpl_db::queries::mini_league::get_league_name;
+use std::time::Instant;
+use tracing::debug;
+
+use crate::autocompletes::autocomplete_mini_league;
+use crate::utils::embed::{Embed, EmbedPage};
+use crate::{handle_async_fallible, log_call, log_timer, start_timer, Context, Error};
+
+const COMMAND: &str = "/homeleague";
+
+/// Set the mini league this server follows, leave it empty to clear it
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "homeleague",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn home_league(
+    ctx: Context<'_>,
+    #[description = "Mini League"]
+    #[autocomplete = "autocomplete_mini_league"]
+    league_id: Option<LeagueId>,
+) -> Result<(), Error> {
+    log_call!(COMMAND, ctx, "league_id", league_id);
+    let timer: Instant = start_timer!();
+
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("Command must be used in a server")?
+        .get() as i64;
+
+    let Some(league_id) = league_id else {
+        let cleared = handle_async_fallible!(
+            ctx,
+            clear_guild_home_league(&ctx.data().pool, guild_id),
+            "Error calling clear_guild_home_league"
+        );
+        log_timer!(timer, COMMAND, ctx, "cleared home league");
+
+        let body = match cleared {
+            true => "This server no longer has a home league.",
+            false => "This server didn't have a home league.",
+        };
+        Embed::from_ctx(ctx)?
+            .success()
+            .title("Home league cleared")
+            .add_page(EmbedPage::new().add_row(body))
+            .send()
+            .await?;
+        return Ok(());
+    };
+
+    let set = handle_async_fallible!(
+        ctx,
+        set_guild_home_league(
+            &ctx.data().pool,
+            guild_id,
+            league_id,
+            ctx.author().id.get() as i64
+        ),
+        "Error calling set_guild_home_league"
+    );
+    log_timer!(timer, COMMAND, ctx, "set home league");
+
+    // Only leagues the bot already tracks, /register pulls them in
+    if !set {
+        Embed::from_ctx(ctx)?
+            .error()
+            .title("Unknown league")
+            .body(format!(
+                "League {league_id} isn't tracked yet, a member of it needs to /register first."
+            ))
+            .send()
+            .await?;
+        return Ok(());
+    }
+
+    let league_name = handle_async_fallible!(
+        ctx,
+        get_league_name(&ctx.data().pool, league_id),
+        "Error calling get_league_name"
+    );
+
+    Embed::from_ctx(ctx)?
+        .success()
+        .title(format!("Home league set to {league_name}"))
+        .add_page(EmbedPage::new().add_row(
+            "It'll show first when picking a league and can be used by anyone in this server.",
+        ))
+        .send()
+        .await?;
+    Ok(())
+}
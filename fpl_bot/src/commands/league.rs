@@ -0,0 +1,248 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Instant;
+
+use fpl_api::concurrency::MAX_IN_FLIGHT_REQUESTS;
+use fpl_api::requests::TeamRequest;
+use fpl_common::types::{LeagueId, TeamId};
+use fpl_db::models::{DiscordUser, MiniLeague, MiniLeagueStanding, Team};
+use fpl_db::queries::discord::{get_linked_team_ids, insert_discord_users};
+use fpl_db::queries::guild::get_unregistered_guild_members;
+use fpl_db::queries::mini_league::{upsert_mini_league_standings, upsert_mini_leagues};
+use fpl_db::queries::team::upsert_teams;
+use fpl_scraper::mini_leagues::MiniLeaguesScraper;
+use fpl_services::league_import::match_members_to_standings;
+use futures::StreamExt;
+use tracing::error;
+
+use crate::commands::register::process_team_game_week_data;
+use crate::utils::embed::{Embed, EmbedPage};
+use crate::{handle_async_fallible, log_call, log_timer, start_timer, Context, Error};
+
+const COMMAND: &str = "/league import";
+// Only this many are listed and registered at once, running the import again picks up the rest
+const MAX_LISTED_MATCHES: usize = 30;
+
+/// Manage the mini leagues tracked in this server
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("league_import"),
+    subcommand_required,
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn league(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Import a whole mini league and register the members of this server who are in it
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "import",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn league_import(
+    ctx: Context<'_>,
+    #[description = "League ID from the FPL website"] league_id: LeagueId,
+) -> Result<(), Error> {
+    log_call!(COMMAND, ctx, "league_id", league_id);
+    let timer: Instant = start_timer!();
+
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("Command must be used in a server")?
+        .get() as i64;
+
+    let embed = Embed::from_ctx(ctx)?
+        .processing()
+        .title("Importing league")
+        .body(format!("Fetching standings for league {}", league_id))
+        .send()
+        .await?;
+
+    let (league, standings) = handle_async_fallible!(
+        ctx,
+        embed,
+        MiniLeaguesScraper::handle_mini_league(Arc::clone(&ctx.data().client), league_id),
+        "Error calling handle_mini_league"
+    );
+    log_timer!(timer, COMMAND, ctx, "fetched standings");
+
+    let mini_league: MiniLeague = (&league).into();
+    let mini_league_standings: Vec<MiniLeagueStanding> = standings
+        .iter()
+        .map(|standing| (&league.league.id, standing).into())
+        .collect();
+    handle_async_fallible!(
+        ctx,
+        embed,
+        upsert_mini_leagues(&ctx.data().pool, &[mini_league]),
+        "Error calling upsert_mini_leagues"
+    );
+    handle_async_fallible!(
+        ctx,
+        embed,
+        upsert_mini_league_standings(&ctx.data().pool, &mini_league_standings),
+        "Error calling upsert_mini_league_standings"
+    );
+
+    let members = handle_async_fallible!(
+        ctx,
+        embed,
+        get_unregistered_guild_members(&ctx.data().pool, guild_id),
+        "Error calling get_unregistered_guild_members"
+    );
+    let team_ids: Vec<TeamId> = mini_league_standings
+        .iter()
+        .map(|standing| standing.team_id)
+        .collect();
+    let linked_team_ids: HashSet<TeamId> = handle_async_fallible!(
+        ctx,
+        embed,
+        get_linked_team_ids(&ctx.data().pool, &team_ids),
+        "Error calling get_linked_team_ids"
+    )
+    .into_iter()
+    .collect();
+    let unlinked_standings: Vec<MiniLeagueStanding> = mini_league_standings
+        .into_iter()
+        .filter(|standing| !linked_team_ids.contains(&standing.team_id))
+        .collect();
+
+    let mut matches = match_members_to_standings(&members, &unlinked_standings);
+    log_timer!(timer, COMMAND, ctx, "matched members");
+
+    if matches.is_empty() {
+        embed
+            .error()
+            .title(format!("Imported {}", league.league.name))
+            .body(format!(
+                "Couldn't match any of the {} unregistered members of this server to an unlinked team, they can still /register themselves.",
+                members.len()
+            ))
+            .send()
+            .await?;
+        return Ok(());
+    }
+
+    // Only register who was shown for confirmation
+    let remaining = matches.len().saturating_sub(MAX_LISTED_MATCHES);
+    matches.truncate(MAX_LISTED_MATCHES);
+    let mut rows: Vec<String> = matches
+        .iter()
+        .map(|m| {
+            format!(
+                "**{}** → {} ({})",
+                m.member.display_name, m.standing.entry_name, m.standing.player_name
+            )
+        })
+        .collect();
+    if remaining > 0 {
+        rows.push(format!(
+            "...and {} more, run the import again to register them",
+            remaining
+        ));
+    }
+
+    let (embed, confirmed) = embed
+        .success()
+        .title(format!(
+            "Register {} members from {}?",
+            matches.len(),
+            league.league.name
+        ))
+        .add_page(EmbedPage::new().add_rows(rows))
+        .send_for_confirmation()
+        .await?;
+
+    if !confirmed {
+        embed
+            .error()
+            .title("Import cancelled")
+            .body(format!(
+                "{} was imported but nobody was registered.",
+                league.league.name
+            ))
+            .send()
+            .await?;
+        return Ok(());
+    }
+
+    let embed = embed
+        .processing()
+        .title("Importing league")
+        .body(format!("Fetching {} teams", matches.len()))
+        .send()
+        .await?;
+
+    let matched_team_ids: Vec<TeamId> = matches.iter().map(|m| m.standing.team_id).collect();
+    let teams = handle_async_fallible!(
+        ctx,
+        embed,
+        fetch_teams(&ctx, matched_team_ids),
+        "Error fetching matched teams"
+    );
+    handle_async_fallible!(
+        ctx,
+        embed,
+        upsert_teams(&ctx.data().pool, &teams),
+        "Error calling upsert_teams"
+    );
+    log_timer!(timer, COMMAND, ctx, "fetched teams");
+
+    let discord_users: Vec<DiscordUser> = matches
+        .iter()
+        .map(|m| DiscordUser::new(m.member.discord_id, m.standing.team_id))
+        .collect();
+    let registered = handle_async_fallible!(
+        ctx,
+        embed,
+        insert_discord_users(&ctx.data().pool, &discord_users),
+        "Error calling insert_discord_users"
+    );
+    log_timer!(timer, COMMAND, ctx, "registered members");
+
+    embed
+        .success()
+        .title(format!(
+            "Registered {} members from {}",
+            registered, league.league.name
+        ))
+        .send()
+        .await?;
+
+    // Game week history is slow to fetch and only needed for the history commands
+    let pool = Arc::clone(&ctx.data().pool);
+    let client = Arc::clone(&ctx.data().client);
+    let mut stream = futures::stream::iter(teams)
+        .map(|team| {
+            let pool = Arc::clone(&pool);
+            let client = Arc::clone(&client);
+            async move {
+                if let Err(e) = process_team_game_week_data(&pool, &client, team.id).await {
+                    error!("Error processing game weeks for team {}: {}", team.id, e);
+                }
+            }
+        })
+        .buffer_unordered(MAX_IN_FLIGHT_REQUESTS);
+    while stream.next().await.is_some() {}
+    log_timer!(timer, COMMAND, ctx, "fetched game weeks");
+
+    Ok(())
+}
+
+async fn fetch_teams(ctx: &Context<'_>, team_ids: Vec<TeamId>) -> Result<Vec<Team>, Error> {
+    let mut stream = futures::stream::iter(team_ids)
+        .map(|team_id| {
+            let client = Arc::clone(&ctx.data().client);
+            async move { client.get(TeamRequest::new(team_id)).await }
+        })
+        .buffer_unordered(MAX_IN_FLIGHT_REQUESTS);
+
+    let mut teams = Vec::new();
+    while let Some(result) = stream.next().await {
+        teams.push(result?.into());
+    }
+    Ok(teams)
+}
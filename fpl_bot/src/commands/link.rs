@@ -0,0 +1,117 @@
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use fpl_api::requests::MeRequest;
+use fpl_api::FplClientError;
+use fpl_db::models::StoredFplSession;
+use fpl_db::queries::discord::get_discord_user;
+use fpl_db::queries::session::{delete_fpl_session, upsert_fpl_session};
+use poise::CreateReply;
+use tracing::{debug, warn};
+
+use crate::utils::common::get_not_registered_title_and_message;
+use crate::utils::credentials::encrypt_session;
+use crate::{log_call, log_timer, start_timer};
+use crate::{Context, Data, Error};
+
+const COMMAND: &str = "/link";
+const MODAL_TIMEOUT: Duration = Duration::from_secs(300);
+
+// No Debug, the password mustn't end up in logs
+#[derive(poise::Modal)]
+#[name = "Link your FPL account"]
+struct LinkModal {
+    #[name = "FPL email"]
+    email: String,
+    #[name = "FPL password (only sent to FPL, never stored)"]
+    password: String,
+}
+
+async fn reply(ctx: Context<'_>, message: impl Into<String>) -> Result<(), Error> {
+    ctx.send(CreateReply::default().content(message).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Link your FPL login so /team can show your picks before the deadline
+#[poise::command(slash_command)]
+pub async fn link(ctx: poise::ApplicationContext<'_, Data, Error>) -> Result<(), Error> {
+    log_call!(COMMAND, ctx);
+    let timer = start_timer!();
+    let discord_id = ctx.author().id.get() as i64;
+
+    let Some(discord_user) = get_discord_user(&ctx.data().pool, discord_id).await? else {
+        let (title, message) = get_not_registered_title_and_message(discord_id);
+        return reply(ctx.into(), format!("**{}**\n{}", title, message)).await;
+    };
+
+    let Some(modal) =
+        poise::execute_modal::<_, _, LinkModal>(ctx, None, Some(MODAL_TIMEOUT)).await?
+    else {
+        debug!("{} modal timed out for {}", COMMAND, discord_id);
+        return Ok(());
+    };
+    log_timer!(timer, COMMAND, ctx, "modal submitted");
+
+    let session = match ctx.data().client.login(&modal.email, &modal.password).await {
+        Ok(session) => session,
+        Err(FplClientError::Unauthorized { .. }) => {
+            return reply(ctx.into(), "FPL didn't accept that email and password.").await;
+        }
+        Err(e) => {
+            warn!("{} login failed: {}", COMMAND, e);
+            return reply(ctx.into(), "Couldn't log in to FPL, try again later.").await;
+        }
+    };
+    drop(modal);
+
+    let me = ctx
+        .data()
+        .client
+        .get_with_session(MeRequest::new(), &session)
+        .await?;
+    let linked_team_id = me.player.and_then(|player| player.entry);
+    if linked_team_id != Some(discord_user.team_id) {
+        return reply(
+            ctx.into(),
+            format!(
+                "That FPL account doesn't own Team ID {}, the team you registered with.",
+                discord_user.team_id
+            ),
+        )
+        .await;
+    }
+
+    let (encrypted_cookie, nonce) = encrypt_session(&session)?;
+    upsert_fpl_session(
+        &ctx.data().pool,
+        &StoredFplSession {
+            discord_id,
+            team_id: discord_user.team_id,
+            encrypted_cookie,
+            nonce,
+            linked_at: Utc::now(),
+        },
+    )
+    .await?;
+    log_timer!(timer, COMMAND, ctx, "stored session");
+
+    reply(
+        ctx.into(),
+        "Linked! **/team** for the next game week now shows your picks before the deadline. Use **/unlink** to remove access at any time.",
+    )
+    .await
+}
+
+/// Remove your linked FPL login
+#[poise::command(slash_command)]
+pub async fn unlink(ctx: Context<'_>) -> Result<(), Error> {
+    log_call!("/unlink", ctx);
+
+    let removed = delete_fpl_session(&ctx.data().pool, ctx.author().id.get() as i64).await?;
+    if removed {
+        reply(ctx, "Your FPL login has been removed.").await
+    } else {
+        reply(ctx, "You don't have a linked FPL login.").await
+    }
+}
@@ -0,0 +1,126 @@
+use chrono::Utc;
+use fpl_common::types::LeagueId;
+use fpl_db::models::LiveTable;
+use fpl_db::queries::game_week::get_current_game_week_id;
+use fpl_db::queries::live_table::{get_game_week_fixture_progress, insert_live_table};
+use serenity::all::CreateMessage;
+use std::time::Instant;
+use tracing::debug;
+
+use crate::autocompletes::{autocomplete_mini_league, autocomplete_overall_or_week};
+use crate::notifications::render_live_table;
+use crate::utils::embed::{Embed, EmbedPage};
+use crate::{handle_async_fallible, log_call, log_timer, start_timer, Context, Error};
+
+const COMMAND: &str = "/livetable";
+
+/// Post a league table that keeps itself updated while this game week's fixtures are live
+#[poise::command(slash_command, guild_only)]
+pub async fn livetable(
+    ctx: Context<'_>,
+    #[description = "Mini League"]
+    #[autocomplete = "autocomplete_mini_league"]
+    league_id: LeagueId,
+    #[description = "Overall or Current Game Week"]
+    #[autocomplete = "autocomplete_overall_or_week"]
+    overall_or_week: String,
+) -> Result<(), Error> {
+    log_call!(
+        COMMAND,
+        ctx,
+        "league_id",
+        league_id,
+        "overall_or_week",
+        overall_or_week
+    );
+    let timer: Instant = start_timer!();
+
+    let embed = Embed::from_ctx(ctx)?
+        .processing()
+        .title("Processing livetable request")
+        .send()
+        .await?;
+
+    let game_week_id = handle_async_fallible!(
+        ctx,
+        embed,
+        get_current_game_week_id(&ctx.data().pool),
+        "Error calling get_current_game_week_id"
+    );
+
+    let (_, remaining) = handle_async_fallible!(
+        ctx,
+        embed,
+        get_game_week_fixture_progress(&ctx.data().pool, game_week_id),
+        "Error calling get_game_week_fixture_progress"
+    );
+    log_timer!(timer, COMMAND, ctx, "fetched game week progress");
+
+    if remaining == 0 {
+        embed
+            .error()
+            .title("Game week is over")
+            .body(format!(
+                "Every fixture in game week {game_week_id} has finished, use /table instead."
+            ))
+            .send()
+            .await?;
+        return Ok(());
+    }
+
+    let mut live_table = LiveTable {
+        message_id: 0,
+        channel_id: ctx.channel_id().get() as i64,
+        league_id,
+        overall_or_week,
+        game_week_id,
+        created_by: ctx.author().id.get() as i64,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        finished: false,
+    };
+
+    let (table_embed, attachment) = handle_async_fallible!(
+        ctx,
+        embed,
+        render_live_table(
+            &ctx.data().pool,
+            &ctx.data().render_cache,
+            &live_table,
+            false
+        ),
+        "Error calling render_live_table"
+    );
+    log_timer!(timer, COMMAND, ctx, "rendered image");
+
+    let message = handle_async_fallible!(
+        ctx,
+        embed,
+        ctx.channel_id().send_message(
+            ctx.http(),
+            CreateMessage::new()
+                .add_embed(table_embed)
+                .add_file(attachment),
+        ),
+        "Error posting live table"
+    );
+
+    live_table.message_id = message.id.get() as i64;
+    handle_async_fallible!(
+        ctx,
+        embed,
+        insert_live_table(&ctx.data().pool, &live_table),
+        "Error calling insert_live_table"
+    );
+    log_timer!(timer, COMMAND, ctx, "saved live table");
+
+    embed
+        .success()
+        .title("Live table posted")
+        .add_page(EmbedPage::new().add_row(format!(
+            "The table above will update every minute until game week {game_week_id} is over."
+        )))
+        .send()
+        .await?;
+    Ok(())
+}
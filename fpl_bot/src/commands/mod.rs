@@ -1,41 +1,49 @@
 pub mod captains;
 pub mod chips;
+pub mod cup;
 pub mod deadline;
 pub mod differentials;
+pub mod export;
 pub mod hits;
+pub mod home_league;
+pub mod league;
+pub mod link;
+pub mod livetable;
 pub mod loglevel;
+pub mod notifications;
 pub mod register;
 pub mod table;
 pub mod team;
+pub mod theme;
 pub mod transfers;
 pub mod unique;
+pub mod watch;
+pub mod webhook;
 pub mod whohas;
 
 pub use captains::*;
 pub use chips::*;
+pub use cup::*;
 pub use deadline::*;
 pub use differentials::*;
+pub use export::*;
 pub use hits::*;
+pub use home_league::*;
+pub use league::*;
+pub use link::*;
+pub use livetable::*;
 pub use loglevel::*;
+pub use notifications::*;
 pub use register::*;
 pub use table::*;
 pub use team::*;
+pub use theme::*;
 pub use transfers::*;
 pub use unique::*;
+pub use watch::*;
+pub use webhook::*;
 pub use whohas::*;
 
-use crate::Context;
-
-pub fn get_image_file_path(command: &str, ctx: &Context<'_>) -> String {
-    let user_id = ctx.author().id.get() as i64;
-    let server_id = match ctx.guild() {
-        Some(guild) => guild.id.get() as i64,
-        None => 0,
-    };
-
-    fpl_common::paths::get_generated_image_path(command, user_id, server_id)
-}
-
 /// Macro to handle database query errors, creating a new embed if needed
 #[macro_export]
 macro_rules! handle_async_fallible {
@@ -100,12 +108,16 @@ macro_rules! handle_parse_value {
     };
 }
 
+/// Macro to render through the render cache, returning the `RenderedImage`
 #[macro_export]
 macro_rules! render {
     // Version with embed that shows errors
-    ($ctx:expr, $embed:expr, $renderer:expr, $data:expr, $file_name:expr, $error_message:expr) => {
-        match $renderer.render($data, $file_name).await {
-            Ok(result) => result,
+    ($ctx:expr, $embed:expr, $renderer:expr, $data:expr, $error_message:expr) => {
+        match $renderer
+            .render_svg($data)
+            .and_then(|svg| $ctx.data().render_cache.render(&svg))
+        {
+            Ok(image) => image,
             Err(e) => {
                 $embed
                     .error()
@@ -118,9 +130,12 @@ macro_rules! render {
     };
 
     // Version without existing embed
-    ($ctx:expr, $renderer:expr, $data:expr, $file_name:expr, $error_message:expr) => {
-        match $renderer.render($data, $file_name).await {
-            Ok(result) => result,
+    ($ctx:expr, $renderer:expr, $data:expr, $error_message:expr) => {
+        match $renderer
+            .render_svg($data)
+            .and_then(|svg| $ctx.data().render_cache.render(&svg))
+        {
+            Ok(image) => image,
             Err(e) => {
                 Embed::from_ctx($ctx)?
                     .error()
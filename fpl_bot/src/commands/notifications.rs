@@ -0,0 +1,92 @@
+use std::time::Instant;
+use tracing::debug;
+
+use crate::utils::embed::{Embed, EmbedPage};
+use crate::{handle_async_fallible, log_call, log_timer, start_timer};
+use crate::{Context, Error};
+use fpl_db::models::{GuildNotificationSettings, PointsNotificationMode};
+use fpl_db::queries::guild_settings::{
+    get_guild_notification_settings, upsert_guild_notification_settings,
+};
+
+const COMMAND: &str = "/notifications";
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum PointsModeChoice {
+    #[name = "Instant - one message per change"]
+    Instant,
+    #[name = "Digest - one image per window"]
+    Digest,
+}
+
+impl From<PointsModeChoice> for PointsNotificationMode {
+    fn from(choice: PointsModeChoice) -> Self {
+        match choice {
+            PointsModeChoice::Instant => PointsNotificationMode::Instant,
+            PointsModeChoice::Digest => PointsNotificationMode::Digest,
+        }
+    }
+}
+
+/// Choose how live points notifications are sent in this server
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "notifications",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn notification_settings(
+    ctx: Context<'_>,
+    #[description = "Points notification mode"] mode: PointsModeChoice,
+    #[description = "Digest window in seconds (default 120)"]
+    #[min = 30]
+    #[max = 900]
+    window: Option<u32>,
+) -> Result<(), Error> {
+    log_call!(COMMAND, ctx, "mode", mode, "window", window);
+    let timer = start_timer!();
+
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("Command must be used in a server")?
+        .get() as i64;
+
+    let mut settings = handle_async_fallible!(
+        ctx,
+        get_guild_notification_settings(&ctx.data().pool, guild_id),
+        "Error calling get_guild_notification_settings"
+    )
+    .unwrap_or_else(|| GuildNotificationSettings::new(guild_id));
+
+    let mode = PointsNotificationMode::from(mode);
+    settings.points_mode = mode.to_string();
+    if let Some(window) = window {
+        settings.digest_window_seconds = window as i32;
+    }
+
+    handle_async_fallible!(
+        ctx,
+        upsert_guild_notification_settings(&ctx.data().pool, &settings),
+        "Error calling upsert_guild_notification_settings"
+    );
+    log_timer!(timer, COMMAND, ctx, "saved settings");
+
+    let body = match mode {
+        PointsNotificationMode::Instant => {
+            "Points changes will be posted as they happen.".to_string()
+        }
+        PointsNotificationMode::Digest => format!(
+            "Points changes will be collected into one image every {} seconds.",
+            settings.digest_window_seconds
+        ),
+    };
+
+    Embed::from_ctx(ctx)?
+        .success()
+        .title("Notification settings updated")
+        .add_page(EmbedPage::new().add_row(body))
+        .send()
+        .await?;
+
+    Ok(())
+}
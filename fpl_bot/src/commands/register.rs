@@ -1,4 +1,6 @@
-use crate::utils::embed::{Embed, SentState};
+use crate::autocompletes::{autocomplete_linked_team, autocomplete_team};
+use crate::utils::common::get_not_registered_title_and_message;
+use crate::utils::embed::{Embed, EmbedPage, SentState};
 use crate::{log_call, log_timer, start_timer};
 use crate::{Context, Error};
 use std::time::Instant;
@@ -13,25 +15,40 @@ use fpl_db::queries::team_game_week::{
 use futures::StreamExt;
 use std::collections::HashSet;
 use std::sync::Arc;
+use thousands::Separable;
 use tracing::{debug, info, warn};
 
+use fpl_api::concurrency::MAX_IN_FLIGHT_REQUESTS;
 use fpl_api::requests::{MiniLeagueRequest, TeamGameWeekRequest, TeamRequest};
-use fpl_api::FplClient;
+use fpl_api::{FplClient, FplClientError};
 use fpl_common::types::{GameWeekId, LeagueId, TeamId};
-use fpl_db::models::{DiscordUser, MiniLeague, MiniLeagueStanding, Team};
-use fpl_db::queries::discord::{get_discord_user, insert_discord_user};
+use fpl_db::models::{
+    DiscordUser, LinkedTeam, MiniLeague, MiniLeagueStanding, Team, MAX_LINKED_TEAMS,
+};
+use fpl_db::queries::discord::{
+    delete_discord_user, get_linked_teams, insert_discord_user, link_discord_user_team,
+    unlink_discord_user_team,
+};
 use fpl_db::queries::mini_league::{upsert_mini_league_standings, upsert_mini_leagues};
 use fpl_db::queries::team::upsert_teams;
 
 const COMMAND: &str = "/register";
+const RELINK_COMMAND: &str = "/relink";
+const UNREGISTER_COMMAND: &str = "/unregister";
 const MAX_MINI_LEAGUE_ENTRIES: i32 = 25;
 
+/// Link an FPL team to your discord account, run again to link more than one
 #[poise::command(slash_command)]
 pub async fn register(
     ctx: Context<'_>,
-    #[description = "Team ID from the FPL website"] team_id: TeamId,
+    #[description = "Team name, manager name or Team ID from the FPL website"]
+    #[autocomplete = "autocomplete_team"]
+    team_id: TeamId,
+    #[description = "Make this your default team if you already have one linked"] default: Option<
+        bool,
+    >,
 ) -> Result<(), Error> {
-    log_call!(COMMAND, ctx, "team_id", team_id);
+    log_call!(COMMAND, ctx, "team_id", team_id, "default", default);
     let timer = start_timer!();
 
     let embed = Embed::from_ctx(ctx)?
@@ -45,17 +62,222 @@ pub async fn register(
         .send()
         .await?;
 
-    let embed = check_user_registered(&ctx, embed).await?;
+    let (embed, linked_teams) = check_team_can_be_linked(&ctx, embed, team_id).await?;
     log_timer!(timer, COMMAND, ctx, "checked already registered");
 
-    let embed = embed
+    let Some((embed, team)) = confirm_team(&ctx, embed, team_id).await? else {
+        return Ok(());
+    };
+    log_timer!(timer, COMMAND, ctx, "confirmed team");
+
+    let embed = fetch_team(&ctx, embed, team, &timer).await?;
+
+    let discord_id: i64 = ctx.author().id.into();
+    let title = if linked_teams.is_empty() {
+        let discord_user = DiscordUser::new(discord_id, team_id);
+        insert_discord_user(&ctx.data().pool, &discord_user).await?;
+        format!("Registered Team ID {}.", team_id)
+    } else {
+        let make_default = default.unwrap_or(false);
+        link_discord_user_team(&ctx.data().pool, discord_id, team_id, make_default).await?;
+        match make_default {
+            true => format!("Linked Team ID {} as your default team.", team_id),
+            false => format!("Linked Team ID {} as an extra team.", team_id),
+        }
+    };
+    log_timer!(timer, COMMAND, ctx, "added discord user");
+
+    embed.success().title(title).send().await?;
+
+    log_timer!(timer, COMMAND, ctx, "completed successfully");
+
+    Ok(())
+}
+
+/// Switch your default FPL team, linking it first if needed
+#[poise::command(slash_command)]
+pub async fn relink(
+    ctx: Context<'_>,
+    #[description = "Team ID to use by default"]
+    #[autocomplete = "autocomplete_linked_team"]
+    team_id: TeamId,
+) -> Result<(), Error> {
+    log_call!(RELINK_COMMAND, ctx, "team_id", team_id);
+    let timer = start_timer!();
+    let discord_id: i64 = ctx.author().id.into();
+
+    let embed = Embed::from_ctx(ctx)?
         .processing()
-        .title("Registering")
-        .body("Fetching team information.")
+        .title("Relinking")
+        .body(format!(
+            "Switching your default team to Team ID {}",
+            team_id
+        ))
+        .send()
+        .await?;
+
+    let linked_teams = get_linked_teams(&ctx.data().pool, discord_id).await?;
+    if linked_teams.is_empty() {
+        let (title, message) = get_not_registered_title_and_message(discord_id);
+        embed.error().title(title).body(message).send().await?;
+        return Ok(());
+    }
+
+    let embed = match linked_teams.iter().find(|team| team.team_id == team_id) {
+        Some(team) if team.is_default => {
+            embed
+                .error()
+                .title("Already your default")
+                .body(format!("{} is already your default team.", team.name))
+                .send()
+                .await?;
+            return Ok(());
+        }
+        Some(_) => embed,
+        None => {
+            if linked_teams.len() >= MAX_LINKED_TEAMS {
+                embed
+                    .error()
+                    .title("Too many teams")
+                    .body(too_many_teams_message())
+                    .send()
+                    .await?;
+                return Ok(());
+            }
+            let (embed, team) = get_and_upsert_team_information(&ctx, embed, team_id).await?;
+            fetch_team(&ctx, embed, team, &timer).await?
+        }
+    };
+
+    link_discord_user_team(&ctx.data().pool, discord_id, team_id, true).await?;
+    log_timer!(timer, RELINK_COMMAND, ctx, "switched default team");
+
+    embed
+        .success()
+        .title(format!("Team ID {} is now your default team.", team_id))
+        .send()
+        .await?;
+    Ok(())
+}
+
+/// Unlink one of your FPL teams, or all of them if no team is given
+#[poise::command(slash_command)]
+pub async fn unregister(
+    ctx: Context<'_>,
+    #[description = "Team to unlink, leave empty to unregister completely"]
+    #[autocomplete = "autocomplete_linked_team"]
+    team_id: Option<TeamId>,
+) -> Result<(), Error> {
+    log_call!(UNREGISTER_COMMAND, ctx, "team_id", team_id);
+    let timer = start_timer!();
+    let discord_id: i64 = ctx.author().id.into();
+
+    let embed = Embed::from_ctx(ctx)?
+        .processing()
+        .title("Unregistering")
         .send()
         .await?;
 
-    let (embed, team) = get_and_upsert_team_information(&ctx, embed, team_id).await?;
+    let linked_teams = get_linked_teams(&ctx.data().pool, discord_id).await?;
+    if linked_teams.is_empty() {
+        let (title, message) = get_not_registered_title_and_message(discord_id);
+        embed.error().title(title).body(message).send().await?;
+        return Ok(());
+    }
+
+    let title = match team_id {
+        None => {
+            delete_discord_user(&ctx.data().pool, discord_id).await?;
+            "Unregistered, all your teams have been unlinked.".to_string()
+        }
+        Some(team_id) => {
+            if !linked_teams.iter().any(|team| team.team_id == team_id) {
+                embed
+                    .error()
+                    .title("Team not linked")
+                    .body(format!("Team ID {} isn't linked to your account.", team_id))
+                    .send()
+                    .await?;
+                return Ok(());
+            }
+
+            match unlink_discord_user_team(&ctx.data().pool, discord_id, team_id).await? {
+                Some(default) => format!(
+                    "Unlinked Team ID {}, your default team is Team ID {}.",
+                    team_id, default
+                ),
+                None => format!("Unlinked Team ID {}, you're no longer registered.", team_id),
+            }
+        }
+    };
+    log_timer!(timer, UNREGISTER_COMMAND, ctx, "unlinked teams");
+
+    embed.success().title(title).send().await?;
+    Ok(())
+}
+
+fn too_many_teams_message() -> String {
+    format!(
+        "You can link up to {} teams, use /unregister to remove one first.",
+        MAX_LINKED_TEAMS
+    )
+}
+
+/// Shows who the team belongs to and how it's doing so a team picked by name can be checked before
+/// it's linked. Returns the team for `fetch_team`, or None if the user cancels.
+async fn confirm_team<'a>(
+    ctx: &Context<'_>,
+    embed: Embed<'a, SentState>,
+    team_id: TeamId,
+) -> Result<Option<(Embed<'a, SentState>, TeamResponse)>, Error> {
+    let (embed, team) = get_and_upsert_team_information(ctx, embed, team_id).await?;
+
+    let event_rank = match team.summary_event_rank {
+        Some(rank) => rank.separate_with_commas(),
+        None => "-".to_string(),
+    };
+    let (embed, confirmed) = embed
+        .success()
+        .title(format!("Is {} your team?", team.name))
+        .add_page(
+            EmbedPage::new()
+                .add_row(format!(
+                    "Manager: **{} {}**",
+                    team.player_first_name, team.player_last_name
+                ))
+                .add_row(format!(
+                    "Overall: **{}** pts, rank **{}**",
+                    team.summary_overall_points,
+                    team.summary_overall_rank.separate_with_commas()
+                ))
+                .add_row(format!(
+                    "GW{}: **{}** pts, rank **{}**",
+                    team.current_event, team.summary_event_points, event_rank
+                )),
+        )
+        .send_for_confirmation()
+        .await?;
+
+    if !confirmed {
+        embed
+            .error()
+            .title("Registration cancelled")
+            .body(format!("Team ID {} wasn't linked.", team_id))
+            .send()
+            .await?;
+        return Ok(None);
+    }
+    Ok(Some((embed, team)))
+}
+
+/// Fetches the already upserted team's game weeks and its mini leagues (and their teams), which
+/// also refreshes `discord_user_mini_leagues` once the team is linked.
+async fn fetch_team<'a>(
+    ctx: &Context<'_>,
+    embed: Embed<'a, SentState>,
+    team: TeamResponse,
+    timer: &Instant,
+) -> Result<Embed<'a, SentState>, Error> {
     let embed = embed
         .processing()
         .title("Registering")
@@ -63,7 +285,7 @@ pub async fn register(
         .send()
         .await?;
 
-    process_team_game_week_data(&ctx.data().pool, &ctx.data().client, team_id).await?;
+    process_team_game_week_data(&ctx.data().pool, &ctx.data().client, team.id).await?;
     log_timer!(
         timer,
         COMMAND,
@@ -79,7 +301,7 @@ pub async fn register(
         .await?;
 
     let embed =
-        get_and_upsert_related_mini_leagues_and_teams(&ctx, embed, team.leagues.classic).await?;
+        get_and_upsert_related_mini_leagues_and_teams(ctx, embed, team.leagues.classic).await?;
     log_timer!(
         timer,
         COMMAND,
@@ -87,27 +309,16 @@ pub async fn register(
         "fetched related team/game week information"
     );
 
-    let discord_user = DiscordUser::new(ctx.author().id.into(), team_id);
-    insert_discord_user(&ctx.data().pool, &discord_user).await?;
-    log_timer!(timer, COMMAND, ctx, "added discord user");
-
-    embed
-        .success()
-        .title(format!("Registered Team ID {}.", team_id))
-        .send()
-        .await?;
-
-    log_timer!(timer, COMMAND, ctx, "completed successfully");
-
-    Ok(())
+    Ok(embed)
 }
 
-async fn check_user_registered<'a>(
+async fn check_team_can_be_linked<'a>(
     ctx: &Context<'_>,
     embed: Embed<'a, SentState>,
-) -> Result<Embed<'a, SentState>, Error> {
-    // If the discord user is already registered, cant reregister
-    match get_discord_user(&ctx.data().pool, ctx.author().id.into()).await {
+    team_id: TeamId,
+) -> Result<(Embed<'a, SentState>, Vec<LinkedTeam>), Error> {
+    // Already registered users can link more teams, just not the same one twice
+    match get_linked_teams(&ctx.data().pool, ctx.author().id.into()).await {
         Err(err) => {
             embed
                 .error()
@@ -122,19 +333,29 @@ async fn check_user_registered<'a>(
             )
             .into())
         }
-        Ok(maybe_discord_user) => match maybe_discord_user {
-            Some(_) => {
-                embed
-                    .error()
-                    .title("Error Registering")
-                    .body("User already registered.")
-                    .send()
-                    .await?;
-
-                Err("User already registered".into())
+        Ok(linked_teams) => {
+            let body = if linked_teams.iter().any(|team| team.team_id == team_id) {
+                Some("Team already linked to your account.".to_string())
+            } else if linked_teams.len() >= MAX_LINKED_TEAMS {
+                Some(too_many_teams_message())
+            } else {
+                None
+            };
+
+            match body {
+                Some(body) => {
+                    embed
+                        .error()
+                        .title("Error Registering")
+                        .body(body.clone())
+                        .send()
+                        .await?;
+
+                    Err(body.into())
+                }
+                None => Ok((embed, linked_teams)),
             }
-            None => Ok(embed),
-        },
+        }
     }
 }
 
@@ -147,13 +368,20 @@ async fn get_and_upsert_team_information<'a>(
     let team = match team_response {
         Ok(team) => team,
         Err(err) => {
+            let body = match err {
+                FplClientError::NotFound { .. } => format!(
+                    "Team ID {} doesn't exist. Check the ID in the URL of your team's points page on the FPL website.",
+                    team_id
+                ),
+                FplClientError::GameUpdating { .. } | FplClientError::Maintenance { .. } => {
+                    "FPL is being updated right now. Try again in a few minutes.".to_string()
+                }
+                _ => format!("Failed to get team from FPL for Team ID {}", team_id),
+            };
             embed
                 .error()
                 .title("Error Registering")
-                .body(format!(
-                    "Failed to get team from FPL for Team ID {}",
-                    team_id
-                ))
+                .body(body)
                 .send()
                 .await?;
             return Err(err.into());
@@ -164,7 +392,7 @@ async fn get_and_upsert_team_information<'a>(
     Ok((embed, team))
 }
 
-async fn process_team_game_week_data(
+pub(crate) async fn process_team_game_week_data(
     pool: &sqlx::PgPool,
     client: &FplClient,
     team_id: TeamId,
@@ -181,7 +409,7 @@ async fn process_team_game_week_data(
                     .await
             }
         })
-        .buffer_unordered(5); // 5 concurrent requests per team
+        .buffer_unordered(MAX_IN_FLIGHT_REQUESTS);
 
     let mut game_week_picks = Vec::new();
     let mut game_week_automatic_subs = Vec::new();
@@ -190,6 +418,7 @@ async fn process_team_game_week_data(
     while let Some(result) = stream.next().await {
         let response = match result {
             Ok(response) => response,
+            Err(FplClientError::NotStarted { .. }) => continue,
             Err(e) => {
                 warn!("{}", e);
                 continue;
@@ -252,7 +481,7 @@ async fn get_mini_leagues<'a>(
             let client = Arc::clone(&ctx.data().client);
             async move { handle_mini_league_requests(client, league_id).await }
         })
-        .buffer_unordered(5);
+        .buffer_unordered(MAX_IN_FLIGHT_REQUESTS);
 
     let mut leagues_info: Vec<MiniLeague> = Vec::new();
     let mut leagues_standing_info: Vec<MiniLeagueStanding> = Vec::new();
@@ -298,7 +527,7 @@ async fn get_all_related_teams<'a>(
             let client = Arc::clone(&ctx.data().client);
             async move { client.get(TeamRequest::new(team_id)).await }
         })
-        .buffer_unordered(5);
+        .buffer_unordered(MAX_IN_FLIGHT_REQUESTS);
 
     while let Some(result) = stream.next().await {
         let response = match result {
@@ -371,7 +600,7 @@ async fn get_and_upsert_related_mini_leagues_and_teams<'a>(
                                 Ok::<(), Error>(())
                             }
                         })
-                        .buffer_unordered(2);
+                        .buffer_unordered(MAX_IN_FLIGHT_REQUESTS);
 
                     while let Some(result) = stream.next().await {
                         result?;
@@ -2,21 +2,33 @@ use std::{collections::HashMap, str::FromStr, time::Instant};
 
 use crate::{
     handle_async_fallible,
-    images::{
-        GameStatus, PlayerGameInfo, PlayerInfo, TeamData, TeamDataBuilder, TeamRenderer,
-        TransferInfo,
-    },
+    images::{PlayerGameInfo, PlayerInfo, TeamData, TeamRenderer},
     render,
 };
+use chrono::Utc;
+use fpl_api::{
+    requests::MyTeamRequest,
+    responses::my_team::{MyTeamChip, MyTeamTransfers},
+    FplClientError,
+};
 use fpl_common::types::{Chip, GameWeekId, PlayerPosition};
-use fpl_db::queries::{game_week::get_current_game_week, team::get_team_name_from_discord_id};
+use fpl_db::queries::{
+    discord::get_discord_user,
+    game_week::{get_current_game_week, get_next_game_week},
+    session::get_fpl_session,
+    team::get_team_name_from_discord_id,
+};
+use fpl_services::team::{get_team_data, get_transfers_data};
 use serenity::all::User;
 use tracing::debug;
 
 use crate::{
-    commands::get_image_file_path,
     log_call, log_timer, start_timer,
-    utils::embed::{Embed, EmbedPage},
+    utils::{
+        credentials::decrypt_session,
+        embed::{Embed, EmbedPage},
+        theme::get_caller_theme,
+    },
     Context, Error,
 };
 
@@ -27,8 +39,18 @@ pub async fn team(
     ctx: Context<'_>,
     #[description = "User"] user: Option<User>,
     #[description = "Game Week"] game_week: Option<GameWeekId>,
+    #[description = "Show club shirts instead of player photos"] shirts: Option<bool>,
 ) -> Result<(), Error> {
-    log_call!(COMMAND, ctx, "user", user, "game_week", game_week);
+    log_call!(
+        COMMAND,
+        ctx,
+        "user",
+        user,
+        "game_week",
+        game_week,
+        "shirts",
+        shirts
+    );
     let timer: Instant = start_timer!();
 
     let embed = Embed::from_ctx(ctx)?
@@ -55,13 +77,94 @@ pub async fn team(
         None => i64::from(ctx.author().id),
     };
 
-    let data: TeamData = handle_async_fallible!(
+    let next_game_week = handle_async_fallible!(
         ctx,
         embed,
-        get_team_data(ctx, user_id, game_week_id, &timer),
-        "Error calling get_team_data"
+        get_next_game_week(&ctx.data().pool),
+        "Error calling get_next_game_week"
     );
-    let file_name = get_image_file_path(COMMAND, &ctx);
+    let before_deadline = next_game_week
+        .is_some_and(|gw| i16::from(gw.id) == game_week_id && gw.deadline_time > Utc::now());
+
+    let (data, rows): (TeamData, Vec<String>) = if before_deadline {
+        // Picks for the next game week are private until the deadline, so only the owner's
+        // linked session can see them
+        if user_id != i64::from(ctx.author().id) {
+            embed
+                .error()
+                .title("Team not available yet")
+                .body(format!(
+                    "Gameweek {game_week_id} teams are hidden until the deadline."
+                ))
+                .send()
+                .await?;
+            return Ok(());
+        }
+
+        match get_upcoming_team_data(ctx, user_id, game_week_id, &timer).await {
+            Ok(Some(upcoming)) => upcoming,
+            Ok(None) => {
+                embed
+                    .error()
+                    .title("FPL account not linked")
+                    .body("Use **/link** to let the bot see your team before the deadline.")
+                    .send()
+                    .await?;
+                return Ok(());
+            }
+            Err(e)
+                if matches!(
+                    e.downcast_ref::<FplClientError>(),
+                    Some(FplClientError::Unauthorized { .. })
+                ) =>
+            {
+                embed
+                    .error()
+                    .title("FPL login expired")
+                    .body("Use **/link** again to refresh your FPL login.")
+                    .send()
+                    .await?;
+                return Ok(());
+            }
+            Err(e) => {
+                embed
+                    .error()
+                    .body(format!("Error when calling {}", COMMAND))
+                    .send()
+                    .await?;
+                return Err(format!("Error calling get_upcoming_team_data: {}", e).into());
+            }
+        }
+    } else {
+        let discord_user = handle_async_fallible!(
+            ctx,
+            embed,
+            get_discord_user(&ctx.data().pool, user_id),
+            "Error calling get_discord_user"
+        );
+        let Some(discord_user) = discord_user else {
+            embed
+                .error()
+                .title("Not registered")
+                .body("That user hasn't linked an FPL team, they can use **/register**.")
+                .send()
+                .await?;
+            return Ok(());
+        };
+
+        let data = handle_async_fallible!(
+            ctx,
+            embed,
+            get_team_data(
+                &ctx.data().pool,
+                i32::from(discord_user.team_id),
+                game_week_id
+            ),
+            "Error calling get_team_data"
+        );
+        log_timer!(timer, COMMAND, ctx, "Got team data");
+        (data, Vec::new())
+    };
 
     let team_name = handle_async_fallible!(
         ctx,
@@ -71,324 +174,167 @@ pub async fn team(
     );
     log_timer!(timer, COMMAND, ctx, "fetched team_name");
 
-    let renderer = TeamRenderer::default();
-    render!(
-        ctx,
-        embed,
-        renderer,
-        data,
-        &file_name,
-        "Failed to render team"
-    );
+    let renderer = TeamRenderer::default()
+        .with_theme(get_caller_theme(ctx).await)
+        .with_shirts(shirts.unwrap_or(false));
+    let image = render!(ctx, embed, renderer, data, "Failed to render team");
     log_timer!(timer, COMMAND, ctx, "rendered image");
 
     embed
         .success()
         .title(format!("Team for {team_name} in Gameweek {game_week_id}"))
-        .add_page(EmbedPage::new().with_image(file_name))
+        .add_page(EmbedPage::new().add_rows(rows).with_rendered_image(image))
         .send()
         .await?;
 
     Ok(())
 }
 
-async fn get_team_data(
+/// Builds the team for the next game week from the owner's `my-team` picks, along with rows for
+/// their free transfers and the chips they have left. Returns `None` if they haven't linked their
+/// FPL account.
+async fn get_upcoming_team_data(
     ctx: Context<'_>,
     user_id: i64,
     game_week_id: i16,
     timer: &Instant,
-) -> Result<TeamData, Error> {
-    let mut data = TeamData::builder();
-    data = get_basic_team_data(ctx, user_id, game_week_id, data).await?;
-    log_timer!(timer, COMMAND, ctx, "Got basic team data");
-    data = get_player_data(ctx, user_id, game_week_id, data).await?;
-    log_timer!(timer, COMMAND, ctx, "Got player data");
-    data = get_transfers_data(ctx, user_id, game_week_id, data).await?;
-    log_timer!(timer, COMMAND, ctx, "Got transfers data");
-    Ok(data.build()?)
-}
-
-#[derive(sqlx::FromRow)]
-struct TeamQueryResult {
-    team_name: String,
-    gw_rank: Option<i32>,
-    overall_rank: i32,
-    chip: Option<String>,
-    points: Option<i16>,
-}
-
-async fn get_basic_team_data(
-    ctx: Context<'_>,
-    user_id: i64,
-    game_week: i16,
-    mut team_data: TeamDataBuilder,
-) -> Result<TeamDataBuilder, Error> {
-    let current_gw = get_current_game_week(&ctx.data().pool).await?.id;
-    let is_current = i16::from(current_gw) == game_week;
-
-    let result = if is_current {
-        sqlx::query_as!(
-            TeamQueryResult,
-            r#"
-            SELECT t.name AS team_name, 
-                   tgw.rank AS gw_rank, 
-                   tgw.overall_rank AS overall_rank, 
-                   tgw.active_chip AS chip, 
-                   lp.calculated_week_points::smallint AS points
-            FROM team_game_weeks tgw
-            JOIN teams t ON t.id = tgw.team_id
-            JOIN discord_users du ON du.team_id = t.id
-            JOIN live_points lp ON lp.team_id = t.id
-            WHERE tgw.game_week_id = $1
-            AND du.discord_id = $2
-            LIMIT 1;
-            "#,
-            game_week,
-            user_id
-        )
-        .fetch_one(&*ctx.data().pool)
-        .await?
-    } else {
-        sqlx::query_as!(
-            TeamQueryResult,
-            r#"
-            SELECT t.name AS team_name, 
-                   tgw.rank AS gw_rank, 
-                   tgw.overall_rank AS overall_rank, 
-                   tgw.active_chip AS chip, 
-                   tgw.points AS points
-            FROM team_game_weeks tgw
-            JOIN teams t ON t.id = tgw.team_id
-            JOIN discord_users du ON du.team_id = t.id
-            WHERE tgw.game_week_id = $1
-            AND du.discord_id = $2
-            LIMIT 1;
-            "#,
-            game_week,
-            user_id
-        )
-        .fetch_one(&*ctx.data().pool)
-        .await?
+) -> Result<Option<(TeamData, Vec<String>)>, Error> {
+    let Some(stored) = get_fpl_session(&ctx.data().pool, user_id).await? else {
+        return Ok(None);
     };
+    let session = decrypt_session(&stored.encrypted_cookie, &stored.nonce)?;
 
-    team_data = team_data
-        .points(result.points.unwrap_or_default() as i64)
-        .team_name(result.team_name)
-        .gw_rank(result.gw_rank.unwrap_or_default() as i64)
-        .overall_rank(result.overall_rank.into())
-        .game_week(GameWeekId::new(game_week)?);
-
-    if let Some(chip_str) = result.chip {
-        if let Ok(chip) = Chip::from_str(chip_str.as_str()) {
-            team_data = team_data.add_chip(chip);
-        }
-    }
-
-    Ok(team_data)
-}
+    let my_team = ctx
+        .data()
+        .client
+        .get_with_session(MyTeamRequest::new(stored.team_id), &session)
+        .await?;
+    log_timer!(timer, COMMAND, ctx, "Got my-team picks");
 
-async fn get_player_data(
-    ctx: Context<'_>,
-    user_id: i64,
-    game_week: i16,
-    mut team_data: TeamDataBuilder,
-) -> Result<TeamDataBuilder, Error> {
-    let mut results = sqlx::query!(
+    let team = sqlx::query!(
         r#"
-        WITH combined_player_fixtures AS (
-            SELECT
-                ph.player_id,
-                ph.fixture_id,
-                ph.was_home AS is_home,
-                f.game_week_id
-            FROM player_history ph
-            JOIN fixtures f ON ph.fixture_id = f.id AND f.started = true
-            UNION ALL
-            SELECT
-                pf.player_id,
-                pf.fixture_id,
-                pf.is_home,
-                f.game_week_id
-            FROM player_fixtures pf
-            JOIN fixtures f ON pf.fixture_id = f.id AND f.started = false
-        )
-        SELECT
-            p.web_name as name,
-            p.code as code,
-            CASE
-                WHEN bwc.bonus = 0 AND bwc.bps > 0 THEN gwp.total_points + bwc.calculated_bonus
-                ELSE gwp.total_points
-            END as "points!",
-            tgwp.is_captain as captain,
-            tgwp.is_vice_captain as vice_captain,
-            tgwp.multiplier as multiplier,
-            tgwp.position as "position!",
-            tgwp.element_type as player_position,
-            COALESCE(f.started, false) as "started!",
-            gwp.minutes as minutes,
-            tgwp.player_id as player_id,
-            coalesce(c.short_name, 'N/A') as "short_name!",
-            COALESCE(cpf.is_home, false) as "is_home!",
-            CASE WHEN cpf.player_id IS NULL THEN false ELSE true END as "has_fixture!"
-        FROM team_game_week_picks tgwp
-        JOIN discord_users du ON du.team_id = tgwp.team_id
-        JOIN game_week_players gwp ON tgwp.player_id = gwp.player_id AND gwp.game_week_id = tgwp.game_week_id
-        JOIN players p ON gwp.player_id = p.id
-        LEFT JOIN combined_player_fixtures cpf ON cpf.player_id = tgwp.player_id AND cpf.game_week_id = tgwp.game_week_id
-        LEFT JOIN fixtures f ON f.id = cpf.fixture_id
-        LEFT JOIN clubs c ON c.id = CASE WHEN cpf.is_home THEN f.home_team_id ELSE f.away_team_id END
-        LEFT JOIN bonus_with_calculated bwc ON f.id = bwc.fixture_id AND p.id = bwc.player_id
-        WHERE du.discord_id = $1 AND tgwp.game_week_id = $2;
+        SELECT t.name, t.summary_overall_rank, c.code AS "favourite_club_code?"
+        FROM teams t
+        LEFT JOIN clubs c ON c.id = t.favourite_team
+        WHERE t.id = $1;
         "#,
-        user_id,
-        game_week
+        i32::from(stored.team_id)
     )
-    .fetch_all(&*ctx.data().pool)
+    .fetch_one(&*ctx.data().pool)
     .await?;
 
-    let mut player_games: HashMap<i16, Vec<PlayerGameInfo>> = HashMap::new();
-
-    for result in &results {
-        let player_game_info = if !result.has_fixture {
-            PlayerGameInfo::FreeText("-".to_string())
-        } else if result.started {
-            if result.minutes == 0 && result.position != 16 {
-                PlayerGameInfo::Status(GameStatus::NotPlayed)
-            } else {
-                let heuristic_multiplier = if result.position <= 15 && result.position >= 12 {
-                    1
-                } else {
-                    result.multiplier
-                };
-                PlayerGameInfo::Status(GameStatus::Played(
-                    result.points as i16 * heuristic_multiplier,
-                ))
-            }
-        } else {
-            let home_or_away = if result.is_home { "H" } else { "A" };
-            PlayerGameInfo::Fixture(format!("{} ({})", result.short_name, home_or_away))
-        };
-
-        player_games
-            .entry(result.player_id)
-            .or_default()
-            .push(player_game_info);
+    let mut data = TeamData::builder()
+        .points(0)
+        .team_name(team.name)
+        .gw_rank(0)
+        .overall_rank(team.summary_overall_rank.into())
+        .game_week(GameWeekId::new(game_week_id)?);
+    if let Some(club_code) = team.favourite_club_code {
+        data = data.favourite_club(club_code);
     }
 
-    // POST PROCESS: If all of their games have been played, combine into 1 entry
-    for games in player_games.values_mut() {
-        if games.len() > 1 {
-            // Check if all entries are GameStatus::Played
-            let all_played = games
-                .iter()
-                .all(|g| matches!(g, PlayerGameInfo::Status(GameStatus::Played(_))));
+    if let Some(chip) = my_team
+        .chips
+        .iter()
+        .find(|chip| chip.status_for_entry == "active")
+        .and_then(|chip| Chip::from_str(&chip.name).ok())
+    {
+        data = data.add_chip(chip);
+    }
 
-            if all_played {
-                let points = match games.first() {
-                    Some(PlayerGameInfo::Status(GameStatus::Played(p))) => *p,
-                    _ => continue,
-                };
+    let player_ids: Vec<i16> = my_team
+        .picks
+        .iter()
+        .map(|pick| i16::from(pick.element))
+        .collect();
+    let players = sqlx::query!(
+        r#"
+        SELECT p.id, p.web_name, p.code, p.team_code, po.opponents
+        FROM players p
+        LEFT JOIN player_opponents po ON po.player_id = p.id AND po.game_week_id = $2
+        WHERE p.id = ANY($1);
+        "#,
+        &player_ids,
+        game_week_id
+    )
+    .fetch_all(&*ctx.data().pool)
+    .await?;
+    let players: HashMap<i16, _> = players.into_iter().map(|p| (p.id, p)).collect();
+    log_timer!(timer, COMMAND, ctx, "Got upcoming player data");
 
-                games.clear();
-                games.push(PlayerGameInfo::Status(GameStatus::Played(points)));
-            }
-        }
-    }
-    results.sort_by_key(|r| r.player_id);
-    results.dedup_by_key(|r| r.player_id);
+    let rows = vec![
+        format_free_transfers(&my_team.transfers),
+        format_available_chips(&my_team.chips),
+    ];
 
-    let mut bench_players = Vec::new();
+    let mut picks = my_team.picks;
+    picks.sort_by_key(|pick| pick.position);
 
-    for result in results {
-        let game_info = player_games
-            .get(&result.player_id)
-            .expect("Player should exist in games map");
+    for pick in picks {
+        let player = players
+            .get(&i16::from(pick.element))
+            .ok_or_else(|| format!("Player {} missing from players table", pick.element))?;
 
+        let (game_info, has_fixture) = match &player.opponents {
+            Some(opponents) => (PlayerGameInfo::Fixture(opponents.clone()), true),
+            None => (PlayerGameInfo::FreeText("-".to_string()), false),
+        };
         let player_info = PlayerInfo::new(
-            result.name,
-            result.code as u32,
-            game_info.clone(),
-            result.captain,
-            result.vice_captain,
-            result.has_fixture,
+            player.web_name.clone(),
+            player.code as u32,
+            vec![game_info],
+            pick.is_captain,
+            pick.is_vice_captain,
+            has_fixture,
+        )
+        .club(
+            player.team_code as u32,
+            pick.element_type == PlayerPosition::Goalkeeper,
         );
 
-        match result.position {
-            // Playing team
-            1..=11 => match PlayerPosition::from_str(result.player_position.as_str()) {
-                Ok(position) => match position {
-                    PlayerPosition::Goalkeeper => {
-                        team_data = team_data.goalkeeper(player_info);
-                    }
-                    PlayerPosition::Defender => {
-                        team_data = team_data.add_defender(player_info);
-                    }
-                    PlayerPosition::Midfielder => {
-                        team_data = team_data.add_midfielder(player_info);
-                    }
-                    PlayerPosition::Attacker => {
-                        team_data = team_data.add_forward(player_info);
-                    }
-                    PlayerPosition::Manager => {
-                        team_data = team_data.add_manager(player_info);
-                    }
-                },
-                Err(e) => return Err(e.into()),
+        data = match pick.position {
+            1..=11 => match pick.element_type {
+                PlayerPosition::Goalkeeper => data.goalkeeper(player_info),
+                PlayerPosition::Defender => data.add_defender(player_info),
+                PlayerPosition::Midfielder => data.add_midfielder(player_info),
+                PlayerPosition::Attacker => data.add_forward(player_info),
+                PlayerPosition::Manager => data.add_manager(player_info),
             },
-            // Bench - collect for later
-            12..=15 => {
-                bench_players.push((result.position, player_info));
-            }
-            16 => {
-                team_data = team_data.add_manager(player_info);
-            }
-            _ => {
-                return Err("Position > 16 on team game week pick!".into());
-            }
-        }
-    }
-
-    // Sort bench players by position and add them in order
-    bench_players.sort_by_key(|(pos, _)| *pos);
-    for (_, player_info) in bench_players {
-        team_data = team_data.add_bench_player(player_info);
+            12..=15 => data.add_bench_player(player_info),
+            16 => data.add_manager(player_info),
+            _ => return Err("Position > 16 on my-team pick!".into()),
+        };
     }
 
-    Ok(team_data)
-}
-
-async fn get_transfers_data(
-    ctx: Context<'_>,
-    user_id: i64,
-    game_week: i16,
-    mut team_data: TeamDataBuilder,
-) -> Result<TeamDataBuilder, Error> {
-    let transfers: Vec<TransferInfo> = sqlx::query_as!(
-        TransferInfo,
-        r#"
-    SELECT 
-        player_in.web_name as "player_in_name!",
-        player_in.code as "player_in_code!",
-        (t.player_in_cost::float8 / 10) as "player_in_cost!",
-        player_out.web_name as "player_out_name!",
-        player_out.code as "player_out_code!",
-        (t.player_out_cost::float8 / 10) as "player_out_cost!"
-    FROM 
-        transfers t
-        LEFT JOIN players player_in ON t.player_in_id = player_in.id
-        LEFT JOIN players player_out ON t.player_out_id = player_out.id
-        left join discord_users du on du.team_id = t.team_id 
-        WHERE t.game_week_id = $1 and du.discord_id = $2;
-    "#,
-        game_week,
-        user_id
+    data = get_transfers_data(
+        &ctx.data().pool,
+        i32::from(stored.team_id),
+        game_week_id,
+        data,
     )
-    .fetch_all(&*ctx.data().pool)
     .await?;
+    log_timer!(timer, COMMAND, ctx, "Got transfers data");
+
+    Ok(Some((data.build()?, rows)))
+}
 
-    for transfer in transfers {
-        team_data = team_data.add_transfer(transfer);
+fn format_free_transfers(transfers: &MyTeamTransfers) -> String {
+    match transfers.limit {
+        // No limit while a Wildcard or Free Hit is played
+        None => "Free transfers: **unlimited**".to_string(),
+        Some(limit) => format!("Free transfers: **{}**", (limit - transfers.made).max(0)),
     }
+}
 
-    Ok(team_data)
+fn format_available_chips(chips: &[MyTeamChip]) -> String {
+    let available: Vec<String> = chips
+        .iter()
+        .filter(|chip| chip.is_available())
+        .filter_map(|chip| Chip::from_str(&chip.name).ok())
+        .map(|chip| format!("**{}**", chip.pretty_name()))
+        .collect();
+    match available.is_empty() {
+        true => "Chips available: none".to_string(),
+        false => format!("Chips available: {}", available.join(", ")),
+    }
 }
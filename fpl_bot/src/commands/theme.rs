@@ -0,0 +1,114 @@
+use std::time::Instant;
+use tracing::debug;
+
+use crate::images::ThemeName;
+use crate::utils::embed::{Embed, EmbedPage};
+use crate::{handle_async_fallible, log_call, log_timer, start_timer};
+use crate::{Context, Error};
+use fpl_db::queries::theme::{delete_user_theme, upsert_guild_theme, upsert_user_theme};
+
+const COMMAND: &str = "/theme";
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum ThemeChoice {
+    #[name = "Light"]
+    Light,
+    #[name = "Dark"]
+    Dark,
+    #[name = "High contrast"]
+    HighContrast,
+}
+
+impl From<ThemeChoice> for ThemeName {
+    fn from(choice: ThemeChoice) -> Self {
+        match choice {
+            ThemeChoice::Light => ThemeName::Light,
+            ThemeChoice::Dark => ThemeName::Dark,
+            ThemeChoice::HighContrast => ThemeName::HighContrast,
+        }
+    }
+}
+
+/// Choose the colours your images are drawn in, leave it empty to use the server's
+#[poise::command(slash_command)]
+pub async fn theme(
+    ctx: Context<'_>,
+    #[description = "Theme (default: the server's)"] theme: Option<ThemeChoice>,
+) -> Result<(), Error> {
+    log_call!(COMMAND, ctx, "theme", theme);
+    let timer = start_timer!();
+
+    let discord_id = i64::from(ctx.author().id);
+    let body = match theme {
+        Some(theme) => {
+            let theme = ThemeName::from(theme);
+            handle_async_fallible!(
+                ctx,
+                upsert_user_theme(&ctx.data().pool, discord_id, theme.as_str()),
+                "Error calling upsert_user_theme"
+            );
+            format!(
+                "Your images will be drawn in the **{}** theme.",
+                theme.pretty_name()
+            )
+        }
+        None => {
+            handle_async_fallible!(
+                ctx,
+                delete_user_theme(&ctx.data().pool, discord_id),
+                "Error calling delete_user_theme"
+            );
+            "Your images will be drawn in each server's theme.".to_string()
+        }
+    };
+    log_timer!(timer, COMMAND, ctx, "saved theme");
+
+    Embed::from_ctx(ctx)?
+        .success()
+        .title("Theme updated")
+        .add_page(EmbedPage::new().add_row(body))
+        .send()
+        .await?;
+
+    Ok(())
+}
+
+/// Choose the colours images are drawn in on this server, unless a user has picked their own
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "servertheme",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn server_theme(
+    ctx: Context<'_>,
+    #[description = "Theme"] theme: ThemeChoice,
+) -> Result<(), Error> {
+    log_call!("/servertheme", ctx, "theme", theme);
+    let timer = start_timer!();
+
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("Command must be used in a server")?
+        .get() as i64;
+
+    let theme = ThemeName::from(theme);
+    handle_async_fallible!(
+        ctx,
+        upsert_guild_theme(&ctx.data().pool, guild_id, theme.as_str()),
+        "Error calling upsert_guild_theme"
+    );
+    log_timer!(timer, "/servertheme", ctx, "saved theme");
+
+    Embed::from_ctx(ctx)?
+        .success()
+        .title("Server theme updated")
+        .add_page(EmbedPage::new().add_row(format!(
+            "Images in this server will be drawn in the **{}** theme.",
+            theme.pretty_name()
+        )))
+        .send()
+        .await?;
+
+    Ok(())
+}
@@ -2,10 +2,7 @@ use std::time::Instant;
 
 use crate::handle_async_fallible;
 use crate::handle_parse_value;
-use crate::images::colours::GREY_COLOUR;
-use crate::images::colours::PURPLE_COLOUR;
 use crate::images::TransfersRenderer;
-use crate::images::{PlayerGameInfo, PlayerInfo};
 use crate::render;
 use fpl_common::types::{GameWeekId, LeagueId};
 use fpl_db::queries::{
@@ -13,14 +10,16 @@ use fpl_db::queries::{
     mini_league::{get_league_name, get_team_ids_from_league_id},
     team::{get_team_ids_from_discord_ids, get_team_name_from_discord_id},
 };
+use fpl_services::transfers::get_transfers;
 use tracing::debug;
 
 use crate::{
     autocompletes::{autocomplete_league_or_user, autocomplete_league_or_user_value},
-    commands::get_image_file_path,
-    images::{Transfers, TransfersKey},
     log_call, log_timer, start_timer,
-    utils::embed::{Embed, EmbedPage},
+    utils::{
+        embed::{Embed, EmbedPage},
+        theme::get_caller_theme,
+    },
     Context, Error,
 };
 
@@ -105,7 +104,7 @@ pub async fn transfers(
     let transfers = handle_async_fallible!(
         ctx,
         embed,
-        get_transfers(ctx, &team_ids, game_week_id),
+        get_transfers(&ctx.data().pool, &team_ids, game_week_id),
         "Error calling get_transfers"
     );
 
@@ -148,14 +147,12 @@ pub async fn transfers(
         return Ok(());
     }
 
-    let file_name = get_image_file_path(COMMAND, &ctx);
-    let renderer = TransfersRenderer::default();
-    render!(
+    let renderer = TransfersRenderer::default().with_theme(get_caller_theme(ctx).await);
+    let image = render!(
         ctx,
         embed,
         renderer,
         transfers,
-        &file_name,
         "Failed to render transfers"
     );
     log_timer!(timer, COMMAND, ctx, "rendered transfers image");
@@ -163,102 +160,9 @@ pub async fn transfers(
     embed
         .success()
         .title(title)
-        .add_page(EmbedPage::new().with_image(file_name))
+        .add_page(EmbedPage::new().with_rendered_image(image))
         .send()
         .await?;
 
     Ok(())
 }
-
-async fn get_transfers(
-    ctx: Context<'_>,
-    team_ids: &[i32],
-    game_week_id: i16,
-) -> Result<Transfers, Error> {
-    let records = sqlx::query!(
-        r#"
-        SELECT 
-            teams.player_first_name as "user_first_name",
-            teams.player_last_name as "user_last_name",
-            teams.name,
-            player_in.web_name as "player_in_name!",
-            player_in.code as "player_in_code!",
-            po_in.opponents as "player_in_opponents",
-            gwp_in.total_points as "player_in_points",
-            gwp_in.minutes as "player_in_minutes",
-            player_out.web_name as "player_out_name!",
-            player_out.code as "player_out_code!",
-            po_out.opponents as "player_out_opponents",
-            gwp_out.total_points as "player_out_points",
-            gwp_out.minutes as "player_out_minutes"
-        FROM 
-            transfers t
-            join teams on teams.id = t.team_id 
-            LEFT JOIN players player_in ON t.player_in_id = player_in.id
-            LEFT JOIN players player_out ON t.player_out_id = player_out.id
-            left join player_opponents po_in on po_in.player_id = player_in.id and po_in.game_week_id = $1
-            left join player_opponents po_out on po_out.player_id = player_out.id and po_out.game_week_id = $1
-            LEFT JOIN game_week_players gwp_in  ON player_in.id = gwp_in.player_id and gwp_in.game_week_id  = $1
-   	        LEFT JOIN game_week_players gwp_out  ON player_out.id = gwp_out.player_id and gwp_out.game_week_id  = $1
-            WHERE t.game_week_id = $1 and t.team_id = ANY($2);
-        "#,
-        game_week_id,
-        team_ids
-    ).fetch_all(&*ctx.data().pool).await?;
-
-    let mut transfers = Transfers::new();
-
-    for row in records {
-        let key = TransfersKey {
-            team_name: row.name,
-            user_first_name: row.user_first_name,
-            user_last_name: row.user_last_name,
-        };
-
-        let player_in_text = match row.player_in_minutes {
-            0 => row.player_in_opponents.unwrap_or("N/A".to_string()),
-            _ => row.player_in_points.to_string(),
-        };
-
-        // Grey them out if they arent playing. Will be active_bg_colour as we are using free text
-        let player_in_bg_colour = match row.player_in_minutes {
-            0 => GREY_COLOUR,
-            _ => PURPLE_COLOUR,
-        };
-
-        let transfer_in = PlayerInfo::new(
-            row.player_in_name,
-            row.player_in_code as u32,
-            vec![PlayerGameInfo::FreeText(player_in_text)],
-            false,
-            false,
-            true,
-        )
-        .status_active_bg_color(player_in_bg_colour);
-
-        let player_out_text = match row.player_out_minutes {
-            0 => row.player_out_opponents.unwrap_or("N/A".to_string()),
-            _ => row.player_out_points.to_string(),
-        };
-
-        // Grey them out if they arent playing. Will be active_bg_colour as we are using free text
-        let player_out_bg_colour = match row.player_out_minutes {
-            0 => GREY_COLOUR,
-            _ => PURPLE_COLOUR,
-        };
-
-        let transfer_out = PlayerInfo::new(
-            row.player_out_name,
-            row.player_out_code as u32,
-            vec![PlayerGameInfo::FreeText(player_out_text)],
-            false,
-            false,
-            true,
-        )
-        .status_active_bg_color(player_out_bg_colour);
-
-        transfers = transfers.add_transfer(key, transfer_out, transfer_in);
-    }
-
-    Ok(transfers)
-}
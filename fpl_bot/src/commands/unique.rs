@@ -15,9 +15,11 @@ use tracing::debug;
 
 use crate::{
     autocompletes::autocomplete_mini_league,
-    commands::get_image_file_path,
     log_call, log_timer, start_timer,
-    utils::embed::{Embed, EmbedPage},
+    utils::{
+        embed::{Embed, EmbedPage},
+        theme::get_caller_theme,
+    },
     Context, Error,
 };
 
@@ -81,14 +83,12 @@ pub async fn unique(
     );
     log_timer!(timer, COMMAND, ctx, "fetched league_name");
 
-    let file_name = get_image_file_path(COMMAND, &ctx);
-    let renderer = UniqueRenderer::default();
-    render!(
+    let renderer = UniqueRenderer::default().with_theme(get_caller_theme(ctx).await);
+    let image = render!(
         ctx,
         embed,
         renderer,
         unique_players,
-        &file_name,
         "Failed to render unique players"
     );
     log_timer!(timer, COMMAND, ctx, "rendered image");
@@ -98,7 +98,7 @@ pub async fn unique(
         .title(format!(
             "Unique players for {team_name} in Gameweek {game_week_id} among {league_name}"
         ))
-        .add_page(EmbedPage::new().with_image(file_name))
+        .add_page(EmbedPage::new().with_rendered_image(image))
         .send()
         .await?;
 
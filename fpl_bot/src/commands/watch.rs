@@ -0,0 +1,159 @@
+use fpl_common::types::PlayerId;
+use fpl_db::models::MAX_WATCHED_PLAYERS;
+use fpl_db::queries::watchlist::{
+    add_watched_player, count_watched_players, get_watched_players, remove_watched_player,
+};
+use std::time::Instant;
+use tracing::debug;
+
+use crate::autocompletes::autocomplete_player;
+use crate::notifications::{format_cost, status_description};
+use crate::utils::embed::{Embed, EmbedPage};
+use crate::{handle_async_fallible, log_call, log_timer, start_timer, Context, Error};
+
+const COMMAND: &str = "/watch";
+
+/// Get DMs when players you're watching change price, pick up news, score or get booked
+#[poise::command(
+    slash_command,
+    subcommands("watch_add", "watch_remove", "watch_list"),
+    subcommand_required
+)]
+pub async fn watch(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Add a player to your watchlist
+#[poise::command(slash_command, rename = "add")]
+pub async fn watch_add(
+    ctx: Context<'_>,
+    #[description = "Player"]
+    #[autocomplete = "autocomplete_player"]
+    player_id: PlayerId,
+) -> Result<(), Error> {
+    log_call!(COMMAND, ctx, "player_id", player_id);
+    let timer: Instant = start_timer!();
+    let discord_id = ctx.author().id.get() as i64;
+
+    let watched = handle_async_fallible!(
+        ctx,
+        count_watched_players(&ctx.data().pool, discord_id),
+        "Error calling count_watched_players"
+    );
+    if watched >= MAX_WATCHED_PLAYERS {
+        Embed::from_ctx(ctx)?
+            .error()
+            .title("Watchlist full")
+            .body(format!(
+                "You can watch up to {MAX_WATCHED_PLAYERS} players, remove one with /watch remove first."
+            ))
+            .send()
+            .await?;
+        return Ok(());
+    }
+
+    let added = handle_async_fallible!(
+        ctx,
+        add_watched_player(&ctx.data().pool, discord_id, player_id),
+        "Error calling add_watched_player"
+    );
+    log_timer!(timer, COMMAND, ctx, "added watched player");
+
+    let body = match added {
+        true => "You'll get a DM when their price, status or next fixture changes, or when they score or get a card.",
+        false => "You're already watching them.",
+    };
+    Embed::from_ctx(ctx)?
+        .success()
+        .title("Added to watchlist")
+        .add_page(EmbedPage::new().add_row(body))
+        .send()
+        .await?;
+    Ok(())
+}
+
+/// Remove a player from your watchlist
+#[poise::command(slash_command, rename = "remove")]
+pub async fn watch_remove(
+    ctx: Context<'_>,
+    #[description = "Player"]
+    #[autocomplete = "autocomplete_player"]
+    player_id: PlayerId,
+) -> Result<(), Error> {
+    log_call!(COMMAND, ctx, "player_id", player_id);
+    let timer: Instant = start_timer!();
+
+    let removed = handle_async_fallible!(
+        ctx,
+        remove_watched_player(&ctx.data().pool, ctx.author().id.get() as i64, player_id),
+        "Error calling remove_watched_player"
+    );
+    log_timer!(timer, COMMAND, ctx, "removed watched player");
+
+    if !removed {
+        Embed::from_ctx(ctx)?
+            .error()
+            .title("Not on your watchlist")
+            .body("You weren't watching that player.")
+            .send()
+            .await?;
+        return Ok(());
+    }
+
+    Embed::from_ctx(ctx)?
+        .success()
+        .title("Removed from watchlist")
+        .add_page(EmbedPage::new().add_row("You won't get any more DMs about them."))
+        .send()
+        .await?;
+    Ok(())
+}
+
+/// Show the players on your watchlist
+#[poise::command(slash_command, rename = "list")]
+pub async fn watch_list(ctx: Context<'_>) -> Result<(), Error> {
+    log_call!(COMMAND, ctx);
+    let timer: Instant = start_timer!();
+
+    let watched = handle_async_fallible!(
+        ctx,
+        get_watched_players(&ctx.data().pool, ctx.author().id.get() as i64),
+        "Error calling get_watched_players"
+    );
+    log_timer!(timer, COMMAND, ctx, "fetched watched players");
+
+    if watched.is_empty() {
+        Embed::from_ctx(ctx)?
+            .error()
+            .title("Your watchlist is empty")
+            .body("Add players with /watch add.")
+            .send()
+            .await?;
+        return Ok(());
+    }
+
+    let rows: Vec<String> = watched
+        .iter()
+        .map(|player| {
+            let mut row = format!(
+                "**{}** ({}) {} - {}",
+                player.web_name,
+                player.club_short_name,
+                format_cost(player.now_cost),
+                status_description(&player.status)
+            );
+            if !player.news.is_empty() {
+                row.push_str(&format!("\n> {}", player.news));
+            }
+            row
+        })
+        .collect();
+
+    Embed::from_ctx(ctx)?
+        .success()
+        .title(format!("Your watchlist ({} players)", watched.len()))
+        .add_pages_from_strings(rows, None)
+        .send()
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,186 @@
+use std::time::Instant;
+
+use fpl_db::models::{WebhookEvent, WebhookOwner};
+use fpl_db::queries::webhook::{delete_webhook, get_webhooks};
+use fpl_webhooks::management::{create_webhook, parse_events};
+use fpl_webhooks::WebhookError;
+use poise::CreateReply;
+use tracing::debug;
+
+use crate::utils::embed::{Embed, EmbedPage};
+use crate::{handle_async_fallible, log_call, log_timer, start_timer, Context, Error};
+
+const COMMAND: &str = "/webhook";
+
+fn guild_owner(ctx: Context<'_>) -> Result<WebhookOwner, Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("Command must be used in a server")?
+        .get() as i64;
+    Ok(WebhookOwner::Guild(guild_id))
+}
+
+/// Send live game events from this server to your own tools over HTTP
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("webhook_add", "webhook_list", "webhook_remove"),
+    subcommand_required,
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn webhook(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Register a webhook, the signing secret is only shown to you
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "add",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn webhook_add(
+    ctx: Context<'_>,
+    #[description = "URL to POST events to"] url: String,
+    #[description = "Comma separated events, e.g. fixture_score,deadline_passed (default all)"]
+    events: Option<String>,
+) -> Result<(), Error> {
+    log_call!(COMMAND, ctx, "url", url, "events", events);
+    let timer: Instant = start_timer!();
+    let owner = guild_owner(ctx)?;
+
+    let created = match parse_events(events.as_deref()) {
+        Ok(events) => create_webhook(&ctx.data().pool, &owner, &url, &events).await,
+        Err(e) => Err(e),
+    };
+    let webhook = match created {
+        Ok(webhook) => webhook,
+        Err(WebhookError::Database(e)) => {
+            Embed::from_ctx(ctx)?
+                .error()
+                .body(format!("Error when calling {}", COMMAND))
+                .send()
+                .await?;
+            return Err(format!("Error calling create_webhook: {}", e).into());
+        }
+        Err(e) => {
+            let events = WebhookEvent::ALL.map(|event| event.as_str()).join(", ");
+            Embed::from_ctx(ctx)?
+                .error()
+                .title("Couldn't add webhook")
+                .body(format!("{e}\nEvents: {events}"))
+                .send()
+                .await?;
+            return Ok(());
+        }
+    };
+    log_timer!(timer, COMMAND, ctx, "created webhook");
+
+    let message = format!(
+        "Webhook **{}** added for {}.\n\
+         Events: {}\n\
+         Secret: `{}`\n\
+         Every delivery has an `X-Fpl-Signature` header, `sha256=` then the hex HMAC-SHA256 of \
+         `{{X-Fpl-Timestamp}}.{{body}}` keyed with the secret. It won't be shown again.",
+        webhook.id,
+        webhook.url,
+        webhook.events.join(", "),
+        webhook.secret
+    );
+    ctx.send(CreateReply::default().content(message).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Show this server's webhooks
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "list",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn webhook_list(ctx: Context<'_>) -> Result<(), Error> {
+    log_call!(COMMAND, ctx);
+    let timer: Instant = start_timer!();
+    let owner = guild_owner(ctx)?;
+
+    let webhooks = handle_async_fallible!(
+        ctx,
+        get_webhooks(&ctx.data().pool, &owner),
+        "Error calling get_webhooks"
+    );
+    log_timer!(timer, COMMAND, ctx, "fetched webhooks");
+
+    if webhooks.is_empty() {
+        Embed::from_ctx(ctx)?
+            .error()
+            .title("No webhooks")
+            .body("Add one with /webhook add.")
+            .send()
+            .await?;
+        return Ok(());
+    }
+
+    let rows: Vec<String> = webhooks
+        .iter()
+        .map(|webhook| {
+            format!(
+                "**{}** {}\n> {}",
+                webhook.id,
+                webhook.url,
+                webhook.events.join(", ")
+            )
+        })
+        .collect();
+
+    Embed::from_ctx(ctx)?
+        .success()
+        .title(format!("Webhooks ({})", webhooks.len()))
+        .add_pages_from_strings(rows, None)
+        .send()
+        .await?;
+    Ok(())
+}
+
+/// Remove a webhook and its delivery log
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "remove",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn webhook_remove(
+    ctx: Context<'_>,
+    #[description = "Webhook ID from /webhook list"] webhook_id: i64,
+) -> Result<(), Error> {
+    log_call!(COMMAND, ctx, "webhook_id", webhook_id);
+    let timer: Instant = start_timer!();
+    let owner = guild_owner(ctx)?;
+
+    let removed = handle_async_fallible!(
+        ctx,
+        delete_webhook(&ctx.data().pool, &owner, webhook_id),
+        "Error calling delete_webhook"
+    );
+    log_timer!(timer, COMMAND, ctx, "removed webhook");
+
+    if !removed {
+        Embed::from_ctx(ctx)?
+            .error()
+            .title("Webhook not found")
+            .body(format!("This server has no webhook {webhook_id}."))
+            .send()
+            .await?;
+        return Ok(());
+    }
+
+    Embed::from_ctx(ctx)?
+        .success()
+        .title("Webhook removed")
+        .add_page(
+            EmbedPage::new().add_row(format!("Webhook {webhook_id} won't be sent anything else.")),
+        )
+        .send()
+        .await?;
+    Ok(())
+}
@@ -2,7 +2,7 @@
 pub mod autocompletes;
 pub mod commands;
 pub mod constants;
-pub mod images;
+pub use fpl_services::images;
 pub mod notifications;
 pub mod utils;
 
@@ -11,8 +11,11 @@ pub use crate::utils::embed::Embed;
 
 // Define core types that should be accessible throughout the project
 use fpl_api::FplClient;
+use fpl_db::models::GuildMember;
+use fpl_services::images::RenderCache;
 use sqlx::PgPool;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use tracing_subscriber::reload::Handle;
 use tracing_subscriber::EnvFilter;
 use tracing_subscriber::Registry;
@@ -21,7 +24,13 @@ pub struct Data {
     pub pool: Arc<PgPool>,
     pub client: Arc<FplClient>,
     pub log_levels: Arc<Handle<EnvFilter, Registry>>,
+    pub render_cache: Arc<RenderCache>,
+    pub member_chunks: Arc<PendingMemberChunks>,
 }
 
+/// Members from the chunks received so far for each guild, with how many chunks that was. Held
+/// until the last chunk arrives so the guild's members can be synced in one go.
+pub type PendingMemberChunks = Mutex<HashMap<i64, (u32, Vec<GuildMember>)>>;
+
 pub type Error = Box<dyn std::error::Error + Send + Sync>;
 pub type Context<'a> = poise::Context<'a, Data, Error>;
@@ -1,19 +1,30 @@
 mod autocompletes;
 mod commands;
 mod constants;
-pub mod images;
+pub use fpl_services::images;
 pub mod notifications;
 mod utils;
 
 use commands::{
-    captains, chips, deadline, differentials, hits, loglevel, register, table, team, transfers,
-    unique, whohas,
+    captains, chips, cup, deadline, differentials, export, hits, home_league, league, link,
+    livetable, loglevel, notification_settings, register, relink, server_theme, table, team, theme,
+    transfers, unique, unlink, unregister, watch, webhook, whohas,
 };
 
 use ::serenity::all::ChannelId;
+use utils::guild::{check_league_in_guild, handle_guild_member_event};
+use fpl_api::concurrency::RequestPriority;
 use fpl_api::FplClient;
+use fpl_bot::notifications::CupNotifications;
+use fpl_bot::notifications::LiveTableUpdater;
+use fpl_bot::notifications::MessageQueue;
 use fpl_bot::notifications::PointsNotifications;
+use fpl_bot::notifications::RateLimitGate;
 use fpl_bot::notifications::ScoreNotifications;
+use fpl_bot::notifications::WatchlistNotifications;
+use fpl_db::rate_limit::PgRateLimiter;
+use fpl_services::images::RenderCache;
+use fpl_services::notifications::{ChangeHandler, ChangeListener};
 use poise::serenity_prelude as serenity;
 use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
 use std::{str::FromStr, sync::Arc, time::Duration};
@@ -24,6 +35,21 @@ use fpl_bot::Context;
 use fpl_bot::Data;
 use fpl_bot::Error;
 
+const RENDER_CLEANUP_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Deletes expired renders, otherwise the generated directory grows with every image sent.
+async fn clean_up_renders(render_cache: Arc<RenderCache>) {
+    let mut interval = tokio::time::interval(RENDER_CLEANUP_INTERVAL);
+    loop {
+        interval.tick().await;
+        match render_cache.cleanup() {
+            Ok(0) => {}
+            Ok(removed) => info!("Removed {} expired renders", removed),
+            Err(e) => error!("Error when cleaning up renders: {}", e),
+        }
+    }
+}
+
 async fn handle_bot_error(error: poise::FrameworkError<'_, Data, Error>) {
     match &error {
         poise::FrameworkError::Command { error, ctx, .. } => {
@@ -85,12 +111,25 @@ async fn main() -> Result<(), Box<(dyn std::error::Error + std::marker::Send + S
             .connect_with(options)
             .await?,
     );
-    let client = Arc::new(FplClient::new());
+    let client = Arc::new(
+        FplClient::new()
+            .with_priority(RequestPriority::Interactive)
+            .with_shared_rate_limiter(Arc::new(PgRateLimiter::new(Arc::clone(&pool)))),
+    );
+    let render_cache = Arc::new(RenderCache::new(
+        fpl_common::paths::get_generated_image_dir(),
+    ));
+
+    let mut http = serenity::HttpBuilder::new(&token).build();
+    let rate_limit_gate = Arc::new(RateLimitGate::new());
+    rate_limit_gate.attach(&mut http);
 
     let framework = poise::Framework::builder()
         .options(poise::FrameworkOptions {
             commands: vec![
                 register(),
+                relink(),
+                unregister(),
                 captains(),
                 deadline(),
                 whohas(),
@@ -98,12 +137,28 @@ async fn main() -> Result<(), Box<(dyn std::error::Error + std::marker::Send + S
                 loglevel(),
                 hits(),
                 table(),
+                livetable(),
                 team(),
                 unique(),
                 differentials(),
                 transfers(),
+                link(),
+                unlink(),
+                notification_settings(),
+                watch(),
+                home_league(),
+                league(),
+                cup(),
+                webhook(),
+                export(),
+                theme(),
+                server_theme(),
             ],
             on_error: |error| Box::pin(handle_bot_error(error)),
+            command_check: Some(|ctx| Box::pin(check_league_in_guild(ctx))),
+            event_handler: |ctx, event, _framework, data| {
+                Box::pin(handle_guild_member_event(ctx, event, data))
+            },
             allowed_mentions: Some(
                 serenity::CreateAllowedMentions::new()
                     .empty_roles()
@@ -152,32 +207,67 @@ async fn main() -> Result<(), Box<(dyn std::error::Error + std::marker::Send + S
                         .unwrap(),
                 );
 
+                let message_queue = MessageQueue::start(Arc::clone(&ctx.http), rate_limit_gate);
+
                 let live_points_notifications = Arc::new(PointsNotifications::new(
                     Arc::clone(&pool),
                     Arc::clone(&ctx.http),
+                    Arc::clone(&message_queue),
+                    Arc::clone(&render_cache),
                     notification_channel,
                 ));
 
-                live_points_notifications.start().await?;
+                Arc::clone(&live_points_notifications).start_digest().await?;
 
                 let live_score_notifications = Arc::new(ScoreNotifications::new(
+                    Arc::clone(&pool),
+                    Arc::clone(&message_queue),
+                    notification_channel,
+                ));
+
+                let watchlist_notifications = Arc::new(WatchlistNotifications::new(
                     Arc::clone(&pool),
                     Arc::clone(&ctx.http),
+                    Arc::clone(&message_queue),
+                ));
+
+                let cup_notifications = Arc::new(CupNotifications::new(
+                    Arc::clone(&pool),
+                    Arc::clone(&message_queue),
                     notification_channel,
                 ));
 
-                live_score_notifications.start().await?;
+                let handlers: Vec<Arc<dyn ChangeHandler>> = vec![
+                    live_points_notifications,
+                    live_score_notifications,
+                    watchlist_notifications,
+                    cup_notifications,
+                ];
+                let change_listener = Arc::new(ChangeListener::new(Arc::clone(&pool), handlers));
+
+                change_listener.start();
+
+                let live_table_updater = Arc::new(LiveTableUpdater::new(
+                    Arc::clone(&pool),
+                    Arc::clone(&ctx.http),
+                    Arc::clone(&render_cache),
+                ));
+                live_table_updater.start().await?;
+
+                tokio::spawn(clean_up_renders(Arc::clone(&render_cache)));
 
                 Ok(Data {
                     pool,
                     client,
                     log_levels,
+                    render_cache,
+                    member_chunks: Arc::default(),
                 })
             })
         })
         .build();
 
-    let client = serenity::ClientBuilder::new(token, intents)
+    let client = serenity::ClientBuilder::new_with_http(http, intents)
         .framework(framework)
         .await;
     client.unwrap().start().await.unwrap();
@@ -0,0 +1,163 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use fpl_common::types::TeamId;
+use fpl_db::events::ChangeEvent;
+use fpl_db::models::{NotificationKind, NotificationTransition};
+use fpl_db::queries::cup::{get_cup_match, get_team_discord_ids};
+use fpl_db::queries::notification_state::{claim_notification, reclaim_unsent_notifications};
+use fpl_services::notifications::{ChangeHandler, MAX_SEND_ATTEMPTS, REDRIVE_LEASE};
+use itertools::Itertools;
+use serenity::all::{ChannelId, CreateEmbed, CreateMessage};
+use sqlx::PgPool;
+use tracing::{debug, info};
+
+use crate::Error;
+
+use super::MessageQueue;
+
+pub struct CupNotifications {
+    pool: Arc<PgPool>,
+    queue: Arc<MessageQueue>,
+    notification_channel: ChannelId,
+}
+
+impl CupNotifications {
+    /*
+
+    Updates logic:
+
+    - The cup scraper publishes a CupMatchDecided event when a tie it already had stored gets a winner,
+      which happens once the game week's points are final
+
+    - - Ignore it unless the losing team is linked to someone
+    - - Claim the knock out in notification_state, keyed by the losing team and game week, so it's only
+        sent once even with multiple bot instances
+    - - Post it to the notification channel, mentioning whoever has the team linked
+    - - The claim is marked sent once Discord has the message, redrive() re-sends it if it never was
+
+     */
+    pub fn new(
+        pool: Arc<PgPool>,
+        queue: Arc<MessageQueue>,
+        notification_channel: ChannelId,
+    ) -> Self {
+        Self {
+            pool,
+            queue,
+            notification_channel,
+        }
+    }
+}
+
+#[async_trait]
+impl ChangeHandler for CupNotifications {
+    fn name(&self) -> &'static str {
+        "cup"
+    }
+
+    async fn handle_event(&self, event: &ChangeEvent) -> Result<(), Error> {
+        let ChangeEvent::CupMatchDecided {
+            match_id,
+            game_week_id,
+            loser_team_id,
+            ..
+        } = *event
+        else {
+            return Ok(());
+        };
+
+        let discord_ids = get_team_discord_ids(&self.pool, TeamId::new(loser_team_id)).await?;
+        if discord_ids.is_empty() {
+            debug!("Team {} isn't linked, not notifying", loser_team_id);
+            return Ok(());
+        }
+
+        let transition = NotificationTransition {
+            channel_id: self.notification_channel.get() as i64,
+            kind: NotificationKind::CupEliminated,
+            subject_id: loser_team_id,
+            game_week_id,
+            previous_value: None,
+            value: match_id.to_string(),
+        };
+        if !claim_notification(&self.pool, &transition).await? {
+            debug!("Already notified {}", transition.idempotency_key());
+            return Ok(());
+        }
+
+        self.send(transition, discord_ids).await
+    }
+
+    /// Re-sends knock outs that were claimed but never confirmed sent.
+    async fn redrive(&self) -> Result<(), Error> {
+        let unsent = reclaim_unsent_notifications(
+            &self.pool,
+            &[NotificationKind::CupEliminated],
+            Some(self.notification_channel.get() as i64),
+            REDRIVE_LEASE.as_secs_f64(),
+            MAX_SEND_ATTEMPTS,
+        )
+        .await?;
+
+        for state in unsent {
+            let transition = state.transition()?;
+            let discord_ids =
+                get_team_discord_ids(&self.pool, TeamId::new(transition.subject_id)).await?;
+            self.send(transition, discord_ids).await?;
+        }
+        Ok(())
+    }
+
+    /// Posts the knock out claimed in `transition`, whose subject is the losing team and value
+    /// the match.
+    async fn send(
+        &self,
+        transition: NotificationTransition,
+        discord_ids: Vec<i64>,
+    ) -> Result<(), Error> {
+        let loser_team_id = transition.subject_id;
+        let match_id: i32 = transition.value.parse()?;
+        let cup_match = get_cup_match(&self.pool, match_id).await?;
+        let loser_is_entry_1 = i32::from(cup_match.entry_1_team_id) == loser_team_id;
+        let (loser_name, loser_points, winner_name, winner_points) = if loser_is_entry_1 {
+            (
+                cup_match.entry_1_name,
+                cup_match.entry_1_points,
+                cup_match.entry_2_name.unwrap_or_default(),
+                cup_match.entry_2_points,
+            )
+        } else {
+            (
+                cup_match.entry_2_name.unwrap_or_default(),
+                cup_match.entry_2_points,
+                cup_match.entry_1_name,
+                cup_match.entry_1_points,
+            )
+        };
+        let game_week_id = transition.game_week_id;
+        let mentions = discord_ids.iter().map(|id| format!("<@{id}>")).join(" ");
+        let tiebreak = match cup_match.tiebreak {
+            Some(tiebreak) => format!(" on {}", tiebreak.replace('_', " ")),
+            None => String::new(),
+        };
+
+        info!(
+            "Sending cup knock out notification for team {}",
+            loser_team_id
+        );
+        let embed = CreateEmbed::new()
+            .title(format!("🏆 Knocked out in the {}", cup_match.knockout_name))
+            .description(format!(
+                "**{loser_name}** ({mentions}) lost {loser_points}-{winner_points} to **{winner_name}**{tiebreak} in GW{game_week_id}."
+            ))
+            .color((255, 69, 58));
+        self.queue.send_claimed(
+            &self.pool,
+            self.notification_channel,
+            CreateMessage::new().add_embed(embed),
+            vec![transition],
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use std::{str::FromStr, sync::Arc, time::Duration};
+
+use fpl_db::models::LiveTable;
+use fpl_db::queries::live_table::{
+    finish_live_table, get_active_live_tables, get_game_week_fixture_progress,
+    mark_live_table_updated,
+};
+use fpl_db::queries::mini_league::get_league_name;
+use fpl_services::table::{build_table_data, get_points, TableView};
+use fpl_services::theme::get_theme;
+use serenity::all::{
+    ChannelId, CreateAttachment, CreateEmbed, EditAttachments, EditMessage, Http, MessageId,
+    Timestamp,
+};
+use sqlx::PgPool;
+use tracing::{debug, error, info, warn};
+
+use crate::images::{RenderCache, TableRenderer};
+use crate::Error;
+
+const UPDATE_INTERVAL: Duration = Duration::from_secs(60);
+
+pub struct LiveTableUpdater {
+    pool: Arc<PgPool>,
+    http: Arc<Http>,
+    render_cache: Arc<RenderCache>,
+}
+
+impl LiveTableUpdater {
+    /*
+
+    Updates logic:
+
+    - Every live table lives in the live_tables table until it's finished, so a restart just picks the
+      unfinished ones back up on the next tick
+
+    - - If the table's GW has fixtures in progress, re-render and edit the message
+    - - If every fixture in the GW has finished, do one last edit and mark it finished
+    - - If the message has been deleted, mark it finished so we stop trying
+
+     */
+    pub fn new(pool: Arc<PgPool>, http: Arc<Http>, render_cache: Arc<RenderCache>) -> Self {
+        Self {
+            pool,
+            http,
+            render_cache,
+        }
+    }
+
+    pub async fn start(self: Arc<Self>) -> Result<(), Error> {
+        info!("Starting live table updates");
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(UPDATE_INTERVAL);
+
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.update_all().await {
+                    error!("Error when updating live tables: {}", e);
+                }
+            }
+        });
+        Ok(())
+    }
+
+    async fn update_all(&self) -> Result<(), Error> {
+        let live_tables = get_active_live_tables(&self.pool).await?;
+        debug!("Checking {} live tables", live_tables.len());
+
+        for live_table in live_tables {
+            if let Err(e) = self.update(&live_table).await {
+                error!(
+                    "Error when updating live table {}: {}",
+                    live_table.message_id, e
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn update(&self, live_table: &LiveTable) -> Result<(), Error> {
+        let (live, remaining) =
+            get_game_week_fixture_progress(&self.pool, live_table.game_week_id).await?;
+        let finished = remaining == 0;
+        if live == 0 && !finished {
+            return Ok(());
+        }
+
+        let (embed, attachment) =
+            render_live_table(&self.pool, &self.render_cache, live_table, finished).await?;
+        let edit = EditMessage::new()
+            .embed(embed)
+            .attachments(EditAttachments::new().add(attachment));
+
+        let channel_id = ChannelId::new(live_table.channel_id as u64);
+        let message_id = MessageId::new(live_table.message_id as u64);
+        if let Err(e) = channel_id.edit_message(&self.http, message_id, edit).await {
+            if is_not_found(&e) {
+                warn!(
+                    "Live table message {} was deleted, no longer updating it",
+                    live_table.message_id
+                );
+                finish_live_table(&self.pool, live_table.message_id).await?;
+                return Ok(());
+            }
+            return Err(e.into());
+        }
+
+        if finished {
+            info!(
+                "Game week {} finished, live table {} is final",
+                live_table.game_week_id, live_table.message_id
+            );
+            finish_live_table(&self.pool, live_table.message_id).await?;
+        } else {
+            mark_live_table_updated(&self.pool, live_table.message_id).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders the current standings for a live table into an embed and its image attachment,
+/// in the theme of whoever posted it so every update looks the same.
+pub async fn render_live_table(
+    pool: &PgPool,
+    render_cache: &RenderCache,
+    live_table: &LiveTable,
+    finished: bool,
+) -> Result<(CreateEmbed, CreateAttachment), Error> {
+    let live_points = get_points(pool, live_table.league_id).await?;
+    let league_name = get_league_name(pool, live_table.league_id).await?;
+    let view = TableView::from_str(&live_table.overall_or_week)?;
+    let data = build_table_data(live_points, view, None);
+    let theme = get_theme(pool, Some(live_table.created_by), None).await?;
+
+    let image = render_cache.render(
+        &TableRenderer::default()
+            .with_theme(theme)
+            .render_svg(data)?,
+    )?;
+
+    let attachment = CreateAttachment::bytes(image.png.to_vec(), image.file_name());
+    let status = if finished { "Final" } else { "Live" };
+    let embed = CreateEmbed::new()
+        .title(format!(
+            "{status} {} League standings for {league_name}",
+            live_table.overall_or_week
+        ))
+        .color((0, 255, 136))
+        .image(format!("attachment://{}", attachment.filename))
+        .timestamp(Timestamp::now());
+
+    Ok((embed, attachment))
+}
+
+fn is_not_found(error: &serenity::Error) -> bool {
+    match error {
+        serenity::Error::Http(e) => e.status_code().map(|status| status.as_u16()) == Some(404),
+        _ => false,
+    }
+}
@@ -1,5 +1,13 @@
+pub mod cup;
+pub mod live_table;
 pub mod points;
+pub mod queue;
 pub mod scores;
+pub mod watchlist;
 
+pub use cup::*;
+pub use live_table::*;
 pub use points::*;
+pub use queue::*;
 pub use scores::*;
+pub use watchlist::*;
@@ -2,50 +2,49 @@ use std::{
     cmp::Ordering,
     collections::HashMap,
     sync::{Arc, Mutex},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-use fpl_common::types::GameWeekId;
-use fpl_db::queries::game_week::get_current_game_week_id;
+use async_trait::async_trait;
+use fpl_db::events::ChangeEvent;
+use fpl_db::models::{GuildNotificationSettings, NotificationTransition, PointsNotificationMode};
+use fpl_db::queries::guild_settings::get_guild_notification_settings;
+use fpl_db::queries::notification_state::mark_notification_sent;
+use fpl_services::notifications::{
+    ChangeHandler, PointsAudience, PointsChange, PointsChanges, REDRIVE_LEASE,
+};
+use fpl_services::theme::get_theme;
 use itertools::Itertools;
-use serenity::all::{ChannelId, Http};
+use serenity::all::{ChannelId, GuildId, Http};
 use sqlx::PgPool;
-use tracing::{error, info};
+use tokio::sync::OnceCell;
+use tracing::{debug, error, info};
 
+use crate::images::{PointsDigestData, PointsDigestRenderer, PointsDigestRow, RenderCache};
 use crate::Error;
 
-#[derive(Debug)]
-pub struct LiveOwners {
-    pub web_name: String,
-    pub code: i32,
-    pub total_points: i16,
-    pub player_id: i16,
-    pub owners: Vec<i64>,
-}
+use super::MessageQueue;
+
+const DIGEST_CHECK_INTERVAL: Duration = Duration::from_secs(5);
 
 pub struct PointsNotifications {
-    player_points: Mutex<HashMap<i16, i16>>,
     pool: Arc<PgPool>,
     http: Arc<Http>,
+    queue: Arc<MessageQueue>,
+    render_cache: Arc<RenderCache>,
     notification_channel: ChannelId,
-    current_game_week: Mutex<Option<GameWeekId>>,
-}
-
-pub struct PointsNotification {
-    pub web_name: String,
-    pub code: i32,
-    pub old_points: i16,
-    pub new_points: i16,
-    pub owners: Vec<i64>,
+    guild_id: OnceCell<Option<GuildId>>,
+    digest: Mutex<Option<PendingDigest>>,
+    changes: PointsChanges,
 }
 
-impl PointsNotification {
-    pub fn owners_to_str(&self) -> String {
-        self.owners
-            .iter()
-            .map(|owner| format!("<@{owner}>"))
-            .join(" ,")
-    }
+/// Points changes waiting to go out as one image once `window` has passed since the first. Only
+/// held in memory, but each change is still only claimed in notification_state until the digest
+/// is sent, so if the bot stops first redrive() picks them back up.
+struct PendingDigest {
+    started: Instant,
+    window: Duration,
+    notifications: Vec<PointsChange>,
 }
 
 impl PointsNotifications {
@@ -53,157 +52,254 @@ impl PointsNotifications {
 
     Updates logic:
 
-    - If GW has changed, reset player_points and update current_game_week, return and poll again later
+    - Which changes to send, and claiming them so other bot instances don't, is PointsChanges' job, see
+      fpl_services::notifications. Points changes only go out for players a registered Discord user owns
 
-    - Can now assume we are in the same GW as before so player_points are relevant
-    - - If player_points is empty, populate and do nothing (bot startup or just refreshed after GW change)
-    - - Otherwise, query the live_owners view and compare against stored player_points. If anything is different, need notif
-    - - - Group all notifs then format and send in send_notifications()
+    - Guilds in instant mode get one embed per change, mentioning the owners
+    - Guilds in digest mode get every change within their window merged into one image, sent by start_digest().
+      The changes stay claimed until then, so redrive() waits out the window before taking them back
+    - Claims are marked sent once Discord has the message, redrive() re-sends any left unsent by a failed
+      send or a restart
 
      */
-    pub fn new(pool: Arc<PgPool>, http: Arc<Http>, notification_channel: ChannelId) -> Self {
+    pub fn new(
+        pool: Arc<PgPool>,
+        http: Arc<Http>,
+        queue: Arc<MessageQueue>,
+        render_cache: Arc<RenderCache>,
+        notification_channel: ChannelId,
+    ) -> Self {
+        let changes = PointsChanges::new(
+            Arc::clone(&pool),
+            notification_channel.get() as i64,
+            PointsAudience::Discord,
+        );
         Self {
             pool,
             http,
+            queue,
+            render_cache,
             notification_channel,
-            current_game_week: Mutex::new(None),
-            player_points: Mutex::new(HashMap::new()),
+            guild_id: OnceCell::new(),
+            digest: Mutex::new(None),
+            changes,
         }
     }
 
-    pub async fn start(self: Arc<Self>) -> Result<(), Error> {
-        info!("Starting live points tracking & notifications");
-        // start the polling thread here
+    pub async fn start_digest(self: Arc<Self>) -> Result<(), Error> {
+        info!("Starting points digest");
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(Duration::from_secs(10));
+            let mut interval = tokio::time::interval(DIGEST_CHECK_INTERVAL);
 
             loop {
                 interval.tick().await;
-                if let Err(e) = self.poll().await {
-                    error!("Error when polling live points notifications: {}", e);
+                if let Err(e) = self.flush_digest(false).await {
+                    error!("Error when sending points digest: {}", e);
                 }
             }
         });
         Ok(())
     }
 
-    pub async fn poll(&self) -> Result<(), Error> {
-        info!(
-            "Polling live points. Game Week = [{:?}], ChannelID=[{}], Tracked Player Count = [{}]",
-            self.current_game_week.lock().unwrap(),
-            self.notification_channel,
-            self.player_points.lock().unwrap().len()
-        );
-
-        // Check for GW change. Extra scope so the mutex guard on stored_gw is dropped.
-        {
-            let current_game_week = get_current_game_week_id(&self.pool).await?;
-            let mut stored_gw = self.current_game_week.lock().unwrap();
-            if let Some(s_gw) = *stored_gw {
-                if s_gw != current_game_week {
-                    info!(
-                        "Game Week has changed from GW{} to GW{}. Refreshing live owners.",
-                        s_gw, current_game_week
-                    );
-
-                    *stored_gw = Some(current_game_week);
-                    self.player_points.lock().unwrap().clear();
-                    return Ok(());
+    async fn guild_id(&self) -> Option<GuildId> {
+        *self
+            .guild_id
+            .get_or_init(|| async {
+                match self.notification_channel.to_channel(&self.http).await {
+                    Ok(channel) => channel.guild().map(|channel| channel.guild_id),
+                    Err(e) => {
+                        error!(
+                            "Failed to look up guild for channel {}: {}",
+                            self.notification_channel, e
+                        );
+                        None
+                    }
                 }
-            } else {
-                info!("Game Week didn't exist, setting to GW{}", current_game_week);
-                *stored_gw = Some(current_game_week);
-                return Ok(());
-            }
-        }
+            })
+            .await
+    }
 
-        self.check_live_owners().await?;
+    async fn settings(&self) -> Result<Option<GuildNotificationSettings>, Error> {
+        let Some(guild_id) = self.guild_id().await else {
+            return Ok(None);
+        };
 
-        Ok(())
+        let settings = get_guild_notification_settings(&self.pool, guild_id.get() as i64).await?;
+        Ok(Some(settings.unwrap_or_else(|| {
+            GuildNotificationSettings::new(guild_id.get() as i64)
+        })))
     }
 
-    async fn check_live_owners(&self) -> Result<(), Error> {
-        let raw_owners = sqlx::query!(
-            r#"    
-            SELECT 
-                web_name as "web_name!",
-                code as "code!",
-                total_points as "total_points!",
-                player_id as "player_id!",
-                owners as "owners!"
-            FROM live_owners;
-            "#
-        )
-        .fetch_all(&*self.pool)
-        .await?;
+    async fn digest_window(&self) -> Result<Option<Duration>, Error> {
+        Ok(match self.settings().await? {
+            Some(settings) if settings.points_mode() == PointsNotificationMode::Digest => Some(
+                Duration::from_secs(settings.digest_window_seconds.max(0) as u64),
+            ),
+            _ => None,
+        })
+    }
 
-        if raw_owners.is_empty() {
-            info!("No entries in the live_owners view. Returning.");
+    async fn send_updates(&self, notifications: &[PointsChange]) -> Result<(), Error> {
+        if notifications.is_empty() {
             return Ok(());
         }
 
-        // Parse out the owners csv into a Vec<i64>
-        let new_live_owners: Vec<LiveOwners> = raw_owners
-            .into_iter()
-            .map(|row| LiveOwners {
-                web_name: row.web_name,
-                code: row.code,
-                total_points: row.total_points,
-                player_id: row.player_id,
-                owners: match row.owners {
-                    owners_str if !owners_str.is_empty() => owners_str
-                        .split(',')
-                        .filter_map(|id| id.parse::<i64>().ok())
-                        .collect(),
-                    _ => Vec::new(),
-                },
-            })
-            .collect();
-
-        let notifications = {
-            let mut stored_player_points = self.player_points.lock().unwrap();
-            let mut notifications_vec: Vec<PointsNotification> = Vec::new();
-
-            match stored_player_points.len() {
-                0 => {
-                    // No notifications on first load
-                    info!("Got information from live_owners but nothing currently stored. Not sending notifications.\nNew live owners: {:?}", new_live_owners);
-                    for owner in new_live_owners {
-                        stored_player_points.insert(owner.player_id, owner.total_points);
+        match self.digest_window().await? {
+            Some(window) => {
+                let mut digest = self.digest.lock().unwrap();
+                let pending = digest.get_or_insert_with(|| PendingDigest {
+                    started: Instant::now(),
+                    window,
+                    notifications: Vec::new(),
+                });
+                for notification in notifications {
+                    let key = notification.transition.idempotency_key();
+                    if !pending
+                        .notifications
+                        .iter()
+                        .any(|pending| pending.transition.idempotency_key() == key)
+                    {
+                        pending.notifications.push(notification.clone());
                     }
-                    Vec::new()
                 }
-                _ => {
-                    for info in new_live_owners {
-                        let existing_points = stored_player_points
-                            .entry(info.player_id)
-                            .or_insert(info.total_points);
-
-                        if *existing_points != info.total_points {
-                            notifications_vec.push(PointsNotification {
-                                web_name: info.web_name,
-                                code: info.code,
-                                old_points: *existing_points,
-                                new_points: info.total_points,
-                                owners: info.owners.clone(),
-                            });
-
-                            *existing_points = info.total_points;
-                        }
-                    }
-                    notifications_vec
+                debug!(
+                    "Added {} point updates to the digest, {} pending",
+                    notifications.len(),
+                    pending.notifications.len()
+                );
+                Ok(())
+            }
+            None => {
+                // Anything left over from digest mode goes out first so changes stay in order
+                self.flush_digest(true).await?;
+                self.send_instant(notifications).await
+            }
+        }
+    }
+
+    async fn flush_digest(&self, force: bool) -> Result<(), Error> {
+        let notifications = {
+            let mut digest = self.digest.lock().unwrap();
+            match digest.as_ref() {
+                Some(pending) if force || pending.started.elapsed() >= pending.window => {
+                    digest.take().map(|pending| pending.notifications)
                 }
+                _ => None,
             }
         };
 
-        if !notifications.is_empty() {
-            self.send_updates(&notifications).await?;
+        match notifications {
+            Some(notifications) => self.send_digest(notifications).await,
+            None => Ok(()),
         }
+    }
 
+    async fn send_digest(&self, notifications: Vec<PointsChange>) -> Result<(), Error> {
+        let transitions: Vec<NotificationTransition> = notifications
+            .iter()
+            .map(|notification| notification.transition.clone())
+            .collect();
+
+        // Merge repeated changes for the same player, e.g. 2 -> 6 -> 7 becomes 2 -> 7
+        let mut merged: Vec<PointsChange> = Vec::new();
+        for notification in notifications {
+            match merged
+                .iter_mut()
+                .find(|m| m.player_id == notification.player_id)
+            {
+                Some(existing) => {
+                    existing.new_points = notification.new_points;
+                    existing.owners = notification.owners;
+                }
+                None => merged.push(notification),
+            }
+        }
+        merged.retain(|notification| notification.old_points != notification.new_points);
+
+        if merged.is_empty() {
+            // Everything cancelled out, there's nothing to send but they've been dealt with
+            for transition in &transitions {
+                mark_notification_sent(&self.pool, transition).await?;
+            }
+            return Ok(());
+        }
+        info!("Sending points digest with {} players", merged.len());
+
+        let owner_ids: Vec<i64> = merged
+            .iter()
+            .flat_map(|notification| &notification.owners)
+            .filter_map(|owner| owner.parse::<i64>().ok())
+            .unique()
+            .collect();
+        let owner_names: HashMap<i64, String> = sqlx::query!(
+            r#"
+            SELECT du.discord_id, t.player_first_name, t.player_last_name
+            FROM discord_users du
+            JOIN teams t ON t.id = du.team_id
+            WHERE du.discord_id = ANY($1);
+            "#,
+            &owner_ids
+        )
+        .fetch_all(&*self.pool)
+        .await?
+        .into_iter()
+        .map(|row| {
+            (
+                row.discord_id,
+                format!("{} {}", row.player_first_name, row.player_last_name),
+            )
+        })
+        .collect();
+
+        let mut data = PointsDigestData::new("Points Digest".to_string());
+        for notification in &merged {
+            data.add_row(PointsDigestRow::new(
+                notification.web_name.clone(),
+                notification.code,
+                notification.old_points,
+                notification.new_points,
+                notification
+                    .owners
+                    .iter()
+                    .filter_map(|owner| owner_names.get(&owner.parse::<i64>().ok()?).cloned())
+                    .collect(),
+            ));
+        }
+
+        let guild_id = self.guild_id().await.map(|guild_id| guild_id.get() as i64);
+        let theme = get_theme(&self.pool, None, guild_id).await?;
+        let image = self.render_cache.render(
+            &PointsDigestRenderer::default()
+                .with_theme(theme)
+                .render_svg(data)?,
+        )?;
+        let image_attachment =
+            serenity::builder::CreateAttachment::bytes(image.png.to_vec(), image.file_name());
+        let image_filename = image_attachment.filename.clone();
+
+        let embed = serenity::builder::CreateEmbed::new()
+            .title(format!("🔔 Points Digest ({} players)", merged.len()))
+            .color((252, 186, 3))
+            .image(format!("attachment://{}", image_filename));
+
+        let owners = owner_ids
+            .iter()
+            .map(|owner| format!("<@{owner}>"))
+            .join(" ");
+
+        self.queue.send_claimed(
+            &self.pool,
+            self.notification_channel,
+            serenity::builder::CreateMessage::new()
+                .content(owners)
+                .add_embed(embed)
+                .add_file(image_attachment),
+            transitions,
+        );
         Ok(())
     }
 
-    async fn send_updates(&self, notifications: &[PointsNotification]) -> Result<(), Error> {
+    async fn send_instant(&self, notifications: &[PointsChange]) -> Result<(), Error> {
         info!("Sending {} point update notifications", notifications.len());
         for notification in notifications {
             let red_arrow = "<:arrow_green:1284491445323169835>";
@@ -221,7 +317,11 @@ impl PointsNotifications {
                 notification.old_points,
                 emoji,
                 notification.new_points,
-                notification.owners_to_str()
+                notification
+                    .owners
+                    .iter()
+                    .map(|owner| format!("<@{owner}>"))
+                    .join(" ,")
             );
 
             let image_path = fpl_common::paths::get_player_image_path(notification.code);
@@ -235,15 +335,54 @@ impl PointsNotifications {
                 .color((252, 186, 3))
                 .thumbnail(format!("attachment://{}", image_filename));
 
-            self.notification_channel
-                .send_message(
-                    &self.http,
-                    serenity::builder::CreateMessage::new()
-                        .add_embed(embed)
-                        .add_file(image_attachment),
-                )
-                .await?;
+            self.queue.send_claimed(
+                &self.pool,
+                self.notification_channel,
+                serenity::builder::CreateMessage::new()
+                    .add_embed(embed)
+                    .add_file(image_attachment),
+                vec![notification.transition.clone()],
+            );
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ChangeHandler for PointsNotifications {
+    fn name(&self) -> &'static str {
+        "live points"
+    }
+
+    async fn handle_event(&self, event: &ChangeEvent) -> Result<(), Error> {
+        if let Some(change) = self.changes.from_event(event).await? {
+            self.send_updates(&[change]).await?;
         }
         Ok(())
     }
+
+    async fn poll(&self) -> Result<(), Error> {
+        let changes = self.changes.poll().await?;
+        self.send_updates(&changes).await
+    }
+
+    /// Re-sends claimed changes that were never confirmed sent, through the digest if the guild
+    /// uses one.
+    async fn redrive(&self) -> Result<(), Error> {
+        // Changes sit claimed in a pending digest for the whole window, only take them back once
+        // it should have been sent
+        let digest_window = self.digest_window().await?.unwrap_or_default();
+        let changes = self.changes.redrive(REDRIVE_LEASE + digest_window).await?;
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        info!(
+            "Re-sending {} unsent point update notifications",
+            changes.len()
+        );
+        self.send_updates(&changes).await?;
+        // They've already waited out a window, don't hold them for another
+        self.flush_digest(true).await
+    }
 }
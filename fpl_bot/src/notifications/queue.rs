@@ -0,0 +1,165 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use fpl_db::models::NotificationTransition;
+use fpl_db::queries::notification_state::mark_notification_sent;
+use serenity::all::{ChannelId, CreateMessage, Http, RatelimitInfo};
+use sqlx::PgPool;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::oneshot;
+use tracing::{debug, error, warn};
+
+/// Tracks when Discord last told us to back off, fed by serenity's ratelimit callback (which is
+/// driven by the `Retry-After` header on 429s).
+#[derive(Debug, Default)]
+pub struct RateLimitGate {
+    paused_until: Mutex<Option<Instant>>,
+}
+
+impl RateLimitGate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the gate with `http`'s ratelimiter. Has to happen before the client is built.
+    pub fn attach(self: &Arc<Self>, http: &mut Http) {
+        let gate = Arc::clone(self);
+        if let Some(ratelimiter) = http.ratelimiter.as_mut() {
+            ratelimiter.set_ratelimit_callback(Box::new(move |info| gate.record(&info)));
+        }
+    }
+
+    pub fn record(&self, info: &RatelimitInfo) {
+        warn!(
+            "Discord rate limited {:?} {} for {:?}",
+            info.method, info.path, info.timeout
+        );
+
+        let until = Instant::now() + info.timeout;
+        let mut paused_until = self.paused_until.lock().unwrap();
+        if paused_until.is_none_or(|current| current < until) {
+            *paused_until = Some(until);
+        }
+    }
+
+    pub fn remaining(&self) -> Option<Duration> {
+        let mut paused_until = self.paused_until.lock().unwrap();
+        match *paused_until {
+            Some(until) if until > Instant::now() => Some(until - Instant::now()),
+            Some(_) => {
+                *paused_until = None;
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub async fn wait(&self) {
+        while let Some(remaining) = self.remaining() {
+            tokio::time::sleep(remaining).await;
+        }
+    }
+}
+
+struct QueuedMessage {
+    channel_id: ChannelId,
+    message: CreateMessage,
+    /// Told once Discord has the message, dropped if the send fails
+    sent: Option<oneshot::Sender<()>>,
+}
+
+/// Sends notification messages one at a time, holding the queue while Discord has us rate
+/// limited instead of letting a burst of goals fan out into parallel requests.
+pub struct MessageQueue {
+    sender: UnboundedSender<QueuedMessage>,
+}
+
+impl MessageQueue {
+    pub fn start(http: Arc<Http>, gate: Arc<RateLimitGate>) -> Arc<Self> {
+        let (sender, receiver) = unbounded_channel();
+        tokio::spawn(Self::run(http, gate, receiver));
+        Arc::new(Self { sender })
+    }
+
+    pub fn send(&self, channel_id: ChannelId, message: CreateMessage) {
+        self.enqueue(channel_id, message, None);
+    }
+
+    /// Sends a notification claimed in `notification_state` and marks its transitions sent once
+    /// Discord has it. If the send fails or the bot stops first they're left claimed, and the
+    /// notifier's `redrive` sends them again.
+    pub fn send_claimed(
+        &self,
+        pool: &Arc<PgPool>,
+        channel_id: ChannelId,
+        message: CreateMessage,
+        transitions: Vec<NotificationTransition>,
+    ) {
+        let (sent, receipt) = oneshot::channel();
+        self.enqueue(channel_id, message, Some(sent));
+
+        let pool = Arc::clone(pool);
+        tokio::spawn(async move {
+            // The failure has already been logged by run()
+            if receipt.await.is_err() {
+                return;
+            }
+            for transition in transitions {
+                if let Err(e) = mark_notification_sent(&pool, &transition).await {
+                    error!(
+                        "Failed to mark {} as sent: {}",
+                        transition.idempotency_key(),
+                        e
+                    );
+                }
+            }
+        });
+    }
+
+    fn enqueue(
+        &self,
+        channel_id: ChannelId,
+        message: CreateMessage,
+        sent: Option<oneshot::Sender<()>>,
+    ) {
+        if self
+            .sender
+            .send(QueuedMessage {
+                channel_id,
+                message,
+                sent,
+            })
+            .is_err()
+        {
+            error!(
+                "Message queue has stopped, dropping message for {}",
+                channel_id
+            );
+        }
+    }
+
+    async fn run(
+        http: Arc<Http>,
+        gate: Arc<RateLimitGate>,
+        mut receiver: UnboundedReceiver<QueuedMessage>,
+    ) {
+        while let Some(queued) = receiver.recv().await {
+            gate.wait().await;
+            debug!("Sending queued message to {}", queued.channel_id);
+
+            match queued.channel_id.send_message(&http, queued.message).await {
+                Ok(_) => {
+                    if let Some(sent) = queued.sent {
+                        let _ = sent.send(());
+                    }
+                }
+                Err(e) => error!(
+                    "Failed to send queued message to {}: {}",
+                    queued.channel_id, e
+                ),
+            }
+        }
+    }
+}
@@ -0,0 +1,466 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use fpl_common::types::{FixtureId, PlayerId};
+use fpl_db::events::ChangeEvent;
+use fpl_db::models::{NotificationKind, NotificationTransition, Watcher};
+use fpl_db::queries::fixture::get_fixture_name;
+use fpl_db::queries::notification_state::{
+    claim_notification, get_notification_state, mark_notification_sent,
+    reclaim_unsent_notifications,
+};
+use fpl_db::queries::player::get_player;
+use fpl_db::queries::watchlist::{get_fixture_watchers, get_player_watchers};
+use fpl_services::notifications::{ChangeHandler, MAX_SEND_ATTEMPTS, REDRIVE_LEASE};
+use itertools::Itertools;
+use serenity::all::{CreateEmbed, CreateMessage, Http, UserId};
+use sqlx::PgPool;
+use tracing::{debug, info};
+
+use crate::Error;
+
+use super::MessageQueue;
+
+// Price, status and fixture changes aren't tied to a game week
+const NO_GAME_WEEK: i16 = 0;
+
+pub struct WatchlistNotifications {
+    pool: Arc<PgPool>,
+    http: Arc<Http>,
+    queue: Arc<MessageQueue>,
+}
+
+impl WatchlistNotifications {
+    /*
+
+    Updates logic:
+
+    - The scraper publishes price, status/news, goal/card and reschedule events when it upserts players,
+      game week players and fixtures, and the ChangeListener hands them to handle_event()
+
+    - - Look up everyone watching the player (or a player at either club for a fixture)
+    - - Claim the change in notification_state with the user's discord id as the channel, so a change is
+        only DMed once even with multiple bot instances. It's claimed from what that user was last sent,
+        so the DM is built from the stored values and redrive() can build it again
+    - - DM each user through the MessageQueue so it shares the rate limit budget, and mark the claim sent
+        once Discord has it
+
+     */
+    pub fn new(pool: Arc<PgPool>, http: Arc<Http>, queue: Arc<MessageQueue>) -> Self {
+        Self { pool, http, queue }
+    }
+
+    /// Claims the change for `discord_id` from whatever they were last sent, falling back to the
+    /// event's `previous_value` if they've never been sent anything for it.
+    async fn claim(
+        &self,
+        discord_id: i64,
+        kind: NotificationKind,
+        subject_id: i32,
+        game_week_id: i16,
+        previous_value: &str,
+        value: &str,
+    ) -> Result<Option<NotificationTransition>, Error> {
+        let stored =
+            get_notification_state(&self.pool, discord_id, kind, subject_id, game_week_id).await?;
+        let previous_value = stored.unwrap_or_else(|| previous_value.to_string());
+        if previous_value == value {
+            return Ok(None);
+        }
+
+        let transition = NotificationTransition {
+            channel_id: discord_id,
+            kind,
+            subject_id,
+            game_week_id,
+            previous_value: Some(previous_value),
+            value: value.to_string(),
+        };
+        if !claim_notification(&self.pool, &transition).await? {
+            debug!("Already notified {}", transition.idempotency_key());
+            return Ok(None);
+        }
+        Ok(Some(transition))
+    }
+
+    async fn notify_player_watchers(
+        &self,
+        player_id: i16,
+        kind: NotificationKind,
+        game_week_id: i16,
+        previous_value: String,
+        value: String,
+    ) -> Result<(), Error> {
+        let watchers = get_player_watchers(&self.pool, PlayerId::from(player_id)).await?;
+        debug!(
+            "{} watchers of player {} for {}",
+            watchers.len(),
+            player_id,
+            kind
+        );
+
+        for watcher in watchers {
+            let Some(transition) = self
+                .claim(
+                    watcher.discord_id,
+                    kind,
+                    player_id.into(),
+                    game_week_id,
+                    &previous_value,
+                    &value,
+                )
+                .await?
+            else {
+                continue;
+            };
+
+            self.send_player_dm(&watcher.web_name, transition).await?;
+        }
+        Ok(())
+    }
+
+    async fn send_player_dm(
+        &self,
+        web_name: &str,
+        transition: NotificationTransition,
+    ) -> Result<(), Error> {
+        match player_embed(web_name, &transition) {
+            Some(embed) => self.send_dm(embed, transition).await,
+            // Nothing worth saying, e.g. a goal taken away, but what's stored is still up to date
+            None => Ok(mark_notification_sent(&self.pool, &transition).await?),
+        }
+    }
+
+    async fn notify_fixture_watchers(
+        &self,
+        fixture_id: i16,
+        previous: String,
+        current: String,
+    ) -> Result<(), Error> {
+        let fixture_id = FixtureId::try_from(fixture_id)?;
+        let watchers = get_fixture_watchers(&self.pool, fixture_id).await?;
+        if watchers.is_empty() {
+            return Ok(());
+        }
+        let fixture_name = get_fixture_name(&self.pool, fixture_id).await?;
+
+        // One DM per user, however many of their watched players are in the fixture
+        let mut by_user: BTreeMap<i64, Vec<Watcher>> = BTreeMap::new();
+        for watcher in watchers {
+            by_user.entry(watcher.discord_id).or_default().push(watcher);
+        }
+
+        for (discord_id, watchers) in by_user {
+            let Some(transition) = self
+                .claim(
+                    discord_id,
+                    NotificationKind::WatchFixture,
+                    i16::from(fixture_id).into(),
+                    NO_GAME_WEEK,
+                    &previous,
+                    &current,
+                )
+                .await?
+            else {
+                continue;
+            };
+
+            let embed = fixture_embed(&fixture_name, &watchers, &transition);
+            self.send_dm(embed, transition).await?;
+        }
+        Ok(())
+    }
+
+    async fn send_dm(
+        &self,
+        embed: CreateEmbed,
+        transition: NotificationTransition,
+    ) -> Result<(), Error> {
+        let discord_id = transition.channel_id;
+        info!("Sending watchlist DM to {}", discord_id);
+        let channel = UserId::new(discord_id as u64)
+            .create_dm_channel(&self.http)
+            .await?;
+        self.queue.send_claimed(
+            &self.pool,
+            channel.id,
+            CreateMessage::new().add_embed(embed),
+            vec![transition],
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ChangeHandler for WatchlistNotifications {
+    fn name(&self) -> &'static str {
+        "watchlist"
+    }
+
+    async fn handle_event(&self, event: &ChangeEvent) -> Result<(), Error> {
+        match event {
+            ChangeEvent::PlayerPriceChanged {
+                player_id,
+                previous_cost,
+                now_cost,
+            } => {
+                self.notify_player_watchers(
+                    *player_id,
+                    NotificationKind::WatchPrice,
+                    NO_GAME_WEEK,
+                    previous_cost.to_string(),
+                    now_cost.to_string(),
+                )
+                .await
+            }
+            ChangeEvent::PlayerStatusChanged {
+                player_id,
+                previous_status,
+                status,
+                previous_news,
+                news,
+            } => {
+                self.notify_player_watchers(
+                    *player_id,
+                    NotificationKind::WatchStatus,
+                    NO_GAME_WEEK,
+                    status_value(previous_status, previous_news),
+                    status_value(status, news),
+                )
+                .await
+            }
+            ChangeEvent::PlayerMatchStatsUpdated {
+                player_id,
+                game_week_id,
+                goals_scored,
+                previous_goals_scored,
+                yellow_cards,
+                previous_yellow_cards,
+                red_cards,
+                previous_red_cards,
+            } => {
+                let previous = MatchStats {
+                    goals_scored: *previous_goals_scored,
+                    yellow_cards: *previous_yellow_cards,
+                    red_cards: *previous_red_cards,
+                };
+                let current = MatchStats {
+                    goals_scored: *goals_scored,
+                    yellow_cards: *yellow_cards,
+                    red_cards: *red_cards,
+                };
+                // Goals and cards taken away after VAR aren't worth a DM
+                if match_stats_lines(&previous, &current).is_empty() {
+                    return Ok(());
+                }
+
+                self.notify_player_watchers(
+                    *player_id,
+                    NotificationKind::WatchMatchStats,
+                    *game_week_id,
+                    previous.to_value(),
+                    current.to_value(),
+                )
+                .await
+            }
+            ChangeEvent::FixtureRescheduled {
+                fixture_id,
+                previous_game_week_id,
+                game_week_id,
+                previous_kickoff_time,
+                kickoff_time,
+                ..
+            } => {
+                let previous = format_schedule(*previous_game_week_id, *previous_kickoff_time);
+                let current = format_schedule(*game_week_id, *kickoff_time);
+                self.notify_fixture_watchers(*fixture_id, previous, current)
+                    .await
+            }
+            ChangeEvent::FixtureUpdated { .. }
+            | ChangeEvent::PlayerPointsUpdated { .. }
+            | ChangeEvent::CupMatchDecided { .. }
+            | ChangeEvent::DeadlinePassed { .. }
+            | ChangeEvent::GameWeekFinished { .. } => Ok(()),
+        }
+    }
+
+    /// Re-sends DMs that were claimed but never confirmed sent.
+    async fn redrive(&self) -> Result<(), Error> {
+        let unsent = reclaim_unsent_notifications(
+            &self.pool,
+            &[
+                NotificationKind::WatchPrice,
+                NotificationKind::WatchStatus,
+                NotificationKind::WatchMatchStats,
+                NotificationKind::WatchFixture,
+            ],
+            None,
+            REDRIVE_LEASE.as_secs_f64(),
+            MAX_SEND_ATTEMPTS,
+        )
+        .await?;
+
+        for state in unsent {
+            let transition = state.transition()?;
+            match transition.kind {
+                NotificationKind::WatchFixture => {
+                    let fixture_id = FixtureId::try_from(i16::try_from(transition.subject_id)?)?;
+                    let watchers: Vec<Watcher> = get_fixture_watchers(&self.pool, fixture_id)
+                        .await?
+                        .into_iter()
+                        .filter(|watcher| watcher.discord_id == transition.channel_id)
+                        .collect();
+                    let fixture_name = get_fixture_name(&self.pool, fixture_id).await?;
+                    let embed = fixture_embed(&fixture_name, &watchers, &transition);
+                    self.send_dm(embed, transition).await?;
+                }
+                _ => {
+                    let player_id = PlayerId::from(i16::try_from(transition.subject_id)?);
+                    let Some(player) = get_player(&self.pool, player_id).await? else {
+                        continue;
+                    };
+                    self.send_player_dm(&player.web_name, transition).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The DM for a price, status or goal/card change, built from the claimed values.
+fn player_embed(web_name: &str, transition: &NotificationTransition) -> Option<CreateEmbed> {
+    let previous = transition.previous_value.as_deref()?;
+    let value = transition.value.as_str();
+
+    let embed = match transition.kind {
+        NotificationKind::WatchPrice => CreateEmbed::new()
+            .title(format!("💷 {web_name} price change"))
+            .description(format!(
+                "{} → **{}**",
+                format_cost(previous.parse().ok()?),
+                format_cost(value.parse().ok()?)
+            )),
+        NotificationKind::WatchStatus => {
+            let (previous_status, _) = previous.split_once(':')?;
+            let (status, news) = value.split_once(':')?;
+            let news = match news.is_empty() {
+                true => "No news",
+                false => news,
+            };
+            CreateEmbed::new()
+                .title(format!("📰 {web_name} status update"))
+                .description(format!(
+                    "{} → **{}**\n\n{news}",
+                    status_description(previous_status),
+                    status_description(status)
+                ))
+        }
+        NotificationKind::WatchMatchStats => {
+            let lines = match_stats_lines(
+                &MatchStats::from_value(previous)?,
+                &MatchStats::from_value(value)?,
+            );
+            if lines.is_empty() {
+                return None;
+            }
+            CreateEmbed::new()
+                .title(format!("🔔 {web_name}"))
+                .description(lines.join("\n"))
+        }
+        _ => return None,
+    };
+    Some(embed.color((252, 186, 3)))
+}
+
+fn fixture_embed(
+    fixture_name: &str,
+    watchers: &[Watcher],
+    transition: &NotificationTransition,
+) -> CreateEmbed {
+    let previous = transition.previous_value.as_deref().unwrap_or_default();
+    let current = &transition.value;
+    let players = watchers.iter().map(|w| w.web_name.as_str()).join(", ");
+    CreateEmbed::new()
+        .title(format!("📅 {fixture_name} rescheduled"))
+        .description(format!(
+            "{previous} → **{current}**\n\nWatched players: {players}"
+        ))
+        .color((252, 186, 3))
+}
+
+/// Readable text for the single letter `players.status` codes FPL uses.
+pub fn status_description(status: &str) -> &str {
+    match status {
+        "a" => "Available",
+        "d" => "Doubtful",
+        "i" => "Injured",
+        "s" => "Suspended",
+        "u" => "Unavailable",
+        "n" => "Not eligible",
+        other => other,
+    }
+}
+
+/// `now_cost` is in tenths of a million, e.g. 125 is £12.5m.
+pub fn format_cost(cost: i16) -> String {
+    format!("£{:.1}m", cost as f32 / 10.0)
+}
+
+fn status_value(status: &str, news: &str) -> String {
+    format!("{status}:{news}")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MatchStats {
+    goals_scored: i16,
+    yellow_cards: i16,
+    red_cards: i16,
+}
+
+impl MatchStats {
+    fn to_value(self) -> String {
+        format!(
+            "{}g{}y{}r",
+            self.goals_scored, self.yellow_cards, self.red_cards
+        )
+    }
+
+    fn from_value(value: &str) -> Option<Self> {
+        let (goals_scored, rest) = value.split_once('g')?;
+        let (yellow_cards, rest) = rest.split_once('y')?;
+        let red_cards = rest.strip_suffix('r')?;
+        Some(Self {
+            goals_scored: goals_scored.parse().ok()?,
+            yellow_cards: yellow_cards.parse().ok()?,
+            red_cards: red_cards.parse().ok()?,
+        })
+    }
+}
+
+fn match_stats_lines(previous: &MatchStats, current: &MatchStats) -> Vec<String> {
+    let mut lines = vec![];
+    match current.goals_scored - previous.goals_scored {
+        1 => lines.push("⚽ Goal!".to_string()),
+        goals if goals > 1 => lines.push(format!("⚽ {goals} goals!")),
+        _ => {}
+    }
+    if current.yellow_cards > previous.yellow_cards {
+        lines.push("🟨 Yellow card".to_string());
+    }
+    if current.red_cards > previous.red_cards {
+        lines.push("🟥 Red card".to_string());
+    }
+    lines
+}
+
+fn format_schedule(game_week_id: Option<i16>, kickoff_time: Option<DateTime<Utc>>) -> String {
+    let game_week = match game_week_id {
+        Some(game_week_id) => format!("GW{game_week_id}"),
+        None => "Unscheduled".to_string(),
+    };
+    match kickoff_time {
+        Some(kickoff_time) => format!("{game_week} ({})", kickoff_time.format("%a %d %b %H:%M")),
+        None => game_week,
+    }
+}
@@ -0,0 +1,92 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use fpl_api::session::FplSession;
+
+use crate::Error;
+
+const SESSION_KEY_ENV: &str = "FPL_SESSION_KEY";
+
+/// FPL session cookies are encrypted with AES-256-GCM before being stored, using a 32 byte key
+/// from `FPL_SESSION_KEY` (base64). Without the key nobody can link an account.
+fn cipher() -> Result<Aes256Gcm, Error> {
+    let encoded = std::env::var(SESSION_KEY_ENV)
+        .map_err(|_| format!("{} must be set to link FPL accounts", SESSION_KEY_ENV))?;
+    cipher_from_key(&encoded)
+}
+
+fn cipher_from_key(encoded: &str) -> Result<Aes256Gcm, Error> {
+    let key = STANDARD.decode(encoded.trim())?;
+    if key.len() != 32 {
+        return Err(format!("{} must be 32 bytes, base64 encoded", SESSION_KEY_ENV).into());
+    }
+
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))
+}
+
+/// Returns `(ciphertext, nonce)`
+pub fn encrypt_session(session: &FplSession) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    encrypt(&cipher()?, session)
+}
+
+pub fn decrypt_session(ciphertext: &[u8], nonce: &[u8]) -> Result<FplSession, Error> {
+    decrypt(&cipher()?, ciphertext, nonce)
+}
+
+fn encrypt(cipher: &Aes256Gcm, session: &FplSession) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, session.cookie().as_bytes())
+        .map_err(|_| "Failed to encrypt FPL session")?;
+
+    Ok((ciphertext, nonce.to_vec()))
+}
+
+fn decrypt(cipher: &Aes256Gcm, ciphertext: &[u8], nonce: &[u8]) -> Result<FplSession, Error> {
+    if nonce.len() != 12 {
+        return Err("Stored FPL session has an invalid nonce".into());
+    }
+
+    let cookie = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Failed to decrypt FPL session")?;
+
+    Ok(FplSession::from_cookie(String::from_utf8(cookie)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_cipher(byte: u8) -> Aes256Gcm {
+        cipher_from_key(&STANDARD.encode([byte; 32])).unwrap()
+    }
+
+    #[test]
+    fn test_session_round_trip() {
+        let cipher = test_cipher(7);
+        let session = FplSession::from_cookie("pl_profile=abc123; sessionid=xyz");
+
+        let (ciphertext, nonce) = encrypt(&cipher, &session).unwrap();
+        assert_ne!(ciphertext, session.cookie().as_bytes());
+        assert_eq!(
+            decrypt(&cipher, &ciphertext, &nonce).unwrap().cookie(),
+            session.cookie()
+        );
+    }
+
+    #[test]
+    fn test_decrypt_rejects_wrong_key_and_nonce() {
+        let session = FplSession::from_cookie("sessionid=xyz");
+        let (ciphertext, nonce) = encrypt(&test_cipher(7), &session).unwrap();
+
+        assert!(decrypt(&test_cipher(8), &ciphertext, &nonce).is_err());
+        assert!(decrypt(&test_cipher(7), &ciphertext, &nonce[..8]).is_err());
+    }
+
+    #[test]
+    fn test_key_must_be_32_bytes() {
+        assert!(cipher_from_key(&STANDARD.encode([7u8; 16])).is_err());
+        assert!(cipher_from_key("not base64!").is_err());
+    }
+}
@@ -9,6 +9,7 @@ use std::{
 use tracing::{debug, warn};
 
 use crate::constants::text_response::MAX_ROWS_PER_PAGE;
+use crate::images::RenderedImage;
 use crate::{Context, Error};
 pub trait State {}
 pub trait Sendable: State {}
@@ -77,10 +78,17 @@ impl EmbedState for SuccessState {
     }
 }
 
+#[derive(Clone, Debug)]
+pub enum EmbedImage {
+    Path(PathBuf),
+    /// Attached straight from memory, without reading it back from disk
+    Rendered(RenderedImage),
+}
+
 #[derive(Clone, Debug)]
 pub struct EmbedPage {
     pub rows: Vec<String>,
-    pub image: Option<PathBuf>,
+    pub image: Option<EmbedImage>,
 }
 
 impl EmbedPage {
@@ -104,7 +112,12 @@ impl EmbedPage {
     }
 
     pub fn with_image(mut self, image_path: impl AsRef<Path>) -> Self {
-        self.image = Some(image_path.as_ref().to_path_buf());
+        self.image = Some(EmbedImage::Path(image_path.as_ref().to_path_buf()));
+        self
+    }
+
+    pub fn with_rendered_image(mut self, image: RenderedImage) -> Self {
+        self.image = Some(EmbedImage::Rendered(image));
         self
     }
 
@@ -288,6 +301,50 @@ impl<'a, S: ReadyForSend + EmbedState + Sync + Send> Embed<'a, S> {
         Ok(self.transition())
     }
 
+    /// Sends the first page with confirm and cancel buttons and waits for the command's author to
+    /// press one. Returns false if they cancel or don't answer in time, the buttons are removed by
+    /// the next send.
+    pub async fn send_for_confirmation(mut self) -> Result<(Embed<'a, SentState>, bool), Error> {
+        if self.pages.is_empty() {
+            self.pages.push(EmbedPage::new());
+        }
+        let (embed, attachments) = self.prepare_embed_with_attachments(0).await?;
+        let components = self.create_confirmation_buttons();
+
+        if self.sent {
+            self.http
+                .edit_original_interaction_response(
+                    &self.token,
+                    &serenity::json::json!({
+                        "embeds": [embed],
+                        "components": [components]
+                    }),
+                    attachments,
+                )
+                .await?;
+        } else {
+            let mut response_message = serenity::builder::CreateInteractionResponseMessage::new()
+                .embed(embed)
+                .components(vec![components]);
+
+            if !attachments.is_empty() {
+                response_message = response_message.files(attachments);
+            }
+
+            self.interaction
+                .create_response(
+                    &self.http,
+                    serenity::builder::CreateInteractionResponse::Message(response_message),
+                )
+                .await?;
+
+            self.sent = true;
+        }
+
+        let confirmed = self.handle_confirmation().await?;
+        Ok((self.transition(), confirmed))
+    }
+
     async fn send_or_edit_single_page(&mut self) -> Result<(), Error> {
         let (embed, attachments) = self.prepare_embed_with_attachments(0).await?;
 
@@ -401,6 +458,47 @@ impl<'a, S: ReadyForSend + EmbedState + Sync + Send> Embed<'a, S> {
         Ok(())
     }
 
+    async fn handle_confirmation(&self) -> Result<bool, Error> {
+        let ctx_id = self.ctx_id;
+        let author_id = self.ctx.author().id;
+        let confirm_button_id = format!("{}confirm", ctx_id);
+
+        let press = serenity::collector::ComponentInteractionCollector::new(self.ctx)
+            .filter(move |press| {
+                press.data.custom_id.starts_with(&ctx_id.to_string()) && press.user.id == author_id
+            })
+            .timeout(std::time::Duration::from_secs(300))
+            .await;
+
+        let Some(press) = press else {
+            debug!("No confirmation received ({})", ctx_id);
+            return Ok(false);
+        };
+
+        press
+            .create_response(
+                &self.ctx.serenity_context(),
+                serenity::builder::CreateInteractionResponse::Acknowledge,
+            )
+            .await?;
+
+        Ok(press.data.custom_id == confirm_button_id)
+    }
+
+    fn create_confirmation_buttons(&self) -> serenity::builder::CreateActionRow {
+        let confirm_button_id = format!("{}confirm", self.ctx_id);
+        let cancel_button_id = format!("{}cancel", self.ctx_id);
+
+        serenity::builder::CreateActionRow::Buttons(vec![
+            serenity::builder::CreateButton::new(&confirm_button_id)
+                .label("Confirm")
+                .style(serenity::ButtonStyle::Success),
+            serenity::builder::CreateButton::new(&cancel_button_id)
+                .label("Cancel")
+                .style(serenity::ButtonStyle::Danger),
+        ])
+    }
+
     fn create_pagination_buttons(&self) -> serenity::builder::CreateActionRow {
         let prev_button_id = format!("{}prev", self.ctx_id);
         let next_button_id = format!("{}next", self.ctx_id);
@@ -423,20 +521,31 @@ impl<'a, S: ReadyForSend + EmbedState + Sync + Send> Embed<'a, S> {
         let mut embed = self.create_base_embed(&page.content(), page_index);
         let mut attachments = Vec::new();
 
-        if let Some(image_path) = &page.image {
-            match serenity::builder::CreateAttachment::path(image_path).await {
-                Ok(attachment) => {
-                    embed = embed.image(format!("attachment://{}", attachment.filename));
-                    attachments.push(attachment);
-                }
-                Err(e) => {
-                    warn!(
-                        "Failed to create attachment: {} - {}",
-                        image_path.display(),
-                        e
-                    );
+        match &page.image {
+            Some(EmbedImage::Path(image_path)) => {
+                match serenity::builder::CreateAttachment::path(image_path).await {
+                    Ok(attachment) => {
+                        embed = embed.image(format!("attachment://{}", attachment.filename));
+                        attachments.push(attachment);
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Failed to create attachment: {} - {}",
+                            image_path.display(),
+                            e
+                        );
+                    }
                 }
             }
+            Some(EmbedImage::Rendered(image)) => {
+                let attachment = serenity::builder::CreateAttachment::bytes(
+                    image.png.to_vec(),
+                    image.file_name(),
+                );
+                embed = embed.image(format!("attachment://{}", attachment.filename));
+                attachments.push(attachment);
+            }
+            None => {}
         }
 
         Ok((embed, attachments))
@@ -0,0 +1,194 @@
+use fpl_common::types::LeagueId;
+use fpl_db::models::GuildMember;
+use fpl_db::queries::guild::{
+    is_guild_member, is_league_visible_in_guild, remove_guild, remove_guild_member,
+    sync_guild_members, upsert_guild_members,
+};
+use poise::serenity_prelude as serenity;
+use poise::CreateReply;
+use serenity::all::{ChunkGuildFilter, FullEvent, Member, ResolvedOption, ResolvedValue};
+use tracing::{debug, info};
+
+use crate::{Context, Data, Error};
+
+const UNSCOPED_COMMANDS: [&str; 2] = ["homeleague", "league import"];
+
+fn to_guild_member(member: &Member) -> GuildMember {
+    GuildMember::new(
+        member.guild_id.get() as i64,
+        member.user.id.get() as i64,
+        member.user.name.clone(),
+        member.display_name().to_string(),
+    )
+}
+
+/// Keeps `guild_members` in step with the gateway, using the `GUILD_MEMBERS` intent.
+///
+/// Small guilds come with their full member list on guild create, so they're synced outright.
+/// Bigger ones are requested in chunks, each upserted as it arrives and the guild synced once
+/// the last one is in.
+pub async fn handle_guild_member_event(
+    ctx: &serenity::Context,
+    event: &FullEvent,
+    data: &Data,
+) -> Result<(), Error> {
+    match event {
+        FullEvent::GuildCreate { guild, .. } => {
+            let guild_id = guild.id.get() as i64;
+            let members: Vec<GuildMember> = guild.members.values().map(to_guild_member).collect();
+
+            if members.len() as u64 >= guild.member_count {
+                info!("Syncing {} members for guild {}", members.len(), guild_id);
+                sync_guild_members(&data.pool, guild_id, &members).await?;
+            } else {
+                info!(
+                    "Guild {} has {} members but only {} were sent, requesting the rest",
+                    guild_id,
+                    guild.member_count,
+                    members.len()
+                );
+                upsert_guild_members(&data.pool, &members).await?;
+                // Anything left over from an earlier request would be counted twice
+                data.member_chunks
+                    .lock()
+                    .unwrap()
+                    .insert(guild_id, (0, Vec::new()));
+                ctx.shard
+                    .chunk_guild(guild.id, None, false, ChunkGuildFilter::None, None);
+            }
+        }
+        FullEvent::GuildMembersChunk { chunk } => {
+            debug!(
+                "Received member chunk {}/{} for guild {}",
+                chunk.chunk_index + 1,
+                chunk.chunk_count,
+                chunk.guild_id
+            );
+            let guild_id = chunk.guild_id.get() as i64;
+            let members: Vec<GuildMember> = chunk.members.values().map(to_guild_member).collect();
+            upsert_guild_members(&data.pool, &members).await?;
+
+            let complete = {
+                let mut pending = data.member_chunks.lock().unwrap();
+                let (received, all_members) = pending.entry(guild_id).or_default();
+                *received += 1;
+                all_members.extend(members);
+                if *received < chunk.chunk_count {
+                    None
+                } else {
+                    pending
+                        .remove(&guild_id)
+                        .map(|(_, all_members)| all_members)
+                }
+            };
+            if let Some(all_members) = complete {
+                info!(
+                    "Syncing {} members for guild {}",
+                    all_members.len(),
+                    guild_id
+                );
+                sync_guild_members(&data.pool, guild_id, &all_members).await?;
+            }
+        }
+        FullEvent::GuildMemberAddition { new_member } => {
+            upsert_guild_members(&data.pool, &[to_guild_member(new_member)]).await?;
+        }
+        FullEvent::GuildMemberUpdate { event, .. } => {
+            let display_name = event
+                .nick
+                .as_ref()
+                .or(event.user.global_name.as_ref())
+                .unwrap_or(&event.user.name);
+            let member = GuildMember::new(
+                event.guild_id.get() as i64,
+                event.user.id.get() as i64,
+                event.user.name.clone(),
+                display_name.clone(),
+            );
+            upsert_guild_members(&data.pool, &[member]).await?;
+        }
+        FullEvent::GuildMemberRemoval { guild_id, user, .. } => {
+            remove_guild_member(&data.pool, guild_id.get() as i64, user.id.get() as i64).await?;
+        }
+        // Unavailable means an outage, not that the bot was removed
+        FullEvent::GuildDelete { incomplete, .. } if !incomplete.unavailable => {
+            info!(
+                "Removed from guild {}, forgetting its members",
+                incomplete.id
+            );
+            remove_guild(&data.pool, incomplete.id.get() as i64).await?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// The league or user a command was asked about, read from its `league_id` option or the
+/// `league_or_user` pair.
+enum Subject {
+    League(i64),
+    User(i64),
+}
+
+fn get_subject(args: &[ResolvedOption]) -> Option<Subject> {
+    let find = |name: &str| {
+        args.iter()
+            .find(|opt| opt.name == name)
+            .map(|opt| &opt.value)
+    };
+
+    if let Some(ResolvedValue::Integer(league_id)) = find("league_id") {
+        return Some(Subject::League(*league_id));
+    }
+
+    let (Some(ResolvedValue::String(kind)), Some(ResolvedValue::String(value))) =
+        (find("league_or_user"), find("league_or_user_value"))
+    else {
+        return None;
+    };
+    let value = value.parse::<i64>().ok()?;
+    match *kind {
+        "League" => Some(Subject::League(value)),
+        "User" => Some(Subject::User(value)),
+        _ => None,
+    }
+}
+
+/// Framework wide check that stops a command run in a server from showing a mini league or
+/// user from a different server. Commands run in DMs aren't scoped, and `UNSCOPED_COMMANDS` are
+/// how a league gets into a server in the first place.
+pub async fn check_league_in_guild(ctx: Context<'_>) -> Result<bool, Error> {
+    let (Context::Application(app_ctx), Some(guild_id)) = (ctx, ctx.guild_id()) else {
+        return Ok(true);
+    };
+    if UNSCOPED_COMMANDS.contains(&ctx.command().qualified_name.as_str()) {
+        return Ok(true);
+    }
+    let Some(subject) = get_subject(app_ctx.args) else {
+        return Ok(true);
+    };
+
+    let pool = &ctx.data().pool;
+    let guild_id = guild_id.get() as i64;
+    let (visible, message) = match subject {
+        Subject::League(league_id) => (
+            is_league_visible_in_guild(pool, guild_id, LeagueId::new(league_id as i32)).await?,
+            "That mini league isn't linked to anyone in this server.",
+        ),
+        Subject::User(discord_id) => (
+            is_guild_member(pool, guild_id, discord_id).await?,
+            "That user isn't a member of this server.",
+        ),
+    };
+
+    if !visible {
+        debug!(
+            "Refusing {} in guild {}, subject isn't visible there",
+            ctx.command().name,
+            guild_id
+        );
+        ctx.send(CreateReply::default().content(message).ephemeral(true))
+            .await?;
+    }
+    Ok(visible)
+}
@@ -1,3 +1,6 @@
 pub mod common;
+pub mod credentials;
 pub mod embed;
+pub mod guild;
 pub mod macros;
+pub mod theme;
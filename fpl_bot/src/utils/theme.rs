@@ -0,0 +1,19 @@
+use fpl_services::images::Theme;
+use fpl_services::theme::get_theme;
+use tracing::warn;
+
+use crate::Context;
+
+/// The theme to render the caller's images with. A broken preference shouldn't stop the image
+/// being sent, so errors fall back to the default theme.
+pub async fn get_caller_theme(ctx: Context<'_>) -> Theme {
+    let discord_id = i64::from(ctx.author().id);
+    let guild_id = ctx.guild_id().map(|id| id.get() as i64);
+    match get_theme(&ctx.data().pool, Some(discord_id), guild_id).await {
+        Ok(theme) => theme,
+        Err(e) => {
+            warn!("Error calling get_theme: {}", e);
+            Theme::default()
+        }
+    }
+}
@@ -0,0 +1,459 @@
+mod output;
+
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand};
+use fpl_common::types::{ClubId, GameWeekId, LeagueId, PlayerId, TeamId};
+use fpl_db::queries::game_week::get_current_game_week_id;
+use fpl_db::queries::mini_league::{get_league_name, get_team_ids_from_league_id};
+use fpl_services::export::{ExportDataset, ExportFilter, ExportFormat};
+use fpl_services::images::{
+    PlayerInfo, TableRenderer, TeamData, TeamRenderer, Theme, ThemeName, TransfersRenderer,
+};
+use fpl_services::table::{build_table_data, get_points, TableView};
+use fpl_services::team::get_team_data;
+use fpl_services::transfers::get_transfers;
+use fpl_services::{chips, hits, whohas};
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::PgPool;
+
+use output::{print_rows, write_image, Align, Style, Table};
+
+type CliResult = Result<(), Box<dyn std::error::Error>>;
+
+#[derive(Parser)]
+#[command(
+    name = "fpl",
+    about = "Query league data from the local database, like the Discord commands do"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Colours for images written with --out: light, dark or high_contrast
+    #[arg(long, global = true, default_value = "light")]
+    theme: ThemeName,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// League standings with live points
+    Table {
+        league_id: i32,
+        /// Rank by this game week's points instead of overall
+        #[arg(long)]
+        week: bool,
+        /// Write the rendered table to a .svg or .png instead of printing it
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// A team's picks for a game week that's passed its deadline
+    Team {
+        team_id: TeamId,
+        /// Defaults to the current game week
+        #[arg(long)]
+        game_week: Option<GameWeekId>,
+        /// Write the rendered team sheet to a .svg or .png instead of printing it
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Draw club shirts instead of player photos in the rendered team sheet
+        #[arg(long, requires = "out")]
+        shirts: bool,
+    },
+    /// Chips played by a team or everyone in a league
+    Chips {
+        #[command(flatten)]
+        target: Target,
+    },
+    /// Points hits taken by a team or everyone in a league
+    Hits {
+        #[command(flatten)]
+        target: Target,
+    },
+    /// Transfers made by a team or everyone in a league
+    Transfers {
+        #[command(flatten)]
+        target: Target,
+        /// Defaults to the current game week
+        #[arg(long)]
+        game_week: Option<GameWeekId>,
+        /// Write the rendered transfers to a .svg or .png instead of printing them
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Who in a league owns a player, or players from a club
+    Whohas {
+        league_id: i32,
+        #[arg(long, required_unless_present = "club", conflicts_with = "club")]
+        player: Option<i16>,
+        #[arg(long)]
+        club: Option<i16>,
+        /// Defaults to the current game week
+        #[arg(long)]
+        game_week: Option<GameWeekId>,
+    },
+    /// Standings, picks, transfers, chips or player stats as a CSV, JSON or Parquet file
+    Export {
+        /// standings, picks, transfers, chips or player_stats
+        dataset: ExportDataset,
+        /// csv, json or parquet
+        #[arg(long, default_value = "csv")]
+        format: ExportFormat,
+        /// Required for standings
+        #[arg(long)]
+        league: Option<i32>,
+        #[arg(long)]
+        team: Option<TeamId>,
+        /// First game week, defaults to 1
+        #[arg(long)]
+        from: Option<GameWeekId>,
+        /// Last game week, defaults to 38
+        #[arg(long)]
+        to: Option<GameWeekId>,
+        /// Defaults to a name made from the filters in the current directory
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+}
+
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+struct Target {
+    #[arg(long)]
+    league: Option<i32>,
+    #[arg(long)]
+    team: Option<TeamId>,
+}
+
+async fn game_week_or_current(
+    pool: &PgPool,
+    game_week: Option<GameWeekId>,
+) -> Result<GameWeekId, sqlx::Error> {
+    match game_week {
+        Some(game_week) => Ok(game_week),
+        None => get_current_game_week_id(pool).await,
+    }
+}
+
+async fn table(
+    pool: &PgPool,
+    league_id: LeagueId,
+    week: bool,
+    out: Option<PathBuf>,
+    theme: Theme,
+) -> CliResult {
+    let view = match week {
+        true => TableView::CurrentGameWeek,
+        false => TableView::Overall,
+    };
+    let data = build_table_data(get_points(pool, league_id).await?, view, None);
+
+    if let Some(path) = out {
+        return write_image(
+            &TableRenderer::default()
+                .with_theme(theme)
+                .render_svg(data)?,
+            &path,
+        );
+    }
+
+    let style = Style::detect();
+    let league_name = get_league_name(pool, league_id).await?;
+    println!(
+        "{}",
+        style_title(&format!("{} — {}", data.title, league_name), style)
+    );
+    let mut table = Table::new(&["#", "Team", "Manager", "Points", "Live"], style)
+        .align(0, Align::Right)
+        .align(3, Align::Right)
+        .align(4, Align::Right);
+    for (rank, row) in data.rows.into_iter().enumerate() {
+        table.add_row(vec![
+            (rank + 1).to_string(),
+            row.name,
+            row.team_name,
+            row.confirmed_points.to_string(),
+            row.live_points.to_string(),
+        ]);
+    }
+    println!("{table}");
+    Ok(())
+}
+
+fn add_players(table: &mut Table, position: &str, players: &[PlayerInfo]) {
+    for player in players {
+        let armband = match (player.captain, player.vice_captain) {
+            (true, _) => "C",
+            (_, true) => "VC",
+            _ => "",
+        };
+        let games = player
+            .games
+            .iter()
+            .map(|game| game.pretty_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        table.add_row(vec![
+            position.to_string(),
+            player.name.clone(),
+            armband.to_string(),
+            games,
+        ]);
+    }
+}
+
+fn print_team(data: &TeamData, style: Style) {
+    println!(
+        "{}",
+        style_title(
+            &format!("{} — Gameweek {}", data.team_name, data.game_week),
+            style
+        )
+    );
+    print!(
+        "{} points, GW rank {}, overall rank {}",
+        data.points, data.gw_rank, data.overall_rank
+    );
+    match data.chip {
+        Some(chip) => println!(", {} active", chip.pretty_name()),
+        None => println!(),
+    }
+
+    let mut table = Table::new(&["Pos", "Player", "", "Game"], style);
+    add_players(&mut table, "GK", std::slice::from_ref(&data.goalkeeper));
+    add_players(&mut table, "DEF", &data.defenders);
+    add_players(&mut table, "MID", &data.midfielders);
+    add_players(&mut table, "FWD", &data.forwards);
+    if let Some(assman) = &data.assman {
+        add_players(&mut table, "MGR", std::slice::from_ref(assman));
+    }
+    add_players(&mut table, "SUB", &data.bench);
+    println!("{table}");
+
+    if !data.transfers.is_empty() {
+        let mut transfers = Table::new(&["Out", "In"], style);
+        for transfer in &data.transfers {
+            transfers.add_row(vec![
+                format!(
+                    "{} (£{:.1}m)",
+                    transfer.player_out_name, transfer.player_out_cost
+                ),
+                format!(
+                    "{} (£{:.1}m)",
+                    transfer.player_in_name, transfer.player_in_cost
+                ),
+            ]);
+        }
+        println!("{transfers}");
+    }
+}
+
+async fn team(
+    pool: &PgPool,
+    team_id: TeamId,
+    game_week: Option<GameWeekId>,
+    out: Option<PathBuf>,
+    shirts: bool,
+    theme: Theme,
+) -> CliResult {
+    let game_week_id = game_week_or_current(pool, game_week).await?;
+    let data = get_team_data(pool, i32::from(team_id), i16::from(game_week_id)).await?;
+
+    match out {
+        Some(path) => write_image(
+            &TeamRenderer::default()
+                .with_theme(theme)
+                .with_shirts(shirts)
+                .render_svg(data)?,
+            &path,
+        ),
+        None => {
+            print_team(&data, Style::detect());
+            Ok(())
+        }
+    }
+}
+
+async fn transfers(
+    pool: &PgPool,
+    target: Target,
+    game_week: Option<GameWeekId>,
+    out: Option<PathBuf>,
+    theme: Theme,
+) -> CliResult {
+    let game_week_id = game_week_or_current(pool, game_week).await?;
+    let team_ids = match (target.league, target.team) {
+        (Some(league_id), _) => get_team_ids_from_league_id(pool, LeagueId::new(league_id)).await?,
+        (_, Some(team_id)) => vec![i32::from(team_id)],
+        (None, None) => unreachable!("clap requires one of --league or --team"),
+    };
+    let data = get_transfers(pool, &team_ids, i16::from(game_week_id)).await?;
+
+    if let Some(path) = out {
+        return write_image(
+            &TransfersRenderer::default()
+                .with_theme(theme)
+                .render_svg(data)?,
+            &path,
+        );
+    }
+
+    let style = Style::detect();
+    println!(
+        "{}",
+        style_title(&format!("Transfers in Gameweek {game_week_id}"), style)
+    );
+    if data.user_to_transfers.is_empty() {
+        println!("No transfers");
+        return Ok(());
+    }
+
+    let mut managers: Vec<_> = data.user_to_transfers.into_iter().collect();
+    managers.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut table = Table::new(&["Manager", "Out", "", "In", ""], style);
+    for (manager, transfers) in managers {
+        // `Transfers` pairs are stored as (out, in)
+        for (player_out, player_in) in transfers {
+            let game = |player: &PlayerInfo| {
+                player
+                    .games
+                    .iter()
+                    .map(|game| game.pretty_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            table.add_row(vec![
+                manager.clone(),
+                player_out.name.clone(),
+                game(&player_out),
+                player_in.name.clone(),
+                game(&player_in),
+            ]);
+        }
+    }
+    println!("{table}");
+    Ok(())
+}
+
+async fn export(
+    pool: &PgPool,
+    dataset: ExportDataset,
+    format: ExportFormat,
+    filter: ExportFilter,
+    out: Option<PathBuf>,
+) -> CliResult {
+    let export = fpl_services::export::export(pool, dataset, &filter, format).await?;
+    let path = out.unwrap_or_else(|| PathBuf::from(&export.file_name));
+    std::fs::write(&path, &export.bytes)?;
+    println!("Wrote {} rows to {}", export.rows, path.display());
+    Ok(())
+}
+
+fn style_title(title: &str, style: Style) -> String {
+    output::markdown(&format!("**{title}**"), style)
+}
+
+#[tokio::main]
+async fn main() -> CliResult {
+    let cli = Cli::parse();
+
+    dotenv::from_filename("../.env").ok();
+    let database_url =
+        std::env::var("DATABASE_URL").expect("DATABASE_URL must be set in .env file");
+
+    let options = PgConnectOptions::from_str(&database_url)?.application_name("fpl_cli");
+    let pool = PgPoolOptions::new()
+        .max_connections(2)
+        .acquire_timeout(Duration::from_secs(5))
+        .connect_with(options)
+        .await?;
+
+    let theme = cli.theme.theme();
+    match cli.command {
+        Command::Table {
+            league_id,
+            week,
+            out,
+        } => table(&pool, LeagueId::new(league_id), week, out, theme).await?,
+        Command::Team {
+            team_id,
+            game_week,
+            out,
+            shirts,
+        } => team(&pool, team_id, game_week, out, shirts, theme).await?,
+        Command::Chips { target } => {
+            let rows = match (target.league, target.team) {
+                (Some(league_id), _) => {
+                    chips::get_league_chips(&pool, LeagueId::new(league_id)).await?
+                }
+                (_, Some(team_id)) => chips::get_team_chips(&pool, team_id).await?,
+                (None, None) => unreachable!("clap requires one of --league or --team"),
+            };
+            match rows.is_empty() {
+                true => println!("No chips played"),
+                false => print_rows(&rows, Style::detect()),
+            }
+        }
+        Command::Hits { target } => {
+            let rows = match (target.league, target.team) {
+                (Some(league_id), _) => {
+                    hits::get_league_hits(&pool, LeagueId::new(league_id)).await?
+                }
+                (_, Some(team_id)) => hits::get_team_hits(&pool, team_id).await?,
+                (None, None) => unreachable!("clap requires one of --league or --team"),
+            };
+            print_rows(&rows, Style::detect());
+        }
+        Command::Transfers {
+            target,
+            game_week,
+            out,
+        } => transfers(&pool, target, game_week, out, theme).await?,
+        Command::Whohas {
+            league_id,
+            player,
+            club,
+            game_week,
+        } => {
+            let league_id = LeagueId::new(league_id);
+            let game_week_id = game_week_or_current(&pool, game_week).await?;
+            let rows = match (player, club) {
+                (Some(player_id), _) => {
+                    whohas::get_whohas_player(
+                        &pool,
+                        league_id,
+                        game_week_id,
+                        PlayerId::new(player_id),
+                    )
+                    .await?
+                }
+                (_, Some(club_id)) => {
+                    whohas::get_whohas_club(&pool, league_id, game_week_id, ClubId::new(club_id)?)
+                        .await?
+                }
+                (None, None) => unreachable!("clap requires one of --player or --club"),
+            };
+            print_rows(&rows, Style::detect());
+        }
+        Command::Export {
+            dataset,
+            format,
+            league,
+            team,
+            from,
+            to,
+            out,
+        } => {
+            let filter = ExportFilter {
+                league_id: league.map(LeagueId::new),
+                team_id: team,
+                from_game_week: from,
+                to_game_week: to,
+            };
+            export(&pool, dataset, format, filter, out).await?
+        }
+    }
+
+    Ok(())
+}
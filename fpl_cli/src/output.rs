@@ -0,0 +1,221 @@
+use std::fmt;
+use std::io::IsTerminal;
+use std::path::Path;
+
+use fpl_services::images::save_png;
+
+const BOLD: &str = "\x1b[1m";
+const UNDERLINE: &str = "\x1b[4m";
+const RESET: &str = "\x1b[0m";
+
+/// How text is styled on the way out. ANSI codes only when stdout is a terminal and
+/// `NO_COLOR` isn't set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    Ansi,
+    Plain,
+}
+
+impl Style {
+    pub fn detect() -> Self {
+        if std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none() {
+            Style::Ansi
+        } else {
+            Style::Plain
+        }
+    }
+
+    fn bold(&self, text: &str) -> String {
+        match self {
+            Style::Ansi => format!("{BOLD}{text}{RESET}"),
+            Style::Plain => text.to_string(),
+        }
+    }
+}
+
+/// Turns the Discord flavoured Markdown in service rows (`**bold**`, `__underline__`) into
+/// terminal styling, or drops the markers for plain output.
+pub fn markdown(row: &str, style: Style) -> String {
+    let mut out = String::with_capacity(row.len());
+    let (mut bold, mut underline) = (false, false);
+    let mut rest = row;
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("**") {
+            bold = !bold;
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("__") {
+            underline = !underline;
+            rest = after;
+        } else {
+            let c = rest.chars().next().expect("rest isn't empty");
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        if style == Style::Ansi {
+            out.push_str(RESET);
+            if bold {
+                out.push_str(BOLD);
+            }
+            if underline {
+                out.push_str(UNDERLINE);
+            }
+        }
+    }
+
+    out
+}
+
+pub fn print_rows(rows: &[String], style: Style) {
+    for row in rows {
+        println!("{}", markdown(row, style));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// A box drawn table sized to its contents.
+pub struct Table {
+    headers: Vec<String>,
+    align: Vec<Align>,
+    rows: Vec<Vec<String>>,
+    style: Style,
+}
+
+impl Table {
+    pub fn new(headers: &[&str], style: Style) -> Self {
+        Self {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            align: vec![Align::Left; headers.len()],
+            rows: Vec::new(),
+            style,
+        }
+    }
+
+    pub fn align(mut self, column: usize, align: Align) -> Self {
+        self.align[column] = align;
+        self
+    }
+
+    pub fn add_row(&mut self, row: Vec<String>) {
+        debug_assert_eq!(row.len(), self.headers.len());
+        self.rows.push(row);
+    }
+
+    fn widths(&self) -> Vec<usize> {
+        self.headers
+            .iter()
+            .enumerate()
+            .map(|(i, header)| {
+                self.rows
+                    .iter()
+                    .map(|row| row[i].chars().count())
+                    .chain([header.chars().count()])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    fn border(widths: &[usize], left: char, middle: char, right: char) -> String {
+        let inner = widths
+            .iter()
+            .map(|w| "─".repeat(w + 2))
+            .collect::<Vec<_>>()
+            .join(&middle.to_string());
+        format!("{left}{inner}{right}")
+    }
+
+    fn line(&self, cells: &[String], widths: &[usize], bold: bool) -> String {
+        let cells = cells
+            .iter()
+            .zip(widths)
+            .zip(&self.align)
+            .map(|((cell, width), align)| {
+                let padded = match align {
+                    Align::Left => format!("{cell:<width$}"),
+                    Align::Right => format!("{cell:>width$}"),
+                };
+                match bold {
+                    true => self.style.bold(&padded),
+                    false => padded,
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" │ ");
+        format!("│ {cells} │")
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let widths = self.widths();
+        writeln!(f, "{}", Self::border(&widths, '┌', '┬', '┐'))?;
+        writeln!(f, "{}", self.line(&self.headers, &widths, true))?;
+        writeln!(f, "{}", Self::border(&widths, '├', '┼', '┤'))?;
+        for row in &self.rows {
+            writeln!(f, "{}", self.line(row, &widths, false))?;
+        }
+        write!(f, "{}", Self::border(&widths, '└', '┴', '┘'))
+    }
+}
+
+/// Writes a rendered image to `path`, as SVG or PNG depending on its extension.
+pub fn write_image(svg: &str, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("svg") => std::fs::write(path, svg)?,
+        Some("png") => save_png(svg, &path.to_string_lossy())?,
+        _ => return Err(format!("{} should end in .svg or .png", path.display()).into()),
+    }
+    println!("Wrote {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_plain_drops_markers() {
+        assert_eq!(markdown("**__Hits__**", Style::Plain), "Hits".to_string());
+        assert_eq!(
+            markdown("- **Alice** (Alice FC) 2_000", Style::Plain),
+            "- Alice (Alice FC) 2_000"
+        );
+    }
+
+    #[test]
+    fn test_markdown_ansi_styles_spans() {
+        assert_eq!(
+            markdown("**GW1** - 1", Style::Ansi),
+            format!("{RESET}{BOLD}GW1{RESET} - 1")
+        );
+    }
+
+    #[test]
+    fn test_table_pads_to_widest_cell() {
+        let mut table = Table::new(&["#", "Team", "Pts"], Style::Plain).align(2, Align::Right);
+        table.add_row(vec!["1".into(), "Alice FC".into(), "150".into()]);
+        table.add_row(vec!["2".into(), "Bob".into(), "9".into()]);
+
+        assert_eq!(
+            table.to_string(),
+            "┌───┬──────────┬─────┐\n\
+             │ # │ Team     │ Pts │\n\
+             ├───┼──────────┼─────┤\n\
+             │ 1 │ Alice FC │ 150 │\n\
+             │ 2 │ Bob      │   9 │\n\
+             └───┴──────────┴─────┘"
+        );
+    }
+}
@@ -4,24 +4,38 @@ pub fn get_base_path() -> String {
     env::var("FPL_BASE_PATH").unwrap_or_else(|_| "/home/dyche".to_string())
 }
 
+pub fn get_player_image_dir() -> String {
+    format!("{}/fpl_assets/player_images", get_base_path())
+}
+
 pub fn get_player_image_path(code: impl ToString) -> String {
+    format!("{}/{}.png", get_player_image_dir(), code.to_string())
+}
+
+pub fn get_club_image_dir() -> String {
+    format!("{}/fpl_assets/club_images", get_base_path())
+}
+
+/// Clubs' shirts, keyed by `clubs.code`. Goalkeepers wear a different one.
+pub fn get_club_shirt_path(club_code: impl ToString, goalkeeper: bool) -> String {
+    let suffix = if goalkeeper { "_gk" } else { "" };
     format!(
-        "{}/fpl_assets/player_images/{}.png",
-        get_base_path(),
-        code.to_string()
+        "{}/shirts/{}{}.png",
+        get_club_image_dir(),
+        club_code.to_string(),
+        suffix
     )
 }
 
-pub fn get_generated_image_path(
-    command: &str,
-    user_id: impl ToString,
-    server_id: impl ToString,
-) -> String {
+pub fn get_club_badge_path(club_code: impl ToString) -> String {
     format!(
-        "{}/fpl_bot/generated/{}_{}_{}.png",
-        get_base_path(),
-        command,
-        user_id.to_string(),
-        server_id.to_string()
+        "{}/badges/{}.png",
+        get_club_image_dir(),
+        club_code.to_string()
     )
 }
+
+/// Where rendered images are written, see `fpl_services::images::RenderCache`.
+pub fn get_generated_image_dir() -> String {
+    format!("{}/fpl_bot/generated", get_base_path())
+}
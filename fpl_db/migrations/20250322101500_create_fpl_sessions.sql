@@ -0,0 +1,8 @@
+-- Add migration script here
+CREATE TABLE fpl_sessions (
+    discord_id BIGINT PRIMARY KEY REFERENCES discord_users (discord_id) ON DELETE CASCADE,
+    team_id INTEGER NOT NULL REFERENCES teams (id),
+    encrypted_cookie BYTEA NOT NULL,
+    nonce BYTEA NOT NULL,
+    linked_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
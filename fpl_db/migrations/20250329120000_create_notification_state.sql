@@ -0,0 +1,11 @@
+-- Add migration script here
+CREATE TABLE notification_state (
+    channel_id BIGINT NOT NULL,
+    kind VARCHAR(32) NOT NULL,
+    subject_id INTEGER NOT NULL,
+    game_week_id SMALLINT NOT NULL,
+    last_value TEXT NOT NULL,
+    idempotency_key TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (channel_id, kind, subject_id, game_week_id)
+);
@@ -0,0 +1,7 @@
+-- Add migration script here
+CREATE TABLE guild_notification_settings (
+    guild_id BIGINT PRIMARY KEY,
+    points_mode VARCHAR(16) NOT NULL DEFAULT 'instant',
+    digest_window_seconds INTEGER NOT NULL DEFAULT 120,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
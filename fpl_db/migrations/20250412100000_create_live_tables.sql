@@ -0,0 +1,14 @@
+-- Add migration script here
+CREATE TABLE live_tables (
+    message_id BIGINT PRIMARY KEY,
+    channel_id BIGINT NOT NULL,
+    league_id INTEGER NOT NULL REFERENCES mini_leagues (id) ON DELETE CASCADE,
+    overall_or_week VARCHAR(32) NOT NULL,
+    game_week_id SMALLINT NOT NULL REFERENCES game_weeks (id),
+    created_by BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX idx_live_tables_active ON live_tables (finished) WHERE finished = FALSE;
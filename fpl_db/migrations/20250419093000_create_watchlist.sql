@@ -0,0 +1,9 @@
+-- Add migration script here
+CREATE TABLE watchlist (
+    discord_id BIGINT NOT NULL,
+    player_id SMALLINT NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (discord_id, player_id)
+);
+
+CREATE INDEX idx_watchlist_player_id ON watchlist(player_id);
@@ -0,0 +1,37 @@
+-- Add migration script here
+-- Every FPL team a discord user has linked, discord_users.team_id stays as their default
+CREATE TABLE discord_user_teams (
+    discord_id BIGINT NOT NULL REFERENCES discord_users (discord_id) ON DELETE CASCADE,
+    team_id INTEGER NOT NULL REFERENCES teams (id),
+    linked_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (discord_id, team_id)
+);
+
+CREATE INDEX idx_discord_user_teams_team_id ON discord_user_teams (team_id);
+
+INSERT INTO discord_user_teams (discord_id, team_id)
+SELECT discord_id, team_id FROM discord_users;
+
+-- Leagues for every linked team, not just the default
+DROP MATERIALIZED VIEW IF EXISTS discord_user_mini_leagues;
+
+CREATE MATERIALIZED VIEW discord_user_mini_leagues AS
+SELECT DISTINCT
+    dut.discord_id,
+    ml.id,
+    ml.name
+FROM
+    mini_league_standings mls
+    JOIN mini_leagues ml ON ml.id = mls.league_id
+    JOIN discord_user_teams dut ON dut.team_id = mls.team_id;
+
+CREATE UNIQUE INDEX idx_discord_user_mini_leagues_unique
+ON discord_user_mini_leagues (discord_id, id);
+
+CREATE INDEX idx_discord_user_mini_leagues_discord_id
+ON discord_user_mini_leagues (discord_id);
+
+CREATE TRIGGER refresh_discord_user_mini_leagues_on_dut_change
+    AFTER INSERT OR UPDATE OR DELETE ON discord_user_teams
+    FOR EACH STATEMENT
+    EXECUTE FUNCTION refresh_discord_user_mini_leagues();
@@ -0,0 +1,18 @@
+-- Add migration script here
+CREATE TABLE guild_members (
+    guild_id BIGINT NOT NULL,
+    discord_id BIGINT NOT NULL,
+    username VARCHAR(64) NOT NULL,
+    display_name VARCHAR(64) NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (guild_id, discord_id)
+);
+
+CREATE INDEX idx_guild_members_discord_id ON guild_members (discord_id);
+
+CREATE TABLE guild_home_leagues (
+    guild_id BIGINT PRIMARY KEY,
+    league_id INTEGER NOT NULL REFERENCES mini_leagues (id) ON DELETE CASCADE,
+    set_by BIGINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
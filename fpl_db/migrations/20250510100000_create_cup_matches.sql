@@ -0,0 +1,31 @@
+-- Add migration script here
+CREATE TABLE cup_matches (
+    id INTEGER PRIMARY KEY,
+    league_id INTEGER NOT NULL,
+    game_week_id SMALLINT NOT NULL,
+    knockout_name VARCHAR(64) NOT NULL,
+    entry_1_team_id INTEGER NOT NULL,
+    entry_1_name VARCHAR(64) NOT NULL,
+    entry_1_player_name VARCHAR(64) NOT NULL,
+    entry_1_points SMALLINT NOT NULL,
+    entry_2_team_id INTEGER,
+    entry_2_name VARCHAR(64),
+    entry_2_player_name VARCHAR(64),
+    entry_2_points SMALLINT NOT NULL,
+    is_bye BOOLEAN NOT NULL,
+    winner INTEGER,
+    tiebreak VARCHAR(32),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_cup_matches_entry_1_team_id ON cup_matches(entry_1_team_id);
+CREATE INDEX idx_cup_matches_entry_2_team_id ON cup_matches(entry_2_team_id);
+
+CREATE TABLE team_cup_status (
+    team_id INTEGER PRIMARY KEY REFERENCES teams(id) ON DELETE CASCADE,
+    qualification_event SMALLINT,
+    qualification_numbers INTEGER,
+    qualification_rank INTEGER,
+    qualification_state VARCHAR(32),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
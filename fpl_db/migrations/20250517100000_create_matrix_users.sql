@@ -0,0 +1,15 @@
+-- Add migration script here
+CREATE TABLE matrix_users (
+    matrix_id VARCHAR(255) PRIMARY KEY,
+    team_id INTEGER NOT NULL REFERENCES teams (id),
+    registered_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_matrix_users_team_id ON matrix_users (team_id);
+
+-- notification_state is keyed by a BIGINT channel, Matrix rooms get theirs from here
+CREATE TABLE matrix_rooms (
+    id BIGSERIAL PRIMARY KEY,
+    room_id VARCHAR(255) NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
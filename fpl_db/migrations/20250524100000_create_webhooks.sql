@@ -0,0 +1,37 @@
+-- Add migration script here
+CREATE TABLE webhooks (
+    id BIGSERIAL PRIMARY KEY,
+    -- Exactly one owner, a Discord guild or a REST API key (stored as its SHA-256 hex digest)
+    guild_id BIGINT,
+    api_key_hash VARCHAR(64),
+    url TEXT NOT NULL,
+    secret VARCHAR(64) NOT NULL,
+    events TEXT[] NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((guild_id IS NULL) <> (api_key_hash IS NULL))
+);
+
+CREATE INDEX idx_webhooks_guild_id ON webhooks(guild_id);
+CREATE INDEX idx_webhooks_api_key_hash ON webhooks(api_key_hash);
+
+CREATE TABLE webhook_deliveries (
+    id BIGSERIAL PRIMARY KEY,
+    webhook_id BIGINT NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+    event VARCHAR(32) NOT NULL,
+    idempotency_key TEXT NOT NULL,
+    -- The exact body that's signed and sent, so retries are byte for byte identical
+    payload TEXT NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    attempts SMALLINT NOT NULL DEFAULT 0,
+    last_status_code SMALLINT,
+    last_error TEXT,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    delivered_at TIMESTAMPTZ,
+    UNIQUE (webhook_id, idempotency_key)
+);
+
+CREATE INDEX idx_webhook_deliveries_pending ON webhook_deliveries(next_attempt_at)
+    WHERE status = 'pending';
+CREATE INDEX idx_webhook_deliveries_webhook_id ON webhook_deliveries(webhook_id, created_at DESC);
@@ -0,0 +1,12 @@
+-- Add migration script here
+CREATE TABLE user_theme_preferences (
+    discord_id BIGINT PRIMARY KEY,
+    theme VARCHAR(16) NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE guild_theme_preferences (
+    guild_id BIGINT PRIMARY KEY,
+    theme VARCHAR(16) NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
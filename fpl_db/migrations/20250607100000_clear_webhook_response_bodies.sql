@@ -0,0 +1,2 @@
+-- Deliveries no longer keep response bodies, they could be used to read internal services
+UPDATE webhook_deliveries SET last_error = NULL WHERE last_status_code IS NOT NULL;
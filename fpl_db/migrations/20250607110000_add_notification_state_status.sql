@@ -0,0 +1,10 @@
+-- A claim is only final once the notification has been sent, anything left 'claimed' is re-driven.
+-- Existing rows were all sent (or seeded) before this existed.
+ALTER TABLE notification_state
+    ADD COLUMN previous_value TEXT,
+    ADD COLUMN status VARCHAR(16) NOT NULL DEFAULT 'sent',
+    ADD COLUMN attempts SMALLINT NOT NULL DEFAULT 0,
+    ADD COLUMN claimed_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+CREATE INDEX idx_notification_state_claimed ON notification_state(kind, claimed_at)
+    WHERE status = 'claimed';
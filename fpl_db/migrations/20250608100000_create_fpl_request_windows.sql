@@ -0,0 +1,8 @@
+-- Requests made to each FPL host per second, counted by every process that calls FPL so the bot
+-- and the scraper share one rate limit. Only the last few seconds matter, so it isn't logged.
+CREATE UNLOGGED TABLE fpl_request_windows (
+    host VARCHAR(255) NOT NULL,
+    window_start TIMESTAMPTZ NOT NULL,
+    requests INTEGER NOT NULL,
+    PRIMARY KEY (host, window_start)
+);
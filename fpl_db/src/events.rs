@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgListener, PgConnection, PgPool};
+
+/// Postgres channel the upserts publish `ChangeEvent`s on.
+pub const CHANGE_EVENTS_CHANNEL: &str = "fpl_changes";
+
+/// A change the scraper has written to the database, sent as JSON via `pg_notify`. Events are
+/// only sent for rows that already existed and actually changed, and are delivered when the
+/// upsert's transaction commits.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChangeEvent {
+    FixtureUpdated {
+        fixture_id: i16,
+        started: bool,
+        finished: bool,
+        minutes: i16,
+        home_team_score: Option<i16>,
+        away_team_score: Option<i16>,
+        was_started: bool,
+        previous_home_team_score: Option<i16>,
+        previous_away_team_score: Option<i16>,
+    },
+    PlayerPointsUpdated {
+        player_id: i16,
+        game_week_id: i16,
+        previous_points: i16,
+        total_points: i16,
+    },
+    PlayerMatchStatsUpdated {
+        player_id: i16,
+        game_week_id: i16,
+        goals_scored: i16,
+        previous_goals_scored: i16,
+        yellow_cards: i16,
+        previous_yellow_cards: i16,
+        red_cards: i16,
+        previous_red_cards: i16,
+    },
+    PlayerPriceChanged {
+        player_id: i16,
+        previous_cost: i16,
+        now_cost: i16,
+    },
+    PlayerStatusChanged {
+        player_id: i16,
+        previous_status: String,
+        status: String,
+        previous_news: String,
+        news: String,
+    },
+    FixtureRescheduled {
+        fixture_id: i16,
+        home_team_id: i16,
+        away_team_id: i16,
+        previous_game_week_id: Option<i16>,
+        game_week_id: Option<i16>,
+        previous_kickoff_time: Option<DateTime<Utc>>,
+        kickoff_time: Option<DateTime<Utc>>,
+    },
+    CupMatchDecided {
+        match_id: i32,
+        game_week_id: i16,
+        winner_team_id: i32,
+        winner_points: i16,
+        loser_team_id: i32,
+        loser_points: i16,
+    },
+    /// FPL moves `is_current` onto a game week once its deadline has passed.
+    DeadlinePassed {
+        game_week_id: i16,
+        deadline_time: DateTime<Utc>,
+    },
+    GameWeekFinished {
+        game_week_id: i16,
+    },
+}
+
+impl ChangeEvent {
+    pub fn from_payload(payload: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(payload)
+    }
+}
+
+pub async fn notify_change(
+    connection: &mut PgConnection,
+    event: &ChangeEvent,
+) -> Result<(), sqlx::Error> {
+    let payload = serde_json::to_string(event).expect("ChangeEvent always serializes");
+
+    sqlx::query!("SELECT pg_notify($1, $2);", CHANGE_EVENTS_CHANNEL, payload)
+        .execute(connection)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn listen_for_changes(pool: &PgPool) -> Result<PgListener, sqlx::Error> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener.listen(CHANGE_EVENTS_CHANNEL).await?;
+    Ok(listener)
+}
@@ -1,2 +1,4 @@
+pub mod events;
 pub mod models;
 pub mod queries;
+pub mod rate_limit;
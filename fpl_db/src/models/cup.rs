@@ -0,0 +1,92 @@
+use fpl_api::responses::cup::{CupMatchResponse, CupStatus};
+use fpl_common::types::{GameWeekId, TeamId};
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct CupMatch {
+    pub id: i32,
+    pub league_id: i32,
+    pub game_week_id: GameWeekId,
+    pub knockout_name: String,
+    pub entry_1_team_id: TeamId,
+    pub entry_1_name: String,
+    pub entry_1_player_name: String,
+    pub entry_1_points: i16,
+    pub entry_2_team_id: Option<TeamId>,
+    pub entry_2_name: Option<String>,
+    pub entry_2_player_name: Option<String>,
+    pub entry_2_points: i16,
+    pub is_bye: bool,
+    pub winner: Option<TeamId>,
+    pub tiebreak: Option<String>,
+}
+
+impl From<&CupMatchResponse> for CupMatch {
+    fn from(cup_match: &CupMatchResponse) -> Self {
+        Self {
+            id: cup_match.id,
+            league_id: cup_match.league,
+            game_week_id: cup_match.event,
+            knockout_name: cup_match.knockout_name.clone(),
+            entry_1_team_id: cup_match.entry_1_entry,
+            entry_1_name: cup_match.entry_1_name.clone(),
+            entry_1_player_name: cup_match.entry_1_player_name.clone(),
+            entry_1_points: cup_match.entry_1_points,
+            entry_2_team_id: cup_match.entry_2_entry,
+            entry_2_name: cup_match.entry_2_name.clone(),
+            entry_2_player_name: cup_match.entry_2_player_name.clone(),
+            entry_2_points: cup_match.entry_2_points,
+            is_bye: cup_match.is_bye,
+            winner: cup_match.winner,
+            tiebreak: cup_match.tiebreak.clone(),
+        }
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct TeamCupStatus {
+    pub team_id: TeamId,
+    pub qualification_event: Option<GameWeekId>,
+    pub qualification_numbers: Option<i32>,
+    pub qualification_rank: Option<i32>,
+    pub qualification_state: Option<String>,
+}
+
+impl From<(TeamId, &CupStatus)> for TeamCupStatus {
+    fn from((team_id, status): (TeamId, &CupStatus)) -> Self {
+        Self {
+            team_id,
+            qualification_event: status.qualification_event,
+            qualification_numbers: status.qualification_numbers,
+            qualification_rank: status.qualification_rank,
+            qualification_state: status.qualification_state.clone(),
+        }
+    }
+}
+
+/// A registered user's most recent cup tie, with live points for both sides where we have them.
+#[derive(Debug, sqlx::FromRow)]
+pub struct CupTie {
+    pub discord_id: i64,
+    pub team_id: TeamId,
+    pub team_name: String,
+    pub game_week_id: GameWeekId,
+    pub knockout_name: String,
+    pub points: i16,
+    pub opponent_name: Option<String>,
+    pub opponent_player_name: Option<String>,
+    pub opponent_points: Option<i16>,
+    /// False while an undecided tie's opponent isn't in `live_points`, their points are then
+    /// from the last cup scrape
+    pub opponent_points_live: bool,
+    pub is_bye: bool,
+    pub winner: Option<TeamId>,
+    pub tiebreak: Option<String>,
+}
+
+impl CupTie {
+    /// Knocked out in this tie. Earlier rounds don't need checking, a team only has a tie in a
+    /// round if it won the one before.
+    pub fn is_eliminated(&self) -> bool {
+        self.winner.is_some_and(|winner| winner != self.team_id)
+    }
+}
@@ -14,3 +14,14 @@ impl DiscordUser {
         }
     }
 }
+
+/// Most FPL teams one discord user can link.
+pub const MAX_LINKED_TEAMS: usize = 5;
+
+/// One of the FPL teams a discord user has linked.
+#[derive(Debug, sqlx::FromRow)]
+pub struct LinkedTeam {
+    pub team_id: TeamId,
+    pub name: String,
+    pub is_default: bool,
+}
@@ -0,0 +1,30 @@
+use fpl_common::types::LeagueId;
+
+/// A member of a discord server, kept up to date from gateway member events.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct GuildMember {
+    pub guild_id: i64,
+    pub discord_id: i64,
+    pub username: String,
+    pub display_name: String,
+}
+
+impl GuildMember {
+    pub fn new(guild_id: i64, discord_id: i64, username: String, display_name: String) -> Self {
+        Self {
+            guild_id,
+            discord_id,
+            username,
+            display_name,
+        }
+    }
+}
+
+/// The mini league a server's admins have picked as theirs.
+#[derive(Debug, sqlx::FromRow)]
+pub struct GuildHomeLeague {
+    pub guild_id: i64,
+    pub league_id: LeagueId,
+    pub name: String,
+    pub set_by: i64,
+}
@@ -0,0 +1,61 @@
+use std::fmt;
+use std::str::FromStr;
+
+pub const DEFAULT_DIGEST_WINDOW_SECONDS: i32 = 120;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PointsNotificationMode {
+    /// One message per points change.
+    #[default]
+    Instant,
+    /// Changes within the guild's window are collected into a single image.
+    Digest,
+}
+
+impl PointsNotificationMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PointsNotificationMode::Instant => "instant",
+            PointsNotificationMode::Digest => "digest",
+        }
+    }
+}
+
+impl fmt::Display for PointsNotificationMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PointsNotificationMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "instant" => Ok(Self::Instant),
+            "digest" => Ok(Self::Digest),
+            _ => Err(format!("Unknown points notification mode: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct GuildNotificationSettings {
+    pub guild_id: i64,
+    pub points_mode: String,
+    pub digest_window_seconds: i32,
+}
+
+impl GuildNotificationSettings {
+    pub fn new(guild_id: i64) -> Self {
+        Self {
+            guild_id,
+            points_mode: PointsNotificationMode::default().to_string(),
+            digest_window_seconds: DEFAULT_DIGEST_WINDOW_SECONDS,
+        }
+    }
+
+    pub fn points_mode(&self) -> PointsNotificationMode {
+        self.points_mode.parse().unwrap_or_default()
+    }
+}
@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use fpl_common::types::{GameWeekId, LeagueId, TeamId};
+
+/// A `/livetable` message the bot keeps editing until `game_week_id` is over.
+#[derive(Debug, sqlx::FromRow)]
+pub struct LiveTable {
+    pub message_id: i64,
+    pub channel_id: i64,
+    pub league_id: LeagueId,
+    pub overall_or_week: String,
+    pub game_week_id: GameWeekId,
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub finished: bool,
+}
+
+/// A team's row in the `live_points` view, where the `calculated_` totals include provisional
+/// bonus for the current game week.
+#[derive(Debug, sqlx::FromRow)]
+pub struct LeagueLivePoints {
+    pub game_week_id: GameWeekId,
+    pub team_id: TeamId,
+    pub name: String,
+    pub player_first_name: String,
+    pub player_last_name: String,
+    pub week_points: i16,
+    pub calculated_week_points: i64,
+    pub overall_points: i16,
+    pub calculated_overall_points: i64,
+}
@@ -0,0 +1,14 @@
+use fpl_common::types::TeamId;
+
+/// A Matrix user, e.g. `@alice:example.org`, and the FPL team they registered.
+#[derive(Debug, sqlx::FromRow)]
+pub struct MatrixUser {
+    pub matrix_id: String,
+    pub team_id: TeamId,
+}
+
+impl MatrixUser {
+    pub fn new(matrix_id: String, team_id: TeamId) -> Self {
+        Self { matrix_id, team_id }
+    }
+}
@@ -1,21 +1,39 @@
 pub mod club;
+pub mod cup;
 pub mod discord;
 pub mod fixture;
 pub mod game_week;
 pub mod game_week_player;
+pub mod guild;
+pub mod guild_settings;
+pub mod live_table;
+pub mod matrix;
 pub mod mini_league;
+pub mod notification_state;
 pub mod player;
+pub mod session;
 pub mod team;
 pub mod team_game_week;
 pub mod transfers;
+pub mod watchlist;
+pub mod webhook;
 
 pub use club::*;
+pub use cup::*;
 pub use discord::*;
 pub use fixture::*;
 pub use game_week::*;
 pub use game_week_player::*;
+pub use guild::*;
+pub use guild_settings::*;
+pub use live_table::*;
+pub use matrix::*;
 pub use mini_league::*;
+pub use notification_state::*;
 pub use player::*;
+pub use session::*;
 pub use team::*;
 pub use team_game_week::*;
 pub use transfers::*;
+pub use watchlist::*;
+pub use webhook::*;
@@ -0,0 +1,136 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    FixtureScore,
+    PlayerPoints,
+    WatchPrice,
+    WatchStatus,
+    WatchMatchStats,
+    WatchFixture,
+    CupEliminated,
+}
+
+impl NotificationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::FixtureScore => "fixture_score",
+            NotificationKind::PlayerPoints => "player_points",
+            NotificationKind::WatchPrice => "watch_price",
+            NotificationKind::WatchStatus => "watch_status",
+            NotificationKind::WatchMatchStats => "watch_match_stats",
+            NotificationKind::WatchFixture => "watch_fixture",
+            NotificationKind::CupEliminated => "cup_eliminated",
+        }
+    }
+}
+
+impl fmt::Display for NotificationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NotificationKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fixture_score" => Ok(Self::FixtureScore),
+            "player_points" => Ok(Self::PlayerPoints),
+            "watch_price" => Ok(Self::WatchPrice),
+            "watch_status" => Ok(Self::WatchStatus),
+            "watch_match_stats" => Ok(Self::WatchMatchStats),
+            "watch_fixture" => Ok(Self::WatchFixture),
+            "cup_eliminated" => Ok(Self::CupEliminated),
+            _ => Err(format!("Unknown notification kind: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationStatus {
+    /// Won by an instance that hasn't confirmed sending it yet
+    Claimed,
+    /// Sent, or seeded without anything to send
+    Sent,
+    /// Still unsent after every re-drive
+    Failed,
+}
+
+impl NotificationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationStatus::Claimed => "claimed",
+            NotificationStatus::Sent => "sent",
+            NotificationStatus::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for NotificationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The last value notified for a fixture or player in a channel, e.g. `"2-1"` for a score or
+/// `"6"` for points.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct NotificationState {
+    pub channel_id: i64,
+    pub kind: String,
+    pub subject_id: i32,
+    pub game_week_id: i16,
+    pub previous_value: Option<String>,
+    pub last_value: String,
+    pub idempotency_key: String,
+    pub status: String,
+    pub attempts: i16,
+    pub claimed_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl NotificationState {
+    /// The change this row was claimed for, to send it again after it was left unsent.
+    pub fn transition(&self) -> Result<NotificationTransition, String> {
+        Ok(NotificationTransition {
+            channel_id: self.channel_id,
+            kind: self.kind.parse()?,
+            subject_id: self.subject_id,
+            game_week_id: self.game_week_id,
+            previous_value: self.previous_value.clone(),
+            value: self.last_value.clone(),
+        })
+    }
+}
+
+/// A change from `previous_value` to `value` that someone wants to notify about. Every bot
+/// instance builds the same idempotency key for the same change, so only one of them wins the
+/// claim and sends it.
+#[derive(Debug, Clone)]
+pub struct NotificationTransition {
+    pub channel_id: i64,
+    pub kind: NotificationKind,
+    pub subject_id: i32,
+    pub game_week_id: i16,
+    pub previous_value: Option<String>,
+    pub value: String,
+}
+
+impl NotificationTransition {
+    pub fn idempotency_key(&self) -> String {
+        format!(
+            "{}:{}:{}:{}:{}->{}",
+            self.kind,
+            self.channel_id,
+            self.game_week_id,
+            self.subject_id,
+            self.previous_value.as_deref().unwrap_or("none"),
+            self.value
+        )
+    }
+}
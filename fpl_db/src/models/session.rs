@@ -0,0 +1,12 @@
+use chrono::{DateTime, Utc};
+use fpl_common::types::TeamId;
+
+/// An `FplSession` cookie, encrypted by the bot before it gets anywhere near the database.
+#[derive(Debug, sqlx::FromRow)]
+pub struct StoredFplSession {
+    pub discord_id: i64,
+    pub team_id: TeamId,
+    pub encrypted_cookie: Vec<u8>,
+    pub nonce: Vec<u8>,
+    pub linked_at: DateTime<Utc>,
+}
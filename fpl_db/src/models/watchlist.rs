@@ -0,0 +1,23 @@
+use fpl_common::types::PlayerId;
+
+/// Most players a single user can have on their watchlist.
+pub const MAX_WATCHED_PLAYERS: i64 = 25;
+
+/// A player on a user's watchlist, with what `/watch list` shows about them.
+#[derive(Debug, sqlx::FromRow)]
+pub struct WatchedPlayer {
+    pub player_id: PlayerId,
+    pub web_name: String,
+    pub club_short_name: String,
+    pub now_cost: i16,
+    pub status: String,
+    pub news: String,
+}
+
+/// Someone to DM when something happens to `player_id`.
+#[derive(Debug, sqlx::FromRow)]
+pub struct Watcher {
+    pub discord_id: i64,
+    pub player_id: PlayerId,
+    pub web_name: String,
+}
@@ -0,0 +1,167 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+
+/// Most webhooks a single guild or API key can register.
+pub const MAX_WEBHOOKS_PER_OWNER: i64 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WebhookEvent {
+    FixtureKickoff,
+    FixtureScore,
+    PlayerPoints,
+    DeadlinePassed,
+    GameWeekFinished,
+}
+
+impl WebhookEvent {
+    pub const ALL: [WebhookEvent; 5] = [
+        WebhookEvent::FixtureKickoff,
+        WebhookEvent::FixtureScore,
+        WebhookEvent::PlayerPoints,
+        WebhookEvent::DeadlinePassed,
+        WebhookEvent::GameWeekFinished,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::FixtureKickoff => "fixture_kickoff",
+            WebhookEvent::FixtureScore => "fixture_score",
+            WebhookEvent::PlayerPoints => "player_points",
+            WebhookEvent::DeadlinePassed => "deadline_passed",
+            WebhookEvent::GameWeekFinished => "game_week_finished",
+        }
+    }
+}
+
+impl fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WebhookEvent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|event| event.as_str() == s)
+            .ok_or_else(|| format!("Unknown webhook event: {}", s))
+    }
+}
+
+/// Who registered a webhook, only they can list or remove it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WebhookOwner {
+    Guild(i64),
+    /// SHA-256 hex digest of the REST API key, the key itself is never stored
+    ApiKey(String),
+}
+
+impl WebhookOwner {
+    pub fn guild_id(&self) -> Option<i64> {
+        match self {
+            WebhookOwner::Guild(guild_id) => Some(*guild_id),
+            WebhookOwner::ApiKey(_) => None,
+        }
+    }
+
+    pub fn api_key_hash(&self) -> Option<&str> {
+        match self {
+            WebhookOwner::Guild(_) => None,
+            WebhookOwner::ApiKey(hash) => Some(hash),
+        }
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Webhook {
+    pub id: i64,
+    pub guild_id: Option<i64>,
+    pub api_key_hash: Option<String>,
+    pub url: String,
+    /// Key for the HMAC signature on every delivery
+    pub secret: String,
+    pub events: Vec<String>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    pub fn events(&self) -> Vec<WebhookEvent> {
+        self.events
+            .iter()
+            .filter_map(|event| event.parse().ok())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    /// Waiting for its first attempt or a retry
+    Pending,
+    Delivered,
+    /// Out of retries, or the endpoint rejected it outright
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for DeliveryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DeliveryStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "delivered" => Ok(Self::Delivered),
+            "failed" => Ok(Self::Failed),
+            _ => Err(format!("Unknown delivery status: {}", s)),
+        }
+    }
+}
+
+/// One event sent (or still to be sent) to one webhook, kept as the delivery log.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event: String,
+    pub idempotency_key: String,
+    pub payload: String,
+    pub status: String,
+    pub attempts: i16,
+    pub last_status_code: Option<i16>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// A delivery that's due, joined with where it's going.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DueWebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub url: String,
+    pub secret: String,
+    pub event: String,
+    pub idempotency_key: String,
+    pub payload: String,
+    pub attempts: i16,
+}
@@ -59,3 +59,14 @@ pub async fn upsert_clubs(pool: &PgPool, clubs: &[Club]) -> Result<(), sqlx::Err
     debug!("Upsert Completed");
     Ok(())
 }
+
+pub async fn get_all_club_codes(pool: &PgPool) -> Result<Vec<u32>, sqlx::Error> {
+    let codes = sqlx::query!("SELECT code FROM clubs")
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|row| row.code as u32)
+        .collect();
+
+    Ok(codes)
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+
+use fpl_common::types::{GameWeekId, TeamId};
+use sqlx::PgPool;
+use tracing::debug;
+
+use crate::events::{notify_change, ChangeEvent};
+use crate::models::{CupMatch, CupTie, TeamCupStatus};
+
+pub async fn upsert_cup_matches(
+    pool: &PgPool,
+    cup_matches: &[CupMatch],
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    debug!("Upserting {} CupMatch rows", cup_matches.len());
+
+    let match_ids: Vec<i32> = cup_matches.iter().map(|m| m.id).collect();
+    let previous_winners: HashMap<i32, Option<i32>> = sqlx::query!(
+        "SELECT id, winner FROM cup_matches WHERE id = ANY($1)",
+        &match_ids
+    )
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    .map(|row| (row.id, row.winner))
+    .collect();
+
+    for cup_match in cup_matches {
+        sqlx::query!(
+            r#"
+            INSERT INTO cup_matches (
+                id, league_id, game_week_id, knockout_name,
+                entry_1_team_id, entry_1_name, entry_1_player_name, entry_1_points,
+                entry_2_team_id, entry_2_name, entry_2_player_name, entry_2_points,
+                is_bye, winner, tiebreak
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+            ON CONFLICT (id) DO UPDATE SET
+                entry_1_name = EXCLUDED.entry_1_name,
+                entry_1_player_name = EXCLUDED.entry_1_player_name,
+                entry_1_points = EXCLUDED.entry_1_points,
+                entry_2_name = EXCLUDED.entry_2_name,
+                entry_2_player_name = EXCLUDED.entry_2_player_name,
+                entry_2_points = EXCLUDED.entry_2_points,
+                winner = EXCLUDED.winner,
+                tiebreak = EXCLUDED.tiebreak,
+                updated_at = NOW()
+            "#,
+            cup_match.id,
+            cup_match.league_id,
+            i16::from(cup_match.game_week_id),
+            cup_match.knockout_name,
+            i32::from(cup_match.entry_1_team_id),
+            cup_match.entry_1_name,
+            cup_match.entry_1_player_name,
+            cup_match.entry_1_points,
+            cup_match.entry_2_team_id.map(i32::from),
+            cup_match.entry_2_name,
+            cup_match.entry_2_player_name,
+            cup_match.entry_2_points,
+            cup_match.is_bye,
+            cup_match.winner.map(i32::from),
+            cup_match.tiebreak
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // Only ties that were already stored undecided, and byes have nobody to knock out
+        let was_undecided = matches!(previous_winners.get(&cup_match.id), Some(None));
+        let (true, Some(winner), Some(entry_2_team_id)) =
+            (was_undecided, cup_match.winner, cup_match.entry_2_team_id)
+        else {
+            continue;
+        };
+
+        let (winner_points, loser_team_id, loser_points) = if winner == cup_match.entry_1_team_id {
+            (
+                cup_match.entry_1_points,
+                entry_2_team_id,
+                cup_match.entry_2_points,
+            )
+        } else {
+            (
+                cup_match.entry_2_points,
+                cup_match.entry_1_team_id,
+                cup_match.entry_1_points,
+            )
+        };
+        let event = ChangeEvent::CupMatchDecided {
+            match_id: cup_match.id,
+            game_week_id: i16::from(cup_match.game_week_id),
+            winner_team_id: i32::from(winner),
+            winner_points,
+            loser_team_id: i32::from(loser_team_id),
+            loser_points,
+        };
+        notify_change(&mut tx, &event).await?;
+    }
+    tx.commit().await?;
+    debug!("Upsert Completed");
+    Ok(())
+}
+
+pub async fn upsert_team_cup_statuses(
+    pool: &PgPool,
+    statuses: &[TeamCupStatus],
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    debug!("Upserting {} TeamCupStatus rows", statuses.len());
+    for status in statuses {
+        sqlx::query!(
+            r#"
+            INSERT INTO team_cup_status (
+                team_id, qualification_event, qualification_numbers,
+                qualification_rank, qualification_state
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (team_id) DO UPDATE SET
+                qualification_event = EXCLUDED.qualification_event,
+                qualification_numbers = EXCLUDED.qualification_numbers,
+                qualification_rank = EXCLUDED.qualification_rank,
+                qualification_state = EXCLUDED.qualification_state,
+                updated_at = NOW()
+            "#,
+            i32::from(status.team_id),
+            status.qualification_event.map(i16::from),
+            status.qualification_numbers,
+            status.qualification_rank,
+            status.qualification_state
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    debug!("Upsert Completed");
+    Ok(())
+}
+
+/// Cups are only worth scraping for the default teams `get_cup_ties` shows.
+pub async fn get_cup_team_ids(pool: &PgPool) -> Result<Vec<TeamId>, sqlx::Error> {
+    sqlx::query!(r#"SELECT DISTINCT team_id as "team_id: TeamId" FROM discord_users"#)
+        .map(|row| row.team_id)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn get_cup_match(pool: &PgPool, match_id: i32) -> Result<CupMatch, sqlx::Error> {
+    sqlx::query_as!(
+        CupMatch,
+        r#"
+        SELECT
+            id, league_id, game_week_id as "game_week_id: GameWeekId", knockout_name,
+            entry_1_team_id as "entry_1_team_id: TeamId", entry_1_name, entry_1_player_name,
+            entry_1_points, entry_2_team_id as "entry_2_team_id: TeamId", entry_2_name,
+            entry_2_player_name, entry_2_points, is_bye, winner as "winner: TeamId", tiebreak
+        FROM cup_matches
+        WHERE id = $1
+        "#,
+        match_id
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// The latest tie for each registered user's default team, from their side of the draw. Points
+/// for a tie that hasn't been decided come from `live_points` when the team's in there.
+/// Opponents usually aren't, so `opponent_points_live` says whether theirs are live or as of
+/// the last cup scrape.
+pub async fn get_cup_ties(
+    pool: &PgPool,
+    guild_id: Option<i64>,
+) -> Result<Vec<CupTie>, sqlx::Error> {
+    sqlx::query_as!(
+        CupTie,
+        r#"
+        SELECT DISTINCT ON (du.discord_id)
+            du.discord_id,
+            du.team_id as "team_id!: TeamId",
+            t.name as team_name,
+            cm.game_week_id as "game_week_id!: GameWeekId",
+            cm.knockout_name as "knockout_name!",
+            COALESCE(
+                CASE WHEN cm.winner IS NULL THEN own.calculated_week_points::SMALLINT END,
+                CASE WHEN cm.entry_1_team_id = du.team_id THEN cm.entry_1_points ELSE cm.entry_2_points END
+            ) as "points!",
+            CASE WHEN cm.entry_1_team_id = du.team_id THEN cm.entry_2_name ELSE cm.entry_1_name END as opponent_name,
+            CASE WHEN cm.entry_1_team_id = du.team_id THEN cm.entry_2_player_name ELSE cm.entry_1_player_name END as opponent_player_name,
+            CASE WHEN cm.is_bye THEN NULL ELSE COALESCE(
+                CASE WHEN cm.winner IS NULL THEN opp.calculated_week_points::SMALLINT END,
+                CASE WHEN cm.entry_1_team_id = du.team_id THEN cm.entry_2_points ELSE cm.entry_1_points END
+            ) END as opponent_points,
+            (cm.winner IS NOT NULL OR opp.team_id IS NOT NULL) as "opponent_points_live!",
+            cm.is_bye as "is_bye!",
+            cm.winner as "winner: TeamId",
+            cm.tiebreak
+        FROM discord_users du
+        JOIN teams t ON t.id = du.team_id
+        JOIN cup_matches cm ON du.team_id IN (cm.entry_1_team_id, cm.entry_2_team_id)
+        LEFT JOIN live_points own
+            ON own.team_id = du.team_id AND own.game_week_id = cm.game_week_id
+        LEFT JOIN live_points opp
+            ON opp.team_id = CASE WHEN cm.entry_1_team_id = du.team_id THEN cm.entry_2_team_id ELSE cm.entry_1_team_id END
+            AND opp.game_week_id = cm.game_week_id
+        WHERE $1::BIGINT IS NULL
+            OR du.discord_id IN (SELECT discord_id FROM guild_members WHERE guild_id = $1)
+        ORDER BY du.discord_id, cm.game_week_id DESC
+        "#,
+        guild_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Discord users with `team_id` linked, for telling them they've been knocked out.
+pub async fn get_team_discord_ids(pool: &PgPool, team_id: TeamId) -> Result<Vec<i64>, sqlx::Error> {
+    sqlx::query!(
+        "SELECT discord_id FROM discord_user_teams WHERE team_id = $1",
+        i32::from(team_id)
+    )
+    .map(|row| row.discord_id)
+    .fetch_all(pool)
+    .await
+}
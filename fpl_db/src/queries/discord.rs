@@ -1,7 +1,8 @@
+use fpl_common::types::TeamId;
 use sqlx::PgPool;
 use tracing::debug;
 
-use crate::models::DiscordUser;
+use crate::models::{DiscordUser, LinkedTeam};
 
 pub async fn insert_discord_user(pool: &PgPool, user: &DiscordUser) -> Result<(), sqlx::Error> {
     let mut tx = pool.begin().await?;
@@ -17,11 +18,69 @@ pub async fn insert_discord_user(pool: &PgPool, user: &DiscordUser) -> Result<()
     )
     .execute(&mut *tx)
     .await?;
+    sqlx::query!(
+        "INSERT INTO discord_user_teams (discord_id, team_id) VALUES ($1, $2)",
+        user.discord_id,
+        i32::from(user.team_id)
+    )
+    .execute(&mut *tx)
+    .await?;
     tx.commit().await?;
     debug!("Insert Completed");
     Ok(())
 }
 
+/// Registers several users at once, e.g. from a league import. Users that registered themselves
+/// in the meantime are left alone, returns how many were inserted.
+pub async fn insert_discord_users(
+    pool: &PgPool,
+    users: &[DiscordUser],
+) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    debug!("Inserting {} DiscordUser rows", users.len());
+
+    let mut inserted = 0;
+    for user in users {
+        let result = sqlx::query!(
+            "INSERT INTO discord_users (discord_id, team_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            user.discord_id,
+            i32::from(user.team_id)
+        )
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            continue;
+        }
+
+        sqlx::query!(
+            "INSERT INTO discord_user_teams (discord_id, team_id) VALUES ($1, $2)",
+            user.discord_id,
+            i32::from(user.team_id)
+        )
+        .execute(&mut *tx)
+        .await?;
+        inserted += 1;
+    }
+    tx.commit().await?;
+    debug!("Insert Completed");
+    Ok(inserted)
+}
+
+/// Which of `team_ids` are already linked to a discord user.
+pub async fn get_linked_team_ids(
+    pool: &PgPool,
+    team_ids: &[TeamId],
+) -> Result<Vec<TeamId>, sqlx::Error> {
+    let team_ids: Vec<i32> = team_ids.iter().map(|id| i32::from(*id)).collect();
+    sqlx::query!(
+        r#"SELECT DISTINCT team_id as "team_id: TeamId" FROM discord_user_teams WHERE team_id = ANY($1)"#,
+        &team_ids
+    )
+    .map(|row| row.team_id)
+    .fetch_all(pool)
+    .await
+}
+
 pub async fn get_discord_user(
     pool: &PgPool,
     user_id: i64,
@@ -34,3 +93,140 @@ pub async fn get_discord_user(
     .fetch_optional(pool)
     .await
 }
+
+/// Every team the user has linked, default first.
+pub async fn get_linked_teams(
+    pool: &PgPool,
+    discord_id: i64,
+) -> Result<Vec<LinkedTeam>, sqlx::Error> {
+    sqlx::query_as!(
+        LinkedTeam,
+        r#"
+        SELECT
+            dut.team_id as "team_id: TeamId",
+            t.name,
+            dut.team_id = du.team_id as "is_default!"
+        FROM discord_user_teams dut
+        JOIN discord_users du ON du.discord_id = dut.discord_id
+        JOIN teams t ON t.id = dut.team_id
+        WHERE dut.discord_id = $1
+        ORDER BY dut.team_id = du.team_id DESC, dut.linked_at ASC
+        "#,
+        discord_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Links another team to an already registered user, optionally making it their default.
+pub async fn link_discord_user_team(
+    pool: &PgPool,
+    discord_id: i64,
+    team_id: TeamId,
+    make_default: bool,
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query!(
+        r#"
+        INSERT INTO discord_user_teams (discord_id, team_id)
+        VALUES ($1, $2)
+        ON CONFLICT (discord_id, team_id) DO NOTHING
+        "#,
+        discord_id,
+        i32::from(team_id)
+    )
+    .execute(&mut *tx)
+    .await?;
+    if make_default {
+        sqlx::query!(
+            "UPDATE discord_users SET team_id = $2 WHERE discord_id = $1",
+            discord_id,
+            i32::from(team_id)
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    debug!("Insert Completed");
+    Ok(())
+}
+
+/// Unlinks one team. If it was the default the longest linked remaining team takes over, and if
+/// it was the last one the user is unregistered. Returns the default afterwards, `None` if the
+/// user is no longer registered.
+pub async fn unlink_discord_user_team(
+    pool: &PgPool,
+    discord_id: i64,
+    team_id: TeamId,
+) -> Result<Option<TeamId>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query!(
+        "DELETE FROM discord_user_teams WHERE discord_id = $1 AND team_id = $2",
+        discord_id,
+        i32::from(team_id)
+    )
+    .execute(&mut *tx)
+    .await?;
+    // A stored FPL login only works for the team it was linked with
+    sqlx::query!(
+        "DELETE FROM fpl_sessions WHERE discord_id = $1 AND team_id = $2",
+        discord_id,
+        i32::from(team_id)
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let remaining = sqlx::query!(
+        r#"
+        SELECT dut.team_id as "team_id: TeamId", dut.team_id = du.team_id as "is_default!"
+        FROM discord_user_teams dut
+        JOIN discord_users du ON du.discord_id = dut.discord_id
+        WHERE dut.discord_id = $1
+        ORDER BY dut.linked_at ASC
+        "#,
+        discord_id
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let default = match remaining.iter().find(|team| team.is_default) {
+        Some(team) => Some(team.team_id),
+        None => match remaining.first() {
+            Some(team) => {
+                sqlx::query!(
+                    "UPDATE discord_users SET team_id = $2 WHERE discord_id = $1",
+                    discord_id,
+                    i32::from(team.team_id)
+                )
+                .execute(&mut *tx)
+                .await?;
+                Some(team.team_id)
+            }
+            None => {
+                sqlx::query!(
+                    "DELETE FROM discord_users WHERE discord_id = $1",
+                    discord_id
+                )
+                .execute(&mut *tx)
+                .await?;
+                None
+            }
+        },
+    };
+
+    tx.commit().await?;
+    debug!("Delete Completed");
+    Ok(default)
+}
+
+/// Removes the user and, through the foreign keys, every linked team and stored FPL login.
+pub async fn delete_discord_user(pool: &PgPool, discord_id: i64) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        "DELETE FROM discord_users WHERE discord_id = $1",
+        discord_id
+    )
+    .execute(pool)
+    .await?;
+    debug!("Delete Completed");
+    Ok(result.rows_affected() > 0)
+}
@@ -1,12 +1,55 @@
-use fpl_common::types::GameWeekId;
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use fpl_common::types::{ClubId, FixtureId, GameWeekId};
 use sqlx::PgPool;
 use tracing::debug;
 
+use crate::events::{notify_change, ChangeEvent};
 use crate::models::{fixture::Fixture, Bonus};
 
+struct PreviousFixture {
+    started: bool,
+    finished: bool,
+    home_team_score: Option<i16>,
+    away_team_score: Option<i16>,
+    game_week_id: Option<i16>,
+    kickoff_time: Option<DateTime<Utc>>,
+}
+
 pub async fn upsert_fixtures(pool: &PgPool, fixtures: &[Fixture]) -> Result<(), sqlx::Error> {
     let mut tx = pool.begin().await?;
     debug!("Upserting {} Fixture rows", fixtures.len());
+
+    let fixture_ids: Vec<i16> = fixtures.iter().map(|f| i16::from(f.id)).collect();
+    // Minutes tick over constantly while a fixture is live, so they don't count as a change
+    let previous_fixtures: HashMap<i16, PreviousFixture> = sqlx::query!(
+        r#"
+        SELECT id, started, finished, home_team_score, away_team_score, game_week_id, kickoff_time
+        FROM fixtures
+        WHERE id = ANY($1)
+        "#,
+        &fixture_ids
+    )
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    .map(|row| {
+        (
+            row.id,
+            PreviousFixture {
+                started: row.started.unwrap_or(false),
+                finished: row.finished,
+                home_team_score: row.home_team_score,
+                away_team_score: row.away_team_score,
+                game_week_id: row.game_week_id,
+                kickoff_time: row.kickoff_time,
+            },
+        )
+    })
+    .collect();
+
     for fixture in fixtures {
         sqlx::query!(
             r#"
@@ -18,6 +61,9 @@ pub async fn upsert_fixtures(pool: &PgPool, fixtures: &[Fixture]) -> Result<(),
            )
            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
            ON CONFLICT (id) DO UPDATE SET
+               game_week_id = EXCLUDED.game_week_id,
+               kickoff_time = EXCLUDED.kickoff_time,
+               provisional_start_time = EXCLUDED.provisional_start_time,
                home_team_score = EXCLUDED.home_team_score,
                away_team_score = EXCLUDED.away_team_score,
                finished = EXCLUDED.finished,
@@ -44,6 +90,44 @@ pub async fn upsert_fixtures(pool: &PgPool, fixtures: &[Fixture]) -> Result<(),
         )
         .execute(&mut *tx)
         .await?;
+
+        let started = fixture.started.unwrap_or(false);
+        let game_week_id = fixture.game_week_id.map(i16::from);
+        if let Some(previous) = previous_fixtures.get(&i16::from(fixture.id)) {
+            if previous.started != started
+                || previous.finished != fixture.finished
+                || previous.home_team_score != fixture.home_team_score
+                || previous.away_team_score != fixture.away_team_score
+            {
+                let event = ChangeEvent::FixtureUpdated {
+                    fixture_id: i16::from(fixture.id),
+                    started,
+                    finished: fixture.finished,
+                    minutes: fixture.minutes,
+                    home_team_score: fixture.home_team_score,
+                    away_team_score: fixture.away_team_score,
+                    was_started: previous.started,
+                    previous_home_team_score: previous.home_team_score,
+                    previous_away_team_score: previous.away_team_score,
+                };
+                notify_change(&mut tx, &event).await?;
+            }
+
+            if previous.game_week_id != game_week_id
+                || previous.kickoff_time != fixture.kickoff_time
+            {
+                let event = ChangeEvent::FixtureRescheduled {
+                    fixture_id: i16::from(fixture.id),
+                    home_team_id: i16::from(fixture.home_team_id),
+                    away_team_id: i16::from(fixture.away_team_id),
+                    previous_game_week_id: previous.game_week_id,
+                    game_week_id,
+                    previous_kickoff_time: previous.kickoff_time,
+                    kickoff_time: fixture.kickoff_time,
+                };
+                notify_change(&mut tx, &event).await?;
+            }
+        }
     }
     tx.commit().await?;
     debug!("Upsert Completed");
@@ -76,3 +160,68 @@ pub async fn upsert_bonuses(pool: &PgPool, bonuses: &[Bonus]) -> Result<(), sqlx
     debug!("Upsert Completed");
     Ok(())
 }
+
+/// Short name for a fixture, e.g. `"ARS v CHE"`.
+pub async fn get_fixture_name(pool: &PgPool, fixture_id: FixtureId) -> Result<String, sqlx::Error> {
+    let record = sqlx::query!(
+        r#"
+        SELECT home.short_name as home_short_name, away.short_name as away_short_name
+        FROM fixtures f
+        JOIN clubs home ON home.id = f.home_team_id
+        JOIN clubs away ON away.id = f.away_team_id
+        WHERE f.id = $1
+        "#,
+        i16::from(fixture_id)
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(format!(
+        "{} v {}",
+        record.home_short_name, record.away_short_name
+    ))
+}
+
+/// A page of fixtures in kick off order, optionally only those in one game week.
+pub async fn get_fixtures(
+    pool: &PgPool,
+    game_week_id: Option<GameWeekId>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Fixture>, sqlx::Error> {
+    sqlx::query_as!(
+        Fixture,
+        r#"
+        SELECT
+            id as "id: FixtureId", code, game_week_id as "game_week_id: GameWeekId",
+            home_team_id as "home_team_id: ClubId", away_team_id as "away_team_id: ClubId",
+            home_team_score, away_team_score, kickoff_time, finished, started, minutes,
+            provisional_start_time, team_h_difficulty, team_a_difficulty, pulse_id
+        FROM fixtures
+        WHERE $1::smallint IS NULL OR game_week_id = $1
+        ORDER BY kickoff_time NULLS LAST, id
+        LIMIT $2 OFFSET $3
+        "#,
+        game_week_id.map(i16::from),
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn count_fixtures(
+    pool: &PgPool,
+    game_week_id: Option<GameWeekId>,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!"
+        FROM fixtures
+        WHERE $1::smallint IS NULL OR game_week_id = $1
+        "#,
+        game_week_id.map(i16::from)
+    )
+    .fetch_one(pool)
+    .await
+}
@@ -1,13 +1,40 @@
+use std::collections::HashMap;
+
 use fpl_common::types::GameWeekId;
 use sqlx::PgPool;
 use tracing::debug;
 
+use crate::events::{notify_change, ChangeEvent};
 use crate::models::game_week::{GameWeek, GameWeekChipPlay, GameWeekTopElement};
 
+struct PreviousGameWeek {
+    is_current: bool,
+    finished: bool,
+}
+
 pub async fn upsert_game_weeks(pool: &PgPool, game_weeks: &[GameWeek]) -> Result<(), sqlx::Error> {
     let mut tx = pool.begin().await?;
     debug!("Upserting {} GameWeek rows", game_weeks.len());
 
+    let game_week_ids: Vec<i16> = game_weeks.iter().map(|gw| i16::from(gw.id)).collect();
+    let previous_game_weeks: HashMap<i16, PreviousGameWeek> = sqlx::query!(
+        "SELECT id, is_current, finished FROM game_weeks WHERE id = ANY($1)",
+        &game_week_ids
+    )
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    .map(|row| {
+        (
+            row.id,
+            PreviousGameWeek {
+                is_current: row.is_current,
+                finished: row.finished,
+            },
+        )
+    })
+    .collect();
+
     for game_week in game_weeks {
         sqlx::query!(
             r#"
@@ -62,6 +89,23 @@ pub async fn upsert_game_weeks(pool: &PgPool, game_weeks: &[GameWeek]) -> Result
         )
         .execute(&mut *tx)
         .await?;
+
+        if let Some(previous) = previous_game_weeks.get(&i16::from(game_week.id)) {
+            if !previous.is_current && game_week.is_current {
+                let event = ChangeEvent::DeadlinePassed {
+                    game_week_id: i16::from(game_week.id),
+                    deadline_time: game_week.deadline_time,
+                };
+                notify_change(&mut tx, &event).await?;
+            }
+
+            if !previous.finished && game_week.finished {
+                let event = ChangeEvent::GameWeekFinished {
+                    game_week_id: i16::from(game_week.id),
+                };
+                notify_change(&mut tx, &event).await?;
+            }
+        }
     }
     tx.commit().await?;
     debug!("Upsert Completed");
@@ -144,6 +188,21 @@ pub async fn get_current_game_week(pool: &PgPool) -> Result<GameWeek, sqlx::Erro
     Ok(current_game_week)
 }
 
+pub async fn get_next_game_week(pool: &PgPool) -> Result<Option<GameWeek>, sqlx::Error> {
+    let next_game_week = sqlx::query_as::<_, GameWeek>(
+        "SELECT
+            *
+        FROM
+            game_weeks
+        WHERE
+            is_next = true;",
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(next_game_week)
+}
+
 pub async fn get_current_game_week_id(pool: &PgPool) -> Result<GameWeekId, sqlx::Error> {
     let current_game_week = sqlx::query_as::<_, GameWeekId>(
         "SELECT
@@ -156,3 +215,14 @@ pub async fn get_current_game_week_id(pool: &PgPool) -> Result<GameWeekId, sqlx:
 
     Ok(current_game_week)
 }
+
+/// Cups are drawn once, `cup_leagues_created` is only set on the game week they were drawn in.
+pub async fn get_cup_leagues_created(pool: &PgPool) -> Result<bool, sqlx::Error> {
+    let record = sqlx::query!(
+        r#"SELECT EXISTS (SELECT 1 FROM game_weeks WHERE cup_leagues_created) as "created!""#
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(record.created)
+}
@@ -1,7 +1,18 @@
+use std::collections::HashMap;
+
 use sqlx::PgPool;
 use tracing::debug;
 
+use crate::events::{notify_change, ChangeEvent};
 use crate::models::game_week_player::GameWeekPlayerDb;
+
+struct PreviousStats {
+    total_points: i16,
+    goals_scored: i16,
+    yellow_cards: i16,
+    red_cards: i16,
+}
+
 pub async fn upsert_game_week_players(
     pool: &PgPool,
     game_week_players: &[GameWeekPlayerDb],
@@ -12,6 +23,38 @@ pub async fn upsert_game_week_players(
         game_week_players.len()
     );
 
+    let (player_ids, game_week_ids): (Vec<i16>, Vec<i16>) = game_week_players
+        .iter()
+        .map(|gwp| (i16::from(gwp.player_id), i16::from(gwp.game_week_id)))
+        .unzip();
+    let previous_stats: HashMap<(i16, i16), PreviousStats> = sqlx::query!(
+        r#"
+        SELECT
+            gwp.player_id, gwp.game_week_id, gwp.total_points,
+            gwp.goals_scored, gwp.yellow_cards, gwp.red_cards
+        FROM game_week_players gwp
+        JOIN UNNEST($1::smallint[], $2::smallint[]) AS u(player_id, game_week_id)
+            ON u.player_id = gwp.player_id AND u.game_week_id = gwp.game_week_id
+        "#,
+        &player_ids,
+        &game_week_ids
+    )
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    .map(|row| {
+        (
+            (row.player_id, row.game_week_id),
+            PreviousStats {
+                total_points: row.total_points,
+                goals_scored: row.goals_scored,
+                yellow_cards: row.yellow_cards,
+                red_cards: row.red_cards,
+            },
+        )
+    })
+    .collect();
+
     for game_week_player in game_week_players {
         match sqlx::query!(
             r#"
@@ -81,7 +124,7 @@ pub async fn upsert_game_week_players(
         .execute(&mut *tx)
         .await
         {
-            Ok(_) => continue,
+            Ok(_) => {}
             Err(e) => {
                 tracing::error!(
                     "Failed to upsert game week player. player_id: {}, game_week_id: {}, error: {}",
@@ -92,6 +135,37 @@ pub async fn upsert_game_week_players(
                 return Err(e);
             }
         }
+
+        let player_id = i16::from(game_week_player.player_id);
+        let game_week_id = i16::from(game_week_player.game_week_id);
+        if let Some(previous) = previous_stats.get(&(player_id, game_week_id)) {
+            if previous.total_points != game_week_player.total_points {
+                let event = ChangeEvent::PlayerPointsUpdated {
+                    player_id,
+                    game_week_id,
+                    previous_points: previous.total_points,
+                    total_points: game_week_player.total_points,
+                };
+                notify_change(&mut tx, &event).await?;
+            }
+
+            if previous.goals_scored != game_week_player.goals_scored
+                || previous.yellow_cards != game_week_player.yellow_cards
+                || previous.red_cards != game_week_player.red_cards
+            {
+                let event = ChangeEvent::PlayerMatchStatsUpdated {
+                    player_id,
+                    game_week_id,
+                    goals_scored: game_week_player.goals_scored,
+                    previous_goals_scored: previous.goals_scored,
+                    yellow_cards: game_week_player.yellow_cards,
+                    previous_yellow_cards: previous.yellow_cards,
+                    red_cards: game_week_player.red_cards,
+                    previous_red_cards: previous.red_cards,
+                };
+                notify_change(&mut tx, &event).await?;
+            }
+        }
     }
     tx.commit().await?;
     debug!("Upsert Completed");
@@ -0,0 +1,245 @@
+use fpl_common::types::LeagueId;
+use sqlx::PgPool;
+use tracing::debug;
+
+use crate::models::{GuildHomeLeague, GuildMember};
+
+/// Replaces the stored members of a guild with `members`, e.g. from a complete member list on
+/// guild create.
+pub async fn sync_guild_members(
+    pool: &PgPool,
+    guild_id: i64,
+    members: &[GuildMember],
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    debug!("Syncing {} GuildMember rows", members.len());
+
+    let discord_ids: Vec<i64> = members.iter().map(|m| m.discord_id).collect();
+    sqlx::query!(
+        "DELETE FROM guild_members WHERE guild_id = $1 AND discord_id <> ALL($2)",
+        guild_id,
+        &discord_ids
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    for member in members {
+        upsert_member(&mut tx, member).await?;
+    }
+
+    tx.commit().await?;
+    debug!("Upsert Completed");
+    Ok(())
+}
+
+pub async fn upsert_guild_members(
+    pool: &PgPool,
+    members: &[GuildMember],
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    debug!("Upserting {} GuildMember rows", members.len());
+    for member in members {
+        upsert_member(&mut tx, member).await?;
+    }
+    tx.commit().await?;
+    debug!("Upsert Completed");
+    Ok(())
+}
+
+async fn upsert_member(
+    tx: &mut sqlx::PgConnection,
+    member: &GuildMember,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO guild_members (guild_id, discord_id, username, display_name)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (guild_id, discord_id) DO UPDATE SET
+            username = EXCLUDED.username,
+            display_name = EXCLUDED.display_name,
+            updated_at = NOW()
+        "#,
+        member.guild_id,
+        member.discord_id,
+        member.username,
+        member.display_name
+    )
+    .execute(tx)
+    .await?;
+    Ok(())
+}
+
+pub async fn remove_guild_member(
+    pool: &PgPool,
+    guild_id: i64,
+    discord_id: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "DELETE FROM guild_members WHERE guild_id = $1 AND discord_id = $2",
+        guild_id,
+        discord_id
+    )
+    .execute(pool)
+    .await?;
+    debug!("Delete Completed");
+    Ok(())
+}
+
+/// Forgets a guild the bot has been removed from.
+pub async fn remove_guild(pool: &PgPool, guild_id: i64) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query!("DELETE FROM guild_members WHERE guild_id = $1", guild_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query!(
+        "DELETE FROM guild_home_leagues WHERE guild_id = $1",
+        guild_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    debug!("Delete Completed");
+    Ok(())
+}
+
+/// Members of the guild who have registered a team.
+pub async fn get_registered_guild_members(
+    pool: &PgPool,
+    guild_id: i64,
+) -> Result<Vec<GuildMember>, sqlx::Error> {
+    sqlx::query_as!(
+        GuildMember,
+        r#"
+        SELECT gm.guild_id, gm.discord_id, gm.username, gm.display_name
+        FROM guild_members gm
+        JOIN discord_users du ON du.discord_id = gm.discord_id
+        WHERE gm.guild_id = $1
+        ORDER BY gm.display_name ASC
+        "#,
+        guild_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Members of the guild who haven't registered a team yet.
+pub async fn get_unregistered_guild_members(
+    pool: &PgPool,
+    guild_id: i64,
+) -> Result<Vec<GuildMember>, sqlx::Error> {
+    sqlx::query_as!(
+        GuildMember,
+        r#"
+        SELECT gm.guild_id, gm.discord_id, gm.username, gm.display_name
+        FROM guild_members gm
+        LEFT JOIN discord_users du ON du.discord_id = gm.discord_id
+        WHERE gm.guild_id = $1 AND du.discord_id IS NULL
+        ORDER BY gm.display_name ASC
+        "#,
+        guild_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// A league is visible in a guild if it's the guild's home league, or a registered member of
+/// the guild has a team in it.
+pub async fn is_league_visible_in_guild(
+    pool: &PgPool,
+    guild_id: i64,
+    league_id: LeagueId,
+) -> Result<bool, sqlx::Error> {
+    let record = sqlx::query!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1
+            FROM discord_user_mini_leagues dum
+            JOIN guild_members gm ON gm.discord_id = dum.discord_id
+            WHERE gm.guild_id = $1 AND dum.id = $2
+            UNION ALL
+            SELECT 1
+            FROM guild_home_leagues
+            WHERE guild_id = $1 AND league_id = $2
+        ) as "visible!"
+        "#,
+        guild_id,
+        i32::from(league_id)
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(record.visible)
+}
+
+pub async fn get_guild_home_league(
+    pool: &PgPool,
+    guild_id: i64,
+) -> Result<Option<GuildHomeLeague>, sqlx::Error> {
+    sqlx::query_as!(
+        GuildHomeLeague,
+        r#"
+        SELECT ghl.guild_id, ghl.league_id as "league_id: LeagueId", ml.name, ghl.set_by
+        FROM guild_home_leagues ghl
+        JOIN mini_leagues ml ON ml.id = ghl.league_id
+        WHERE ghl.guild_id = $1
+        "#,
+        guild_id
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+/// Returns false without setting anything if the league isn't in `mini_leagues`.
+pub async fn set_guild_home_league(
+    pool: &PgPool,
+    guild_id: i64,
+    league_id: LeagueId,
+    set_by: i64,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO guild_home_leagues (guild_id, league_id, set_by)
+        SELECT $1, id, $3 FROM mini_leagues WHERE id = $2
+        ON CONFLICT (guild_id) DO UPDATE SET
+            league_id = EXCLUDED.league_id,
+            set_by = EXCLUDED.set_by,
+            updated_at = NOW()
+        "#,
+        guild_id,
+        i32::from(league_id),
+        set_by
+    )
+    .execute(pool)
+    .await?;
+    debug!("Upsert Completed");
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn clear_guild_home_league(pool: &PgPool, guild_id: i64) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        "DELETE FROM guild_home_leagues WHERE guild_id = $1",
+        guild_id
+    )
+    .execute(pool)
+    .await?;
+    debug!("Delete Completed");
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn is_guild_member(
+    pool: &PgPool,
+    guild_id: i64,
+    discord_id: i64,
+) -> Result<bool, sqlx::Error> {
+    let record = sqlx::query!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM guild_members WHERE guild_id = $1 AND discord_id = $2
+        ) as "is_member!"
+        "#,
+        guild_id,
+        discord_id
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(record.is_member)
+}
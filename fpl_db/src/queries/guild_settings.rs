@@ -0,0 +1,44 @@
+use sqlx::PgPool;
+use tracing::debug;
+
+use crate::models::GuildNotificationSettings;
+
+pub async fn get_guild_notification_settings(
+    pool: &PgPool,
+    guild_id: i64,
+) -> Result<Option<GuildNotificationSettings>, sqlx::Error> {
+    sqlx::query_as!(
+        GuildNotificationSettings,
+        r#"
+        SELECT guild_id, points_mode, digest_window_seconds
+        FROM guild_notification_settings
+        WHERE guild_id = $1
+        "#,
+        guild_id
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn upsert_guild_notification_settings(
+    pool: &PgPool,
+    settings: &GuildNotificationSettings,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO guild_notification_settings (guild_id, points_mode, digest_window_seconds)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (guild_id) DO UPDATE SET
+            points_mode = EXCLUDED.points_mode,
+            digest_window_seconds = EXCLUDED.digest_window_seconds,
+            updated_at = NOW()
+        "#,
+        settings.guild_id,
+        settings.points_mode,
+        settings.digest_window_seconds
+    )
+    .execute(pool)
+    .await?;
+    debug!("Upsert Completed");
+    Ok(())
+}
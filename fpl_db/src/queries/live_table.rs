@@ -0,0 +1,141 @@
+use fpl_common::types::{GameWeekId, LeagueId, TeamId};
+use sqlx::PgPool;
+use tracing::debug;
+
+use crate::models::{LeagueLivePoints, LiveTable};
+
+pub async fn insert_live_table(pool: &PgPool, live_table: &LiveTable) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO live_tables (
+            message_id, channel_id, league_id, overall_or_week, game_week_id, created_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+        live_table.message_id,
+        live_table.channel_id,
+        i32::from(live_table.league_id),
+        live_table.overall_or_week,
+        i16::from(live_table.game_week_id),
+        live_table.created_by
+    )
+    .execute(pool)
+    .await?;
+    debug!("Insert Completed");
+    Ok(())
+}
+
+pub async fn get_active_live_tables(pool: &PgPool) -> Result<Vec<LiveTable>, sqlx::Error> {
+    sqlx::query_as!(
+        LiveTable,
+        r#"
+        SELECT
+            message_id, channel_id, league_id as "league_id: LeagueId", overall_or_week,
+            game_week_id as "game_week_id: GameWeekId", created_by, created_at, updated_at, finished
+        FROM live_tables
+        WHERE finished = false
+        "#
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn mark_live_table_updated(pool: &PgPool, message_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE live_tables SET updated_at = NOW() WHERE message_id = $1",
+        message_id
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn finish_live_table(pool: &PgPool, message_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE live_tables SET finished = true, updated_at = NOW() WHERE message_id = $1",
+        message_id
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// `(live, remaining)` fixture counts for a game week. Live means started but not finished,
+/// remaining means not finished.
+pub async fn get_game_week_fixture_progress(
+    pool: &PgPool,
+    game_week_id: GameWeekId,
+) -> Result<(i64, i64), sqlx::Error> {
+    let progress = sqlx::query!(
+        r#"
+        SELECT
+            COUNT(*) FILTER (WHERE started = true AND finished = false) as "live!",
+            COUNT(*) FILTER (WHERE finished = false) as "remaining!"
+        FROM fixtures
+        WHERE game_week_id = $1
+        "#,
+        i16::from(game_week_id)
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok((progress.live, progress.remaining))
+}
+
+/// A page of the `live_points` view for the teams in a league, highest live total first. The view
+/// has a row per linked Discord user so teams are deduplicated here.
+pub async fn get_league_live_points(
+    pool: &PgPool,
+    league_id: LeagueId,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<LeagueLivePoints>, sqlx::Error> {
+    sqlx::query_as!(
+        LeagueLivePoints,
+        r#"
+        SELECT DISTINCT
+            lp.game_week_id as "game_week_id!: GameWeekId",
+            lp.team_id as "team_id!: TeamId",
+            lp.name as "name!",
+            lp.player_first_name as "player_first_name!",
+            lp.player_last_name as "player_last_name!",
+            lp.week_points as "week_points!",
+            lp.calculated_week_points as "calculated_week_points!",
+            lp.overall_points as "overall_points!",
+            lp.calculated_overall_points as "calculated_overall_points!"
+        FROM live_points lp
+        WHERE lp.team_id IN (
+            SELECT team_id
+            FROM mini_league_standings
+            WHERE league_id = $1
+        )
+        ORDER BY lp.calculated_overall_points DESC, lp.team_id
+        LIMIT $2 OFFSET $3
+        "#,
+        i32::from(league_id),
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn count_league_live_points(
+    pool: &PgPool,
+    league_id: LeagueId,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(DISTINCT team_id) as "count!"
+        FROM live_points
+        WHERE team_id IN (
+            SELECT team_id
+            FROM mini_league_standings
+            WHERE league_id = $1
+        )
+        "#,
+        i32::from(league_id)
+    )
+    .fetch_one(pool)
+    .await
+}
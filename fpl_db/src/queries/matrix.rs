@@ -0,0 +1,84 @@
+use fpl_common::types::TeamId;
+use sqlx::PgPool;
+use tracing::debug;
+
+use crate::models::MatrixUser;
+
+/// Registers the user's team, replacing any team they registered before.
+pub async fn upsert_matrix_user(pool: &PgPool, user: &MatrixUser) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO matrix_users (matrix_id, team_id)
+        VALUES ($1, $2)
+        ON CONFLICT (matrix_id) DO UPDATE SET
+            team_id = EXCLUDED.team_id,
+            registered_at = NOW()
+        "#,
+        user.matrix_id,
+        i32::from(user.team_id)
+    )
+    .execute(pool)
+    .await?;
+    debug!("Upsert Completed");
+    Ok(())
+}
+
+pub async fn get_matrix_user(
+    pool: &PgPool,
+    matrix_id: &str,
+) -> Result<Option<MatrixUser>, sqlx::Error> {
+    sqlx::query_as!(
+        MatrixUser,
+        r#"SELECT matrix_id, team_id as "team_id: TeamId" FROM matrix_users WHERE matrix_id = $1"#,
+        matrix_id
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn delete_matrix_user(pool: &PgPool, matrix_id: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!("DELETE FROM matrix_users WHERE matrix_id = $1", matrix_id)
+        .execute(pool)
+        .await?;
+    debug!("Delete Completed");
+    Ok(result.rows_affected() > 0)
+}
+
+/// Matrix users whose team picked the player in the game week.
+pub async fn get_matrix_owners(
+    pool: &PgPool,
+    player_id: i16,
+    game_week_id: i16,
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query!(
+        r#"
+        SELECT DISTINCT mu.matrix_id
+        FROM team_game_week_picks tgwp
+        JOIN matrix_users mu ON mu.team_id = tgwp.team_id
+        WHERE tgwp.player_id = $1 AND tgwp.game_week_id = $2
+        ORDER BY mu.matrix_id
+        "#,
+        player_id,
+        game_week_id
+    )
+    .map(|row| row.matrix_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// The id `notification_state` knows the room by, created on first use. Negated so it can never
+/// clash with a Discord channel snowflake.
+pub async fn get_matrix_room_channel_id(pool: &PgPool, room_id: &str) -> Result<i64, sqlx::Error> {
+    let record = sqlx::query!(
+        r#"
+        INSERT INTO matrix_rooms (room_id)
+        VALUES ($1)
+        ON CONFLICT (room_id) DO UPDATE SET room_id = EXCLUDED.room_id
+        RETURNING id
+        "#,
+        room_id
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(-record.id)
+}
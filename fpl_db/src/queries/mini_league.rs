@@ -1,4 +1,4 @@
-use fpl_common::types::LeagueId;
+use fpl_common::types::{GameWeekId, LeagueId, TeamId};
 use sqlx::PgPool;
 use tracing::debug;
 
@@ -149,3 +149,78 @@ pub async fn get_team_ids_from_league_id(
 
     Ok(records)
 }
+
+pub async fn get_mini_league(
+    pool: &PgPool,
+    league_id: LeagueId,
+) -> Result<Option<MiniLeague>, sqlx::Error> {
+    sqlx::query_as!(
+        MiniLeague,
+        r#"
+        SELECT
+            id as "id: LeagueId", last_updated_data, name, created, closed, max_entries,
+            league_type, scoring, admin_entry as "admin_entry: TeamId",
+            start_event as "start_event: GameWeekId", code_privacy, has_cup, cup_league, rank
+        FROM mini_leagues
+        WHERE id = $1
+        "#,
+        i32::from(league_id)
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+/// Every tracked mini league, by name.
+pub async fn get_mini_leagues(pool: &PgPool) -> Result<Vec<MiniLeague>, sqlx::Error> {
+    sqlx::query_as!(
+        MiniLeague,
+        r#"
+        SELECT
+            id as "id: LeagueId", last_updated_data, name, created, closed, max_entries,
+            league_type, scoring, admin_entry as "admin_entry: TeamId",
+            start_event as "start_event: GameWeekId", code_privacy, has_cup, cup_league, rank
+        FROM mini_leagues
+        ORDER BY name
+        "#
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// A page of a league's standings, ordered the way the FPL site shows them.
+pub async fn get_mini_league_standings(
+    pool: &PgPool,
+    league_id: LeagueId,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<MiniLeagueStanding>, sqlx::Error> {
+    sqlx::query_as!(
+        MiniLeagueStanding,
+        r#"
+        SELECT
+            id, event_total, player_name, rank, last_rank, rank_sort, total as "total!",
+            team_id as "team_id: TeamId", entry_name, has_player, league_id as "league_id: LeagueId"
+        FROM mini_league_standings
+        WHERE league_id = $1
+        ORDER BY rank_sort
+        LIMIT $2 OFFSET $3
+        "#,
+        i32::from(league_id),
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn count_mini_league_standings(
+    pool: &PgPool,
+    league_id: LeagueId,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"SELECT COUNT(*) as "count!" FROM mini_league_standings WHERE league_id = $1"#,
+        i32::from(league_id)
+    )
+    .fetch_one(pool)
+    .await
+}
@@ -1,10 +1,21 @@
 pub mod club;
+pub mod cup;
 pub mod discord;
 pub mod fixture;
 pub mod game_week;
 pub mod game_week_player;
+pub mod guild;
+pub mod guild_settings;
+pub mod live_table;
+pub mod matrix;
 pub mod mini_league;
+pub mod notification_state;
 pub mod player;
+pub mod request_window;
+pub mod session;
 pub mod team;
 pub mod team_game_week;
+pub mod theme;
 pub mod transfers;
+pub mod watchlist;
+pub mod webhook;
@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+
+use sqlx::PgPool;
+use tracing::{debug, warn};
+
+use crate::models::{NotificationKind, NotificationState, NotificationTransition};
+
+/// Last notified value per subject (fixture or player) for a channel and game week.
+pub async fn get_notification_states(
+    pool: &PgPool,
+    channel_id: i64,
+    kind: NotificationKind,
+    game_week_id: i16,
+) -> Result<HashMap<i32, String>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT subject_id, last_value
+        FROM notification_state
+        WHERE channel_id = $1 AND kind = $2 AND game_week_id = $3
+        "#,
+        channel_id,
+        kind.as_str(),
+        game_week_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.subject_id, row.last_value))
+        .collect())
+}
+
+/// Last notified value for one subject, `None` if nothing has been stored for it yet.
+pub async fn get_notification_state(
+    pool: &PgPool,
+    channel_id: i64,
+    kind: NotificationKind,
+    subject_id: i32,
+    game_week_id: i16,
+) -> Result<Option<String>, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT last_value
+        FROM notification_state
+        WHERE channel_id = $1 AND kind = $2 AND subject_id = $3 AND game_week_id = $4
+        "#,
+        channel_id,
+        kind.as_str(),
+        subject_id,
+        game_week_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| row.last_value))
+}
+
+/// Claims the change from `previous_value` to `value`. Only wins if nothing is stored for the
+/// subject yet, or what's stored is still `previous_value`, so a repeated or stale change loses.
+/// Returns `true` if the caller won and should send the notification.
+///
+/// The row is left `claimed` until `mark_notification_sent`. If the send fails or the instance
+/// stops first, `reclaim_unsent_notifications` hands it out again.
+pub async fn claim_notification(
+    pool: &PgPool,
+    transition: &NotificationTransition,
+) -> Result<bool, sqlx::Error> {
+    let claimed = sqlx::query!(
+        r#"
+        INSERT INTO notification_state (
+            channel_id, kind, subject_id, game_week_id, previous_value, last_value,
+            idempotency_key, status, attempts, claimed_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, 'claimed', 1, NOW())
+        ON CONFLICT (channel_id, kind, subject_id, game_week_id) DO UPDATE SET
+            previous_value = EXCLUDED.previous_value,
+            last_value = EXCLUDED.last_value,
+            idempotency_key = EXCLUDED.idempotency_key,
+            status = 'claimed',
+            attempts = 1,
+            claimed_at = NOW(),
+            updated_at = NOW()
+        WHERE notification_state.last_value = EXCLUDED.previous_value
+            AND notification_state.last_value <> EXCLUDED.last_value
+        RETURNING subject_id
+        "#,
+        transition.channel_id,
+        transition.kind.as_str(),
+        transition.subject_id,
+        transition.game_week_id,
+        transition.previous_value,
+        transition.value,
+        transition.idempotency_key()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(claimed.is_some())
+}
+
+/// Confirms a claimed notification went out. Does nothing if the subject has since been claimed
+/// for a newer change, that claim is still owed its own send.
+pub async fn mark_notification_sent(
+    pool: &PgPool,
+    transition: &NotificationTransition,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE notification_state SET status = 'sent', updated_at = NOW()
+        WHERE channel_id = $1 AND kind = $2 AND subject_id = $3 AND game_week_id = $4
+            AND idempotency_key = $5 AND status = 'claimed'
+        "#,
+        transition.channel_id,
+        transition.kind.as_str(),
+        transition.subject_id,
+        transition.game_week_id,
+        transition.idempotency_key()
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Takes claims of `kinds` that haven't been marked sent within `lease_seconds`, pushing their
+/// lease back so another instance won't take them too. Anything already tried `max_attempts`
+/// times is marked failed and not returned. `channel_id` limits it to one channel, `None` is
+/// every channel (e.g. watchlist DMs, where the channel is the user).
+pub async fn reclaim_unsent_notifications(
+    pool: &PgPool,
+    kinds: &[NotificationKind],
+    channel_id: Option<i64>,
+    lease_seconds: f64,
+    max_attempts: i16,
+) -> Result<Vec<NotificationState>, sqlx::Error> {
+    let kinds: Vec<String> = kinds.iter().map(|kind| kind.as_str().to_string()).collect();
+    let reclaimed = sqlx::query_as!(
+        NotificationState,
+        r#"
+        UPDATE notification_state SET
+            attempts = attempts + 1,
+            claimed_at = NOW(),
+            status = CASE WHEN attempts >= $4 THEN 'failed' ELSE status END,
+            updated_at = NOW()
+        WHERE status = 'claimed'
+            AND kind = ANY($1::TEXT[])
+            AND ($2::BIGINT IS NULL OR channel_id = $2)
+            AND claimed_at <= NOW() - make_interval(secs => $3)
+        RETURNING channel_id, kind, subject_id, game_week_id, previous_value, last_value,
+            idempotency_key, status, attempts, claimed_at, updated_at
+        "#,
+        &kinds,
+        channel_id,
+        lease_seconds,
+        max_attempts
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let (reclaimed, failed): (Vec<_>, Vec<_>) = reclaimed
+        .into_iter()
+        .partition(|state| state.status == "claimed");
+    for state in failed {
+        warn!(
+            "Giving up on notification {} after {} attempts",
+            state.idempotency_key, max_attempts
+        );
+    }
+    Ok(reclaimed)
+}
+
+/// Drops state from game weeks before `game_week_id`, nothing can change in them any more. Rows
+/// that aren't tied to a game week (watchlist prices, statuses and reschedules) are kept.
+pub async fn delete_old_notification_states(
+    pool: &PgPool,
+    game_week_id: i16,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM notification_state
+        WHERE game_week_id > 0 AND game_week_id < $1
+        "#,
+        game_week_id
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Stores starting values without notifying, e.g. on the first poll of a game week.
+pub async fn seed_notification_states(
+    pool: &PgPool,
+    transitions: &[NotificationTransition],
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    debug!("Seeding {} NotificationState rows", transitions.len());
+    for transition in transitions {
+        sqlx::query!(
+            r#"
+            INSERT INTO notification_state (
+                channel_id, kind, subject_id, game_week_id, last_value, idempotency_key
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (channel_id, kind, subject_id, game_week_id) DO NOTHING
+            "#,
+            transition.channel_id,
+            transition.kind.as_str(),
+            transition.subject_id,
+            transition.game_week_id,
+            transition.value,
+            transition.idempotency_key()
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    debug!("Seed Completed");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transition(previous_value: Option<&str>, value: &str) -> NotificationTransition {
+        NotificationTransition {
+            channel_id: 1,
+            kind: NotificationKind::FixtureScore,
+            subject_id: 10,
+            game_week_id: 5,
+            previous_value: previous_value.map(str::to_string),
+            value: value.to_string(),
+        }
+    }
+
+    async fn reclaim(pool: &PgPool) -> Vec<NotificationState> {
+        reclaim_unsent_notifications(pool, &[NotificationKind::FixtureScore], Some(1), 0.0, 3)
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_claim_compares_previous_value(pool: PgPool) {
+        assert!(claim_notification(&pool, &transition(None, "0-0"))
+            .await
+            .unwrap());
+        // Same change again, e.g. from another instance
+        assert!(!claim_notification(&pool, &transition(None, "0-0"))
+            .await
+            .unwrap());
+
+        assert!(claim_notification(&pool, &transition(Some("0-0"), "1-0"))
+            .await
+            .unwrap());
+        // Stale, what's stored has already moved on from 0-0
+        assert!(!claim_notification(&pool, &transition(Some("0-0"), "2-0"))
+            .await
+            .unwrap());
+        assert!(!claim_notification(&pool, &transition(Some("1-0"), "1-0"))
+            .await
+            .unwrap());
+
+        let state = get_notification_state(&pool, 1, NotificationKind::FixtureScore, 10, 5)
+            .await
+            .unwrap();
+        assert_eq!(state.as_deref(), Some("1-0"));
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_unsent_claims_are_reclaimed(pool: PgPool) {
+        let kick_off = transition(None, "0-0");
+        assert!(claim_notification(&pool, &kick_off).await.unwrap());
+
+        let reclaimed = reclaim(&pool).await;
+        assert_eq!(reclaimed.len(), 1);
+        assert_eq!(reclaimed[0].attempts, 2);
+        assert_eq!(
+            reclaimed[0].transition().unwrap().idempotency_key(),
+            kick_off.idempotency_key()
+        );
+
+        mark_notification_sent(&pool, &kick_off).await.unwrap();
+        assert!(reclaim(&pool).await.is_empty());
+
+        // Out of attempts
+        let goal = transition(Some("0-0"), "1-0");
+        assert!(claim_notification(&pool, &goal).await.unwrap());
+        assert_eq!(reclaim(&pool).await.len(), 1);
+        assert_eq!(reclaim(&pool).await.len(), 1);
+        assert!(reclaim(&pool).await.is_empty());
+        assert!(reclaim(&pool).await.is_empty());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_mark_sent_ignores_superseded_claims(pool: PgPool) {
+        let goal = transition(Some("0-0"), "1-0");
+        seed_notification_states(&pool, &[transition(None, "0-0")])
+            .await
+            .unwrap();
+        assert!(claim_notification(&pool, &goal).await.unwrap());
+        assert!(claim_notification(&pool, &transition(Some("1-0"), "2-0"))
+            .await
+            .unwrap());
+
+        // The first goal's send finishing late mustn't confirm the second
+        mark_notification_sent(&pool, &goal).await.unwrap();
+        let reclaimed = reclaim(&pool).await;
+        assert_eq!(reclaimed.len(), 1);
+        assert_eq!(reclaimed[0].last_value, "2-0");
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_delete_old_notification_states(pool: PgPool) {
+        let mut seeds = Vec::new();
+        for game_week_id in [0, 3, 4, 5] {
+            seeds.push(NotificationTransition {
+                game_week_id,
+                ..transition(None, "0-0")
+            });
+        }
+        seed_notification_states(&pool, &seeds).await.unwrap();
+
+        assert_eq!(delete_old_notification_states(&pool, 5).await.unwrap(), 2);
+        for (game_week_id, kept) in [(0, true), (3, false), (4, false), (5, true)] {
+            let state =
+                get_notification_state(&pool, 1, NotificationKind::FixtureScore, 10, game_week_id)
+                    .await
+                    .unwrap();
+            assert_eq!(state.is_some(), kept);
+        }
+    }
+}
@@ -1,13 +1,27 @@
+use std::collections::HashMap;
+
 use fpl_common::types::PlayerId;
 use sqlx::PgPool;
 use tracing::debug;
 
+use crate::events::{notify_change, ChangeEvent};
 use crate::models::{player::Player, PlayerFixtureDb, PlayerHistoryDb, PlayerHistoryPastDb};
 
 pub async fn upsert_players(pool: &PgPool, players: &[Player]) -> Result<(), sqlx::Error> {
     let mut tx = pool.begin().await?;
     debug!("Upserting {} Player rows", players.len());
 
+    let player_ids: Vec<i16> = players.iter().map(|p| i16::from(p.id)).collect();
+    let previous_players: HashMap<i16, (i16, String, String)> = sqlx::query!(
+        "SELECT id, now_cost, status, news FROM players WHERE id = ANY($1)",
+        &player_ids
+    )
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    .map(|row| (row.id, (row.now_cost, row.status, row.news)))
+    .collect();
+
     for player in players {
         sqlx::query!(
            r#"
@@ -229,6 +243,31 @@ pub async fn upsert_players(pool: &PgPool, players: &[Player]) -> Result<(), sql
        )
        .execute(&mut *tx)
        .await?;
+
+        let player_id = i16::from(player.id);
+        if let Some((previous_cost, previous_status, previous_news)) =
+            previous_players.get(&player_id)
+        {
+            if *previous_cost != player.now_cost {
+                let event = ChangeEvent::PlayerPriceChanged {
+                    player_id,
+                    previous_cost: *previous_cost,
+                    now_cost: player.now_cost,
+                };
+                notify_change(&mut tx, &event).await?;
+            }
+
+            if *previous_status != player.status || *previous_news != player.news {
+                let event = ChangeEvent::PlayerStatusChanged {
+                    player_id,
+                    previous_status: previous_status.clone(),
+                    status: player.status.clone(),
+                    previous_news: previous_news.clone(),
+                    news: player.news.clone(),
+                };
+                notify_change(&mut tx, &event).await?;
+            }
+        }
     }
 
     tx.commit().await?;
@@ -478,3 +517,29 @@ pub async fn get_all_player_codes(pool: &PgPool) -> Result<Vec<u32>, sqlx::Error
 
     Ok(ids)
 }
+
+/// A page of players ordered by id.
+pub async fn get_players(
+    pool: &PgPool,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Player>, sqlx::Error> {
+    sqlx::query_as::<_, Player>("SELECT * FROM players ORDER BY id LIMIT $1 OFFSET $2")
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn count_players(pool: &PgPool) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(r#"SELECT COUNT(*) as "count!" FROM players"#)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn get_player(pool: &PgPool, player_id: PlayerId) -> Result<Option<Player>, sqlx::Error> {
+    sqlx::query_as::<_, Player>("SELECT * FROM players WHERE id = $1")
+        .bind(i16::from(player_id))
+        .fetch_optional(pool)
+        .await
+}
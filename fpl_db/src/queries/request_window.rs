@@ -0,0 +1,68 @@
+use sqlx::PgPool;
+
+/// Counts a request to `host` in the current second if fewer than `limit` have been made in it,
+/// by any process. Returns `false` if the second is already full.
+pub async fn take_request_slot(pool: &PgPool, host: &str, limit: i32) -> Result<bool, sqlx::Error> {
+    let taken = sqlx::query!(
+        r#"
+        INSERT INTO fpl_request_windows (host, window_start, requests)
+        VALUES ($1, date_trunc('second', clock_timestamp()), 1)
+        ON CONFLICT (host, window_start) DO UPDATE SET
+            requests = fpl_request_windows.requests + 1
+        WHERE fpl_request_windows.requests < $2
+        RETURNING requests
+        "#,
+        host,
+        limit
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    // The first request of a second tidies up after the ones before it
+    if taken.as_ref().is_some_and(|taken| taken.requests == 1) {
+        sqlx::query!(
+            r#"
+            DELETE FROM fpl_request_windows
+            WHERE host = $1 AND window_start < clock_timestamp() - INTERVAL '1 minute'
+            "#,
+            host
+        )
+        .execute(pool)
+        .await?;
+    }
+
+    Ok(taken.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_take_request_slot_stops_at_the_limit(pool: PgPool) {
+        // Fill this second and the next, so it doesn't matter if the test straddles them
+        sqlx::query!(
+            r#"
+            INSERT INTO fpl_request_windows (host, window_start, requests)
+            SELECT 'fantasy.premierleague.com', window_start, 2
+            FROM (VALUES
+                (date_trunc('second', clock_timestamp())),
+                (date_trunc('second', clock_timestamp()) + INTERVAL '1 second')
+            ) AS windows (window_start)
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert!(!take_request_slot(&pool, "fantasy.premierleague.com", 2)
+            .await
+            .unwrap());
+        assert!(take_request_slot(&pool, "fantasy.premierleague.com", 3)
+            .await
+            .unwrap());
+        assert!(take_request_slot(&pool, "resources.premierleague.com", 2)
+            .await
+            .unwrap());
+    }
+}
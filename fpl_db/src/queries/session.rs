@@ -0,0 +1,56 @@
+use sqlx::PgPool;
+use tracing::debug;
+
+use crate::models::StoredFplSession;
+
+pub async fn upsert_fpl_session(
+    pool: &PgPool,
+    session: &StoredFplSession,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO fpl_sessions (
+            discord_id, team_id, encrypted_cookie, nonce, linked_at
+        )
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (discord_id) DO UPDATE SET
+            team_id = EXCLUDED.team_id,
+            encrypted_cookie = EXCLUDED.encrypted_cookie,
+            nonce = EXCLUDED.nonce,
+            linked_at = EXCLUDED.linked_at
+        "#,
+        session.discord_id,
+        i32::from(session.team_id),
+        session.encrypted_cookie,
+        session.nonce,
+        session.linked_at
+    )
+    .execute(pool)
+    .await?;
+    debug!("Upsert Completed");
+    Ok(())
+}
+
+pub async fn get_fpl_session(
+    pool: &PgPool,
+    discord_id: i64,
+) -> Result<Option<StoredFplSession>, sqlx::Error> {
+    sqlx::query_as!(
+        StoredFplSession,
+        r#"
+        SELECT discord_id, team_id, encrypted_cookie, nonce, linked_at
+        FROM fpl_sessions
+        WHERE discord_id = $1
+        "#,
+        discord_id
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn delete_fpl_session(pool: &PgPool, discord_id: i64) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!("DELETE FROM fpl_sessions WHERE discord_id = $1", discord_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
@@ -2,6 +2,7 @@ use crate::models::{
     team_game_week::{TeamGameWeek, TeamGameWeekPick},
     TeamGameWeekAutomaticSub,
 };
+use fpl_common::types::{GameWeekId, PlayerId, TeamId};
 use sqlx::PgPool;
 use tracing::debug;
 
@@ -177,3 +178,48 @@ pub async fn upsert_team_game_week_automatic_subs(
     debug!("Upsert Completed");
     Ok(())
 }
+
+pub async fn get_team_game_week_picks(
+    pool: &PgPool,
+    team_id: TeamId,
+    game_week_id: GameWeekId,
+) -> Result<Vec<TeamGameWeekPick>, sqlx::Error> {
+    sqlx::query_as!(
+        TeamGameWeekPick,
+        r#"
+        SELECT
+            team_id as "team_id: TeamId", game_week_id as "game_week_id: GameWeekId",
+            player_id as "player_id: PlayerId", position, multiplier, is_captain, is_vice_captain,
+            element_type
+        FROM team_game_week_picks
+        WHERE team_id = $1 AND game_week_id = $2
+        ORDER BY position
+        "#,
+        i32::from(team_id),
+        i16::from(game_week_id)
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// The game weeks a team played a chip in, earliest first.
+pub async fn get_team_chips(
+    pool: &PgPool,
+    team_id: TeamId,
+) -> Result<Vec<TeamGameWeek>, sqlx::Error> {
+    sqlx::query_as!(
+        TeamGameWeek,
+        r#"
+        SELECT
+            team_id as "team_id: TeamId", game_week_id as "game_week_id: GameWeekId", active_chip,
+            points, total_points, rank, rank_sort, overall_rank, percentile_rank, bank, value,
+            event_transfers, event_transfers_cost, points_on_bench
+        FROM team_game_weeks
+        WHERE team_id = $1 AND active_chip IS NOT NULL
+        ORDER BY game_week_id
+        "#,
+        i32::from(team_id)
+    )
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,78 @@
+use sqlx::PgPool;
+use tracing::debug;
+
+pub async fn upsert_user_theme(
+    pool: &PgPool,
+    discord_id: i64,
+    theme: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO user_theme_preferences (discord_id, theme)
+        VALUES ($1, $2)
+        ON CONFLICT (discord_id) DO UPDATE SET
+            theme = EXCLUDED.theme,
+            updated_at = NOW()
+        "#,
+        discord_id,
+        theme
+    )
+    .execute(pool)
+    .await?;
+    debug!("Upsert Completed");
+    Ok(())
+}
+
+/// Removes a user's theme so the server's applies again, returning false if they didn't have one.
+pub async fn delete_user_theme(pool: &PgPool, discord_id: i64) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        "DELETE FROM user_theme_preferences WHERE discord_id = $1",
+        discord_id
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn upsert_guild_theme(
+    pool: &PgPool,
+    guild_id: i64,
+    theme: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO guild_theme_preferences (guild_id, theme)
+        VALUES ($1, $2)
+        ON CONFLICT (guild_id) DO UPDATE SET
+            theme = EXCLUDED.theme,
+            updated_at = NOW()
+        "#,
+        guild_id,
+        theme
+    )
+    .execute(pool)
+    .await?;
+    debug!("Upsert Completed");
+    Ok(())
+}
+
+/// The theme a user sees, their own if they've picked one and otherwise the server's.
+pub async fn get_theme_preference(
+    pool: &PgPool,
+    discord_id: Option<i64>,
+    guild_id: Option<i64>,
+) -> Result<Option<String>, sqlx::Error> {
+    let record = sqlx::query!(
+        r#"
+        SELECT COALESCE(
+            (SELECT theme FROM user_theme_preferences WHERE discord_id = $1),
+            (SELECT theme FROM guild_theme_preferences WHERE guild_id = $2)
+        ) as theme
+        "#,
+        discord_id,
+        guild_id
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(record.theme)
+}
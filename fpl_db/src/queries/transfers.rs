@@ -1,3 +1,4 @@
+use fpl_common::types::{GameWeekId, PlayerId, TeamId};
 use sqlx::PgPool;
 use tracing::debug;
 
@@ -34,3 +35,39 @@ pub async fn upsert_transfers(pool: &PgPool, transfers: &[Transfer]) -> Result<(
     debug!("Upsert Completed");
     Ok(())
 }
+
+/// A page of a team's transfers, most recent first.
+pub async fn get_team_transfers(
+    pool: &PgPool,
+    team_id: TeamId,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Transfer>, sqlx::Error> {
+    sqlx::query_as!(
+        Transfer,
+        r#"
+        SELECT
+            player_in_id as "player_in_id: PlayerId", player_out_id as "player_out_id: PlayerId",
+            player_in_cost, player_out_cost, team_id as "team_id: TeamId",
+            game_week_id as "game_week_id: GameWeekId", transfer_time
+        FROM transfers
+        WHERE team_id = $1
+        ORDER BY transfer_time DESC
+        LIMIT $2 OFFSET $3
+        "#,
+        i32::from(team_id),
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn count_team_transfers(pool: &PgPool, team_id: TeamId) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"SELECT COUNT(*) as "count!" FROM transfers WHERE team_id = $1"#,
+        i32::from(team_id)
+    )
+    .fetch_one(pool)
+    .await
+}
@@ -0,0 +1,123 @@
+use fpl_common::types::{FixtureId, PlayerId};
+use sqlx::PgPool;
+use tracing::debug;
+
+use crate::models::{WatchedPlayer, Watcher};
+
+/// Adds a player to a user's watchlist, returning false if they were already watching them.
+pub async fn add_watched_player(
+    pool: &PgPool,
+    discord_id: i64,
+    player_id: PlayerId,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO watchlist (discord_id, player_id)
+        VALUES ($1, $2)
+        ON CONFLICT (discord_id, player_id) DO NOTHING
+        "#,
+        discord_id,
+        i16::from(player_id)
+    )
+    .execute(pool)
+    .await?;
+    debug!("Insert Completed");
+    Ok(result.rows_affected() > 0)
+}
+
+/// Removes a player from a user's watchlist, returning false if they weren't on it.
+pub async fn remove_watched_player(
+    pool: &PgPool,
+    discord_id: i64,
+    player_id: PlayerId,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        "DELETE FROM watchlist WHERE discord_id = $1 AND player_id = $2",
+        discord_id,
+        i16::from(player_id)
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn count_watched_players(pool: &PgPool, discord_id: i64) -> Result<i64, sqlx::Error> {
+    let record = sqlx::query!(
+        r#"SELECT COUNT(*) as "count!" FROM watchlist WHERE discord_id = $1"#,
+        discord_id
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(record.count)
+}
+
+pub async fn get_watched_players(
+    pool: &PgPool,
+    discord_id: i64,
+) -> Result<Vec<WatchedPlayer>, sqlx::Error> {
+    sqlx::query_as!(
+        WatchedPlayer,
+        r#"
+        SELECT
+            p.id as "player_id: PlayerId",
+            p.web_name,
+            c.short_name as club_short_name,
+            p.now_cost,
+            p.status,
+            p.news
+        FROM watchlist w
+        JOIN players p ON p.id = w.player_id
+        JOIN clubs c ON c.id = p.team
+        WHERE w.discord_id = $1
+        ORDER BY p.web_name ASC
+        "#,
+        discord_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_player_watchers(
+    pool: &PgPool,
+    player_id: PlayerId,
+) -> Result<Vec<Watcher>, sqlx::Error> {
+    sqlx::query_as!(
+        Watcher,
+        r#"
+        SELECT
+            w.discord_id,
+            p.id as "player_id: PlayerId",
+            p.web_name
+        FROM watchlist w
+        JOIN players p ON p.id = w.player_id
+        WHERE w.player_id = $1
+        "#,
+        i16::from(player_id)
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Everyone watching a player at either club in a fixture.
+pub async fn get_fixture_watchers(
+    pool: &PgPool,
+    fixture_id: FixtureId,
+) -> Result<Vec<Watcher>, sqlx::Error> {
+    sqlx::query_as!(
+        Watcher,
+        r#"
+        SELECT
+            w.discord_id,
+            p.id as "player_id: PlayerId",
+            p.web_name
+        FROM watchlist w
+        JOIN players p ON p.id = w.player_id
+        JOIN fixtures f ON p.team IN (f.home_team_id, f.away_team_id)
+        WHERE f.id = $1
+        ORDER BY w.discord_id, p.web_name
+        "#,
+        i16::from(fixture_id)
+    )
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,252 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::debug;
+
+use crate::models::{
+    DeliveryStatus, DueWebhookDelivery, Webhook, WebhookDelivery, WebhookEvent, WebhookOwner,
+};
+
+pub async fn count_webhooks(pool: &PgPool, owner: &WebhookOwner) -> Result<i64, sqlx::Error> {
+    let record = sqlx::query!(
+        r#"
+        SELECT COUNT(*) as "count!"
+        FROM webhooks
+        WHERE guild_id IS NOT DISTINCT FROM $1 AND api_key_hash IS NOT DISTINCT FROM $2
+        "#,
+        owner.guild_id(),
+        owner.api_key_hash()
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(record.count)
+}
+
+pub async fn insert_webhook(
+    pool: &PgPool,
+    owner: &WebhookOwner,
+    url: &str,
+    secret: &str,
+    events: &[WebhookEvent],
+) -> Result<Webhook, sqlx::Error> {
+    let events: Vec<String> = events.iter().map(|event| event.to_string()).collect();
+    let webhook = sqlx::query_as!(
+        Webhook,
+        r#"
+        INSERT INTO webhooks (guild_id, api_key_hash, url, secret, events)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, guild_id, api_key_hash, url, secret, events, active, created_at
+        "#,
+        owner.guild_id(),
+        owner.api_key_hash(),
+        url,
+        secret,
+        &events
+    )
+    .fetch_one(pool)
+    .await?;
+    debug!("Insert Completed");
+    Ok(webhook)
+}
+
+pub async fn get_webhooks(
+    pool: &PgPool,
+    owner: &WebhookOwner,
+) -> Result<Vec<Webhook>, sqlx::Error> {
+    sqlx::query_as!(
+        Webhook,
+        r#"
+        SELECT id, guild_id, api_key_hash, url, secret, events, active, created_at
+        FROM webhooks
+        WHERE guild_id IS NOT DISTINCT FROM $1 AND api_key_hash IS NOT DISTINCT FROM $2
+        ORDER BY id ASC
+        "#,
+        owner.guild_id(),
+        owner.api_key_hash()
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Deletes a webhook and its delivery log, returning false if the owner has no webhook with that id.
+pub async fn delete_webhook(
+    pool: &PgPool,
+    owner: &WebhookOwner,
+    webhook_id: i64,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM webhooks
+        WHERE id = $1
+            AND guild_id IS NOT DISTINCT FROM $2
+            AND api_key_hash IS NOT DISTINCT FROM $3
+        "#,
+        webhook_id,
+        owner.guild_id(),
+        owner.api_key_hash()
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Every active webhook subscribed to `event`, whoever owns it.
+pub async fn get_subscribed_webhooks(
+    pool: &PgPool,
+    event: WebhookEvent,
+) -> Result<Vec<Webhook>, sqlx::Error> {
+    sqlx::query_as!(
+        Webhook,
+        r#"
+        SELECT id, guild_id, api_key_hash, url, secret, events, active, created_at
+        FROM webhooks
+        WHERE active AND $1 = ANY(events)
+        "#,
+        event.as_str()
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Queues a delivery, returning false if this webhook already has one with the same
+/// idempotency key, e.g. because another instance saw the same event first.
+pub async fn insert_webhook_delivery(
+    pool: &PgPool,
+    webhook_id: i64,
+    event: WebhookEvent,
+    idempotency_key: &str,
+    payload: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO webhook_deliveries (webhook_id, event, idempotency_key, payload)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (webhook_id, idempotency_key) DO NOTHING
+        "#,
+        webhook_id,
+        event.as_str(),
+        idempotency_key,
+        payload
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Takes up to `limit` pending deliveries that are due and pushes their next attempt back by
+/// `lease_seconds`, so another instance won't pick them up while they're being sent. If this
+/// instance dies mid send they're retried once the lease runs out.
+pub async fn claim_due_webhook_deliveries(
+    pool: &PgPool,
+    limit: i64,
+    lease_seconds: f64,
+) -> Result<Vec<DueWebhookDelivery>, sqlx::Error> {
+    sqlx::query_as!(
+        DueWebhookDelivery,
+        r#"
+        UPDATE webhook_deliveries d SET
+            attempts = d.attempts + 1,
+            next_attempt_at = NOW() + make_interval(secs => $2)
+        FROM webhooks w
+        WHERE w.id = d.webhook_id AND d.id IN (
+            SELECT pending.id
+            FROM webhook_deliveries pending
+            JOIN webhooks hook ON hook.id = pending.webhook_id
+            WHERE pending.status = 'pending' AND pending.next_attempt_at <= NOW() AND hook.active
+            ORDER BY pending.next_attempt_at ASC
+            LIMIT $1
+            FOR UPDATE OF pending SKIP LOCKED
+        )
+        RETURNING d.id, d.webhook_id, w.url, w.secret, d.event, d.idempotency_key, d.payload, d.attempts
+        "#,
+        limit,
+        lease_seconds
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Records how an attempt went. `next_attempt_at` only matters while the delivery is still pending.
+pub async fn record_webhook_delivery_attempt(
+    pool: &PgPool,
+    delivery_id: i64,
+    status: DeliveryStatus,
+    status_code: Option<i16>,
+    error: Option<&str>,
+    next_attempt_at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE webhook_deliveries SET
+            status = $2,
+            last_status_code = $3,
+            last_error = $4,
+            next_attempt_at = $5,
+            delivered_at = CASE WHEN $6 THEN NOW() ELSE NULL END
+        WHERE id = $1
+        "#,
+        delivery_id,
+        status.as_str(),
+        status_code,
+        error,
+        next_attempt_at,
+        status == DeliveryStatus::Delivered
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Newest first delivery log for one of the owner's webhooks.
+pub async fn get_webhook_deliveries(
+    pool: &PgPool,
+    owner: &WebhookOwner,
+    webhook_id: i64,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+    sqlx::query_as!(
+        WebhookDelivery,
+        r#"
+        SELECT
+            d.id, d.webhook_id, d.event, d.idempotency_key, d.payload, d.status, d.attempts,
+            d.last_status_code, d.last_error, d.next_attempt_at, d.created_at, d.delivered_at
+        FROM webhook_deliveries d
+        JOIN webhooks w ON w.id = d.webhook_id
+        WHERE d.webhook_id = $1
+            AND w.guild_id IS NOT DISTINCT FROM $2
+            AND w.api_key_hash IS NOT DISTINCT FROM $3
+        ORDER BY d.created_at DESC, d.id DESC
+        LIMIT $4 OFFSET $5
+        "#,
+        webhook_id,
+        owner.guild_id(),
+        owner.api_key_hash(),
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn count_webhook_deliveries(
+    pool: &PgPool,
+    owner: &WebhookOwner,
+    webhook_id: i64,
+) -> Result<i64, sqlx::Error> {
+    let record = sqlx::query!(
+        r#"
+        SELECT COUNT(*) as "count!"
+        FROM webhook_deliveries d
+        JOIN webhooks w ON w.id = d.webhook_id
+        WHERE d.webhook_id = $1
+            AND w.guild_id IS NOT DISTINCT FROM $2
+            AND w.api_key_hash IS NOT DISTINCT FROM $3
+        "#,
+        webhook_id,
+        owner.guild_id(),
+        owner.api_key_hash()
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(record.count)
+}
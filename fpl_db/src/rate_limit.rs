@@ -0,0 +1,52 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{Timelike, Utc};
+use fpl_api::concurrency::{background_requests_per_second, RequestPriority, SharedRateLimiter};
+use sqlx::PgPool;
+use tracing::warn;
+
+use crate::queries::request_window::take_request_slot;
+
+/// Counts FPL requests in `fpl_request_windows`, so every process calling FPL (the bot, the
+/// scraper and the Matrix bot) shares one limit per host. Background requests can only fill part
+/// of each second, the rest is left for interactive ones.
+///
+/// If the database can't be reached requests go ahead, still limited by each process's own
+/// limiter, rather than stalling every fetch.
+pub struct PgRateLimiter {
+    pool: Arc<PgPool>,
+}
+
+impl PgRateLimiter {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SharedRateLimiter for PgRateLimiter {
+    async fn until_ready(&self, host: &str, requests_per_second: u32, priority: RequestPriority) {
+        let limit = match priority {
+            RequestPriority::Interactive => requests_per_second,
+            RequestPriority::Background => background_requests_per_second(requests_per_second),
+        };
+
+        loop {
+            match take_request_slot(&self.pool, host, limit as i32).await {
+                Ok(true) => return,
+                Ok(false) => {
+                    // Full, try again once the next second starts
+                    let into_second = Utc::now().nanosecond() % 1_000_000_000;
+                    let wait = Duration::from_nanos(u64::from(1_000_000_000 - into_second));
+                    tokio::time::sleep(wait).await;
+                }
+                Err(e) => {
+                    warn!("Shared rate limit for {} unavailable: {}", host, e);
+                    return;
+                }
+            }
+        }
+    }
+}
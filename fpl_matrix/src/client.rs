@@ -0,0 +1,420 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::{Method, RequestBuilder, Response, Url};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::debug;
+
+use crate::error::MatrixError;
+use crate::message::MessageContent;
+
+/// The parts of the Matrix Client-Server API the bot needs. Anything that speaks it works as a
+/// homeserver, Synapse and Conduit included, so a local one is enough to try the bot out.
+pub struct MatrixClient {
+    http: reqwest::Client,
+    homeserver: Url,
+    access_token: String,
+    // Makes transaction ids unique within this process, the start time covers restarts
+    next_transaction: AtomicU64,
+    started_at: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorBody {
+    errcode: String,
+    #[serde(default)]
+    error: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WhoAmI {
+    user_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Uploaded {
+    content_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncResponse {
+    pub next_batch: String,
+    #[serde(default)]
+    pub rooms: SyncRooms,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SyncRooms {
+    #[serde(default)]
+    pub join: HashMap<String, JoinedRoom>,
+    #[serde(default)]
+    pub invite: HashMap<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct JoinedRoom {
+    #[serde(default)]
+    pub timeline: Timeline,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Timeline {
+    #[serde(default)]
+    pub events: Vec<RoomEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RoomEvent {
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub sender: String,
+    #[serde(default)]
+    pub content: Value,
+}
+
+/// A text message someone sent in a room the bot is in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncomingMessage {
+    pub room_id: String,
+    pub sender: String,
+    pub body: String,
+}
+
+impl SyncResponse {
+    /// Every `m.text` message in the joined rooms' timelines, oldest first per room.
+    pub fn messages(&self) -> Vec<IncomingMessage> {
+        let mut messages = Vec::new();
+        for (room_id, room) in &self.rooms.join {
+            for event in &room.timeline.events {
+                if event.event_type != "m.room.message"
+                    || event.content["msgtype"].as_str() != Some("m.text")
+                {
+                    continue;
+                }
+                if let Some(body) = event.content["body"].as_str() {
+                    messages.push(IncomingMessage {
+                        room_id: room_id.clone(),
+                        sender: event.sender.clone(),
+                        body: body.to_string(),
+                    });
+                }
+            }
+        }
+        messages
+    }
+}
+
+impl MatrixClient {
+    pub fn new(homeserver: &str, access_token: String) -> Result<Self, MatrixError> {
+        let homeserver = Url::parse(homeserver).map_err(|e| MatrixError::Url(e.to_string()))?;
+        if homeserver.cannot_be_a_base() {
+            return Err(MatrixError::Url(homeserver.to_string()));
+        }
+
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        Ok(Self {
+            http: reqwest::Client::new(),
+            homeserver,
+            access_token,
+            next_transaction: AtomicU64::new(0),
+            started_at,
+        })
+    }
+
+    /// Builds a URL from path segments, percent encoding each so room ids like
+    /// `!abc:example.org` survive.
+    fn url(&self, segments: &[&str]) -> Url {
+        let mut url = self.homeserver.clone();
+        url.path_segments_mut()
+            .expect("checked in new()")
+            .pop_if_empty()
+            .extend(segments);
+        url
+    }
+
+    fn request(&self, method: Method, url: Url) -> RequestBuilder {
+        self.http
+            .request(method, url)
+            .bearer_auth(&self.access_token)
+    }
+
+    async fn check(response: Response) -> Result<Response, MatrixError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        match response.json::<ErrorBody>().await {
+            Ok(body) => Err(MatrixError::Api {
+                status,
+                errcode: body.errcode,
+                error: body.error,
+            }),
+            Err(_) => Err(MatrixError::Api {
+                status,
+                errcode: "M_UNKNOWN".to_string(),
+                error: "No error body".to_string(),
+            }),
+        }
+    }
+
+    fn transaction_id(&self) -> String {
+        format!(
+            "fpl{}.{}",
+            self.started_at,
+            self.next_transaction.fetch_add(1, Ordering::SeqCst)
+        )
+    }
+
+    /// The user id the access token belongs to.
+    pub async fn whoami(&self) -> Result<String, MatrixError> {
+        let url = self.url(&["_matrix", "client", "v3", "account", "whoami"]);
+        let response = Self::check(self.request(Method::GET, url).send().await?).await?;
+        Ok(response.json::<WhoAmI>().await?.user_id)
+    }
+
+    /// Long polls for anything new since `since`, or a fresh snapshot without it.
+    pub async fn sync(
+        &self,
+        since: Option<&str>,
+        timeout: Duration,
+    ) -> Result<SyncResponse, MatrixError> {
+        let mut url = self.url(&["_matrix", "client", "v3", "sync"]);
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("timeout", &timeout.as_millis().to_string());
+            if let Some(since) = since {
+                query.append_pair("since", since);
+            }
+        }
+
+        let response = Self::check(
+            self.request(Method::GET, url)
+                // Leave room for the homeserver to hold the request open for `timeout`
+                .timeout(timeout + Duration::from_secs(30))
+                .send()
+                .await?,
+        )
+        .await?;
+        Ok(response.json().await?)
+    }
+
+    pub async fn join_room(&self, room_id: &str) -> Result<(), MatrixError> {
+        let url = self.url(&["_matrix", "client", "v3", "join", room_id]);
+        Self::check(self.request(Method::POST, url).json(&json!({})).send().await?).await?;
+        debug!("Joined {}", room_id);
+        Ok(())
+    }
+
+    /// Leaves a room, which for a room the bot is only invited to declines the invite.
+    pub async fn leave_room(&self, room_id: &str) -> Result<(), MatrixError> {
+        let url = self.url(&["_matrix", "client", "v3", "rooms", room_id, "leave"]);
+        Self::check(self.request(Method::POST, url).json(&json!({})).send().await?).await?;
+        debug!("Left {}", room_id);
+        Ok(())
+    }
+
+    pub async fn send_message(
+        &self,
+        room_id: &str,
+        content: &MessageContent,
+    ) -> Result<(), MatrixError> {
+        let transaction_id = self.transaction_id();
+        let url = self.url(&[
+            "_matrix",
+            "client",
+            "v3",
+            "rooms",
+            room_id,
+            "send",
+            "m.room.message",
+            &transaction_id,
+        ]);
+        Self::check(
+            self.request(Method::PUT, url)
+                .json(&content.to_json())
+                .send()
+                .await?,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Uploads a file to the homeserver's media repository, returning its `mxc://` URI.
+    pub async fn upload(
+        &self,
+        bytes: Vec<u8>,
+        content_type: &str,
+        file_name: &str,
+    ) -> Result<String, MatrixError> {
+        let mut url = self.url(&["_matrix", "media", "v3", "upload"]);
+        url.query_pairs_mut().append_pair("filename", file_name);
+
+        let response = Self::check(
+            self.request(Method::POST, url)
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(bytes)
+                .send()
+                .await?,
+        )
+        .await?;
+        Ok(response.json::<Uploaded>().await?.content_uri)
+    }
+
+    /// Uploads a rendered PNG and posts it to the room.
+    pub async fn send_png(
+        &self,
+        room_id: &str,
+        png: Vec<u8>,
+        file_name: &str,
+    ) -> Result<(), MatrixError> {
+        let size = png.len();
+        let content_uri = self.upload(png, "image/png", file_name).await?;
+        self.send_message(
+            room_id,
+            &MessageContent::image(file_name, &content_uri, "image/png", size),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::extract::{Path, State};
+    use axum::http::HeaderMap;
+    use axum::routing::{get, put};
+    use axum::{Json, Router};
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Received {
+        messages: Arc<Mutex<Vec<(String, String, Value)>>>,
+    }
+
+    /// Just enough of a homeserver to answer the client, served on a random local port.
+    async fn homeserver(received: Received) -> String {
+        let app = Router::new()
+            .route(
+                "/_matrix/client/v3/account/whoami",
+                get(|headers: HeaderMap| async move {
+                    match headers.get("authorization").and_then(|h| h.to_str().ok()) {
+                        Some("Bearer token") => Ok(Json(json!({ "user_id": "@fpl:localhost" }))),
+                        _ => Err((
+                            axum::http::StatusCode::UNAUTHORIZED,
+                            Json(json!({ "errcode": "M_UNKNOWN_TOKEN", "error": "Bad token" })),
+                        )),
+                    }
+                }),
+            )
+            .route(
+                "/_matrix/client/v3/rooms/{room_id}/send/{event_type}/{transaction_id}",
+                put(
+                    |State(received): State<Received>,
+                     Path((room_id, event_type, _)): Path<(String, String, String)>,
+                     Json(body): Json<Value>| async move {
+                        received
+                            .messages
+                            .lock()
+                            .unwrap()
+                            .push((room_id, event_type, body));
+                        Json(json!({ "event_id": "$1" }))
+                    },
+                ),
+            )
+            .with_state(received);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{address}")
+    }
+
+    #[tokio::test]
+    async fn test_whoami_checks_the_token() {
+        let homeserver = homeserver(Received::default()).await;
+
+        let client = MatrixClient::new(&homeserver, "token".to_string()).unwrap();
+        assert_eq!(client.whoami().await.unwrap(), "@fpl:localhost");
+
+        let client = MatrixClient::new(&homeserver, "wrong".to_string()).unwrap();
+        match client.whoami().await {
+            Err(MatrixError::Api { errcode, .. }) => assert_eq!(errcode, "M_UNKNOWN_TOKEN"),
+            other => panic!("Expected an API error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_message_puts_event_in_room() {
+        let received = Received::default();
+        let homeserver = homeserver(received.clone()).await;
+        let client = MatrixClient::new(&homeserver, "token".to_string()).unwrap();
+
+        client
+            .send_message(
+                "!room:localhost",
+                &MessageContent::markdown(&["**Hello**".to_string()]),
+            )
+            .await
+            .unwrap();
+
+        let messages = received.messages.lock().unwrap();
+        assert_eq!(messages.len(), 1);
+        let (room_id, event_type, body) = &messages[0];
+        assert_eq!(room_id, "!room:localhost");
+        assert_eq!(event_type, "m.room.message");
+        assert_eq!(body["body"], "Hello");
+    }
+
+    #[test]
+    fn test_sync_messages_only_include_text() {
+        let sync: SyncResponse = serde_json::from_value(json!({
+            "next_batch": "s2",
+            "rooms": {
+                "join": {
+                    "!room:localhost": {
+                        "timeline": {
+                            "events": [
+                                {
+                                    "type": "m.room.message",
+                                    "sender": "@alice:localhost",
+                                    "content": { "msgtype": "m.text", "body": "!table 500" }
+                                },
+                                {
+                                    "type": "m.room.message",
+                                    "sender": "@alice:localhost",
+                                    "content": { "msgtype": "m.image", "body": "cat.png" }
+                                },
+                                {
+                                    "type": "m.room.member",
+                                    "sender": "@bob:localhost",
+                                    "content": { "membership": "join" }
+                                }
+                            ]
+                        }
+                    }
+                },
+                "invite": { "!other:localhost": {} }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            sync.messages(),
+            vec![IncomingMessage {
+                room_id: "!room:localhost".to_string(),
+                sender: "@alice:localhost".to_string(),
+                body: "!table 500".to_string(),
+            }]
+        );
+        assert!(sync.rooms.invite.contains_key("!other:localhost"));
+    }
+}
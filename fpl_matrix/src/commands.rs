@@ -0,0 +1,391 @@
+use std::collections::BTreeSet;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use fpl_api::requests::TeamRequest;
+use fpl_api::FplClientError;
+use fpl_common::types::{GameWeekId, LeagueId, TeamId};
+use fpl_db::models::MatrixUser;
+use fpl_db::queries::game_week::{get_current_game_week_id, get_next_game_week};
+use fpl_db::queries::matrix::{delete_matrix_user, get_matrix_user, upsert_matrix_user};
+use fpl_db::queries::mini_league::{get_league_name, get_team_ids_from_league_id};
+use fpl_db::queries::team::upsert_teams;
+use fpl_scraper::mini_leagues::MiniLeaguesScraper;
+use fpl_scraper::refresh_teams;
+use fpl_services::deadline::get_deadlines;
+use fpl_services::images::{render_png, TableRenderer, TeamRenderer};
+use fpl_services::table::{build_table_data, get_points, TableView};
+use fpl_services::team::get_team_data;
+use tracing::{error, info};
+
+use crate::client::IncomingMessage;
+use crate::message::MessageContent;
+use crate::{Bot, Error};
+
+/// Messages starting with this are commands. Most clients swallow `/` as their own commands.
+pub const COMMAND_PREFIX: char = '!';
+const MAX_MINI_LEAGUE_ENTRIES: i32 = 25;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Help,
+    Register { team_id: TeamId },
+    Unregister,
+    Table { league_id: LeagueId, view: TableView },
+    Team {
+        user: Option<String>,
+        game_week: Option<GameWeekId>,
+    },
+    Deadline { game_week: Option<GameWeekId> },
+}
+
+fn help_rows() -> Vec<String> {
+    vec![
+        "**__Commands__**".to_string(),
+        "**!register [Team ID]** link your FPL team (https://fpl.team/find-id/)".to_string(),
+        "**!unregister** unlink your FPL team".to_string(),
+        "**!table [League ID] (week)** league standings, overall or this game week".to_string(),
+        "**!team (@user:server) (Game Week)** a team, yours by default".to_string(),
+        "**!deadline (Game Week)** upcoming deadlines".to_string(),
+    ]
+}
+
+fn parse_game_week(arg: &str) -> Result<GameWeekId, String> {
+    arg.parse::<i16>()
+        .ok()
+        .and_then(|game_week| GameWeekId::new(game_week).ok())
+        .ok_or_else(|| format!("{arg} isn't a game week between 1 and 38."))
+}
+
+impl Command {
+    /// `None` if the message isn't meant for the bot, `Err` with a reply if it's a command that
+    /// doesn't make sense.
+    pub fn parse(body: &str) -> Option<Result<Self, String>> {
+        let body = body.trim().strip_prefix(COMMAND_PREFIX)?;
+        let mut args = body.split_whitespace();
+        let name = args.next()?.to_ascii_lowercase();
+        let args: Vec<&str> = args.collect();
+
+        let command = match (name.as_str(), args.as_slice()) {
+            ("help", _) => Ok(Command::Help),
+            ("register", [team_id]) => TeamId::from_str(team_id)
+                .map(|team_id| Command::Register { team_id })
+                .map_err(|_| format!("{team_id} isn't a Team ID.")),
+            ("register", _) => Err("Usage: !register [Team ID]".to_string()),
+            ("unregister", []) => Ok(Command::Unregister),
+            ("table", [league_id, rest @ ..]) if rest.len() <= 1 => {
+                let view = match rest.first().map(|view| view.to_ascii_lowercase()) {
+                    None => Ok(TableView::Overall),
+                    Some(view) if view == "week" => Ok(TableView::CurrentGameWeek),
+                    Some(view) if view == "overall" => Ok(TableView::Overall),
+                    Some(view) => Err(format!("{view} isn't overall or week.")),
+                };
+                match (league_id.parse::<i32>(), view) {
+                    (Ok(league_id), Ok(view)) => Ok(Command::Table {
+                        league_id: LeagueId::new(league_id),
+                        view,
+                    }),
+                    (Err(_), _) => Err(format!("{league_id} isn't a League ID.")),
+                    (_, Err(e)) => Err(e),
+                }
+            }
+            ("table", _) => Err("Usage: !table [League ID] (week)".to_string()),
+            ("team", args) if args.len() <= 2 => {
+                let mut user = None;
+                let mut game_week = None;
+                for arg in args {
+                    if arg.starts_with('@') && user.is_none() {
+                        user = Some(arg.to_string());
+                    } else if game_week.is_none() {
+                        match parse_game_week(arg) {
+                            Ok(gw) => game_week = Some(gw),
+                            Err(e) => return Some(Err(e)),
+                        }
+                    } else {
+                        return Some(Err("Usage: !team (@user:server) (Game Week)".to_string()));
+                    }
+                }
+                Ok(Command::Team { user, game_week })
+            }
+            ("team", _) => Err("Usage: !team (@user:server) (Game Week)".to_string()),
+            ("deadline", []) => Ok(Command::Deadline { game_week: None }),
+            ("deadline", [game_week]) => {
+                parse_game_week(game_week).map(|gw| Command::Deadline { game_week: Some(gw) })
+            }
+            ("deadline", _) => Err("Usage: !deadline (Game Week)".to_string()),
+            (name, _) => Err(format!("Unknown command !{name}, try !help.")),
+        };
+        Some(command)
+    }
+}
+
+/// Runs the command in the message, if there is one, and replies in the same room.
+pub async fn handle_message(bot: Arc<Bot>, message: IncomingMessage) {
+    if message.sender == bot.user_id {
+        return;
+    }
+    let Some(command) = Command::parse(&message.body) else {
+        return;
+    };
+    info!(
+        "Command {:?} from {} in {}",
+        command, message.sender, message.room_id
+    );
+
+    let result = match command {
+        Ok(command) => run(&bot, &message, command).await,
+        Err(usage) => reply(&bot, &message, vec![usage]).await,
+    };
+
+    if let Err(e) = result {
+        error!("Error handling {:?}: {}", message.body, e);
+        if let Err(e) = reply(
+            &bot,
+            &message,
+            vec!["Something went wrong, try again later.".to_string()],
+        )
+        .await
+        {
+            error!("Failed to send error reply: {}", e);
+        }
+    }
+}
+
+async fn reply(bot: &Bot, message: &IncomingMessage, rows: Vec<String>) -> Result<(), Error> {
+    bot.matrix
+        .send_message(&message.room_id, &MessageContent::markdown(&rows))
+        .await?;
+    Ok(())
+}
+
+async fn run(bot: &Bot, message: &IncomingMessage, command: Command) -> Result<(), Error> {
+    match command {
+        Command::Help => reply(bot, message, help_rows()).await,
+        Command::Register { team_id } => register(bot, message, team_id).await,
+        Command::Unregister => {
+            let row = match delete_matrix_user(&bot.pool, &message.sender).await? {
+                true => "Unregistered, your team has been unlinked.",
+                false => "You aren't registered.",
+            };
+            reply(bot, message, vec![row.to_string()]).await
+        }
+        Command::Table { league_id, view } => {
+            let live_points = get_points(&bot.pool, league_id).await?;
+            let league_name = get_league_name(&bot.pool, league_id).await?;
+            let data = build_table_data(live_points, view, None);
+
+            let png = render_png(&TableRenderer::default().render_svg(data)?)?;
+            reply(
+                bot,
+                message,
+                vec![format!(
+                    "**{} League standings for {league_name}**",
+                    view.label()
+                )],
+            )
+            .await?;
+            bot.matrix
+                .send_png(&message.room_id, png, &format!("table_{league_id}.png"))
+                .await?;
+            Ok(())
+        }
+        Command::Team { user, game_week } => team(bot, message, user, game_week).await,
+        Command::Deadline { game_week } => {
+            let rows = get_deadlines(&bot.pool, game_week).await?;
+            // Matches the first page of the Discord embed
+            reply(bot, message, rows.into_iter().take(1).collect()).await
+        }
+    }
+}
+
+async fn team(
+    bot: &Bot,
+    message: &IncomingMessage,
+    user: Option<String>,
+    game_week: Option<GameWeekId>,
+) -> Result<(), Error> {
+    let game_week_id = match game_week {
+        Some(gw) => gw,
+        None => get_current_game_week_id(&bot.pool).await?,
+    };
+
+    let next_game_week = get_next_game_week(&bot.pool).await?;
+    if next_game_week.is_some_and(|gw| gw.id == game_week_id && gw.deadline_time > Utc::now()) {
+        return reply(
+            bot,
+            message,
+            vec![format!(
+                "Gameweek {game_week_id} teams are hidden until the deadline."
+            )],
+        )
+        .await;
+    }
+
+    let matrix_id = user.unwrap_or_else(|| message.sender.clone());
+    let Some(matrix_user) = get_matrix_user(&bot.pool, &matrix_id).await? else {
+        return reply(
+            bot,
+            message,
+            vec![format!(
+                "{matrix_id} hasn't registered an FPL team, they can use **!register [Team ID]**."
+            )],
+        )
+        .await;
+    };
+
+    let data = get_team_data(
+        &bot.pool,
+        i32::from(matrix_user.team_id),
+        i16::from(game_week_id),
+    )
+    .await?;
+    let title = format!("**Team for {} in Gameweek {game_week_id}**", data.team_name);
+
+    let png = render_png(&TeamRenderer::default().render_svg(data)?)?;
+    reply(bot, message, vec![title]).await?;
+    bot.matrix
+        .send_png(
+            &message.room_id,
+            png,
+            &format!("team_{}_{game_week_id}.png", matrix_user.team_id),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Fetches the team and everyone in its mini leagues, like the Discord /register does, then
+/// links it to the sender.
+async fn register(bot: &Bot, message: &IncomingMessage, team_id: TeamId) -> Result<(), Error> {
+    let team = match bot.fpl.get(TeamRequest::new(team_id)).await {
+        Ok(team) => team,
+        Err(err) => {
+            let row = match err {
+                FplClientError::NotFound { .. } => format!(
+                    "Team ID {team_id} doesn't exist. Check the ID in the URL of your team's points page on the FPL website."
+                ),
+                FplClientError::GameUpdating { .. } | FplClientError::Maintenance { .. } => {
+                    "FPL is being updated right now. Try again in a few minutes.".to_string()
+                }
+                _ => return Err(err.into()),
+            };
+            return reply(bot, message, vec![row]).await;
+        }
+    };
+    upsert_teams(&bot.pool, &[team.clone().into()]).await?;
+
+    reply(
+        bot,
+        message,
+        vec![format!(
+            "Registering **{}**, fetching its mini leagues. This can take a minute.",
+            team.name
+        )],
+    )
+    .await?;
+
+    let league_ids: Vec<LeagueId> = team
+        .leagues
+        .classic
+        .iter()
+        .filter(|league| {
+            league.admin_entry.is_some() && league.rank_count <= MAX_MINI_LEAGUE_ENTRIES
+        })
+        .map(|league| LeagueId::new(league.id))
+        .collect();
+    MiniLeaguesScraper::new(Arc::clone(&bot.pool), Arc::clone(&bot.fpl), Duration::ZERO)
+        .scrape_leagues(&league_ids)
+        .await?;
+
+    let mut team_ids = BTreeSet::from([team_id]);
+    for league_id in league_ids {
+        team_ids.extend(
+            get_team_ids_from_league_id(&bot.pool, league_id)
+                .await?
+                .into_iter()
+                .map(TeamId::new),
+        );
+    }
+    refresh_teams(&bot.pool, &bot.fpl, team_ids.into_iter().collect()).await?;
+
+    upsert_matrix_user(
+        &bot.pool,
+        &MatrixUser::new(message.sender.clone(), team_id),
+    )
+    .await?;
+    reply(
+        bot,
+        message,
+        vec![format!("Registered **{}** (Team ID {team_id}).", team.name)],
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(body: &str) -> Option<Result<Command, String>> {
+        Command::parse(body)
+    }
+
+    #[test]
+    fn test_plain_messages_are_ignored() {
+        assert_eq!(parse("anyone watching the game?"), None);
+        assert_eq!(parse("!"), None);
+    }
+
+    #[test]
+    fn test_parse_table() {
+        assert_eq!(
+            parse("!table 500"),
+            Some(Ok(Command::Table {
+                league_id: LeagueId::new(500),
+                view: TableView::Overall,
+            }))
+        );
+        assert_eq!(
+            parse("  !TABLE 500 Week "),
+            Some(Ok(Command::Table {
+                league_id: LeagueId::new(500),
+                view: TableView::CurrentGameWeek,
+            }))
+        );
+        assert!(matches!(parse("!table abc"), Some(Err(_))));
+        assert!(matches!(parse("!table"), Some(Err(_))));
+    }
+
+    #[test]
+    fn test_parse_team_args_in_any_order() {
+        let expected = Some(Ok(Command::Team {
+            user: Some("@bob:localhost".to_string()),
+            game_week: Some(GameWeekId::new(3).unwrap()),
+        }));
+        assert_eq!(parse("!team @bob:localhost 3"), expected);
+        assert_eq!(parse("!team 3 @bob:localhost"), expected);
+        assert_eq!(
+            parse("!team"),
+            Some(Ok(Command::Team {
+                user: None,
+                game_week: None
+            }))
+        );
+        assert!(matches!(parse("!team 39"), Some(Err(_))));
+    }
+
+    #[test]
+    fn test_parse_register_and_unknown() {
+        assert_eq!(
+            parse("!register 1234"),
+            Some(Ok(Command::Register {
+                team_id: TeamId::new(1234)
+            }))
+        );
+        assert!(matches!(parse("!register"), Some(Err(_))));
+        assert_eq!(
+            parse("!transfers"),
+            Some(Err("Unknown command !transfers, try !help.".to_string()))
+        );
+    }
+}
@@ -0,0 +1,16 @@
+use reqwest::StatusCode;
+
+#[derive(Debug, thiserror::Error)]
+pub enum MatrixError {
+    #[error("Request to the homeserver failed: {0}")]
+    Http(#[from] reqwest::Error),
+    /// The homeserver answered with a Matrix error body, e.g. `M_FORBIDDEN`
+    #[error("Homeserver returned {status} {errcode}: {error}")]
+    Api {
+        status: StatusCode,
+        errcode: String,
+        error: String,
+    },
+    #[error("Invalid homeserver URL: {0}")]
+    Url(String),
+}
@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+/// Which invites the bot accepts. Entries starting with `!` are room ids, anything else is a
+/// homeserver whose users can invite the bot anywhere.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InviteAllowlist {
+    rooms: HashSet<String>,
+    servers: HashSet<String>,
+}
+
+impl InviteAllowlist {
+    /// Parses a comma separated list, e.g. `!abc:example.org,example.org`.
+    pub fn parse(list: &str) -> Self {
+        let mut allowlist = Self::default();
+        for entry in list
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            if entry.starts_with('!') {
+                allowlist.rooms.insert(entry.to_string());
+            } else {
+                allowlist.servers.insert(entry.to_ascii_lowercase());
+            }
+        }
+        allowlist
+    }
+
+    /// Only invites from users on the bot's own homeserver, taken from its user id.
+    pub fn own_server(user_id: &str) -> Self {
+        Self::parse(server_name(user_id).unwrap_or_default())
+    }
+
+    /// Whether to join `room_id`, given who sent the invite if the sync said.
+    pub fn allows(&self, room_id: &str, inviter: Option<&str>) -> bool {
+        self.rooms.contains(room_id)
+            || inviter
+                .and_then(server_name)
+                .is_some_and(|server| self.servers.contains(&server.to_ascii_lowercase()))
+    }
+}
+
+/// Who invited `user_id`, from the invite's stripped state in a sync.
+pub fn inviter<'a>(invite: &'a Value, user_id: &str) -> Option<&'a str> {
+    invite["invite_state"]["events"]
+        .as_array()?
+        .iter()
+        .find(|event| {
+            event["type"] == "m.room.member"
+                && event["state_key"] == user_id
+                && event["content"]["membership"] == "invite"
+        })?["sender"]
+        .as_str()
+}
+
+/// The server part of a user or room id, `@alice:example.org` is `example.org`.
+fn server_name(id: &str) -> Option<&str> {
+    id.split_once(':').map(|(_, server)| server)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_allows_listed_rooms_and_servers() {
+        let allowlist = InviteAllowlist::parse("!league:example.org, Example.com ,");
+
+        assert!(allowlist.allows("!league:example.org", None));
+        assert!(allowlist.allows("!any:elsewhere.org", Some("@alice:example.com")));
+        assert!(!allowlist.allows("!any:example.com", Some("@mallory:evil.org")));
+        assert!(!allowlist.allows("!other:example.org", None));
+    }
+
+    #[test]
+    fn test_own_server() {
+        let allowlist = InviteAllowlist::own_server("@fpl:localhost");
+        assert!(allowlist.allows("!room:localhost", Some("@alice:localhost")));
+        assert!(!allowlist.allows("!room:localhost", Some("@alice:matrix.org")));
+    }
+
+    #[test]
+    fn test_inviter_reads_invite_state() {
+        let invite = json!({
+            "invite_state": {
+                "events": [
+                    { "type": "m.room.name", "sender": "@bob:localhost", "state_key": "", "content": {} },
+                    {
+                        "type": "m.room.member",
+                        "sender": "@alice:localhost",
+                        "state_key": "@fpl:localhost",
+                        "content": { "membership": "invite" }
+                    }
+                ]
+            }
+        });
+        assert_eq!(inviter(&invite, "@fpl:localhost"), Some("@alice:localhost"));
+        assert_eq!(inviter(&json!({}), "@fpl:localhost"), None);
+    }
+}
@@ -0,0 +1,81 @@
+//! A Matrix frontend for the leagues the Discord bot tracks. It reads the same `fpl_db` data
+//! through `fpl_services`, so tables and team sheets render the same, and answers `!` commands
+//! in the rooms it's allowed to join.
+pub mod client;
+pub mod commands;
+pub mod error;
+pub mod invites;
+pub mod message;
+pub mod notifications;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use fpl_api::FplClient;
+use sqlx::PgPool;
+use tracing::{error, info, warn};
+
+use client::MatrixClient;
+use invites::{inviter, InviteAllowlist};
+
+pub use error::MatrixError;
+
+pub type Error = Box<dyn std::error::Error + Send + Sync>;
+
+const SYNC_TIMEOUT: Duration = Duration::from_secs(30);
+const SYNC_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+pub struct Bot {
+    pub pool: Arc<PgPool>,
+    pub fpl: Arc<FplClient>,
+    pub matrix: Arc<MatrixClient>,
+    /// Who the bot is signed in as, so it ignores its own messages
+    pub user_id: String,
+    /// Invites from anywhere else are declined
+    pub invites: InviteAllowlist,
+}
+
+impl Bot {
+    /// Syncs forever, joining rooms it's allowed to and running commands as they arrive. Messages
+    /// sent while the bot was offline are skipped rather than answered late.
+    pub async fn run(self: Arc<Self>) -> Result<(), Error> {
+        let mut since = self.matrix.sync(None, Duration::ZERO).await?.next_batch;
+        info!("Matrix bot {} synced, waiting for commands", self.user_id);
+
+        loop {
+            let sync = match self.matrix.sync(Some(&since), SYNC_TIMEOUT).await {
+                Ok(sync) => sync,
+                Err(e) => {
+                    error!("Matrix sync failed, retrying: {}", e);
+                    tokio::time::sleep(SYNC_RETRY_DELAY).await;
+                    continue;
+                }
+            };
+
+            for (room_id, invite) in &sync.rooms.invite {
+                let inviter = inviter(invite, &self.user_id);
+                if !self.invites.allows(room_id, inviter) {
+                    warn!(
+                        "Declining invite to {} from {}",
+                        room_id,
+                        inviter.unwrap_or("unknown")
+                    );
+                    if let Err(e) = self.matrix.leave_room(room_id).await {
+                        error!("Failed to decline {}: {}", room_id, e);
+                    }
+                    continue;
+                }
+                if let Err(e) = self.matrix.join_room(room_id).await {
+                    error!("Failed to join {}: {}", room_id, e);
+                }
+            }
+
+            for message in sync.messages() {
+                // Registering can take a while, don't hold up everyone else
+                tokio::spawn(commands::handle_message(Arc::clone(&self), message));
+            }
+
+            since = sync.next_batch;
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use fpl_api::concurrency::RequestPriority;
+use fpl_api::FplClient;
+use fpl_db::rate_limit::PgRateLimiter;
+use fpl_matrix::client::MatrixClient;
+use fpl_matrix::invites::InviteAllowlist;
+use fpl_matrix::notifications::MatrixNotifications;
+use fpl_matrix::{Bot, Error};
+use fpl_services::notifications::{ChangeHandler, ChangeListener};
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use tracing::info;
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .init();
+
+    dotenv::from_filename("../.env").ok();
+    let database_url =
+        std::env::var("DATABASE_URL").expect("DATABASE_URL must be set in .env file");
+    let homeserver =
+        std::env::var("MATRIX_HOMESERVER").expect("MATRIX_HOMESERVER must be set in .env file");
+    let access_token = std::env::var("MATRIX_ACCESS_TOKEN")
+        .expect("MATRIX_ACCESS_TOKEN must be set in .env file");
+    // Without a room the bot only answers commands
+    let notification_room = std::env::var("MATRIX_NOTIFICATION_ROOM").ok();
+    // Comma separated room ids and homeservers, defaults to the bot's own homeserver
+    let allowed_invites = std::env::var("MATRIX_ALLOWED_INVITES").ok();
+
+    let options = PgConnectOptions::from_str(&database_url)?.application_name("fpl_matrix");
+    let pool = Arc::new(
+        PgPoolOptions::new()
+            .max_connections(5)
+            .acquire_timeout(Duration::from_secs(5))
+            .connect_with(options)
+            .await?,
+    );
+
+    let matrix = Arc::new(MatrixClient::new(&homeserver, access_token)?);
+    let user_id = matrix.whoami().await?;
+    info!("Signed in to {} as {}", homeserver, user_id);
+
+    if let Some(room_id) = notification_room {
+        matrix.join_room(&room_id).await?;
+        info!("Starting Matrix notifications for {}", room_id);
+        let notifications: Arc<dyn ChangeHandler> = Arc::new(
+            MatrixNotifications::new(Arc::clone(&pool), Arc::clone(&matrix), room_id).await?,
+        );
+        Arc::new(ChangeListener::new(Arc::clone(&pool), vec![notifications])).start();
+    }
+
+    let invites = match allowed_invites {
+        Some(list) => InviteAllowlist::parse(&list),
+        None => InviteAllowlist::own_server(&user_id),
+    };
+
+    let fpl = Arc::new(
+        FplClient::new()
+            .with_priority(RequestPriority::Interactive)
+            .with_shared_rate_limiter(Arc::new(PgRateLimiter::new(Arc::clone(&pool)))),
+    );
+
+    let bot = Arc::new(Bot {
+        pool,
+        fpl,
+        matrix,
+        user_id,
+        invites,
+    });
+    bot.run().await
+}
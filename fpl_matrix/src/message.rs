@@ -0,0 +1,196 @@
+use serde_json::{json, Value};
+
+/// The content of an `m.room.message` event. Text is sent with a plain `body` for clients that
+/// can't show HTML and a `formatted_body` for those that can.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageContent {
+    Text {
+        body: String,
+        html: String,
+        mentions: Vec<String>,
+    },
+    Image {
+        file_name: String,
+        content_uri: String,
+        mimetype: String,
+        size: usize,
+    },
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Turns the Discord flavoured Markdown in service rows (`**bold**`, `__underline__`) into a
+/// plain line and an HTML one.
+fn convert_row(row: &str) -> (String, String) {
+    let mut plain = String::with_capacity(row.len());
+    let mut html = String::with_capacity(row.len());
+    let (mut bold, mut underline) = (false, false);
+    let mut rest = row;
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("**") {
+            html.push_str(if bold { "</b>" } else { "<b>" });
+            bold = !bold;
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("__") {
+            html.push_str(if underline { "</u>" } else { "<u>" });
+            underline = !underline;
+            rest = after;
+        } else {
+            let c = rest.chars().next().expect("rest isn't empty");
+            plain.push(c);
+            html.push_str(&escape_html(&c.to_string()));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    // Close anything left open so it doesn't bleed into the next row
+    if underline {
+        html.push_str("</u>");
+    }
+    if bold {
+        html.push_str("</b>");
+    }
+
+    (plain, html)
+}
+
+impl MessageContent {
+    /// One line per row, the same rows the Discord embeds page through.
+    pub fn markdown(rows: &[String]) -> Self {
+        let (plain, html): (Vec<String>, Vec<String>) =
+            rows.iter().map(|row| convert_row(row.trim_end())).unzip();
+        Self::Text {
+            body: plain.join("\n"),
+            html: html.join("<br>"),
+            mentions: Vec::new(),
+        }
+    }
+
+    /// Adds a line linking to each user, which pings them in most clients.
+    pub fn with_mentions(self, label: &str, user_ids: &[String]) -> Self {
+        let Self::Text {
+            mut body,
+            mut html,
+            mut mentions,
+        } = self
+        else {
+            return self;
+        };
+        if user_ids.is_empty() {
+            return Self::Text {
+                body,
+                html,
+                mentions,
+            };
+        }
+
+        body.push_str(&format!("\n{label}: {}", user_ids.join(", ")));
+        let links = user_ids
+            .iter()
+            .map(|user_id| {
+                let user_id = escape_html(user_id);
+                format!("<a href=\"https://matrix.to/#/{user_id}\">{user_id}</a>")
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        html.push_str(&format!("<br>{}: {links}", escape_html(label)));
+        mentions.extend(user_ids.iter().cloned());
+
+        Self::Text {
+            body,
+            html,
+            mentions,
+        }
+    }
+
+    pub fn image(file_name: &str, content_uri: &str, mimetype: &str, size: usize) -> Self {
+        Self::Image {
+            file_name: file_name.to_string(),
+            content_uri: content_uri.to_string(),
+            mimetype: mimetype.to_string(),
+            size,
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        match self {
+            Self::Text {
+                body,
+                html,
+                mentions,
+            } => json!({
+                "msgtype": "m.text",
+                "body": body,
+                "format": "org.matrix.custom.html",
+                "formatted_body": html,
+                "m.mentions": { "user_ids": mentions },
+            }),
+            Self::Image {
+                file_name,
+                content_uri,
+                mimetype,
+                size,
+            } => json!({
+                "msgtype": "m.image",
+                "body": file_name,
+                "url": content_uri,
+                "info": { "mimetype": mimetype, "size": size },
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_rows_to_html() {
+        let content = MessageContent::markdown(&[
+            "**__Hits__**\n".to_string(),
+            "- **Alice** (<Alice FC>) 2_000".to_string(),
+        ]);
+        let json = content.to_json();
+        assert_eq!(json["body"], "Hits\n- Alice (<Alice FC>) 2_000");
+        assert_eq!(
+            json["formatted_body"],
+            "<b><u>Hits</u></b><br>- <b>Alice</b> (&lt;Alice FC&gt;) 2_000"
+        );
+    }
+
+    #[test]
+    fn test_unclosed_markers_are_closed() {
+        let json = MessageContent::markdown(&["**GW1".to_string()]).to_json();
+        assert_eq!(json["formatted_body"], "<b>GW1</b>");
+    }
+
+    #[test]
+    fn test_mentions_link_and_ping() {
+        let json = MessageContent::markdown(&["**2** → **6**".to_string()])
+            .with_mentions(
+                "Owners",
+                &["@alice:localhost".to_string(), "@bob:localhost".to_string()],
+            )
+            .to_json();
+        assert_eq!(
+            json["body"],
+            "2 → 6\nOwners: @alice:localhost, @bob:localhost"
+        );
+        assert_eq!(
+            json["formatted_body"],
+            "<b>2</b> → <b>6</b><br>Owners: \
+             <a href=\"https://matrix.to/#/@alice:localhost\">@alice:localhost</a>, \
+             <a href=\"https://matrix.to/#/@bob:localhost\">@bob:localhost</a>"
+        );
+        assert_eq!(
+            json["m.mentions"]["user_ids"],
+            json!(["@alice:localhost", "@bob:localhost"])
+        );
+    }
+}
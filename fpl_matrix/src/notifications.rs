@@ -0,0 +1,214 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use fpl_db::events::ChangeEvent;
+use fpl_db::models::NotificationTransition;
+use fpl_db::queries::matrix::get_matrix_room_channel_id;
+use fpl_db::queries::notification_state::mark_notification_sent;
+use fpl_services::notifications::{
+    ChangeHandler, PointsAudience, PointsChange, PointsChanges, ScoreChange, ScoreChanges,
+    REDRIVE_LEASE,
+};
+use sqlx::PgPool;
+use tracing::info;
+
+use crate::client::MatrixClient;
+use crate::message::MessageContent;
+use crate::Error;
+
+/// The score and points notifications the Discord bot sends, posted to one Matrix room. Points
+/// changes only go out for players a registered Matrix user owns and mention those owners.
+///
+/// Which changes to send is worked out and claimed by `fpl_services::notifications` under the
+/// room's id from `matrix_rooms`, so a restart doesn't resend anything and the Discord bot's
+/// claims don't get in the way. A claim is marked sent once the homeserver has accepted the
+/// message. Hand it to a `ChangeListener` to start it.
+pub struct MatrixNotifications {
+    pool: Arc<PgPool>,
+    matrix: Arc<MatrixClient>,
+    room_id: String,
+    scores: ScoreChanges,
+    points: PointsChanges,
+}
+
+impl MatrixNotifications {
+    pub async fn new(
+        pool: Arc<PgPool>,
+        matrix: Arc<MatrixClient>,
+        room_id: String,
+    ) -> Result<Self, Error> {
+        let channel_id = get_matrix_room_channel_id(&pool, &room_id).await?;
+        Ok(Self {
+            scores: ScoreChanges::new(Arc::clone(&pool), channel_id),
+            points: PointsChanges::new(Arc::clone(&pool), channel_id, PointsAudience::Matrix),
+            pool,
+            matrix,
+            room_id,
+        })
+    }
+
+    async fn send(
+        &self,
+        content: MessageContent,
+        transition: &NotificationTransition,
+    ) -> Result<(), Error> {
+        self.matrix.send_message(&self.room_id, &content).await?;
+        mark_notification_sent(&self.pool, transition).await?;
+        Ok(())
+    }
+
+    async fn send_scores(&self, changes: Vec<ScoreChange>) -> Result<(), Error> {
+        for change in changes {
+            self.send(score_message(&change), &change.transition)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn send_points(&self, changes: Vec<PointsChange>) -> Result<(), Error> {
+        for change in changes {
+            self.send(points_message(&change), &change.transition)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ChangeHandler for MatrixNotifications {
+    fn name(&self) -> &'static str {
+        "Matrix"
+    }
+
+    async fn handle_event(&self, event: &ChangeEvent) -> Result<(), Error> {
+        let scores = self.scores.from_event(event).await?;
+        self.send_scores(scores.into_iter().collect()).await?;
+        let points = self.points.from_event(event).await?;
+        self.send_points(points.into_iter().collect()).await
+    }
+
+    async fn poll(&self) -> Result<(), Error> {
+        self.send_scores(self.scores.poll().await?).await?;
+        self.send_points(self.points.poll().await?).await
+    }
+
+    async fn redrive(&self) -> Result<(), Error> {
+        let scores = self.scores.redrive(REDRIVE_LEASE).await?;
+        let points = self.points.redrive(REDRIVE_LEASE).await?;
+        if !scores.is_empty() || !points.is_empty() {
+            info!(
+                "Re-sending {} unsent Matrix notifications",
+                scores.len() + points.len()
+            );
+        }
+        self.send_scores(scores).await?;
+        self.send_points(points).await
+    }
+}
+
+fn score_message(change: &ScoreChange) -> MessageContent {
+    let rows = if change.is_kick_off() {
+        vec![
+            "**🔔 Fixture Kicked Off**".to_string(),
+            format!(
+                "**{}** vs **{}** has started.",
+                change.home_team, change.away_team
+            ),
+        ]
+    } else {
+        let bold = |changed: bool| if changed { "**" } else { "" };
+        let home_bold = bold(change.home_team_scored());
+        let away_bold = bold(change.away_team_scored());
+        vec![
+            "**🔔 Fixture Score Update**".to_string(),
+            format!(
+                "{home_bold}{} {}{home_bold} - {away_bold}{} {}{away_bold}",
+                change.home_team, change.score.0, change.score.1, change.away_team,
+            ),
+        ]
+    };
+    MessageContent::markdown(&rows)
+}
+
+fn points_message(change: &PointsChange) -> MessageContent {
+    let emoji = match change.old_points.cmp(&change.new_points) {
+        Ordering::Less => "⬆️",
+        Ordering::Greater => "⬇️",
+        Ordering::Equal => "❔",
+    };
+    MessageContent::markdown(&[
+        format!("**🔔 {} Points Update**", change.web_name),
+        format!(
+            "**{}** {emoji} **{}**",
+            change.old_points, change.new_points
+        ),
+    ])
+    .with_mentions("Owners", &change.owners)
+}
+
+#[cfg(test)]
+mod tests {
+    use fpl_db::models::NotificationKind;
+
+    use super::*;
+
+    fn transition() -> NotificationTransition {
+        NotificationTransition {
+            channel_id: -1,
+            kind: NotificationKind::FixtureScore,
+            subject_id: 1,
+            game_week_id: 2,
+            previous_value: None,
+            value: "0-0".to_string(),
+        }
+    }
+
+    fn score_change(previous_score: Option<(i16, i16)>, score: (i16, i16)) -> ScoreChange {
+        ScoreChange {
+            home_team: "Arsenal".to_string(),
+            away_team: "Chelsea".to_string(),
+            previous_score,
+            score,
+            transition: transition(),
+        }
+    }
+
+    #[test]
+    fn test_score_message_bolds_the_side_that_scored() {
+        let json = score_message(&score_change(Some((1, 0)), (1, 1))).to_json();
+        assert_eq!(
+            json["body"],
+            "🔔 Fixture Score Update\nArsenal 1 - 1 Chelsea"
+        );
+        assert_eq!(
+            json["formatted_body"],
+            "<b>🔔 Fixture Score Update</b><br>Arsenal 1 - <b>1 Chelsea</b>"
+        );
+
+        let json = score_message(&score_change(None, (0, 0))).to_json();
+        assert_eq!(
+            json["body"],
+            "🔔 Fixture Kicked Off\nArsenal vs Chelsea has started."
+        );
+    }
+
+    #[test]
+    fn test_points_message_mentions_owners() {
+        let change = PointsChange {
+            player_id: 8,
+            web_name: "Saka".to_string(),
+            code: 1,
+            old_points: 2,
+            new_points: 6,
+            owners: vec!["@alice:localhost".to_string()],
+            transition: transition(),
+        };
+        let json = points_message(&change).to_json();
+        assert_eq!(
+            json["body"],
+            "🔔 Saka Points Update\n2 ⬆️ 6\nOwners: @alice:localhost"
+        );
+        assert_eq!(json["m.mentions"]["user_ids"][0], "@alice:localhost");
+    }
+}
@@ -0,0 +1,65 @@
+use axum::extract::{Request, State};
+use axum::middleware::Next;
+use axum::response::Response;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use tracing::debug;
+
+use crate::error::ApiError;
+use crate::AppState;
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// The key a request was let in with, for routes that scope what they return to it.
+#[derive(Debug, Clone)]
+pub struct ApiKey(pub String);
+
+/// Rejects requests that don't carry one of the configured keys in the `X-API-Key` header.
+pub async fn require_api_key(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let key = request
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    match key {
+        Some(key) if is_valid_key(&state.api_keys, &key) => {
+            request.extensions_mut().insert(ApiKey(key));
+            Ok(next.run(request).await)
+        }
+        _ => {
+            debug!("Rejected request to {} without a valid key", request.uri());
+            Err(ApiError::Unauthorized)
+        }
+    }
+}
+
+/// Compares SHA-256 digests in constant time, against every key, so how long the check takes
+/// doesn't give away how much of a key was right.
+fn is_valid_key(api_keys: &[String], key: &str) -> bool {
+    let digest = Sha256::digest(key.as_bytes());
+    api_keys.iter().fold(false, |valid, api_key| {
+        let matches: bool = Sha256::digest(api_key.as_bytes()).ct_eq(&digest).into();
+        valid | matches
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_key() {
+        let api_keys = ["first".to_string(), "second".to_string()];
+
+        assert!(is_valid_key(&api_keys, "first"));
+        assert!(is_valid_key(&api_keys, "second"));
+        assert!(!is_valid_key(&api_keys, "firs"));
+        assert!(!is_valid_key(&api_keys, ""));
+        assert!(!is_valid_key(&[], "first"));
+    }
+}
@@ -0,0 +1,54 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use fpl_webhooks::WebhookError;
+use serde::Serialize;
+use tracing::error;
+use utoipa::ToSchema;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("missing or invalid API key")]
+    Unauthorized,
+    #[error("{0}")]
+    NotFound(String),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+impl From<WebhookError> for ApiError {
+    fn from(error: WebhookError) -> Self {
+        match error {
+            WebhookError::Database(e) => Self::Database(e),
+            other => Self::BadRequest(other.to_string()),
+        }
+    }
+}
+
+/// Body of every non-2xx response.
+#[derive(Serialize, ToSchema)]
+pub struct ErrorBody {
+    pub error: String,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            Self::BadRequest(_) => StatusCode::BAD_REQUEST,
+            Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        // Don't leak query details to callers
+        let message = match &self {
+            Self::Database(e) => {
+                error!("Database error: {}", e);
+                "internal error".to_string()
+            }
+            _ => self.to_string(),
+        };
+        (status, Json(ErrorBody { error: message })).into_response()
+    }
+}
@@ -0,0 +1,209 @@
+pub mod auth;
+pub mod error;
+pub mod pagination;
+pub mod routes;
+
+use std::sync::Arc;
+
+use axum::routing::{delete, get};
+use axum::{middleware, Json, Router};
+use sqlx::PgPool;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use crate::auth::{require_api_key, API_KEY_HEADER};
+use crate::routes::{fixtures, leagues, players, teams, webhooks};
+
+#[derive(Clone)]
+pub struct AppState {
+    pub pool: Arc<PgPool>,
+    pub api_keys: Arc<Vec<String>>,
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "FPL Bot API",
+        description = "Read only access to the scraped FPL data, plus webhooks for live events"
+    ),
+    servers((url = "/api/v1")),
+    paths(
+        leagues::get_league,
+        leagues::get_standings,
+        leagues::get_live_points,
+        teams::get_picks,
+        teams::get_transfers,
+        teams::get_chips,
+        fixtures::get_fixtures,
+        players::get_players,
+        players::get_player,
+        webhooks::list_webhooks,
+        webhooks::post_webhook,
+        webhooks::remove_webhook,
+        webhooks::list_deliveries,
+    ),
+    modifiers(&ApiKeyAddon),
+    security(("api_key" = []))
+)]
+pub struct ApiDoc;
+
+struct ApiKeyAddon;
+
+impl Modify for ApiKeyAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(API_KEY_HEADER))),
+        );
+    }
+}
+
+async fn openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// Everything under `/api/v1`. The OpenAPI document is public, the data needs an API key.
+pub fn router(state: AppState) -> Router {
+    let api = Router::new()
+        .route("/leagues/{league_id}", get(leagues::get_league))
+        .route(
+            "/leagues/{league_id}/standings",
+            get(leagues::get_standings),
+        )
+        .route("/leagues/{league_id}/live", get(leagues::get_live_points))
+        .route("/teams/{team_id}/picks", get(teams::get_picks))
+        .route("/teams/{team_id}/transfers", get(teams::get_transfers))
+        .route("/teams/{team_id}/chips", get(teams::get_chips))
+        .route("/fixtures", get(fixtures::get_fixtures))
+        .route("/players", get(players::get_players))
+        .route("/players/{player_id}", get(players::get_player))
+        .route(
+            "/webhooks",
+            get(webhooks::list_webhooks).post(webhooks::post_webhook),
+        )
+        .route("/webhooks/{webhook_id}", delete(webhooks::remove_webhook))
+        .route(
+            "/webhooks/{webhook_id}/deliveries",
+            get(webhooks::list_deliveries),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_api_key,
+        ))
+        .route("/openapi.json", get(openapi));
+
+    Router::new().nest("/api/v1", api).with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use sqlx::postgres::PgPoolOptions;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::pagination::Pagination;
+
+    fn test_router() -> Router {
+        // Never connects, the requests below are answered before touching the database
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/fpl")
+            .unwrap();
+        router(AppState {
+            pool: Arc::new(pool),
+            api_keys: Arc::new(vec!["secret".to_string()]),
+        })
+    }
+
+    async fn status(uri: &str, api_key: Option<&str>) -> StatusCode {
+        let mut request = Request::builder().uri(uri);
+        if let Some(api_key) = api_key {
+            request = request.header(API_KEY_HEADER, api_key);
+        }
+        test_router()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_missing_api_key_is_rejected() {
+        assert_eq!(
+            status("/api/v1/players", None).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status("/api/v1/players", Some("wrong")).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_openapi_is_public() {
+        let response = test_router()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/v1/openapi.json")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let document: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(document["paths"]["/leagues/{league_id}/standings"].is_object());
+        assert!(document["components"]["securitySchemes"]["api_key"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_game_week_is_bad_request() {
+        assert_eq!(
+            status("/api/v1/fixtures?game_week=99", Some("secret")).await,
+            StatusCode::BAD_REQUEST
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_webhook_is_bad_request() {
+        let request = |body: &str| {
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/webhooks")
+                .header(API_KEY_HEADER, "secret")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        for body in [
+            r#"{"url": "ftp://example.com"}"#,
+            r#"{"url": "https://example.com", "events": ["goal"]}"#,
+            r#"{"url": "https://example.com", "events": []}"#,
+        ] {
+            let response = test_router().oneshot(request(body)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{body}");
+        }
+    }
+
+    #[test]
+    fn test_pagination_is_clamped() {
+        let pagination = Pagination {
+            page: Some(0),
+            per_page: Some(1000),
+        };
+        assert_eq!(pagination.page(), 1);
+        assert_eq!(pagination.per_page(), 200);
+        assert_eq!(pagination.offset(), 0);
+
+        let pagination = Pagination {
+            page: Some(3),
+            per_page: None,
+        };
+        assert_eq!(pagination.offset(), 100);
+    }
+}
@@ -0,0 +1,50 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use fpl_rest::{router, AppState};
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use tracing::info;
+
+const DEFAULT_BIND_ADDRESS: &str = "127.0.0.1:8080";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .init();
+
+    dotenv::from_filename("../.env").ok();
+    let database_url =
+        std::env::var("DATABASE_URL").expect("DATABASE_URL must be set in .env file");
+    // Comma separated, every key has the same read only access
+    let api_keys: Vec<String> = std::env::var("REST_API_KEYS")
+        .expect("REST_API_KEYS must be set in .env file")
+        .split(',')
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty())
+        .collect();
+    if api_keys.is_empty() {
+        return Err("REST_API_KEYS must contain at least one key".into());
+    }
+    let bind_address =
+        std::env::var("REST_BIND_ADDRESS").unwrap_or_else(|_| DEFAULT_BIND_ADDRESS.to_string());
+
+    let options = PgConnectOptions::from_str(&database_url)?.application_name("fpl_rest");
+    let pool = PgPoolOptions::new()
+        .max_connections(10)
+        .acquire_timeout(Duration::from_secs(5))
+        .connect_with(options)
+        .await?;
+
+    let app = router(AppState {
+        pool: Arc::new(pool),
+        api_keys: Arc::new(api_keys),
+    });
+
+    let listener = tokio::net::TcpListener::bind(&bind_address).await?;
+    info!("REST API listening on {}", bind_address);
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
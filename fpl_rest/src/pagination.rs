@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+const DEFAULT_PER_PAGE: u32 = 50;
+const MAX_PER_PAGE: u32 = 200;
+
+/// `?page=&per_page=` query parameters, pages start at 1.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct Pagination {
+    /// Page to return, starting at 1
+    pub page: Option<u32>,
+    /// Items per page, at most 200
+    pub per_page: Option<u32>,
+}
+
+impl Pagination {
+    pub fn page(&self) -> u32 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn per_page(&self) -> u32 {
+        self.per_page
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .clamp(1, MAX_PER_PAGE)
+    }
+
+    pub fn limit(&self) -> i64 {
+        i64::from(self.per_page())
+    }
+
+    pub fn offset(&self) -> i64 {
+        i64::from(self.page() - 1) * self.limit()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub page: u32,
+    pub per_page: u32,
+    pub total: i64,
+}
+
+impl<T> Page<T> {
+    pub fn new<U: Into<T>>(items: Vec<U>, pagination: &Pagination, total: i64) -> Self {
+        Self {
+            items: items.into_iter().map(Into::into).collect(),
+            page: pagination.page(),
+            per_page: pagination.per_page(),
+            total,
+        }
+    }
+}
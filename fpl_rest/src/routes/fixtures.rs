@@ -0,0 +1,84 @@
+use axum::extract::{Query, State};
+use axum::Json;
+use chrono::{DateTime, Utc};
+use fpl_common::types::GameWeekId;
+use fpl_db::models::Fixture as DbFixture;
+use fpl_db::queries::fixture::{count_fixtures, get_fixtures as get_db_fixtures};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::error::{ApiError, ErrorBody};
+use crate::pagination::{Page, Pagination};
+use crate::AppState;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FixturesQuery {
+    /// Only fixtures in this game week
+    pub game_week: Option<i16>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Fixture {
+    pub id: i16,
+    /// Unset for fixtures that have been postponed and not yet rearranged
+    pub game_week_id: Option<i16>,
+    pub home_club_id: i16,
+    pub away_club_id: i16,
+    pub home_score: Option<i16>,
+    pub away_score: Option<i16>,
+    pub kickoff_time: Option<DateTime<Utc>>,
+    pub started: bool,
+    pub finished: bool,
+    pub minutes: i16,
+}
+
+impl From<DbFixture> for Fixture {
+    fn from(fixture: DbFixture) -> Self {
+        Self {
+            id: fixture.id.into(),
+            game_week_id: fixture.game_week_id.map(Into::into),
+            home_club_id: fixture.home_team_id.into(),
+            away_club_id: fixture.away_team_id.into(),
+            home_score: fixture.home_team_score,
+            away_score: fixture.away_team_score,
+            kickoff_time: fixture.kickoff_time,
+            started: fixture.started.unwrap_or_default(),
+            finished: fixture.finished,
+            minutes: fixture.minutes,
+        }
+    }
+}
+
+/// Fixtures in kick off order
+#[utoipa::path(
+    get,
+    path = "/fixtures",
+    params(FixturesQuery, Pagination),
+    responses(
+        (status = 200, body = Page<Fixture>),
+        (status = 400, body = ErrorBody)
+    ),
+    tag = "fixtures"
+)]
+pub async fn get_fixtures(
+    State(state): State<AppState>,
+    Query(query): Query<FixturesQuery>,
+    Query(pagination): Query<Pagination>,
+) -> Result<Json<Page<Fixture>>, ApiError> {
+    let game_week_id = query
+        .game_week
+        .map(GameWeekId::new)
+        .transpose()
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    let fixtures = get_db_fixtures(
+        &state.pool,
+        game_week_id,
+        pagination.limit(),
+        pagination.offset(),
+    )
+    .await?;
+    let total = count_fixtures(&state.pool, game_week_id).await?;
+    Ok(Json(Page::new(fixtures, &pagination, total)))
+}
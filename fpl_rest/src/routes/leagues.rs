@@ -0,0 +1,179 @@
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use chrono::{DateTime, Utc};
+use fpl_common::types::LeagueId;
+use fpl_db::models::{LeagueLivePoints, MiniLeague, MiniLeagueStanding};
+use fpl_db::queries::live_table::{count_league_live_points, get_league_live_points};
+use fpl_db::queries::mini_league::{
+    count_mini_league_standings, get_mini_league, get_mini_league_standings,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::error::{ApiError, ErrorBody};
+use crate::pagination::{Page, Pagination};
+use crate::AppState;
+
+#[derive(Serialize, ToSchema)]
+pub struct League {
+    pub id: i32,
+    pub name: String,
+    pub created: DateTime<Utc>,
+    pub closed: bool,
+    pub league_type: String,
+    pub scoring: String,
+    pub admin_team_id: i32,
+    pub start_game_week: i16,
+    pub has_cup: bool,
+    pub last_updated: DateTime<Utc>,
+}
+
+impl From<MiniLeague> for League {
+    fn from(league: MiniLeague) -> Self {
+        Self {
+            id: league.id.into(),
+            name: league.name,
+            created: league.created,
+            closed: league.closed,
+            league_type: league.league_type,
+            scoring: league.scoring,
+            admin_team_id: league.admin_entry.into(),
+            start_game_week: league.start_event.into(),
+            has_cup: league.has_cup,
+            last_updated: league.last_updated_data,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Standing {
+    pub rank: i32,
+    pub last_rank: i32,
+    pub team_id: i32,
+    pub team_name: String,
+    pub player_name: String,
+    pub game_week_points: i16,
+    pub total_points: i16,
+}
+
+impl From<MiniLeagueStanding> for Standing {
+    fn from(standing: MiniLeagueStanding) -> Self {
+        Self {
+            rank: standing.rank,
+            last_rank: standing.last_rank,
+            team_id: standing.team_id.into(),
+            team_name: standing.entry_name,
+            player_name: standing.player_name,
+            game_week_points: standing.event_total,
+            total_points: standing.total,
+        }
+    }
+}
+
+/// Points for the current game week, `live_` totals include provisional bonus.
+#[derive(Serialize, ToSchema)]
+pub struct LivePoints {
+    pub game_week_id: i16,
+    pub team_id: i32,
+    pub team_name: String,
+    pub player_name: String,
+    pub game_week_points: i16,
+    pub live_game_week_points: i64,
+    pub total_points: i16,
+    pub live_total_points: i64,
+}
+
+impl From<LeagueLivePoints> for LivePoints {
+    fn from(points: LeagueLivePoints) -> Self {
+        Self {
+            game_week_id: points.game_week_id.into(),
+            team_id: points.team_id.into(),
+            team_name: points.name,
+            player_name: format!("{} {}", points.player_first_name, points.player_last_name),
+            game_week_points: points.week_points,
+            live_game_week_points: points.calculated_week_points,
+            total_points: points.overall_points,
+            live_total_points: points.calculated_overall_points,
+        }
+    }
+}
+
+async fn find_league(state: &AppState, league_id: i32) -> Result<MiniLeague, ApiError> {
+    get_mini_league(&state.pool, LeagueId::new(league_id))
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("league {league_id} isn't tracked")))
+}
+
+/// A tracked mini league
+#[utoipa::path(
+    get,
+    path = "/leagues/{league_id}",
+    params(("league_id" = i32, Path, description = "Mini league ID from the FPL website")),
+    responses(
+        (status = 200, body = League),
+        (status = 404, body = ErrorBody)
+    ),
+    tag = "leagues"
+)]
+pub async fn get_league(
+    State(state): State<AppState>,
+    Path(league_id): Path<i32>,
+) -> Result<Json<League>, ApiError> {
+    Ok(Json(find_league(&state, league_id).await?.into()))
+}
+
+/// A league's standings as of the last scrape
+#[utoipa::path(
+    get,
+    path = "/leagues/{league_id}/standings",
+    params(("league_id" = i32, Path, description = "Mini league ID from the FPL website"), Pagination),
+    responses(
+        (status = 200, body = Page<Standing>),
+        (status = 404, body = ErrorBody)
+    ),
+    tag = "leagues"
+)]
+pub async fn get_standings(
+    State(state): State<AppState>,
+    Path(league_id): Path<i32>,
+    Query(pagination): Query<Pagination>,
+) -> Result<Json<Page<Standing>>, ApiError> {
+    let league = find_league(&state, league_id).await?;
+    let standings = get_mini_league_standings(
+        &state.pool,
+        league.id,
+        pagination.limit(),
+        pagination.offset(),
+    )
+    .await?;
+    let total = count_mini_league_standings(&state.pool, league.id).await?;
+    Ok(Json(Page::new(standings, &pagination, total)))
+}
+
+/// Live points for the current game week of every team in a league, highest live total first
+#[utoipa::path(
+    get,
+    path = "/leagues/{league_id}/live",
+    params(("league_id" = i32, Path, description = "Mini league ID from the FPL website"), Pagination),
+    responses(
+        (status = 200, body = Page<LivePoints>),
+        (status = 404, body = ErrorBody)
+    ),
+    tag = "leagues"
+)]
+pub async fn get_live_points(
+    State(state): State<AppState>,
+    Path(league_id): Path<i32>,
+    Query(pagination): Query<Pagination>,
+) -> Result<Json<Page<LivePoints>>, ApiError> {
+    let league = find_league(&state, league_id).await?;
+    let points = get_league_live_points(
+        &state.pool,
+        league.id,
+        pagination.limit(),
+        pagination.offset(),
+    )
+    .await?;
+    let total = count_league_live_points(&state.pool, league.id).await?;
+    Ok(Json(Page::new(points, &pagination, total)))
+}
@@ -0,0 +1,5 @@
+pub mod fixtures;
+pub mod leagues;
+pub mod players;
+pub mod teams;
+pub mod webhooks;
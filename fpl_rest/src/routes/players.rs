@@ -0,0 +1,100 @@
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use fpl_common::types::PlayerId;
+use fpl_db::models::Player as DbPlayer;
+use fpl_db::queries::player::{
+    count_players, get_player as get_db_player, get_players as get_db_players,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::error::{ApiError, ErrorBody};
+use crate::pagination::{Page, Pagination};
+use crate::AppState;
+
+#[derive(Serialize, ToSchema)]
+pub struct Player {
+    pub id: i16,
+    pub web_name: String,
+    pub first_name: String,
+    pub second_name: String,
+    pub club_id: i16,
+    /// 1 goalkeeper, 2 defender, 3 midfielder, 4 forward
+    pub element_type: i16,
+    /// In tenths of a million
+    pub now_cost: i16,
+    pub status: String,
+    pub news: String,
+    pub total_points: i16,
+    pub event_points: i16,
+    pub form: Option<f32>,
+    pub selected_by_percent: f32,
+    pub minutes: i16,
+    pub goals_scored: i16,
+    pub assists: i16,
+    pub clean_sheets: i16,
+    pub bonus: i16,
+}
+
+impl From<DbPlayer> for Player {
+    fn from(player: DbPlayer) -> Self {
+        Self {
+            id: player.id.into(),
+            web_name: player.web_name,
+            first_name: player.first_name,
+            second_name: player.second_name,
+            club_id: player.team.into(),
+            element_type: player.element_type,
+            now_cost: player.now_cost,
+            status: player.status,
+            news: player.news,
+            total_points: player.total_points,
+            event_points: player.event_points,
+            form: player.form,
+            selected_by_percent: player.selected_by_percent,
+            minutes: player.minutes,
+            goals_scored: player.goals_scored,
+            assists: player.assists,
+            clean_sheets: player.clean_sheets,
+            bonus: player.bonus,
+        }
+    }
+}
+
+/// Every player in the game, ordered by ID
+#[utoipa::path(
+    get,
+    path = "/players",
+    params(Pagination),
+    responses((status = 200, body = Page<Player>)),
+    tag = "players"
+)]
+pub async fn get_players(
+    State(state): State<AppState>,
+    Query(pagination): Query<Pagination>,
+) -> Result<Json<Page<Player>>, ApiError> {
+    let players = get_db_players(&state.pool, pagination.limit(), pagination.offset()).await?;
+    let total = count_players(&state.pool).await?;
+    Ok(Json(Page::new(players, &pagination, total)))
+}
+
+/// A single player
+#[utoipa::path(
+    get,
+    path = "/players/{player_id}",
+    params(("player_id" = i16, Path, description = "Player (element) ID from the FPL website")),
+    responses(
+        (status = 200, body = Player),
+        (status = 404, body = ErrorBody)
+    ),
+    tag = "players"
+)]
+pub async fn get_player(
+    State(state): State<AppState>,
+    Path(player_id): Path<i16>,
+) -> Result<Json<Player>, ApiError> {
+    get_db_player(&state.pool, PlayerId::new(player_id))
+        .await?
+        .map(|player| Json(player.into()))
+        .ok_or_else(|| ApiError::NotFound(format!("player {player_id} doesn't exist")))
+}
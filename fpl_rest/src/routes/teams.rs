@@ -0,0 +1,172 @@
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use chrono::{DateTime, Utc};
+use fpl_common::types::{GameWeekId, TeamId};
+use fpl_db::models::{TeamGameWeek, TeamGameWeekPick, Transfer as DbTransfer};
+use fpl_db::queries::game_week::get_current_game_week_id;
+use fpl_db::queries::team_game_week::{get_team_chips, get_team_game_week_picks};
+use fpl_db::queries::transfers::{count_team_transfers, get_team_transfers};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::error::{ApiError, ErrorBody};
+use crate::pagination::{Page, Pagination};
+use crate::AppState;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PicksQuery {
+    /// Game week to show picks for, defaults to the current one
+    pub game_week: Option<i16>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Pick {
+    pub player_id: i16,
+    /// 1-11 are the starting XI, 12-15 the bench in order
+    pub position: i16,
+    pub multiplier: i16,
+    pub is_captain: bool,
+    pub is_vice_captain: bool,
+}
+
+impl From<TeamGameWeekPick> for Pick {
+    fn from(pick: TeamGameWeekPick) -> Self {
+        Self {
+            player_id: pick.player_id.into(),
+            position: pick.position,
+            multiplier: pick.multiplier,
+            is_captain: pick.is_captain,
+            is_vice_captain: pick.is_vice_captain,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Picks {
+    pub team_id: i32,
+    pub game_week_id: i16,
+    pub picks: Vec<Pick>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Transfer {
+    pub game_week_id: i16,
+    pub player_in_id: i16,
+    /// In tenths of a million
+    pub player_in_cost: i16,
+    pub player_out_id: i16,
+    /// In tenths of a million
+    pub player_out_cost: i16,
+    pub time: DateTime<Utc>,
+}
+
+impl From<DbTransfer> for Transfer {
+    fn from(transfer: DbTransfer) -> Self {
+        Self {
+            game_week_id: transfer.game_week_id.into(),
+            player_in_id: transfer.player_in_id.into(),
+            player_in_cost: transfer.player_in_cost,
+            player_out_id: transfer.player_out_id.into(),
+            player_out_cost: transfer.player_out_cost,
+            time: transfer.transfer_time,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ChipPlay {
+    pub game_week_id: i16,
+    /// FPL's name for the chip, e.g. `wildcard` or `3xc`
+    pub chip: String,
+    pub points: i16,
+}
+
+impl From<TeamGameWeek> for ChipPlay {
+    fn from(team_game_week: TeamGameWeek) -> Self {
+        Self {
+            game_week_id: team_game_week.game_week_id.into(),
+            chip: team_game_week.active_chip.unwrap_or_default(),
+            points: team_game_week.points,
+        }
+    }
+}
+
+/// A team's picks for a game week
+#[utoipa::path(
+    get,
+    path = "/teams/{team_id}/picks",
+    params(("team_id" = i32, Path, description = "Team ID from the FPL website"), PicksQuery),
+    responses(
+        (status = 200, body = Picks),
+        (status = 400, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    ),
+    tag = "teams"
+)]
+pub async fn get_picks(
+    State(state): State<AppState>,
+    Path(team_id): Path<i32>,
+    Query(query): Query<PicksQuery>,
+) -> Result<Json<Picks>, ApiError> {
+    let game_week_id = match query.game_week {
+        Some(game_week) => {
+            GameWeekId::new(game_week).map_err(|e| ApiError::BadRequest(e.to_string()))?
+        }
+        None => get_current_game_week_id(&state.pool).await?,
+    };
+
+    let picks = get_team_game_week_picks(&state.pool, TeamId::new(team_id), game_week_id).await?;
+    if picks.is_empty() {
+        return Err(ApiError::NotFound(format!(
+            "no picks for team {team_id} in game week {game_week_id}"
+        )));
+    }
+
+    Ok(Json(Picks {
+        team_id,
+        game_week_id: game_week_id.into(),
+        picks: picks.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// A team's transfers, most recent first
+#[utoipa::path(
+    get,
+    path = "/teams/{team_id}/transfers",
+    params(("team_id" = i32, Path, description = "Team ID from the FPL website"), Pagination),
+    responses((status = 200, body = Page<Transfer>)),
+    tag = "teams"
+)]
+pub async fn get_transfers(
+    State(state): State<AppState>,
+    Path(team_id): Path<i32>,
+    Query(pagination): Query<Pagination>,
+) -> Result<Json<Page<Transfer>>, ApiError> {
+    let team_id = TeamId::new(team_id);
+    let transfers = get_team_transfers(
+        &state.pool,
+        team_id,
+        pagination.limit(),
+        pagination.offset(),
+    )
+    .await?;
+    let total = count_team_transfers(&state.pool, team_id).await?;
+    Ok(Json(Page::new(transfers, &pagination, total)))
+}
+
+/// The chips a team has played this season
+#[utoipa::path(
+    get,
+    path = "/teams/{team_id}/chips",
+    params(("team_id" = i32, Path, description = "Team ID from the FPL website")),
+    responses((status = 200, body = Vec<ChipPlay>)),
+    tag = "teams"
+)]
+pub async fn get_chips(
+    State(state): State<AppState>,
+    Path(team_id): Path<i32>,
+) -> Result<Json<Vec<ChipPlay>>, ApiError> {
+    let chips = get_team_chips(&state.pool, TeamId::new(team_id)).await?;
+    Ok(Json(chips.into_iter().map(Into::into).collect()))
+}
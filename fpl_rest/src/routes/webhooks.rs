@@ -0,0 +1,200 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use chrono::{DateTime, Utc};
+use fpl_db::models::{
+    Webhook as DbWebhook, WebhookDelivery as DbWebhookDelivery, WebhookEvent, WebhookOwner,
+};
+use fpl_db::queries::webhook::{
+    count_webhook_deliveries, delete_webhook, get_webhook_deliveries, get_webhooks,
+};
+use fpl_webhooks::management::create_webhook;
+use fpl_webhooks::signing::hash_api_key;
+use fpl_webhooks::WebhookError;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::auth::ApiKey;
+use crate::error::{ApiError, ErrorBody};
+use crate::pagination::{Page, Pagination};
+use crate::AppState;
+
+fn owner(api_key: &ApiKey) -> WebhookOwner {
+    WebhookOwner::ApiKey(hash_api_key(&api_key.0))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct NewWebhook {
+    /// Where deliveries are POSTed, http or https
+    pub url: String,
+    /// Any of `fixture_kickoff`, `fixture_score`, `player_points`, `deadline_passed` and
+    /// `game_week_finished`, all of them if left out
+    pub events: Option<Vec<String>>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Webhook {
+    pub id: i64,
+    pub url: String,
+    pub events: Vec<String>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<DbWebhook> for Webhook {
+    fn from(webhook: DbWebhook) -> Self {
+        Self {
+            id: webhook.id,
+            url: webhook.url,
+            events: webhook.events,
+            active: webhook.active,
+            created_at: webhook.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CreatedWebhook {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    /// HMAC-SHA256 key for the `X-Fpl-Signature` header, only ever returned here
+    pub secret: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub event: String,
+    /// Also sent as `X-Fpl-Delivery` and the payload's `id`
+    pub idempotency_key: String,
+    /// The JSON body as sent
+    pub payload: String,
+    /// `pending`, `delivered` or `failed`
+    pub status: String,
+    pub attempts: i16,
+    pub last_status_code: Option<i16>,
+    /// The status line or why the request didn't get through, response bodies aren't kept
+    pub last_error: Option<String>,
+    /// When a pending delivery will next be tried
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl From<DbWebhookDelivery> for WebhookDelivery {
+    fn from(delivery: DbWebhookDelivery) -> Self {
+        Self {
+            id: delivery.id,
+            event: delivery.event,
+            idempotency_key: delivery.idempotency_key,
+            payload: delivery.payload,
+            status: delivery.status,
+            attempts: delivery.attempts,
+            last_status_code: delivery.last_status_code,
+            last_error: delivery.last_error,
+            next_attempt_at: delivery.next_attempt_at,
+            created_at: delivery.created_at,
+            delivered_at: delivery.delivered_at,
+        }
+    }
+}
+
+/// Webhooks registered with this API key
+#[utoipa::path(
+    get,
+    path = "/webhooks",
+    responses((status = 200, body = Vec<Webhook>)),
+    tag = "webhooks"
+)]
+pub async fn list_webhooks(
+    State(state): State<AppState>,
+    Extension(api_key): Extension<ApiKey>,
+) -> Result<Json<Vec<Webhook>>, ApiError> {
+    let webhooks = get_webhooks(&state.pool, &owner(&api_key)).await?;
+    Ok(Json(webhooks.into_iter().map(Into::into).collect()))
+}
+
+/// Register a webhook, deliveries are signed with the returned secret
+#[utoipa::path(
+    post,
+    path = "/webhooks",
+    request_body = NewWebhook,
+    responses(
+        (status = 201, body = CreatedWebhook),
+        (status = 400, body = ErrorBody)
+    ),
+    tag = "webhooks"
+)]
+pub async fn post_webhook(
+    State(state): State<AppState>,
+    Extension(api_key): Extension<ApiKey>,
+    Json(new_webhook): Json<NewWebhook>,
+) -> Result<(StatusCode, Json<CreatedWebhook>), ApiError> {
+    let events = match new_webhook.events {
+        Some(events) if events.is_empty() => return Err(WebhookError::NoEvents.into()),
+        Some(events) => events
+            .iter()
+            .map(|event| event.parse().map_err(WebhookError::InvalidEvent))
+            .collect::<Result<Vec<WebhookEvent>, _>>()?,
+        None => WebhookEvent::ALL.to_vec(),
+    };
+
+    let webhook = create_webhook(&state.pool, &owner(&api_key), &new_webhook.url, &events).await?;
+    let secret = webhook.secret.clone();
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedWebhook {
+            webhook: webhook.into(),
+            secret,
+        }),
+    ))
+}
+
+/// Remove a webhook along with its delivery log
+#[utoipa::path(
+    delete,
+    path = "/webhooks/{webhook_id}",
+    params(("webhook_id" = i64, Path, description = "Webhook ID")),
+    responses(
+        (status = 204, description = "Webhook removed"),
+        (status = 404, body = ErrorBody)
+    ),
+    tag = "webhooks"
+)]
+pub async fn remove_webhook(
+    State(state): State<AppState>,
+    Extension(api_key): Extension<ApiKey>,
+    Path(webhook_id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    if !delete_webhook(&state.pool, &owner(&api_key), webhook_id).await? {
+        return Err(ApiError::NotFound(format!("no webhook {webhook_id}")));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// A webhook's delivery log, newest first
+#[utoipa::path(
+    get,
+    path = "/webhooks/{webhook_id}/deliveries",
+    params(("webhook_id" = i64, Path, description = "Webhook ID"), Pagination),
+    responses((status = 200, body = Page<WebhookDelivery>)),
+    tag = "webhooks"
+)]
+pub async fn list_deliveries(
+    State(state): State<AppState>,
+    Extension(api_key): Extension<ApiKey>,
+    Path(webhook_id): Path<i64>,
+    Query(pagination): Query<Pagination>,
+) -> Result<Json<Page<WebhookDelivery>>, ApiError> {
+    let owner = owner(&api_key);
+    let deliveries = get_webhook_deliveries(
+        &state.pool,
+        &owner,
+        webhook_id,
+        pagination.limit(),
+        pagination.offset(),
+    )
+    .await?;
+    let total = count_webhook_deliveries(&state.pool, &owner, webhook_id).await?;
+    Ok(Json(Page::new(deliveries, &pagination, total)))
+}
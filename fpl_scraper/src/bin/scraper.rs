@@ -2,19 +2,219 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
+use clap::{Parser, Subcommand, ValueEnum};
 use fpl_api::FplClient;
+use fpl_common::types::{GameWeekId, LeagueId, TeamId};
+use fpl_db::queries::mini_league::get_team_ids_from_league_id;
+use fpl_db::queries::team::get_all_team_ids;
+use fpl_db::rate_limit::PgRateLimiter;
 use fpl_scraper::{
-    fixtures::FixturesScraper, game_state::GameStateScraper,
-    game_week_players::GameWeekPlayersScraper, mini_leagues::MiniLeaguesScraper,
-    player_images::PlayerPhotosScraper, players::PlayersScraper,
-    team_game_weeks::TeamGameWeekScraper, teams::TeamsScraper, transfers::TransfersScraper,
-    ScraperManager,
+    club_images::ClubImagesScraper, cup::CupScraper, fixtures::FixturesScraper,
+    game_state::GameStateScraper, game_week_players::GameWeekPlayersScraper,
+    mini_leagues::MiniLeaguesScraper, player_images::PlayerPhotosScraper, players::PlayersScraper,
+    refresh_teams, team_game_weeks::TeamGameWeekScraper, teams::TeamsScraper,
+    transfers::TransfersScraper, ScraperManager,
 };
 use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::PgPool;
 use tracing::info;
 
+#[derive(Parser)]
+#[command(name = "scraper", about = "Scrapes the FPL API into the database")]
+struct Cli {
+    /// Defaults to `run` when no subcommand is given
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the scrapers on their intervals until stopped
+    Run {
+        /// Only register these scrapers (comma separated)
+        #[arg(long, value_delimiter = ',')]
+        only: Vec<ScraperKind>,
+    },
+    /// Run every scraper once, in order, then exit
+    Once {
+        /// Only run these scrapers (comma separated)
+        #[arg(long, value_delimiter = ',')]
+        only: Vec<ScraperKind>,
+    },
+    /// Re-scrape team game weeks and game week players for past game weeks
+    Backfill {
+        /// Inclusive game week range, e.g. `1..20`, or a single game week
+        #[arg(long, value_parser = parse_game_week_range)]
+        gameweeks: GameWeekRange,
+    },
+    /// Refresh a single team: details, every game week so far and transfers
+    Team { team_id: TeamId },
+    /// Refresh a mini league's standings and every team in it
+    League { league_id: i32 },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ScraperKind {
+    GameState,
+    Fixtures,
+    Teams,
+    GameWeekPlayers,
+    Players,
+    TeamGameWeeks,
+    MiniLeagues,
+    Transfers,
+    Cup,
+    PlayerPhotos,
+    ClubImages,
+}
+
+#[derive(Clone, Copy)]
+struct GameWeekRange {
+    start: GameWeekId,
+    end: GameWeekId,
+}
+
+impl GameWeekRange {
+    fn game_weeks(&self) -> Vec<GameWeekId> {
+        GameWeekId::weeks_range_iter(i16::from(self.start), i16::from(self.end)).collect()
+    }
+}
+
+fn parse_game_week_range(value: &str) -> Result<GameWeekRange, String> {
+    let parse = |s: &str| {
+        GameWeekId::from_str(s.trim()).map_err(|_| format!("'{}' is not a valid game week", s))
+    };
+
+    let (start, end) = match value.split_once("..") {
+        Some((start, end)) => (parse(start)?, parse(end.trim_start_matches('='))?),
+        None => {
+            let game_week = parse(value)?;
+            (game_week, game_week)
+        }
+    };
+
+    if start > end {
+        return Err(format!("Game week range {} is empty", value));
+    }
+
+    Ok(GameWeekRange { start, end })
+}
+
+fn build_manager(
+    pool: &Arc<PgPool>,
+    client: &Arc<FplClient>,
+    only: &[ScraperKind],
+) -> ScraperManager {
+    let mut manager = ScraperManager::new();
+    manager.set_circuit_breaker(client.circuit_breaker());
+    let enabled = |kind: ScraperKind| only.is_empty() || only.contains(&kind);
+
+    let fifteen_seconds = Duration::from_secs(15);
+    let one_minute = Duration::from_secs(60);
+    let five_minutes = Duration::from_secs(60 * 5);
+    let one_day = Duration::from_secs(60 * 60 * 24);
+
+    // First
+    if enabled(ScraperKind::GameState) {
+        let game_state_scraper =
+            GameStateScraper::new(Arc::clone(pool), Arc::clone(client), one_minute);
+        manager.register_scraper(game_state_scraper);
+    }
+
+    // Second
+    if enabled(ScraperKind::Fixtures) {
+        let fixtures_scraper =
+            FixturesScraper::new(Arc::clone(pool), Arc::clone(client), fifteen_seconds);
+        manager.register_scraper(fixtures_scraper);
+    }
+
+    if enabled(ScraperKind::Teams) {
+        let teams_scraper = TeamsScraper::new(Arc::clone(pool), Arc::clone(client), five_minutes);
+        manager.register_scraper(teams_scraper);
+    }
+
+    if enabled(ScraperKind::GameWeekPlayers) {
+        let game_week_players_scraper =
+            GameWeekPlayersScraper::new(Arc::clone(pool), Arc::clone(client), fifteen_seconds);
+        manager.register_scraper(game_week_players_scraper);
+    }
+
+    // Third
+    if enabled(ScraperKind::Players) {
+        let player_scraper = PlayersScraper::new(Arc::clone(pool), Arc::clone(client), one_minute);
+        manager.register_scraper(player_scraper);
+    }
+
+    if enabled(ScraperKind::TeamGameWeeks) {
+        let team_game_week_scraper =
+            TeamGameWeekScraper::new(Arc::clone(pool), Arc::clone(client), five_minutes);
+        manager.register_scraper(team_game_week_scraper);
+    }
+
+    if enabled(ScraperKind::MiniLeagues) {
+        let mini_league_scraper =
+            MiniLeaguesScraper::new(Arc::clone(pool), Arc::clone(client), five_minutes);
+        manager.register_scraper(mini_league_scraper);
+    }
+
+    if enabled(ScraperKind::Transfers) {
+        let transfers_scraper =
+            TransfersScraper::new(Arc::clone(pool), Arc::clone(client), five_minutes);
+        manager.register_scraper(transfers_scraper);
+    }
+
+    // Fourth
+    if enabled(ScraperKind::Cup) {
+        let cup_scraper = CupScraper::new(Arc::clone(pool), Arc::clone(client), five_minutes);
+        manager.register_scraper(cup_scraper);
+    }
+
+    if enabled(ScraperKind::PlayerPhotos) {
+        let photos_scraper =
+            PlayerPhotosScraper::new(Arc::clone(pool), Arc::clone(client), one_day);
+        manager.register_scraper(photos_scraper);
+    }
+
+    if enabled(ScraperKind::ClubImages) {
+        let club_images_scraper =
+            ClubImagesScraper::new(Arc::clone(pool), Arc::clone(client), one_day);
+        manager.register_scraper(club_images_scraper);
+    }
+
+    manager
+}
+
+async fn backfill(
+    pool: &Arc<PgPool>,
+    client: &Arc<FplClient>,
+    range: GameWeekRange,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let game_weeks = range.game_weeks();
+    info!("Backfilling game weeks {} to {}", range.start, range.end);
+
+    let game_week_players_scraper =
+        GameWeekPlayersScraper::new(Arc::clone(pool), Arc::clone(client), Duration::ZERO);
+    game_week_players_scraper
+        .scrape_game_weeks(game_weeks.clone())
+        .await?;
+
+    let team_ids = get_all_team_ids(pool).await?;
+    let team_game_week_scraper =
+        TeamGameWeekScraper::new(Arc::clone(pool), Arc::clone(client), Duration::ZERO);
+    for game_week_id in game_weeks {
+        info!("Backfilling team game weeks for game week {}", game_week_id);
+        team_game_week_scraper
+            .scrape_game_week(&team_ids, game_week_id)
+            .await?;
+    }
+
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+
     tracing_subscriber::fmt()
         .with_max_level(tracing::Level::INFO)
         .init();
@@ -36,54 +236,41 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .await?,
     );
 
-    let client = Arc::new(FplClient::new());
+    let client = Arc::new(
+        FplClient::new().with_shared_rate_limiter(Arc::new(PgRateLimiter::new(Arc::clone(&pool)))),
+    );
 
     info!("Scraper Start: DB Pool, Client and .env file loaded.");
 
-    let mut manager = ScraperManager::new();
-
-    let fifteen_seconds = Duration::from_secs(15);
-    let one_minute = Duration::from_secs(60);
-    let five_minutes = Duration::from_secs(60 * 5);
-    let one_day = Duration::from_secs(60 * 60 * 24);
-
-    // First
-    let game_state_scraper =
-        GameStateScraper::new(Arc::clone(&pool), Arc::clone(&client), one_minute);
-    manager.register_scraper(game_state_scraper);
+    match cli.command.unwrap_or(Command::Run { only: Vec::new() }) {
+        Command::Run { only } => {
+            build_manager(&pool, &client, &only).run().await;
+        }
+        Command::Once { only } => {
+            if let Err(errors) = build_manager(&pool, &client, &only).run_once().await {
+                return Err(format!("{} scrapers failed", errors.len()).into());
+            }
+        }
+        Command::Backfill { gameweeks } => {
+            backfill(&pool, &client, gameweeks).await?;
+        }
+        Command::Team { team_id } => {
+            refresh_teams(&pool, &client, vec![team_id]).await?;
+        }
+        Command::League { league_id } => {
+            let league_id = LeagueId::new(league_id);
+            let mini_leagues_scraper =
+                MiniLeaguesScraper::new(Arc::clone(&pool), Arc::clone(&client), Duration::ZERO);
+            mini_leagues_scraper.scrape_leagues(&[league_id]).await?;
 
-    // Second
-    let fixtures_scraper =
-        FixturesScraper::new(Arc::clone(&pool), Arc::clone(&client), fifteen_seconds);
-    manager.register_scraper(fixtures_scraper);
-
-    let teams_scraper = TeamsScraper::new(Arc::clone(&pool), Arc::clone(&client), five_minutes);
-    manager.register_scraper(teams_scraper);
-
-    let game_week_players_scraper =
-        GameWeekPlayersScraper::new(Arc::clone(&pool), Arc::clone(&client), fifteen_seconds);
-    manager.register_scraper(game_week_players_scraper);
-
-    // Third
-    let player_scraper = PlayersScraper::new(Arc::clone(&pool), Arc::clone(&client), one_minute);
-    manager.register_scraper(player_scraper);
-
-    let team_game_week_scraper =
-        TeamGameWeekScraper::new(Arc::clone(&pool), Arc::clone(&client), five_minutes);
-    manager.register_scraper(team_game_week_scraper);
-
-    let mini_league_scraper =
-        MiniLeaguesScraper::new(Arc::clone(&pool), Arc::clone(&client), five_minutes);
-    manager.register_scraper(mini_league_scraper);
-
-    let transfers_scraper =
-        TransfersScraper::new(Arc::clone(&pool), Arc::clone(&client), five_minutes);
-    manager.register_scraper(transfers_scraper);
-
-    // Fourth
-    let photos_scraper = PlayerPhotosScraper::new(Arc::clone(&pool), Arc::clone(&client), one_day);
-    manager.register_scraper(photos_scraper);
+            let team_ids = get_team_ids_from_league_id(&pool, league_id)
+                .await?
+                .into_iter()
+                .map(TeamId::new)
+                .collect();
+            refresh_teams(&pool, &client, team_ids).await?;
+        }
+    }
 
-    manager.run().await;
     Ok(())
 }
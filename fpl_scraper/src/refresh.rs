@@ -0,0 +1,41 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use fpl_api::FplClient;
+use fpl_common::types::{GameWeekId, TeamId};
+use fpl_db::queries::game_week::get_current_game_week;
+use sqlx::PgPool;
+use tracing::info;
+
+use crate::error::ScraperError;
+use crate::team_game_weeks::TeamGameWeekScraper;
+use crate::teams::TeamsScraper;
+use crate::transfers::TransfersScraper;
+
+/// Refreshes the teams' details, every game week so far and their transfers, ignoring the
+/// scrapers' usual intervals.
+pub async fn refresh_teams(
+    pool: &Arc<PgPool>,
+    client: &Arc<FplClient>,
+    team_ids: Vec<TeamId>,
+) -> Result<(), ScraperError> {
+    info!("Refreshing {} teams", team_ids.len());
+
+    let teams_scraper = TeamsScraper::new(Arc::clone(pool), Arc::clone(client), Duration::ZERO);
+    teams_scraper.scrape_teams(team_ids.clone()).await?;
+
+    let current_game_week = get_current_game_week(pool).await?;
+    let team_game_week_scraper =
+        TeamGameWeekScraper::new(Arc::clone(pool), Arc::clone(client), Duration::ZERO);
+    for game_week_id in GameWeekId::weeks_range_iter(1, i16::from(current_game_week.id)) {
+        team_game_week_scraper
+            .scrape_game_week(&team_ids, game_week_id)
+            .await?;
+    }
+
+    let transfers_scraper =
+        TransfersScraper::new(Arc::clone(pool), Arc::clone(client), Duration::ZERO);
+    transfers_scraper.scrape_teams(team_ids).await?;
+
+    Ok(())
+}
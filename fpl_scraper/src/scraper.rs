@@ -1,7 +1,8 @@
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use crate::error::ScraperError;
 use async_trait::async_trait;
+use fpl_api::retry::CircuitBreaker;
 use sqlx::types::chrono;
 use strum::{EnumIter, IntoEnumIterator};
 use tokio::time;
@@ -47,8 +48,9 @@ pub trait Scraper: Send + Sync {
 
 pub struct ScraperManager {
     scrapers: HashMap<ScraperOrder, Vec<Box<dyn Scraper>>>,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
 }
-type ScraperResult = Result<(), Vec<(usize, ScraperError)>>;
+pub type ScraperResult = Result<(), Vec<(usize, ScraperError)>>;
 
 impl ScraperManager {
     #[instrument]
@@ -56,9 +58,15 @@ impl ScraperManager {
         info!("Initializing ScraperManager");
         Self {
             scrapers: HashMap::default(),
+            circuit_breaker: None,
         }
     }
 
+    /// Skip whole cycles while the breaker is open (the game is being updated).
+    pub fn set_circuit_breaker(&mut self, circuit_breaker: Arc<CircuitBreaker>) {
+        self.circuit_breaker = Some(circuit_breaker);
+    }
+
     #[instrument(skip(self, scraper))]
     pub fn register_scraper<S>(&mut self, scraper: S)
     where
@@ -85,6 +93,18 @@ impl ScraperManager {
         let mut interval = time::interval(Duration::from_secs(10));
         loop {
             interval.tick().await;
+            if let Some(remaining) = self
+                .circuit_breaker
+                .as_ref()
+                .and_then(|breaker| breaker.remaining())
+            {
+                info!(
+                    "⏸️ Game is being updated, skipping scraper cycle. Resuming in {:?}",
+                    remaining
+                );
+                continue;
+            }
+
             info!("ℹ️ Starting new scraper cycle at {}", chrono::Utc::now());
             match self.process_all_scrapers().await {
                 Ok(_) => info!(
@@ -102,6 +122,12 @@ impl ScraperManager {
         }
     }
 
+    #[instrument(skip(self))]
+    pub async fn run_once(&self) -> ScraperResult {
+        info!("ℹ️ Starting single scraper cycle at {}", chrono::Utc::now());
+        self.process_all_scrapers().await
+    }
+
     async fn process_all_scrapers(&self) -> ScraperResult {
         let mut all_errors = Vec::new();
 
@@ -0,0 +1,133 @@
+use crate::error::ScraperError;
+use crate::scraper::{Scraper, ScraperOrder, ShouldScrape};
+use crate::NoScrapeReason;
+use async_trait::async_trait;
+use fpl_db::queries::club::get_all_club_codes;
+use futures::StreamExt;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+use fpl_api::concurrency::MAX_IN_FLIGHT_REQUESTS;
+use fpl_api::requests::{ClubImageKind, ClubImageRequest};
+use fpl_api::FplClient;
+
+/// Downloads every club's shirt, goalkeeper shirt and badge, for player cards without a photo,
+/// the shirt style team sheet and the favourite club in the team sheet header.
+pub struct ClubImagesScraper {
+    pool: Arc<PgPool>,
+    client: Arc<FplClient>,
+    min_scrape_interval: Duration,
+    last_scrape: RwLock<Option<SystemTime>>,
+}
+
+impl ClubImagesScraper {
+    pub fn new(pool: Arc<PgPool>, client: Arc<FplClient>, min_scrape_interval: Duration) -> Self {
+        info!("Creating ClubImagesScraper");
+        Self {
+            pool,
+            client,
+            min_scrape_interval,
+            last_scrape: RwLock::new(None),
+        }
+    }
+
+    async fn process_image_request(
+        client: Arc<FplClient>,
+        club_code: u32,
+        kind: ClubImageKind,
+    ) -> Result<(), ScraperError> {
+        let output_path = match kind {
+            ClubImageKind::Shirt => fpl_common::paths::get_club_shirt_path(club_code, false),
+            ClubImageKind::GoalkeeperShirt => {
+                fpl_common::paths::get_club_shirt_path(club_code, true)
+            }
+            ClubImageKind::Badge => fpl_common::paths::get_club_badge_path(club_code),
+        };
+
+        client
+            .get_with_retry(ClubImageRequest::new(club_code, kind, output_path))
+            .await
+            .map_err(ScraperError::FplApiError)
+    }
+}
+
+#[async_trait]
+impl Scraper for ClubImagesScraper {
+    async fn should_scrape(&self) -> ShouldScrape {
+        let last_scrape = self.last_scrape.read().await;
+        let result;
+
+        match *last_scrape {
+            None => result = ShouldScrape::Yes,
+            Some(time) => {
+                let elapsed_time = SystemTime::now()
+                    .duration_since(time)
+                    .unwrap_or(Duration::ZERO);
+
+                if elapsed_time >= self.min_scrape_interval {
+                    result = ShouldScrape::Yes;
+                } else {
+                    let remaining_seconds = (self.min_scrape_interval - elapsed_time).as_secs();
+                    result = ShouldScrape::No(NoScrapeReason::TimeIntervalNotLapsed(
+                        self.min_scrape_interval,
+                        remaining_seconds,
+                    ));
+                }
+            }
+        }
+
+        debug!("[{}] Should Scrape Result: {:?}", self.name(), result);
+        result
+    }
+
+    fn name(&self) -> &'static str {
+        "ClubImagesScraper"
+    }
+
+    async fn scrape(&self) -> Result<(), ScraperError> {
+        let all_club_codes = get_all_club_codes(&self.pool).await?;
+
+        // Collected up front, a lazy iterator held across the awaits below trips up the
+        // async_trait future's lifetimes
+        let requests: Vec<(u32, ClubImageKind)> = all_club_codes
+            .into_iter()
+            .flat_map(|club_code| {
+                ClubImageKind::ALL
+                    .into_iter()
+                    .map(move |kind| (club_code, kind))
+            })
+            .collect();
+        let mut stream = futures::stream::iter(requests.into_iter().map(|(club_code, kind)| {
+            ClubImagesScraper::process_image_request(self.client.clone(), club_code, kind)
+        }))
+        .buffer_unordered(MAX_IN_FLIGHT_REQUESTS);
+
+        let mut error_count = 0;
+        let mut images_processed = 0;
+
+        while let Some(result) = stream.next().await {
+            images_processed += 1;
+            if let Err(err) = result {
+                warn!("Failed to process club image {}", err);
+                error_count += 1;
+            }
+        }
+
+        debug!(
+            "[{}] Successfully processed {} club images ({} errors)",
+            self.name(),
+            images_processed,
+            error_count
+        );
+
+        *self.last_scrape.write().await = Some(SystemTime::now());
+        Ok(())
+    }
+
+    fn position(&self) -> ScraperOrder {
+        ScraperOrder::Fourth
+    }
+}
@@ -0,0 +1,145 @@
+use std::time::{Duration, SystemTime};
+
+use crate::error::ScraperError;
+use crate::scraper::{Scraper, ScraperOrder, ShouldScrape};
+use crate::NoScrapeReason;
+use async_trait::async_trait;
+use fpl_db::models::{CupMatch, TeamCupStatus};
+use fpl_db::queries::cup::{get_cup_team_ids, upsert_cup_matches, upsert_team_cup_statuses};
+use fpl_db::queries::game_week::get_cup_leagues_created;
+use futures::StreamExt;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+use fpl_api::concurrency::MAX_IN_FLIGHT_REQUESTS;
+use fpl_api::requests::CupRequest;
+use fpl_api::FplClient;
+use fpl_common::types::TeamId;
+
+pub struct CupScraper {
+    pool: Arc<PgPool>,
+    client: Arc<FplClient>,
+    min_scrape_interval: Duration,
+    last_scrape: RwLock<Option<SystemTime>>,
+}
+
+impl CupScraper {
+    pub fn new(pool: Arc<PgPool>, client: Arc<FplClient>, min_scrape_interval: Duration) -> Self {
+        info!("Creating CupScraper");
+        Self {
+            pool,
+            client,
+            min_scrape_interval,
+            last_scrape: RwLock::new(None),
+        }
+    }
+
+    async fn process_cup_request(
+        client: Arc<FplClient>,
+        team_id: TeamId,
+    ) -> Result<(Vec<CupMatch>, TeamCupStatus), ScraperError> {
+        let cup_response = client.get_with_retry(CupRequest::new(team_id)).await?;
+        let cup_matches = cup_response
+            .cup_matches
+            .iter()
+            .filter(|cup_match| cup_match.is_knockout)
+            .map(|cup_match| cup_match.into())
+            .collect();
+        Ok((cup_matches, (team_id, &cup_response.cup_status).into()))
+    }
+
+    pub async fn scrape_teams(&self, team_ids: Vec<TeamId>) -> Result<(), ScraperError> {
+        let mut stream = futures::stream::iter(
+            team_ids
+                .into_iter()
+                .map(|team_id| CupScraper::process_cup_request(self.client.clone(), team_id)),
+        )
+        .buffer_unordered(MAX_IN_FLIGHT_REQUESTS);
+
+        let mut cup_matches = Vec::new();
+        let mut statuses = Vec::new();
+        let mut error_count = 0;
+
+        while let Some(result) = stream.next().await {
+            match result {
+                Ok((team_cup_matches, status)) => {
+                    cup_matches.extend(team_cup_matches);
+                    statuses.push(status);
+                }
+                Err(e) => {
+                    warn!("Failed to process team {}", e);
+                    error_count += 1;
+                }
+            }
+        }
+
+        // Two registered teams drawn against each other come back twice
+        cup_matches.sort_unstable_by_key(|cup_match| cup_match.id);
+        cup_matches.dedup_by_key(|cup_match| cup_match.id);
+
+        upsert_team_cup_statuses(&self.pool, &statuses).await?;
+        upsert_cup_matches(&self.pool, &cup_matches).await?;
+
+        debug!(
+            "[{}] Successfully processed {} cup matches for {} teams ({} errors)",
+            self.name(),
+            cup_matches.len(),
+            statuses.len(),
+            error_count
+        );
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Scraper for CupScraper {
+    async fn should_scrape(&self) -> ShouldScrape {
+        let last_scrape = self.last_scrape.read().await;
+        let result;
+
+        match *last_scrape {
+            None => result = ShouldScrape::Yes,
+            Some(time) => {
+                let elapsed_time = SystemTime::now()
+                    .duration_since(time)
+                    .unwrap_or(Duration::ZERO);
+
+                if elapsed_time >= self.min_scrape_interval {
+                    result = ShouldScrape::Yes;
+                } else {
+                    let remaining_seconds = (self.min_scrape_interval - elapsed_time).as_secs();
+                    result = ShouldScrape::No(NoScrapeReason::TimeIntervalNotLapsed(
+                        self.min_scrape_interval,
+                        remaining_seconds,
+                    ));
+                }
+            }
+        }
+
+        debug!("[{}] Should Scrape Result: {:?}", self.name(), result);
+        result
+    }
+
+    fn name(&self) -> &'static str {
+        "CupScraper"
+    }
+
+    async fn scrape(&self) -> Result<(), ScraperError> {
+        if get_cup_leagues_created(&self.pool).await? {
+            let team_ids = get_cup_team_ids(&self.pool).await?;
+            self.scrape_teams(team_ids).await?;
+        } else {
+            debug!("[{}] Cups haven't been drawn yet", self.name());
+        }
+
+        *self.last_scrape.write().await = Some(SystemTime::now());
+        Ok(())
+    }
+
+    fn position(&self) -> ScraperOrder {
+        ScraperOrder::Fourth
+    }
+}
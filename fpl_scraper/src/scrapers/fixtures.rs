@@ -69,7 +69,7 @@ impl Scraper for FixturesScraper {
 
     async fn scrape(&self) -> Result<(), ScraperError> {
         let request = FixtureRequest::new();
-        let fixtures = self.client.get(request).await?;
+        let fixtures = self.client.get_with_retry(request).await?;
 
         let bonuses = fixtures
             .iter()
@@ -159,7 +159,7 @@ impl Scraper for GameStateScraper {
 
     async fn scrape(&self) -> Result<(), ScraperError> {
         let request = GameStateRequest::default();
-        let game_state = self.client.get(request).await?;
+        let game_state = self.client.get_with_retry(request).await?;
 
         GameStateScraper::handle_clubs(&self.pool, self.name(), &game_state.teams).await?;
         GameStateScraper::handle_players(&self.pool, self.name(), &game_state.elements).await?;
@@ -13,6 +13,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+use fpl_api::concurrency::MAX_IN_FLIGHT_REQUESTS;
 use fpl_api::requests::GameWeekPlayersRequest;
 use fpl_api::FplClient;
 use fpl_common::types::GameWeekId;
@@ -40,7 +41,7 @@ impl GameWeekPlayersScraper {
         game_week_id: GameWeekId,
     ) -> Result<Vec<GameWeekPlayerDb>, ScraperError> {
         let game_week_response = client
-            .get(GameWeekPlayersRequest::new(game_week_id))
+            .get_with_retry(GameWeekPlayersRequest::new(game_week_id))
             .await?;
         Ok(game_week_response
             .elements
@@ -48,6 +49,30 @@ impl GameWeekPlayersScraper {
             .map(|gwp| (game_week_id, gwp).into())
             .collect())
     }
+
+    pub async fn scrape_game_weeks(
+        &self,
+        game_week_ids: Vec<GameWeekId>,
+    ) -> Result<(), ScraperError> {
+        let mut stream = futures::stream::iter(game_week_ids.into_iter().map(|game_week_id| {
+            GameWeekPlayersScraper::process_game_week_players(self.client.clone(), game_week_id)
+        }))
+        .buffer_unordered(MAX_IN_FLIGHT_REQUESTS);
+
+        while let Some(result) = stream.next().await {
+            let response = match result {
+                Ok(response) => response,
+                Err(e) => {
+                    warn!("{}", e);
+                    continue;
+                }
+            };
+
+            upsert_game_week_players(&self.pool, &response).await?;
+        }
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -85,25 +110,10 @@ impl Scraper for GameWeekPlayersScraper {
 
     async fn scrape(&self) -> Result<(), ScraperError> {
         let current_game_week = get_current_game_week(&self.pool).await?;
-
-        let mut stream = futures::stream::iter(
-            GameWeekId::weeks_range_iter(1, i16::from(current_game_week.id)).map(|game_week_id| {
-                GameWeekPlayersScraper::process_game_week_players(self.client.clone(), game_week_id)
-            }),
+        self.scrape_game_weeks(
+            GameWeekId::weeks_range_iter(1, i16::from(current_game_week.id)).collect(),
         )
-        .buffer_unordered(5);
-
-        while let Some(result) = stream.next().await {
-            let response = match result {
-                Ok(response) => response,
-                Err(e) => {
-                    warn!("{}", e);
-                    continue;
-                }
-            };
-
-            upsert_game_week_players(&self.pool, &response).await?;
-        }
+        .await?;
 
         *self.last_scrape.write().await = Some(SystemTime::now());
         Ok(())
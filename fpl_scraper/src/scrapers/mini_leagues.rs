@@ -2,7 +2,7 @@ use std::time::{Duration, SystemTime};
 
 use crate::error::ScraperError;
 use crate::scraper::{Scraper, ScraperOrder, ShouldScrape};
-use crate::{with_retry, NoScrapeReason, DEFAULT_MAX_RETRIES};
+use crate::NoScrapeReason;
 use async_trait::async_trait;
 use fpl_api::responses::mini_league::{MiniLeagueResponse, Standing};
 use fpl_common::types::LeagueId;
@@ -16,6 +16,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+use fpl_api::concurrency::MAX_IN_FLIGHT_REQUESTS;
 use fpl_api::requests::MiniLeagueRequest;
 use fpl_api::FplClient;
 
@@ -37,49 +38,69 @@ impl MiniLeaguesScraper {
         }
     }
 
-    async fn handle_mini_league(
+    pub async fn handle_mini_league(
         client: Arc<FplClient>,
         league_id: LeagueId,
     ) -> Result<(MiniLeagueResponse, Vec<Standing>), ScraperError> {
         let mut mini_league_standings: Vec<Standing> = Vec::new();
         let mut page = 1;
-        let mut current_page = with_retry(
-            || {
-                let client_clone = client.clone();
-                let league_id_clone = league_id;
-                async move {
-                    client_clone
-                        .get(MiniLeagueRequest::new(league_id_clone, page))
-                        .await
-                }
-            },
-            DEFAULT_MAX_RETRIES,
-        )
-        .await?;
+        let mut current_page = client
+            .get_with_retry(MiniLeagueRequest::new(league_id, page))
+            .await?;
 
         mini_league_standings.extend(current_page.standings.results.clone());
         while current_page.standings.has_next {
             page += 1;
-            current_page = with_retry(
-                || {
-                    let client_clone = client.clone();
-                    let league_id_clone = league_id;
-                    let page_clone = page;
-                    async move {
-                        client_clone
-                            .get(MiniLeagueRequest::new(league_id_clone, page_clone))
-                            .await
-                    }
-                },
-                DEFAULT_MAX_RETRIES,
-            )
-            .await?;
+            current_page = client
+                .get_with_retry(MiniLeagueRequest::new(league_id, page))
+                .await?;
 
             mini_league_standings.extend(current_page.standings.results.clone());
         }
 
         Ok((current_page, mini_league_standings))
     }
+
+    pub async fn scrape_leagues(&self, league_ids: &[LeagueId]) -> Result<(), ScraperError> {
+        let chunk_size = 100;
+
+        for chunk in league_ids.chunks(chunk_size) {
+            let chunk = chunk.to_vec();
+
+            let mut stream = futures::stream::iter(chunk.into_iter().map(|league_id| {
+                MiniLeaguesScraper::handle_mini_league(self.client.clone(), league_id)
+            }))
+            .buffer_unordered(MAX_IN_FLIGHT_REQUESTS);
+
+            let mut leagues_info: Vec<MiniLeague> = Vec::with_capacity(chunk_size);
+            let mut leagues_standing_info: Vec<MiniLeagueStanding> = Vec::new();
+
+            while let Some(result) = stream.next().await {
+                let response = match result {
+                    Ok(response) => response,
+                    Err(e) => {
+                        warn!("{}", e);
+                        continue;
+                    }
+                };
+
+                let (league, standings) = response;
+
+                leagues_info.push((&league).into());
+                leagues_standing_info.extend(
+                    standings
+                        .into_iter()
+                        .map(|standing| (&league.league.id, &standing).into())
+                        .collect::<Vec<MiniLeagueStanding>>(),
+                )
+            }
+
+            upsert_mini_leagues(&self.pool, &leagues_info).await?;
+            upsert_mini_league_standings(&self.pool, &leagues_standing_info).await?;
+        }
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -112,47 +133,12 @@ impl Scraper for MiniLeaguesScraper {
     }
 
     fn name(&self) -> &'static str {
-        "FixturesScraper"
+        "MiniLeaguesScraper"
     }
 
     async fn scrape(&self) -> Result<(), ScraperError> {
         let all_league_ids = get_all_mini_league_ids(&self.pool).await?;
-        let chunk_size = 100;
-
-        for chunk in all_league_ids.chunks(chunk_size) {
-            let chunk = chunk.to_vec();
-
-            let mut stream = futures::stream::iter(chunk.into_iter().map(|league_id| {
-                MiniLeaguesScraper::handle_mini_league(self.client.clone(), league_id)
-            }))
-            .buffer_unordered(5);
-
-            let mut leagues_info: Vec<MiniLeague> = Vec::with_capacity(chunk_size);
-            let mut leagues_standing_info: Vec<MiniLeagueStanding> = Vec::new();
-
-            while let Some(result) = stream.next().await {
-                let response = match result {
-                    Ok(response) => response,
-                    Err(e) => {
-                        warn!("{}", e);
-                        continue;
-                    }
-                };
-
-                let (league, standings) = response;
-
-                leagues_info.push((&league).into());
-                leagues_standing_info.extend(
-                    standings
-                        .into_iter()
-                        .map(|standing| (&league.league.id, &standing).into())
-                        .collect::<Vec<MiniLeagueStanding>>(),
-                )
-            }
-
-            upsert_mini_leagues(&self.pool, &leagues_info).await?;
-            upsert_mini_league_standings(&self.pool, &leagues_standing_info).await?;
-        }
+        self.scrape_leagues(&all_league_ids).await?;
 
         *self.last_scrape.write().await = Some(SystemTime::now());
         Ok(())
@@ -1,3 +1,5 @@
+pub mod club_images;
+pub mod cup;
 pub mod fixtures;
 pub mod game_state;
 pub mod game_week_players;
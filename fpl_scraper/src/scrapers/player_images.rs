@@ -10,6 +10,7 @@ use std::time::{Duration, SystemTime};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+use fpl_api::concurrency::MAX_IN_FLIGHT_REQUESTS;
 use fpl_api::requests::PlayerPhotoRequest;
 use fpl_api::FplClient;
 
@@ -36,7 +37,7 @@ impl PlayerPhotosScraper {
         player_code: u32,
     ) -> Result<(), ScraperError> {
         client
-            .get(PlayerPhotoRequest::new(
+            .get_with_retry(PlayerPhotoRequest::new(
                 player_code,
                 fpl_common::paths::get_player_image_path(player_code),
             ))
@@ -84,7 +85,7 @@ impl Scraper for PlayerPhotosScraper {
         let mut stream = futures::stream::iter(all_player_codes.into_iter().map(|player_code| {
             PlayerPhotosScraper::process_photo_request(self.client.clone(), player_code)
         }))
-        .buffer_unordered(10);
+        .buffer_unordered(MAX_IN_FLIGHT_REQUESTS);
 
         let mut error_count = 0;
         let mut photos_processed = 0;
@@ -2,7 +2,7 @@ use std::time::{Duration, SystemTime};
 
 use crate::error::ScraperError;
 use crate::scraper::{Scraper, ScraperOrder, ShouldScrape};
-use crate::{with_retry, NoScrapeReason, DEFAULT_MAX_RETRIES};
+use crate::NoScrapeReason;
 use async_trait::async_trait;
 use fpl_db::models::{PlayerFixtureDb, PlayerHistoryDb, PlayerHistoryPastDb};
 use fpl_db::queries::player::{
@@ -16,6 +16,7 @@ use tokio::time::interval;
 use tokio_stream::wrappers::IntervalStream;
 use tracing::{debug, info, warn};
 
+use fpl_api::concurrency::MAX_IN_FLIGHT_REQUESTS;
 use fpl_api::requests::PlayerRequest;
 use fpl_api::FplClient;
 use fpl_common::types::PlayerId;
@@ -49,15 +50,8 @@ impl PlayersScraper {
         ),
         ScraperError,
     > {
-        let player: fpl_api::responses::player::PlayerResponse = with_retry(
-            || {
-                let client_clone = client.clone();
-                let player_id_clone = player_id;
-                async move { client_clone.get(PlayerRequest::new(player_id_clone)).await }
-            },
-            DEFAULT_MAX_RETRIES,
-        )
-        .await?;
+        let player: fpl_api::responses::player::PlayerResponse =
+            client.get_with_retry(PlayerRequest::new(player_id)).await?;
 
         // Process fixtures
         let fixtures: Vec<PlayerFixtureDb> = player
@@ -130,7 +124,7 @@ impl Scraper for PlayersScraper {
                         PlayersScraper::process_player(Arc::clone(&self.client), player_id)
                     });
 
-            let mut stream = combined_stream.buffer_unordered(5);
+            let mut stream = combined_stream.buffer_unordered(MAX_IN_FLIGHT_REQUESTS);
 
             let mut player_fixtures = Vec::new();
             let mut player_history = Vec::new();
@@ -2,7 +2,7 @@ use std::time::{Duration, SystemTime};
 
 use crate::error::ScraperError;
 use crate::scraper::{Scraper, ScraperOrder, ShouldScrape};
-use crate::{with_retry, NoScrapeReason, DEFAULT_MAX_RETRIES};
+use crate::NoScrapeReason;
 use async_trait::async_trait;
 use fpl_api::responses::team_game_week::TeamGameWeekResponse;
 use fpl_db::queries::game_week::get_current_game_week;
@@ -16,6 +16,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+use fpl_api::concurrency::MAX_IN_FLIGHT_REQUESTS;
 use fpl_api::requests::TeamGameWeekRequest;
 use fpl_api::{FplClient, FplClientError};
 use fpl_common::types::{GameWeekId, TeamId};
@@ -43,61 +44,18 @@ impl TeamGameWeekScraper {
         team_id: TeamId,
         game_week_id: GameWeekId,
     ) -> Result<TeamGameWeekResponse, ScraperError> {
-        let team_game_week_response = with_retry(
-            || {
-                let client_clone = client.clone();
-                let team_id_clone = team_id;
-                let game_week_id_clone = game_week_id;
-                async move {
-                    client_clone
-                        .get(TeamGameWeekRequest::new(team_id_clone, game_week_id_clone))
-                        .await
-                }
-            },
-            DEFAULT_MAX_RETRIES,
-        )
-        .await?;
+        let team_game_week_response = client
+            .get_with_retry(TeamGameWeekRequest::new(team_id, game_week_id))
+            .await?;
 
         Ok(team_game_week_response)
     }
-}
 
-#[async_trait]
-impl Scraper for TeamGameWeekScraper {
-    async fn should_scrape(&self) -> ShouldScrape {
-        let last_scrape = self.last_scrape.read().await;
-        let result;
-
-        match *last_scrape {
-            None => result = ShouldScrape::Yes,
-            Some(time) => {
-                let elapsed_time = SystemTime::now()
-                    .duration_since(time)
-                    .unwrap_or(Duration::ZERO);
-
-                if elapsed_time >= self.min_scrape_interval {
-                    result = ShouldScrape::Yes;
-                } else {
-                    let remaining_seconds = (self.min_scrape_interval - elapsed_time).as_secs();
-                    result = ShouldScrape::No(NoScrapeReason::TimeIntervalNotLapsed(
-                        self.min_scrape_interval,
-                        remaining_seconds,
-                    ));
-                }
-            }
-        }
-
-        debug!("[{}] Should Scrape Result: {:?}", self.name(), result);
-        result
-    }
-
-    fn name(&self) -> &'static str {
-        "TeamGameWeekScraper"
-    }
-
-    async fn scrape(&self) -> Result<(), ScraperError> {
-        let current_game_week = get_current_game_week(&self.pool).await?;
-        let team_ids = get_all_team_ids(&self.pool).await?;
+    pub async fn scrape_game_week(
+        &self,
+        team_ids: &[TeamId],
+        target_game_week_id: GameWeekId,
+    ) -> Result<(), ScraperError> {
         let chunk_size = 100;
 
         for chunk in team_ids.chunks(chunk_size) {
@@ -107,10 +65,10 @@ impl Scraper for TeamGameWeekScraper {
                 TeamGameWeekScraper::process_team_game_week(
                     self.client.clone(),
                     team_id,
-                    current_game_week.id,
+                    target_game_week_id,
                 )
             }))
-            .buffer_unordered(5);
+            .buffer_unordered(MAX_IN_FLIGHT_REQUESTS);
 
             let mut game_week_picks = Vec::with_capacity(chunk_size * 15);
             let mut game_week_automatic_subs = Vec::with_capacity(chunk_size * 4);
@@ -119,6 +77,10 @@ impl Scraper for TeamGameWeekScraper {
             while let Some(result) = stream.next().await {
                 let response = match result {
                     Ok(response) => response,
+                    Err(ScraperError::FplApiError(FplClientError::NotStarted { url })) => {
+                        debug!("[{}] No picks yet: {}", self.name(), url);
+                        continue;
+                    }
                     Err(e) => {
                         warn!("{}", e);
                         continue;
@@ -151,9 +113,52 @@ impl Scraper for TeamGameWeekScraper {
                 "[{}] Processed {} teams for week {}",
                 self.name(),
                 team_game_weeks.len(),
-                current_game_week.id
+                target_game_week_id
             );
         }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Scraper for TeamGameWeekScraper {
+    async fn should_scrape(&self) -> ShouldScrape {
+        let last_scrape = self.last_scrape.read().await;
+        let result;
+
+        match *last_scrape {
+            None => result = ShouldScrape::Yes,
+            Some(time) => {
+                let elapsed_time = SystemTime::now()
+                    .duration_since(time)
+                    .unwrap_or(Duration::ZERO);
+
+                if elapsed_time >= self.min_scrape_interval {
+                    result = ShouldScrape::Yes;
+                } else {
+                    let remaining_seconds = (self.min_scrape_interval - elapsed_time).as_secs();
+                    result = ShouldScrape::No(NoScrapeReason::TimeIntervalNotLapsed(
+                        self.min_scrape_interval,
+                        remaining_seconds,
+                    ));
+                }
+            }
+        }
+
+        debug!("[{}] Should Scrape Result: {:?}", self.name(), result);
+        result
+    }
+
+    fn name(&self) -> &'static str {
+        "TeamGameWeekScraper"
+    }
+
+    async fn scrape(&self) -> Result<(), ScraperError> {
+        let current_game_week = get_current_game_week(&self.pool).await?;
+        let team_ids = get_all_team_ids(&self.pool).await?;
+        self.scrape_game_week(&team_ids, current_game_week.id)
+            .await?;
+
         *self.last_scrape.write().await = Some(SystemTime::now());
         Ok(())
     }
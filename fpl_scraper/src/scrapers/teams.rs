@@ -2,7 +2,7 @@ use std::time::{Duration, SystemTime};
 
 use crate::error::ScraperError;
 use crate::scraper::{Scraper, ScraperOrder, ShouldScrape};
-use crate::{with_retry, NoScrapeReason, DEFAULT_MAX_RETRIES};
+use crate::NoScrapeReason;
 use async_trait::async_trait;
 use fpl_db::models::Team;
 use futures::StreamExt;
@@ -11,6 +11,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+use fpl_api::concurrency::MAX_IN_FLIGHT_REQUESTS;
 use fpl_api::requests::TeamRequest;
 use fpl_api::FplClient;
 use fpl_common::types::TeamId;
@@ -38,62 +39,18 @@ impl TeamsScraper {
         client: Arc<FplClient>,
         team_id: TeamId,
     ) -> Result<Team, ScraperError> {
-        let team_response = with_retry(
-            || {
-                let client_clone = client.clone();
-                let team_id_clone = team_id;
-                async move { client_clone.get(TeamRequest::new(team_id_clone)).await }
-            },
-            DEFAULT_MAX_RETRIES,
-        )
-        .await?;
+        let team_response = client.get_with_retry(TeamRequest::new(team_id)).await?;
 
         Ok((&team_response).into())
     }
-}
-
-#[async_trait]
-impl Scraper for TeamsScraper {
-    async fn should_scrape(&self) -> ShouldScrape {
-        let last_scrape = self.last_scrape.read().await;
-        let result;
-
-        match *last_scrape {
-            None => result = ShouldScrape::Yes,
-            Some(time) => {
-                let elapsed_time = SystemTime::now()
-                    .duration_since(time)
-                    .unwrap_or(Duration::ZERO);
-
-                if elapsed_time >= self.min_scrape_interval {
-                    result = ShouldScrape::Yes;
-                } else {
-                    let remaining_seconds = (self.min_scrape_interval - elapsed_time).as_secs();
-                    result = ShouldScrape::No(NoScrapeReason::TimeIntervalNotLapsed(
-                        self.min_scrape_interval,
-                        remaining_seconds,
-                    ));
-                }
-            }
-        }
-
-        debug!("[{}] Should Scrape Result: {:?}", self.name(), result);
-        result
-    }
-
-    fn name(&self) -> &'static str {
-        "TeamsScraper"
-    }
-
-    async fn scrape(&self) -> Result<(), ScraperError> {
-        let team_ids = get_all_team_ids(&self.pool).await?;
 
+    pub async fn scrape_teams(&self, team_ids: Vec<TeamId>) -> Result<(), ScraperError> {
         let mut stream = futures::stream::iter(
             team_ids
                 .into_iter()
                 .map(|team_id| TeamsScraper::process_teams_request(self.client.clone(), team_id)),
         )
-        .buffer_unordered(5);
+        .buffer_unordered(MAX_IN_FLIGHT_REQUESTS);
 
         let batch_size = 1000;
         let mut teams_batch = Vec::with_capacity(batch_size);
@@ -129,6 +86,47 @@ impl Scraper for TeamsScraper {
             error_count
         );
 
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Scraper for TeamsScraper {
+    async fn should_scrape(&self) -> ShouldScrape {
+        let last_scrape = self.last_scrape.read().await;
+        let result;
+
+        match *last_scrape {
+            None => result = ShouldScrape::Yes,
+            Some(time) => {
+                let elapsed_time = SystemTime::now()
+                    .duration_since(time)
+                    .unwrap_or(Duration::ZERO);
+
+                if elapsed_time >= self.min_scrape_interval {
+                    result = ShouldScrape::Yes;
+                } else {
+                    let remaining_seconds = (self.min_scrape_interval - elapsed_time).as_secs();
+                    result = ShouldScrape::No(NoScrapeReason::TimeIntervalNotLapsed(
+                        self.min_scrape_interval,
+                        remaining_seconds,
+                    ));
+                }
+            }
+        }
+
+        debug!("[{}] Should Scrape Result: {:?}", self.name(), result);
+        result
+    }
+
+    fn name(&self) -> &'static str {
+        "TeamsScraper"
+    }
+
+    async fn scrape(&self) -> Result<(), ScraperError> {
+        let team_ids = get_all_team_ids(&self.pool).await?;
+        self.scrape_teams(team_ids).await?;
+
         *self.last_scrape.write().await = Some(SystemTime::now());
         Ok(())
     }
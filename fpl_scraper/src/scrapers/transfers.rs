@@ -2,7 +2,7 @@ use std::time::{Duration, SystemTime};
 
 use crate::error::ScraperError;
 use crate::scraper::{Scraper, ScraperOrder, ShouldScrape};
-use crate::{with_retry, NoScrapeReason, DEFAULT_MAX_RETRIES};
+use crate::NoScrapeReason;
 use async_trait::async_trait;
 use fpl_db::models::Transfer;
 use fpl_db::queries::transfers::upsert_transfers;
@@ -12,6 +12,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+use fpl_api::concurrency::MAX_IN_FLIGHT_REQUESTS;
 use fpl_api::requests::TransfersRequest;
 use fpl_api::FplClient;
 use fpl_common::types::TeamId;
@@ -39,62 +40,20 @@ impl TransfersScraper {
         client: Arc<FplClient>,
         team_id: TeamId,
     ) -> Result<Vec<Transfer>, ScraperError> {
-        let transfers_response = with_retry(
-            || {
-                let client_clone = client.clone();
-                let team_id_clone = team_id;
-                async move { client_clone.get(TransfersRequest::new(team_id_clone)).await }
-            },
-            DEFAULT_MAX_RETRIES,
-        )
-        .await?;
+        let transfers_response = client
+            .get_with_retry(TransfersRequest::new(team_id))
+            .await?;
         Ok(transfers_response
             .into_iter()
             .map(|t| (&t).into())
             .collect())
     }
-}
-
-#[async_trait]
-impl Scraper for TransfersScraper {
-    async fn should_scrape(&self) -> ShouldScrape {
-        let last_scrape = self.last_scrape.read().await;
-        let result;
-
-        match *last_scrape {
-            None => result = ShouldScrape::Yes,
-            Some(time) => {
-                let elapsed_time = SystemTime::now()
-                    .duration_since(time)
-                    .unwrap_or(Duration::ZERO);
-
-                if elapsed_time >= self.min_scrape_interval {
-                    result = ShouldScrape::Yes;
-                } else {
-                    let remaining_seconds = (self.min_scrape_interval - elapsed_time).as_secs();
-                    result = ShouldScrape::No(NoScrapeReason::TimeIntervalNotLapsed(
-                        self.min_scrape_interval,
-                        remaining_seconds,
-                    ));
-                }
-            }
-        }
-
-        debug!("[{}] Should Scrape Result: {:?}", self.name(), result);
-        result
-    }
-
-    fn name(&self) -> &'static str {
-        "TransfersScraper"
-    }
-
-    async fn scrape(&self) -> Result<(), ScraperError> {
-        let team_ids = get_all_team_ids(&self.pool).await?;
 
+    pub async fn scrape_teams(&self, team_ids: Vec<TeamId>) -> Result<(), ScraperError> {
         let mut stream = futures::stream::iter(team_ids.into_iter().map(|team_id| {
             TransfersScraper::process_transfer_request(self.client.clone(), team_id)
         }))
-        .buffer_unordered(5);
+        .buffer_unordered(MAX_IN_FLIGHT_REQUESTS);
 
         let batch_size = 5000;
         let mut transfers_batch = Vec::with_capacity(batch_size);
@@ -130,6 +89,47 @@ impl Scraper for TransfersScraper {
             error_count
         );
 
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Scraper for TransfersScraper {
+    async fn should_scrape(&self) -> ShouldScrape {
+        let last_scrape = self.last_scrape.read().await;
+        let result;
+
+        match *last_scrape {
+            None => result = ShouldScrape::Yes,
+            Some(time) => {
+                let elapsed_time = SystemTime::now()
+                    .duration_since(time)
+                    .unwrap_or(Duration::ZERO);
+
+                if elapsed_time >= self.min_scrape_interval {
+                    result = ShouldScrape::Yes;
+                } else {
+                    let remaining_seconds = (self.min_scrape_interval - elapsed_time).as_secs();
+                    result = ShouldScrape::No(NoScrapeReason::TimeIntervalNotLapsed(
+                        self.min_scrape_interval,
+                        remaining_seconds,
+                    ));
+                }
+            }
+        }
+
+        debug!("[{}] Should Scrape Result: {:?}", self.name(), result);
+        result
+    }
+
+    fn name(&self) -> &'static str {
+        "TransfersScraper"
+    }
+
+    async fn scrape(&self) -> Result<(), ScraperError> {
+        let team_ids = get_all_team_ids(&self.pool).await?;
+        self.scrape_teams(team_ids).await?;
+
         *self.last_scrape.write().await = Some(SystemTime::now());
         Ok(())
     }
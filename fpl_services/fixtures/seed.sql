@@ -0,0 +1,201 @@
+-- Two managers in one mini league across a finished GW1 and a live GW2 (GW3 is next).
+-- Alice FC (101) triple captains in GW2 and transfers Player15 out for Player16.
+-- Bob United (102) bench boosts in GW1 and takes a 4 point hit.
+INSERT INTO clubs (id, code, draw, form, loss, name, played, points, position, short_name, strength, team_division, unavailable, win, strength_overall_home, strength_overall_away, strength_attack_home, strength_attack_away, strength_defence_home, strength_defence_away, pulse_id, created_at, updated_at)
+VALUES
+    (1, 3, 0, NULL, 0, 'Arsenal', 0, 0, 1, 'ARS', 0, NULL, false, 0, 0, 0, 0, 0, 0, 0, 0, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (2, 8, 0, NULL, 0, 'Chelsea', 0, 0, 2, 'CHE', 0, NULL, false, 0, 0, 0, 0, 0, 0, 0, 0, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z');
+
+INSERT INTO game_weeks (id, name, deadline_time, release_time, average_entry_score, finished, data_checked, highest_scoring_entry, deadline_time_epoch, deadline_time_game_offset, highest_score, is_previous, is_current, is_next, cup_leagues_created, h2h_ko_matches_created, can_enter, can_manage, released, ranked_count, transfers_made, most_selected, most_transferred_in, top_element, most_captained, most_vice_captained)
+VALUES
+    (1, 'Gameweek 1', '2024-08-16T17:30:00Z', NULL, 0, true, true, NULL, 0, 0, NULL, true, false, false, false, false, false, false, true, 0, 0, NULL, NULL, NULL, NULL, NULL),
+    (2, 'Gameweek 2', '2024-08-24T10:00:00Z', NULL, 0, false, false, NULL, 0, 0, NULL, false, true, false, false, false, false, false, true, 0, 0, NULL, NULL, NULL, NULL, NULL),
+    (3, 'Gameweek 3', '2099-08-31T10:00:00Z', NULL, 0, false, false, NULL, 0, 0, NULL, false, false, true, false, false, false, false, false, 0, 0, NULL, NULL, NULL, NULL, NULL);
+
+INSERT INTO fixtures (id, code, game_week_id, home_team_id, away_team_id, home_team_score, away_team_score, kickoff_time, finished, started, minutes, provisional_start_time, team_h_difficulty, team_a_difficulty, pulse_id, created_at, updated_at)
+VALUES
+    (1, 1001, 1, 1, 2, 2, 1, '2024-08-17T14:00:00Z', true, true, 90, false, 3, 3, 1, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (2, 1002, 2, 2, 1, 0, 0, '2024-08-24T14:00:00Z', false, true, 45, false, 3, 3, 2, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z');
+
+INSERT INTO players (id, can_transact, can_select, chance_of_playing_next_round, chance_of_playing_this_round, code, cost_change_event, cost_change_event_fall, cost_change_start, cost_change_start_fall, dreamteam_count, element_type, ep_next, ep_this, event_points, first_name, form, in_dreamteam, news, news_added, now_cost, photo, points_per_game, removed, second_name, selected_by_percent, special, squad_number, status, team, team_code, total_points, transfers_in, transfers_in_event, transfers_out, transfers_out_event, value_form, value_season, web_name, region, team_join_date, minutes, goals_scored, assists, clean_sheets, goals_conceded, own_goals, penalties_saved, penalties_missed, yellow_cards, red_cards, saves, bonus, bps, influence, creativity, threat, ict_index, starts, expected_goals, expected_assists, expected_goal_involvements, expected_goals_conceded, influence_rank, influence_rank_type, creativity_rank, creativity_rank_type, threat_rank, threat_rank_type, ict_index_rank, ict_index_rank_type, corners_and_indirect_freekicks_order, corners_and_indirect_freekicks_text, direct_freekicks_order, direct_freekicks_text, penalties_order, penalties_text, expected_goals_per_90, saves_per_90, expected_assists_per_90, expected_goal_involvements_per_90, expected_goals_conceded_per_90, goals_conceded_per_90, now_cost_rank, now_cost_rank_type, form_rank, form_rank_type, points_per_game_rank, points_per_game_rank_type, selected_rank, selected_rank_type, starts_per_90, clean_sheets_per_90)
+VALUES
+    (1, true, true, NULL, NULL, 100001, 0, 0, 0, 0, 0, 1, 0, 0, 1, 'First1', NULL, false, '', NULL, 51, '', 0, false, 'Player1', 1.0, false, NULL, 'a', 1, 3, 2, 0, 0, 0, 0, 0, 0, 'Player1', NULL, NULL, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, NULL, '', NULL, '', NULL, '', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0),
+    (2, true, true, NULL, NULL, 100002, 0, 0, 0, 0, 0, 1, 0, 0, 2, 'First2', NULL, false, '', NULL, 52, '', 0, false, 'Player2', 2.0, false, NULL, 'a', 2, 8, 4, 0, 0, 0, 0, 0, 0, 'Player2', NULL, NULL, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, NULL, '', NULL, '', NULL, '', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0),
+    (3, true, true, NULL, NULL, 100003, 0, 0, 0, 0, 0, 2, 0, 0, 3, 'First3', NULL, false, '', NULL, 53, '', 0, false, 'Player3', 3.0, false, NULL, 'a', 1, 3, 6, 0, 0, 0, 0, 0, 0, 'Player3', NULL, NULL, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, NULL, '', NULL, '', NULL, '', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0),
+    (4, true, true, NULL, NULL, 100004, 0, 0, 0, 0, 0, 2, 0, 0, 4, 'First4', NULL, false, '', NULL, 54, '', 0, false, 'Player4', 4.0, false, NULL, 'a', 2, 8, 8, 0, 0, 0, 0, 0, 0, 'Player4', NULL, NULL, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, NULL, '', NULL, '', NULL, '', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0),
+    (5, true, true, NULL, NULL, 100005, 0, 0, 0, 0, 0, 2, 0, 0, 0, 'First5', NULL, false, '', NULL, 55, '', 0, false, 'Player5', 5.0, false, NULL, 'a', 1, 3, 10, 0, 0, 0, 0, 0, 0, 'Player5', NULL, NULL, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, NULL, '', NULL, '', NULL, '', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0),
+    (6, true, true, NULL, NULL, 100006, 0, 0, 0, 0, 0, 2, 0, 0, 1, 'First6', NULL, false, '', NULL, 56, '', 0, false, 'Player6', 6.0, false, NULL, 'a', 2, 8, 12, 0, 0, 0, 0, 0, 0, 'Player6', NULL, NULL, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, NULL, '', NULL, '', NULL, '', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0),
+    (7, true, true, NULL, NULL, 100007, 0, 0, 0, 0, 0, 2, 0, 0, 2, 'First7', NULL, false, '', NULL, 57, '', 0, false, 'Player7', 7.0, false, NULL, 'a', 1, 3, 14, 0, 0, 0, 0, 0, 0, 'Player7', NULL, NULL, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, NULL, '', NULL, '', NULL, '', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0),
+    (8, true, true, NULL, NULL, 100008, 0, 0, 0, 0, 0, 3, 0, 0, 3, 'First8', NULL, false, '', NULL, 58, '', 0, false, 'Player8', 8.0, false, NULL, 'a', 2, 8, 16, 0, 0, 0, 0, 0, 0, 'Player8', NULL, NULL, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, NULL, '', NULL, '', NULL, '', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0),
+    (9, true, true, NULL, NULL, 100009, 0, 0, 0, 0, 0, 3, 0, 0, 4, 'First9', NULL, false, '', NULL, 59, '', 0, false, 'Player9', 9.0, false, NULL, 'a', 1, 3, 18, 0, 0, 0, 0, 0, 0, 'Player9', NULL, NULL, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, NULL, '', NULL, '', NULL, '', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0),
+    (10, true, true, NULL, NULL, 100010, 0, 0, 0, 0, 0, 3, 0, 0, 0, 'First10', NULL, false, '', NULL, 60, '', 0, false, 'Player10', 10.0, false, NULL, 'a', 2, 8, 20, 0, 0, 0, 0, 0, 0, 'Player10', NULL, NULL, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, NULL, '', NULL, '', NULL, '', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0),
+    (11, true, true, NULL, NULL, 100011, 0, 0, 0, 0, 0, 3, 0, 0, 1, 'First11', NULL, false, '', NULL, 61, '', 0, false, 'Player11', 11.0, false, NULL, 'a', 1, 3, 22, 0, 0, 0, 0, 0, 0, 'Player11', NULL, NULL, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, NULL, '', NULL, '', NULL, '', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0),
+    (12, true, true, NULL, NULL, 100012, 0, 0, 0, 0, 0, 3, 0, 0, 2, 'First12', NULL, false, '', NULL, 62, '', 0, false, 'Player12', 12.0, false, NULL, 'a', 2, 8, 24, 0, 0, 0, 0, 0, 0, 'Player12', NULL, NULL, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, NULL, '', NULL, '', NULL, '', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0),
+    (13, true, true, NULL, NULL, 100013, 0, 0, 0, 0, 0, 4, 0, 0, 3, 'First13', NULL, false, '', NULL, 63, '', 0, false, 'Player13', 13.0, false, NULL, 'a', 1, 3, 26, 0, 0, 0, 0, 0, 0, 'Player13', NULL, NULL, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, NULL, '', NULL, '', NULL, '', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0),
+    (14, true, true, NULL, NULL, 100014, 0, 0, 0, 0, 0, 4, 0, 0, 4, 'First14', NULL, false, '', NULL, 64, '', 0, false, 'Player14', 14.0, false, NULL, 'a', 2, 8, 28, 0, 0, 0, 0, 0, 0, 'Player14', NULL, NULL, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, NULL, '', NULL, '', NULL, '', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0),
+    (15, true, true, NULL, NULL, 100015, 0, 0, 0, 0, 0, 4, 0, 0, 0, 'First15', NULL, false, '', NULL, 65, '', 0, false, 'Player15', 15.0, false, NULL, 'a', 1, 3, 30, 0, 0, 0, 0, 0, 0, 'Player15', NULL, NULL, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, NULL, '', NULL, '', NULL, '', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0),
+    (16, true, true, NULL, NULL, 100016, 0, 0, 0, 0, 0, 4, 0, 0, 1, 'First16', NULL, false, '', NULL, 66, '', 0, false, 'Player16', 16.0, false, NULL, 'a', 2, 8, 32, 0, 0, 0, 0, 0, 0, 'Player16', NULL, NULL, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, NULL, '', NULL, '', NULL, '', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0);
+
+INSERT INTO teams (id, joined_time, started_event, favourite_team, player_first_name, player_last_name, player_region_id, player_region_name, player_region_iso_code_short, player_region_iso_code_long, summary_overall_points, summary_overall_rank, summary_event_points, summary_event_rank, current_event, name, name_change_blocked, last_deadline_bank, last_deadline_value, last_deadline_total_transfers, created_at, updated_at)
+VALUES
+    (101, '2024-08-01T00:00:00Z', 1, NULL, 'Alice', 'Smith', 0, '', '', '', 150, 1000, 60, NULL, 2, 'Alice FC', false, 0, 0, 0, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (102, '2024-08-01T00:00:00Z', 1, NULL, 'Bob', 'Jones', 0, '', '', '', 140, 2000, 55, NULL, 2, 'Bob United', false, 0, 0, 0, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z');
+
+INSERT INTO discord_users (discord_id, team_id)
+VALUES
+    (1001, 101),
+    (1002, 102);
+
+INSERT INTO mini_leagues (id, last_updated_data, name, created, closed, max_entries, league_type, scoring, admin_entry, start_event, code_privacy, has_cup, cup_league, rank)
+VALUES
+    (500, '2024-08-24T12:00:00Z', 'Test League', '2024-07-01T00:00:00Z', false, NULL, 'x', 'c', 101, 1, 'p', false, NULL, NULL);
+
+INSERT INTO mini_league_standings (id, event_total, player_name, rank, last_rank, rank_sort, total, team_id, entry_name, has_player, league_id)
+VALUES
+    (1, 60, 'Alice Smith', 1, 2, 1, 150, 101, 'Alice FC', true, 500),
+    (2, 55, 'Bob Jones', 2, 1, 2, 140, 102, 'Bob United', true, 500);
+
+INSERT INTO team_game_weeks (team_id, game_week_id, active_chip, points, total_points, rank, rank_sort, overall_rank, percentile_rank, bank, value, event_transfers, event_transfers_cost, points_on_bench)
+VALUES
+    (101, 1, NULL, 90, 90, 5000, NULL, 5000, NULL, 5, 1000, 0, 0, 0),
+    (102, 1, 'bboost', 81, 81, 6000, NULL, 6000, NULL, 0, 1000, 1, 4, 0),
+    (101, 2, '3xc', 60, 150, NULL, NULL, 1000, NULL, 0, 1002, 1, 0, 0),
+    (102, 2, NULL, 55, 140, NULL, NULL, 2000, NULL, 0, 1001, 0, 0, 0);
+
+INSERT INTO team_game_week_picks (team_id, game_week_id, player_id, position, multiplier, is_captain, is_vice_captain, element_type)
+VALUES
+    (101, 1, 1, 1, 1, false, false, 'Goalkeeper'),
+    (101, 1, 3, 2, 1, false, false, 'Defender'),
+    (101, 1, 4, 3, 1, false, false, 'Defender'),
+    (101, 1, 5, 4, 1, false, false, 'Defender'),
+    (101, 1, 6, 5, 1, false, false, 'Defender'),
+    (101, 1, 8, 6, 1, false, true, 'Midfielder'),
+    (101, 1, 9, 7, 1, false, false, 'Midfielder'),
+    (101, 1, 10, 8, 1, false, false, 'Midfielder'),
+    (101, 1, 11, 9, 1, false, false, 'Midfielder'),
+    (101, 1, 13, 10, 2, true, false, 'Attacker'),
+    (101, 1, 14, 11, 1, false, false, 'Attacker'),
+    (101, 1, 2, 12, 0, false, false, 'Goalkeeper'),
+    (101, 1, 7, 13, 0, false, false, 'Defender'),
+    (101, 1, 12, 14, 0, false, false, 'Midfielder'),
+    (101, 1, 15, 15, 0, false, false, 'Attacker'),
+    (102, 1, 1, 1, 1, false, false, 'Goalkeeper'),
+    (102, 1, 3, 2, 1, false, false, 'Defender'),
+    (102, 1, 4, 3, 1, false, false, 'Defender'),
+    (102, 1, 5, 4, 1, false, false, 'Defender'),
+    (102, 1, 6, 5, 1, false, false, 'Defender'),
+    (102, 1, 8, 6, 1, false, true, 'Midfielder'),
+    (102, 1, 9, 7, 1, false, false, 'Midfielder'),
+    (102, 1, 10, 8, 1, false, false, 'Midfielder'),
+    (102, 1, 11, 9, 1, false, false, 'Midfielder'),
+    (102, 1, 13, 10, 2, true, false, 'Attacker'),
+    (102, 1, 14, 11, 1, false, false, 'Attacker'),
+    (102, 1, 2, 12, 0, false, false, 'Goalkeeper'),
+    (102, 1, 7, 13, 0, false, false, 'Defender'),
+    (102, 1, 12, 14, 0, false, false, 'Midfielder'),
+    (102, 1, 15, 15, 0, false, false, 'Attacker'),
+    (101, 2, 1, 1, 1, false, false, 'Goalkeeper'),
+    (101, 2, 3, 2, 1, false, false, 'Defender'),
+    (101, 2, 4, 3, 1, false, false, 'Defender'),
+    (101, 2, 5, 4, 1, false, false, 'Defender'),
+    (101, 2, 6, 5, 1, false, false, 'Defender'),
+    (101, 2, 8, 6, 1, false, true, 'Midfielder'),
+    (101, 2, 9, 7, 1, false, false, 'Midfielder'),
+    (101, 2, 10, 8, 1, false, false, 'Midfielder'),
+    (101, 2, 11, 9, 1, false, false, 'Midfielder'),
+    (101, 2, 13, 10, 2, true, false, 'Attacker'),
+    (101, 2, 14, 11, 1, false, false, 'Attacker'),
+    (101, 2, 2, 12, 0, false, false, 'Goalkeeper'),
+    (101, 2, 7, 13, 0, false, false, 'Defender'),
+    (101, 2, 12, 14, 0, false, false, 'Midfielder'),
+    (101, 2, 16, 15, 0, false, false, 'Attacker'),
+    (102, 2, 1, 1, 1, false, false, 'Goalkeeper'),
+    (102, 2, 3, 2, 1, false, false, 'Defender'),
+    (102, 2, 4, 3, 1, false, false, 'Defender'),
+    (102, 2, 5, 4, 1, false, false, 'Defender'),
+    (102, 2, 6, 5, 1, false, false, 'Defender'),
+    (102, 2, 8, 6, 1, false, true, 'Midfielder'),
+    (102, 2, 9, 7, 1, false, false, 'Midfielder'),
+    (102, 2, 10, 8, 1, false, false, 'Midfielder'),
+    (102, 2, 11, 9, 1, false, false, 'Midfielder'),
+    (102, 2, 13, 10, 2, true, false, 'Attacker'),
+    (102, 2, 14, 11, 1, false, false, 'Attacker'),
+    (102, 2, 2, 12, 0, false, false, 'Goalkeeper'),
+    (102, 2, 7, 13, 0, false, false, 'Defender'),
+    (102, 2, 12, 14, 0, false, false, 'Midfielder'),
+    (102, 2, 15, 15, 0, false, false, 'Attacker');
+
+INSERT INTO game_week_players (player_id, game_week_id, minutes, goals_scored, assists, clean_sheets, goals_conceded, own_goals, penalties_saved, penalties_missed, yellow_cards, red_cards, saves, bonus, bps, influence, creativity, threat, ict_index, starts, expected_goals, expected_assists, expected_goal_involvements, expected_goals_conceded, total_points, in_dreamteam, created_at, updated_at)
+VALUES
+    (1, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 1, 0, 0, 0, 0, 2, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (2, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 0, 1, 0, 0, 0, 0, 3, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (3, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 6, 0, 0, 0, 0, 1, 0, 0, 0, 0, 4, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (4, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8, 0, 0, 0, 0, 1, 0, 0, 0, 0, 5, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (5, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 10, 0, 0, 0, 0, 1, 0, 0, 0, 0, 6, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (6, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 12, 0, 0, 0, 0, 1, 0, 0, 0, 0, 7, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (7, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 14, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (8, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16, 0, 0, 0, 0, 1, 0, 0, 0, 0, 2, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (9, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 18, 0, 0, 0, 0, 1, 0, 0, 0, 0, 3, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (10, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 20, 0, 0, 0, 0, 1, 0, 0, 0, 0, 4, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (11, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 22, 0, 0, 0, 0, 1, 0, 0, 0, 0, 5, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (12, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 24, 0, 0, 0, 0, 1, 0, 0, 0, 0, 6, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (13, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 26, 0, 0, 0, 0, 1, 0, 0, 0, 0, 7, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (14, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 28, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (15, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 30, 0, 0, 0, 0, 1, 0, 0, 0, 0, 2, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (16, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 32, 0, 0, 0, 0, 1, 0, 0, 0, 0, 3, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (1, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 1, 0, 0, 0, 0, 2, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (2, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 0, 1, 0, 0, 0, 0, 3, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (3, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 6, 0, 0, 0, 0, 1, 0, 0, 0, 0, 4, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (4, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8, 0, 0, 0, 0, 1, 0, 0, 0, 0, 5, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (5, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 10, 0, 0, 0, 0, 1, 0, 0, 0, 0, 6, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (6, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 12, 0, 0, 0, 0, 1, 0, 0, 0, 0, 7, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (7, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 14, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (8, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16, 0, 0, 0, 0, 1, 0, 0, 0, 0, 2, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (9, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 18, 0, 0, 0, 0, 1, 0, 0, 0, 0, 3, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (10, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 20, 0, 0, 0, 0, 1, 0, 0, 0, 0, 4, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (11, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 22, 0, 0, 0, 0, 1, 0, 0, 0, 0, 5, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (12, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 24, 0, 0, 0, 0, 1, 0, 0, 0, 0, 6, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (13, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 26, 0, 0, 0, 0, 1, 0, 0, 0, 0, 7, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (14, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 28, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (15, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 30, 0, 0, 0, 0, 1, 0, 0, 0, 0, 2, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z'),
+    (16, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 32, 0, 0, 0, 0, 1, 0, 0, 0, 0, 3, false, '2024-08-01T00:00:00Z', '2024-08-01T00:00:00Z');
+
+INSERT INTO player_history (player_id, fixture_id, opponent_team, total_points, was_home, kickoff_time, team_h_score, team_a_score, round, minutes, goals_scored, assists, clean_sheets, goals_conceded, own_goals, penalties_saved, penalties_missed, yellow_cards, red_cards, saves, bonus, bps, influence, creativity, threat, ict_index, starts, expected_goals, expected_assists, expected_goal_involvements, expected_goals_conceded, value, transfers_balance, selected, transfers_in, transfers_out)
+VALUES
+    (1, 1, 2, 2, true, '2024-08-17T14:00:00Z', NULL, NULL, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 51, 0, 0, 0, 0),
+    (1, 2, 2, 2, false, '2024-08-24T14:00:00Z', NULL, NULL, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 51, 0, 0, 0, 0),
+    (2, 1, 1, 3, false, '2024-08-17T14:00:00Z', NULL, NULL, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 52, 0, 0, 0, 0),
+    (2, 2, 1, 3, true, '2024-08-24T14:00:00Z', NULL, NULL, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 52, 0, 0, 0, 0),
+    (3, 1, 2, 4, true, '2024-08-17T14:00:00Z', NULL, NULL, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 53, 0, 0, 0, 0),
+    (3, 2, 2, 4, false, '2024-08-24T14:00:00Z', NULL, NULL, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 53, 0, 0, 0, 0),
+    (4, 1, 1, 5, false, '2024-08-17T14:00:00Z', NULL, NULL, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 54, 0, 0, 0, 0),
+    (4, 2, 1, 5, true, '2024-08-24T14:00:00Z', NULL, NULL, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 54, 0, 0, 0, 0),
+    (5, 1, 2, 6, true, '2024-08-17T14:00:00Z', NULL, NULL, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 55, 0, 0, 0, 0),
+    (5, 2, 2, 6, false, '2024-08-24T14:00:00Z', NULL, NULL, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 55, 0, 0, 0, 0),
+    (6, 1, 1, 7, false, '2024-08-17T14:00:00Z', NULL, NULL, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 56, 0, 0, 0, 0),
+    (6, 2, 1, 7, true, '2024-08-24T14:00:00Z', NULL, NULL, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 56, 0, 0, 0, 0),
+    (7, 1, 2, 1, true, '2024-08-17T14:00:00Z', NULL, NULL, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 57, 0, 0, 0, 0),
+    (7, 2, 2, 1, false, '2024-08-24T14:00:00Z', NULL, NULL, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 57, 0, 0, 0, 0),
+    (8, 1, 1, 2, false, '2024-08-17T14:00:00Z', NULL, NULL, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 58, 0, 0, 0, 0),
+    (8, 2, 1, 2, true, '2024-08-24T14:00:00Z', NULL, NULL, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 58, 0, 0, 0, 0),
+    (9, 1, 2, 3, true, '2024-08-17T14:00:00Z', NULL, NULL, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 59, 0, 0, 0, 0),
+    (9, 2, 2, 3, false, '2024-08-24T14:00:00Z', NULL, NULL, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 59, 0, 0, 0, 0),
+    (10, 1, 1, 4, false, '2024-08-17T14:00:00Z', NULL, NULL, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 60, 0, 0, 0, 0),
+    (10, 2, 1, 4, true, '2024-08-24T14:00:00Z', NULL, NULL, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 60, 0, 0, 0, 0),
+    (11, 1, 2, 5, true, '2024-08-17T14:00:00Z', NULL, NULL, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 61, 0, 0, 0, 0),
+    (11, 2, 2, 5, false, '2024-08-24T14:00:00Z', NULL, NULL, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 61, 0, 0, 0, 0),
+    (12, 1, 1, 6, false, '2024-08-17T14:00:00Z', NULL, NULL, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 62, 0, 0, 0, 0),
+    (12, 2, 1, 6, true, '2024-08-24T14:00:00Z', NULL, NULL, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 62, 0, 0, 0, 0),
+    (13, 1, 2, 7, true, '2024-08-17T14:00:00Z', NULL, NULL, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 63, 0, 0, 0, 0),
+    (13, 2, 2, 7, false, '2024-08-24T14:00:00Z', NULL, NULL, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 63, 0, 0, 0, 0),
+    (14, 1, 1, 1, false, '2024-08-17T14:00:00Z', NULL, NULL, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0),
+    (14, 2, 1, 1, true, '2024-08-24T14:00:00Z', NULL, NULL, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0),
+    (15, 1, 2, 2, true, '2024-08-17T14:00:00Z', NULL, NULL, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 65, 0, 0, 0, 0),
+    (15, 2, 2, 2, false, '2024-08-24T14:00:00Z', NULL, NULL, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 65, 0, 0, 0, 0),
+    (16, 1, 1, 3, false, '2024-08-17T14:00:00Z', NULL, NULL, 1, 90, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 66, 0, 0, 0, 0),
+    (16, 2, 1, 3, true, '2024-08-24T14:00:00Z', NULL, NULL, 2, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 66, 0, 0, 0, 0);
+
+INSERT INTO transfers (player_in_id, player_out_id, team_id, game_week_id, player_in_cost, player_out_cost, transfer_time)
+VALUES
+    (16, 15, 101, 2, 66, 65, '2024-08-23T18:00:00Z');
+
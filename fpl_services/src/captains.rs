@@ -0,0 +1,55 @@
+use fpl_common::types::{GameWeekId, LeagueId};
+use sqlx::PgPool;
+
+use crate::ServiceError;
+
+/// Who each manager in the league captained in the game week, ordered by player.
+pub async fn get_captains(
+    pool: &PgPool,
+    league_id: LeagueId,
+    game_week_id: GameWeekId,
+) -> Result<Vec<String>, ServiceError> {
+    let captains = sqlx::query!(
+        r#"
+        SELECT
+            mls.player_name,
+            p.web_name
+        FROM mini_league_standings mls
+        JOIN mini_leagues ml ON mls.league_id = ml.id
+        JOIN team_game_week_picks tgwp ON tgwp.team_id = mls.team_id
+        JOIN players p ON p.id = tgwp.player_id
+        WHERE tgwp.game_week_id = $1
+        AND ml.id = $2
+        AND tgwp.is_captain = true
+        ORDER BY p.id ASC
+        "#,
+        i16::from(game_week_id),
+        i32::from(league_id)
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(captains
+        .into_iter()
+        .map(|row| format!("**{}** captained **{}**", row.player_name, row.web_name))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../fixtures/seed.sql"))]
+    async fn test_captains(pool: PgPool) {
+        let rows = get_captains(&pool, LeagueId::new(500), GameWeekId::new(2).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                "**Alice Smith** captained **Player13**",
+                "**Bob Jones** captained **Player13**",
+            ]
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use fpl_common::types::{Chip, LeagueId, TeamId};
+use fpl_db::queries::team_game_week;
+use sqlx::PgPool;
+
+use crate::ServiceError;
+
+/// One row per manager in the league who has played a chip, listing each chip and when.
+pub async fn get_league_chips(
+    pool: &PgPool,
+    league_id: LeagueId,
+) -> Result<Vec<String>, ServiceError> {
+    let rows = sqlx::query!(
+        "
+        SELECT tgw.team_id, mls.player_name, mls.entry_name, tgw.active_chip, tgw.game_week_id FROM team_game_weeks tgw
+        JOIN mini_league_standings mls ON mls.team_id = tgw.team_id
+        WHERE tgw.active_chip IS NOT NULL AND mls.league_id = $1;
+        ",
+        i32::from(league_id)
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // Group by team
+    type TeamRows = Vec<(String, String, String, i16)>;
+    let mut grouped: BTreeMap<TeamId, TeamRows> = BTreeMap::new();
+    for row in rows {
+        grouped.entry(TeamId::from(row.team_id)).or_default().push((
+            row.player_name,
+            row.entry_name,
+            row.active_chip.unwrap_or_default(),
+            row.game_week_id,
+        ));
+    }
+
+    Ok(grouped
+        .into_values()
+        .map(|mut team_rows| {
+            team_rows.sort_by_key(|row| row.3);
+
+            let chips = team_rows
+                .iter()
+                .map(|(_, _, chip, game_week)| match Chip::from_str(chip) {
+                    Ok(chip) => format!("**GW{}** {}", game_week, chip.pretty_name()),
+                    // Fallback to raw string if parsing fails
+                    Err(_) => format!("**GW{}** {}", game_week, chip),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            let (player_name, entry_name, _, _) = &team_rows[0];
+            format!("**{}** ({})\n- {}", player_name, entry_name, chips)
+        })
+        .collect())
+}
+
+/// One row per chip the team has played, in game week order.
+pub async fn get_team_chips(pool: &PgPool, team_id: TeamId) -> Result<Vec<String>, ServiceError> {
+    team_game_week::get_team_chips(pool, team_id)
+        .await?
+        .into_iter()
+        .map(|tgw| {
+            let chip = tgw.active_chip.unwrap_or_default();
+            Chip::from_str(&chip)
+                .map(|chip| format!("**GW{}**: {}", tgw.game_week_id, chip.pretty_name()))
+                .map_err(|e| ServiceError::Invalid(e.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../fixtures/seed.sql"))]
+    async fn test_league_chips(pool: PgPool) {
+        let rows = get_league_chips(&pool, LeagueId::new(500)).await.unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                "**Alice Smith** (Alice FC)\n- **GW2** Triple Captain",
+                "**Bob Jones** (Bob United)\n- **GW1** Bench Boost",
+            ]
+        );
+    }
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../fixtures/seed.sql"))]
+    async fn test_team_chips(pool: PgPool) {
+        let rows = get_team_chips(&pool, TeamId::new(102)).await.unwrap();
+        assert_eq!(rows, vec!["**GW1**: Bench Boost"]);
+    }
+}
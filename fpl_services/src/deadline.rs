@@ -0,0 +1,74 @@
+use chrono::Datelike;
+use fpl_common::types::GameWeekId;
+use fpl_db::queries::game_week::get_current_game_week;
+use ordinal::Ordinal;
+use sqlx::types::chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::ServiceError;
+
+/// Every game week's deadline, starting from `game_week_id`. Without one it starts from the
+/// next game week, or the current one if there's no next (e.g. GW38).
+pub async fn get_deadlines(
+    pool: &PgPool,
+    game_week_id: Option<GameWeekId>,
+) -> Result<Vec<String>, ServiceError> {
+    let game_week_deadlines = sqlx::query!(
+        r#"
+        SELECT
+            name, deadline_time
+        FROM
+            game_weeks
+        ORDER BY id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| (row.name, row.deadline_time))
+    .collect::<Vec<(String, DateTime<Utc>)>>();
+
+    let mut deadline_rows = game_week_deadlines
+        .into_iter()
+        .map(|(name, deadline_time)| {
+            let day = deadline_time.day();
+            format!(
+                "**{}**: {}",
+                name,
+                deadline_time.format(&format!("%B {}, %l:%M %p, %Y", Ordinal(day)))
+            )
+        })
+        .collect::<Vec<String>>();
+
+    let start = match game_week_id {
+        Some(gw) => gw,
+        None => {
+            let current_gw = get_current_game_week(pool).await?;
+            current_gw.id.next().unwrap_or(current_gw.id)
+        }
+    };
+    // Game weeks the scraper hasn't stored yet leave nothing to rotate to
+    let index = (start.0 - 1) as usize;
+    if index < deadline_rows.len() {
+        deadline_rows.rotate_left(index);
+    }
+
+    Ok(deadline_rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../fixtures/seed.sql"))]
+    async fn test_deadlines_start_from_next_game_week(pool: PgPool) {
+        let rows = get_deadlines(&pool, None).await.unwrap();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[0].starts_with("**Gameweek 3**"));
+
+        let rows = get_deadlines(&pool, Some(GameWeekId::new(1).unwrap()))
+            .await
+            .unwrap();
+        assert!(rows[0].starts_with("**Gameweek 1**"));
+    }
+}
@@ -0,0 +1,22 @@
+#[derive(Debug, thiserror::Error)]
+pub enum ServiceError {
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    Invalid(String),
+    #[error("Couldn't write export: {0}")]
+    Export(String),
+    #[error(transparent)]
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for ServiceError {
+    fn from(e: sqlx::Error) -> Self {
+        // fetch_one on a missing team or game week is the caller asking for something that
+        // isn't there, not the database falling over
+        match e {
+            sqlx::Error::RowNotFound => Self::NotFound("Nothing found".to_string()),
+            e => Self::Database(e),
+        }
+    }
+}
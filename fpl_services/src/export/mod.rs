@@ -0,0 +1,433 @@
+//! Raw league history as files. Each dataset is queried into a `Table` of typed columns, then
+//! written out as CSV, JSON or Parquet, so every dataset works with every format.
+mod queries;
+mod writers;
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use fpl_common::types::{GameWeekId, LeagueId, TeamId};
+use sqlx::PgPool;
+
+use crate::ServiceError;
+
+pub use writers::{write_csv, write_json, write_parquet};
+
+const FIRST_GAME_WEEK: i16 = 1;
+const LAST_GAME_WEEK: i16 = 38;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportDataset {
+    /// The league table after each game week
+    Standings,
+    /// Every pick from `team_game_week_picks`
+    Picks,
+    Transfers,
+    /// Game weeks a chip was played in
+    Chips,
+    /// `game_week_players` stats
+    PlayerStats,
+}
+
+impl ExportDataset {
+    pub const ALL: [Self; 5] = [
+        Self::Standings,
+        Self::Picks,
+        Self::Transfers,
+        Self::Chips,
+        Self::PlayerStats,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Standings => "standings",
+            Self::Picks => "picks",
+            Self::Transfers => "transfers",
+            Self::Chips => "chips",
+            Self::PlayerStats => "player_stats",
+        }
+    }
+}
+
+impl fmt::Display for ExportDataset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ExportDataset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|dataset| dataset.as_str() == s)
+            .ok_or_else(|| format!("Unknown dataset {s}"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Parquet,
+}
+
+impl ExportFormat {
+    pub const ALL: [Self; 3] = [Self::Csv, Self::Json, Self::Parquet];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+            Self::Parquet => "parquet",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        self.as_str()
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::Json => "application/json",
+            Self::Parquet => "application/vnd.apache.parquet",
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.as_str() == s)
+            .ok_or_else(|| format!("Unknown format {s}"))
+    }
+}
+
+/// Which rows to export. Everything is optional apart from standings, which need a league to
+/// rank teams in.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExportFilter {
+    pub league_id: Option<LeagueId>,
+    pub team_id: Option<TeamId>,
+    pub from_game_week: Option<GameWeekId>,
+    pub to_game_week: Option<GameWeekId>,
+}
+
+impl ExportFilter {
+    /// The inclusive game week range, defaulting to the whole season.
+    fn game_weeks(&self) -> Result<(i16, i16), ServiceError> {
+        let from = self.from_game_week.map_or(FIRST_GAME_WEEK, i16::from);
+        let to = self.to_game_week.map_or(LAST_GAME_WEEK, i16::from);
+        if from > to {
+            return Err(ServiceError::Invalid(format!(
+                "Gameweek {from} is after gameweek {to}"
+            )));
+        }
+        Ok((from, to))
+    }
+
+    fn league_id(&self) -> Option<i32> {
+        self.league_id.map(i32::from)
+    }
+
+    fn team_id(&self) -> Option<i32> {
+        self.team_id.map(i32::from)
+    }
+
+    /// e.g. `picks_league_500_gw1-38`
+    fn file_stem(&self, dataset: ExportDataset) -> Result<String, ServiceError> {
+        let (from, to) = self.game_weeks()?;
+        let mut stem = dataset.to_string();
+        if let Some(league_id) = self.league_id {
+            stem.push_str(&format!("_league_{league_id}"));
+        }
+        if let Some(team_id) = self.team_id {
+            stem.push_str(&format!("_team_{team_id}"));
+        }
+        stem.push_str(&format!("_gw{from}-{to}"));
+        Ok(stem)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Int,
+    Float,
+    Text,
+    Bool,
+    Timestamp,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub name: &'static str,
+    pub column_type: ColumnType,
+}
+
+impl Column {
+    pub const fn new(name: &'static str, column_type: ColumnType) -> Self {
+        Self { name, column_type }
+    }
+}
+
+/// A single cell, `Null` is allowed in any column.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Bool(bool),
+    Timestamp(DateTime<Utc>),
+}
+
+impl From<i16> for Value {
+    fn from(value: i16) -> Self {
+        Self::Int(value.into())
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Self::Int(value.into())
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<f32> for Value {
+    fn from(value: f32) -> Self {
+        Self::Float(value.into())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<DateTime<Utc>> for Value {
+    fn from(value: DateTime<Utc>) -> Self {
+        Self::Timestamp(value)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+/// Rows in column order, every row is as long as `columns`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub columns: Vec<Column>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    pub fn new(columns: &[Column]) -> Self {
+        Self {
+            columns: columns.to_vec(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, row: Vec<Value>) {
+        debug_assert_eq!(row.len(), self.columns.len());
+        self.rows.push(row);
+    }
+}
+
+/// A written export, ready to be saved or attached.
+#[derive(Debug, Clone)]
+pub struct Export {
+    pub file_name: String,
+    pub bytes: Vec<u8>,
+    pub rows: usize,
+}
+
+/// Queries the dataset and writes it out in the given format.
+pub async fn export(
+    pool: &PgPool,
+    dataset: ExportDataset,
+    filter: &ExportFilter,
+    format: ExportFormat,
+) -> Result<Export, ServiceError> {
+    let table = get_table(pool, dataset, filter).await?;
+    let bytes = match format {
+        ExportFormat::Csv => write_csv(&table)?,
+        ExportFormat::Json => write_json(&table)?,
+        ExportFormat::Parquet => write_parquet(&table)?,
+    };
+
+    Ok(Export {
+        file_name: format!("{}.{}", filter.file_stem(dataset)?, format.extension()),
+        bytes,
+        rows: table.rows.len(),
+    })
+}
+
+/// The dataset as a `Table`, before it's written in any format.
+pub async fn get_table(
+    pool: &PgPool,
+    dataset: ExportDataset,
+    filter: &ExportFilter,
+) -> Result<Table, ServiceError> {
+    match dataset {
+        ExportDataset::Standings => queries::get_standings(pool, filter).await,
+        ExportDataset::Picks => queries::get_picks(pool, filter).await,
+        ExportDataset::Transfers => queries::get_transfers(pool, filter).await,
+        ExportDataset::Chips => queries::get_chips(pool, filter).await,
+        ExportDataset::PlayerStats => queries::get_player_stats(pool, filter).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(league_id: Option<i32>, team_id: Option<i32>) -> ExportFilter {
+        ExportFilter {
+            league_id: league_id.map(LeagueId::new),
+            team_id: team_id.map(TeamId::new),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_names() {
+        for dataset in ExportDataset::ALL {
+            assert_eq!(dataset.as_str().parse::<ExportDataset>(), Ok(dataset));
+        }
+        for format in ExportFormat::ALL {
+            assert_eq!(format.as_str().parse::<ExportFormat>(), Ok(format));
+        }
+        assert!("xlsx".parse::<ExportFormat>().is_err());
+    }
+
+    #[test]
+    fn test_game_weeks() {
+        assert_eq!(ExportFilter::default().game_weeks().unwrap(), (1, 38));
+
+        let filter = ExportFilter {
+            from_game_week: Some(GameWeekId::new(5).unwrap()),
+            to_game_week: Some(GameWeekId::new(3).unwrap()),
+            ..Default::default()
+        };
+        assert!(matches!(filter.game_weeks(), Err(ServiceError::Invalid(_))));
+    }
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../../fixtures/seed.sql"))]
+    async fn test_standings(pool: PgPool) {
+        let table = get_table(&pool, ExportDataset::Standings, &filter(Some(500), None))
+            .await
+            .unwrap();
+        // two game weeks of two teams
+        assert_eq!(table.rows.len(), 4);
+        assert_eq!(
+            table.rows[0][..4],
+            [
+                Value::Int(1),
+                Value::Int(1),
+                Value::Int(101),
+                Value::Text("Alice FC".to_string())
+            ]
+        );
+
+        let missing_league = get_table(&pool, ExportDataset::Standings, &filter(None, None)).await;
+        assert!(matches!(missing_league, Err(ServiceError::Invalid(_))));
+    }
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../../fixtures/seed.sql"))]
+    async fn test_picks(pool: PgPool) {
+        let league = get_table(&pool, ExportDataset::Picks, &filter(Some(500), None))
+            .await
+            .unwrap();
+        assert_eq!(league.rows.len(), 60);
+
+        let team = ExportFilter {
+            team_id: Some(TeamId::new(101)),
+            from_game_week: Some(GameWeekId::new(2).unwrap()),
+            to_game_week: Some(GameWeekId::new(2).unwrap()),
+            ..Default::default()
+        };
+        let table = get_table(&pool, ExportDataset::Picks, &team).await.unwrap();
+        assert_eq!(table.rows.len(), 15);
+        assert!(table.rows.iter().all(|row| row[0] == Value::Int(2)));
+    }
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../../fixtures/seed.sql"))]
+    async fn test_transfers_and_chips(pool: PgPool) {
+        let transfers = get_table(&pool, ExportDataset::Transfers, &filter(Some(500), None))
+            .await
+            .unwrap();
+        assert_eq!(transfers.rows.len(), 1);
+        assert!(transfers.rows[0].contains(&Value::Text("Player16".to_string())));
+
+        let chips = get_table(&pool, ExportDataset::Chips, &filter(None, Some(102)))
+            .await
+            .unwrap();
+        assert_eq!(chips.rows.len(), 1);
+        assert!(chips.rows[0].contains(&Value::Text("bboost".to_string())));
+    }
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../../fixtures/seed.sql"))]
+    async fn test_player_stats(pool: PgPool) {
+        let everyone = get_table(&pool, ExportDataset::PlayerStats, &ExportFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(everyone.rows.len(), 32);
+
+        // only the players the team picked
+        let team = ExportFilter {
+            team_id: Some(TeamId::new(101)),
+            to_game_week: Some(GameWeekId::new(1).unwrap()),
+            ..Default::default()
+        };
+        let table = get_table(&pool, ExportDataset::PlayerStats, &team)
+            .await
+            .unwrap();
+        assert_eq!(table.rows.len(), 15);
+    }
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../../fixtures/seed.sql"))]
+    async fn test_export(pool: PgPool) {
+        let export = export(
+            &pool,
+            ExportDataset::Chips,
+            &filter(Some(500), None),
+            ExportFormat::Csv,
+        )
+        .await
+        .unwrap();
+        assert_eq!(export.file_name, "chips_league_500_gw1-38.csv");
+        assert_eq!(export.rows, 2);
+        assert!(String::from_utf8(export.bytes)
+            .unwrap()
+            .starts_with("game_week_id,team_id,"));
+    }
+}
@@ -0,0 +1,338 @@
+//! One query per dataset. The league filter keeps teams in the league's standings, the team
+//! filter a single team, and both can be combined.
+use sqlx::PgPool;
+
+use super::{Column, ColumnType, ExportFilter, Table};
+use crate::ServiceError;
+
+const STANDINGS_COLUMNS: [Column; 10] = [
+    Column::new("game_week_id", ColumnType::Int),
+    Column::new("rank", ColumnType::Int),
+    Column::new("team_id", ColumnType::Int),
+    Column::new("team_name", ColumnType::Text),
+    Column::new("player_name", ColumnType::Text),
+    Column::new("points", ColumnType::Int),
+    Column::new("total_points", ColumnType::Int),
+    Column::new("transfers_cost", ColumnType::Int),
+    Column::new("points_on_bench", ColumnType::Int),
+    Column::new("overall_rank", ColumnType::Int),
+];
+
+const PICKS_COLUMNS: [Column; 11] = [
+    Column::new("game_week_id", ColumnType::Int),
+    Column::new("team_id", ColumnType::Int),
+    Column::new("team_name", ColumnType::Text),
+    Column::new("player_id", ColumnType::Int),
+    Column::new("player_name", ColumnType::Text),
+    Column::new("position", ColumnType::Int),
+    Column::new("element_type", ColumnType::Text),
+    Column::new("multiplier", ColumnType::Int),
+    Column::new("is_captain", ColumnType::Bool),
+    Column::new("is_vice_captain", ColumnType::Bool),
+    Column::new("player_points", ColumnType::Int),
+];
+
+const TRANSFERS_COLUMNS: [Column; 10] = [
+    Column::new("game_week_id", ColumnType::Int),
+    Column::new("team_id", ColumnType::Int),
+    Column::new("team_name", ColumnType::Text),
+    Column::new("transfer_time", ColumnType::Timestamp),
+    Column::new("player_in_id", ColumnType::Int),
+    Column::new("player_in_name", ColumnType::Text),
+    Column::new("player_in_cost", ColumnType::Int),
+    Column::new("player_out_id", ColumnType::Int),
+    Column::new("player_out_name", ColumnType::Text),
+    Column::new("player_out_cost", ColumnType::Int),
+];
+
+const CHIPS_COLUMNS: [Column; 5] = [
+    Column::new("game_week_id", ColumnType::Int),
+    Column::new("team_id", ColumnType::Int),
+    Column::new("team_name", ColumnType::Text),
+    Column::new("chip", ColumnType::Text),
+    Column::new("points", ColumnType::Int),
+];
+
+const PLAYER_STATS_COLUMNS: [Column; 27] = [
+    Column::new("game_week_id", ColumnType::Int),
+    Column::new("player_id", ColumnType::Int),
+    Column::new("player_name", ColumnType::Text),
+    Column::new("total_points", ColumnType::Int),
+    Column::new("minutes", ColumnType::Int),
+    Column::new("starts", ColumnType::Int),
+    Column::new("goals_scored", ColumnType::Int),
+    Column::new("assists", ColumnType::Int),
+    Column::new("clean_sheets", ColumnType::Int),
+    Column::new("goals_conceded", ColumnType::Int),
+    Column::new("own_goals", ColumnType::Int),
+    Column::new("penalties_saved", ColumnType::Int),
+    Column::new("penalties_missed", ColumnType::Int),
+    Column::new("yellow_cards", ColumnType::Int),
+    Column::new("red_cards", ColumnType::Int),
+    Column::new("saves", ColumnType::Int),
+    Column::new("bonus", ColumnType::Int),
+    Column::new("bps", ColumnType::Int),
+    Column::new("influence", ColumnType::Float),
+    Column::new("creativity", ColumnType::Float),
+    Column::new("threat", ColumnType::Float),
+    Column::new("ict_index", ColumnType::Float),
+    Column::new("expected_goals", ColumnType::Float),
+    Column::new("expected_assists", ColumnType::Float),
+    Column::new("expected_goal_involvements", ColumnType::Float),
+    Column::new("expected_goals_conceded", ColumnType::Float),
+    Column::new("in_dreamteam", ColumnType::Bool),
+];
+
+/// The league table as it stood after each game week, ranked on total points.
+pub async fn get_standings(pool: &PgPool, filter: &ExportFilter) -> Result<Table, ServiceError> {
+    let league_id = filter
+        .league_id()
+        .ok_or_else(|| ServiceError::Invalid("Standings need a league".to_string()))?;
+    let (from, to) = filter.game_weeks()?;
+
+    // Ranked before the team filter so a single team keeps its place in the league
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            ranked.game_week_id as "game_week_id!",
+            ranked.rank as "rank!",
+            ranked.team_id as "team_id!",
+            ranked.entry_name as "entry_name!",
+            ranked.player_name as "player_name!",
+            ranked.points as "points!",
+            ranked.total_points as "total_points!",
+            ranked.event_transfers_cost as "event_transfers_cost!",
+            ranked.points_on_bench as "points_on_bench!",
+            ranked.overall_rank as "overall_rank!"
+        FROM (
+            SELECT
+                tgw.game_week_id,
+                RANK() OVER (PARTITION BY tgw.game_week_id ORDER BY tgw.total_points DESC) as rank,
+                tgw.team_id, mls.entry_name, mls.player_name, tgw.points, tgw.total_points,
+                tgw.event_transfers_cost, tgw.points_on_bench, tgw.overall_rank
+            FROM team_game_weeks tgw
+            JOIN mini_league_standings mls ON mls.team_id = tgw.team_id
+            WHERE mls.league_id = $1 AND tgw.game_week_id BETWEEN $2 AND $3
+        ) ranked
+        WHERE $4::INT IS NULL OR ranked.team_id = $4
+        ORDER BY ranked.game_week_id ASC, ranked.rank ASC, ranked.team_id ASC
+        "#,
+        league_id,
+        from,
+        to,
+        filter.team_id()
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut table = Table::new(&STANDINGS_COLUMNS);
+    for row in rows {
+        table.push(vec![
+            row.game_week_id.into(),
+            row.rank.into(),
+            row.team_id.into(),
+            row.entry_name.into(),
+            row.player_name.into(),
+            row.points.into(),
+            row.total_points.into(),
+            row.event_transfers_cost.into(),
+            row.points_on_bench.into(),
+            row.overall_rank.into(),
+        ]);
+    }
+    Ok(table)
+}
+
+/// Every pick with the player's points that game week, before multipliers.
+pub async fn get_picks(pool: &PgPool, filter: &ExportFilter) -> Result<Table, ServiceError> {
+    let (from, to) = filter.game_weeks()?;
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            p.game_week_id, p.team_id, t.name as team_name, p.player_id, pl.web_name,
+            p.position, p.element_type, p.multiplier, p.is_captain, p.is_vice_captain,
+            gwp.total_points as "player_points?"
+        FROM team_game_week_picks p
+        JOIN teams t ON t.id = p.team_id
+        JOIN players pl ON pl.id = p.player_id
+        LEFT JOIN game_week_players gwp
+            ON gwp.player_id = p.player_id AND gwp.game_week_id = p.game_week_id
+        WHERE p.game_week_id BETWEEN $1 AND $2
+            AND ($3::INT IS NULL
+                OR p.team_id IN (SELECT team_id FROM mini_league_standings WHERE league_id = $3))
+            AND ($4::INT IS NULL OR p.team_id = $4)
+        ORDER BY p.game_week_id ASC, p.team_id ASC, p.position ASC
+        "#,
+        from,
+        to,
+        filter.league_id(),
+        filter.team_id()
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut table = Table::new(&PICKS_COLUMNS);
+    for row in rows {
+        table.push(vec![
+            row.game_week_id.into(),
+            row.team_id.into(),
+            row.team_name.into(),
+            row.player_id.into(),
+            row.web_name.into(),
+            row.position.into(),
+            row.element_type.into(),
+            row.multiplier.into(),
+            row.is_captain.into(),
+            row.is_vice_captain.into(),
+            row.player_points.into(),
+        ]);
+    }
+    Ok(table)
+}
+
+/// Transfers in the order they were made. Costs are in tenths of a million like the FPL API.
+pub async fn get_transfers(pool: &PgPool, filter: &ExportFilter) -> Result<Table, ServiceError> {
+    let (from, to) = filter.game_weeks()?;
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            tr.game_week_id, tr.team_id, t.name as team_name, tr.transfer_time,
+            tr.player_in_id, player_in.web_name as player_in_name, tr.player_in_cost,
+            tr.player_out_id, player_out.web_name as player_out_name, tr.player_out_cost
+        FROM transfers tr
+        JOIN teams t ON t.id = tr.team_id
+        JOIN players player_in ON player_in.id = tr.player_in_id
+        JOIN players player_out ON player_out.id = tr.player_out_id
+        WHERE tr.game_week_id BETWEEN $1 AND $2
+            AND ($3::INT IS NULL
+                OR tr.team_id IN (SELECT team_id FROM mini_league_standings WHERE league_id = $3))
+            AND ($4::INT IS NULL OR tr.team_id = $4)
+        ORDER BY tr.transfer_time ASC, tr.team_id ASC
+        "#,
+        from,
+        to,
+        filter.league_id(),
+        filter.team_id()
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut table = Table::new(&TRANSFERS_COLUMNS);
+    for row in rows {
+        table.push(vec![
+            row.game_week_id.into(),
+            row.team_id.into(),
+            row.team_name.into(),
+            row.transfer_time.into(),
+            row.player_in_id.into(),
+            row.player_in_name.into(),
+            row.player_in_cost.into(),
+            row.player_out_id.into(),
+            row.player_out_name.into(),
+            row.player_out_cost.into(),
+        ]);
+    }
+    Ok(table)
+}
+
+pub async fn get_chips(pool: &PgPool, filter: &ExportFilter) -> Result<Table, ServiceError> {
+    let (from, to) = filter.game_weeks()?;
+    let rows = sqlx::query!(
+        r#"
+        SELECT tgw.game_week_id, tgw.team_id, t.name as team_name, tgw.active_chip as "chip!", tgw.points
+        FROM team_game_weeks tgw
+        JOIN teams t ON t.id = tgw.team_id
+        WHERE tgw.active_chip IS NOT NULL AND tgw.game_week_id BETWEEN $1 AND $2
+            AND ($3::INT IS NULL
+                OR tgw.team_id IN (SELECT team_id FROM mini_league_standings WHERE league_id = $3))
+            AND ($4::INT IS NULL OR tgw.team_id = $4)
+        ORDER BY tgw.game_week_id ASC, tgw.team_id ASC
+        "#,
+        from,
+        to,
+        filter.league_id(),
+        filter.team_id()
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut table = Table::new(&CHIPS_COLUMNS);
+    for row in rows {
+        table.push(vec![
+            row.game_week_id.into(),
+            row.team_id.into(),
+            row.team_name.into(),
+            row.chip.into(),
+            row.points.into(),
+        ]);
+    }
+    Ok(table)
+}
+
+/// Player stats per game week. With a league or team only the players they picked that game
+/// week are included, otherwise every player is.
+pub async fn get_player_stats(pool: &PgPool, filter: &ExportFilter) -> Result<Table, ServiceError> {
+    let (from, to) = filter.game_weeks()?;
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            gwp.game_week_id, gwp.player_id, pl.web_name, gwp.total_points, gwp.minutes,
+            gwp.starts, gwp.goals_scored, gwp.assists, gwp.clean_sheets, gwp.goals_conceded,
+            gwp.own_goals, gwp.penalties_saved, gwp.penalties_missed, gwp.yellow_cards,
+            gwp.red_cards, gwp.saves, gwp.bonus, gwp.bps, gwp.influence, gwp.creativity,
+            gwp.threat, gwp.ict_index, gwp.expected_goals, gwp.expected_assists,
+            gwp.expected_goal_involvements, gwp.expected_goals_conceded, gwp.in_dreamteam
+        FROM game_week_players gwp
+        JOIN players pl ON pl.id = gwp.player_id
+        WHERE gwp.game_week_id BETWEEN $1::SMALLINT AND $2::SMALLINT
+            AND (($3::INT IS NULL AND $4::INT IS NULL) OR EXISTS (
+                SELECT 1 FROM team_game_week_picks p
+                WHERE p.player_id = gwp.player_id AND p.game_week_id = gwp.game_week_id
+                    AND ($3::INT IS NULL
+                        OR p.team_id IN (SELECT team_id FROM mini_league_standings WHERE league_id = $3))
+                    AND ($4::INT IS NULL OR p.team_id = $4)
+            ))
+        ORDER BY gwp.game_week_id ASC, gwp.player_id ASC
+        "#,
+        from,
+        to,
+        filter.league_id(),
+        filter.team_id()
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut table = Table::new(&PLAYER_STATS_COLUMNS);
+    for row in rows {
+        table.push(vec![
+            row.game_week_id.into(),
+            row.player_id.into(),
+            row.web_name.into(),
+            row.total_points.into(),
+            row.minutes.into(),
+            row.starts.into(),
+            row.goals_scored.into(),
+            row.assists.into(),
+            row.clean_sheets.into(),
+            row.goals_conceded.into(),
+            row.own_goals.into(),
+            row.penalties_saved.into(),
+            row.penalties_missed.into(),
+            row.yellow_cards.into(),
+            row.red_cards.into(),
+            row.saves.into(),
+            row.bonus.into(),
+            row.bps.into(),
+            row.influence.into(),
+            row.creativity.into(),
+            row.threat.into(),
+            row.ict_index.into(),
+            row.expected_goals.into(),
+            row.expected_assists.into(),
+            row.expected_goal_involvements.into(),
+            row.expected_goals_conceded.into(),
+            row.in_dreamteam.into(),
+        ]);
+    }
+    Ok(table)
+}
@@ -0,0 +1,250 @@
+use std::sync::Arc;
+
+use arrow_array::{
+    ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray,
+    TimestampMicrosecondArray,
+};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use serde_json::{Map, Number};
+
+use super::{ColumnType, Table, Value};
+use crate::ServiceError;
+
+fn export_error(e: impl std::error::Error) -> ServiceError {
+    ServiceError::Export(e.to_string())
+}
+
+/// A header row then one line per row. Nulls are empty fields and timestamps RFC 3339.
+pub fn write_csv(table: &Table) -> Result<Vec<u8>, ServiceError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(table.columns.iter().map(|column| column.name))
+        .map_err(export_error)?;
+    for row in &table.rows {
+        writer
+            .write_record(row.iter().map(|value| match value {
+                Value::Null => String::new(),
+                Value::Int(n) => n.to_string(),
+                Value::Float(n) => n.to_string(),
+                Value::Text(s) => s.clone(),
+                Value::Bool(b) => b.to_string(),
+                Value::Timestamp(t) => t.to_rfc3339(),
+            }))
+            .map_err(export_error)?;
+    }
+    writer.into_inner().map_err(export_error)
+}
+
+/// An array with an object per row, keyed by column name.
+pub fn write_json(table: &Table) -> Result<Vec<u8>, ServiceError> {
+    let rows: Vec<serde_json::Value> = table
+        .rows
+        .iter()
+        .map(|row| {
+            let object: Map<String, serde_json::Value> = table
+                .columns
+                .iter()
+                .zip(row)
+                .map(|(column, value)| {
+                    let value = match value {
+                        Value::Null => serde_json::Value::Null,
+                        Value::Int(n) => (*n).into(),
+                        // NaN and infinity aren't JSON
+                        Value::Float(n) => Number::from_f64(*n)
+                            .map_or(serde_json::Value::Null, serde_json::Value::Number),
+                        Value::Text(s) => s.clone().into(),
+                        Value::Bool(b) => (*b).into(),
+                        Value::Timestamp(t) => t.to_rfc3339().into(),
+                    };
+                    (column.name.to_string(), value)
+                })
+                .collect();
+            serde_json::Value::Object(object)
+        })
+        .collect();
+    serde_json::to_vec_pretty(&rows).map_err(export_error)
+}
+
+fn data_type(column_type: ColumnType) -> DataType {
+    match column_type {
+        ColumnType::Int => DataType::Int64,
+        ColumnType::Float => DataType::Float64,
+        ColumnType::Text => DataType::Utf8,
+        ColumnType::Bool => DataType::Boolean,
+        ColumnType::Timestamp => DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+    }
+}
+
+/// A single row group with a nullable column per `Table` column. Values that don't match
+/// their column's type are written as null.
+pub fn write_parquet(table: &Table) -> Result<Vec<u8>, ServiceError> {
+    let schema = Arc::new(Schema::new(
+        table
+            .columns
+            .iter()
+            .map(|column| Field::new(column.name, data_type(column.column_type), true))
+            .collect::<Vec<_>>(),
+    ));
+
+    let arrays: Vec<ArrayRef> = table
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let values = table.rows.iter().map(|row| &row[i]);
+            let array: ArrayRef = match column.column_type {
+                ColumnType::Int => Arc::new(
+                    values
+                        .map(|value| match value {
+                            Value::Int(n) => Some(*n),
+                            _ => None,
+                        })
+                        .collect::<Int64Array>(),
+                ),
+                ColumnType::Float => Arc::new(
+                    values
+                        .map(|value| match value {
+                            Value::Float(n) => Some(*n),
+                            _ => None,
+                        })
+                        .collect::<Float64Array>(),
+                ),
+                ColumnType::Text => Arc::new(
+                    values
+                        .map(|value| match value {
+                            Value::Text(s) => Some(s.as_str()),
+                            _ => None,
+                        })
+                        .collect::<StringArray>(),
+                ),
+                ColumnType::Bool => Arc::new(
+                    values
+                        .map(|value| match value {
+                            Value::Bool(b) => Some(*b),
+                            _ => None,
+                        })
+                        .collect::<BooleanArray>(),
+                ),
+                ColumnType::Timestamp => Arc::new(
+                    values
+                        .map(|value| match value {
+                            Value::Timestamp(t) => Some(t.timestamp_micros()),
+                            _ => None,
+                        })
+                        .collect::<TimestampMicrosecondArray>()
+                        .with_timezone("UTC"),
+                ),
+            };
+            array
+        })
+        .collect();
+
+    let batch = RecordBatch::try_new(Arc::clone(&schema), arrays).map_err(export_error)?;
+    let mut bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut bytes, schema, None).map_err(export_error)?;
+    writer.write(&batch).map_err(export_error)?;
+    writer.close().map_err(export_error)?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::Array;
+    use bytes::Bytes;
+    use chrono::{DateTime, Utc};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use super::*;
+    use crate::export::Column;
+
+    fn table() -> Table {
+        let mut table = Table::new(&[
+            Column::new("game_week_id", ColumnType::Int),
+            Column::new("player_name", ColumnType::Text),
+            Column::new("ict_index", ColumnType::Float),
+            Column::new("is_captain", ColumnType::Bool),
+            Column::new("transfer_time", ColumnType::Timestamp),
+        ]);
+        let time: DateTime<Utc> = DateTime::parse_from_rfc3339("2024-08-23T18:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        table.push(vec![
+            Value::Int(1),
+            Value::Text("Saka, B".to_string()),
+            Value::Float(7.5),
+            Value::Bool(true),
+            Value::Timestamp(time),
+        ]);
+        table.push(vec![
+            Value::Int(2),
+            Value::Null,
+            Value::Float(0.0),
+            Value::Bool(false),
+            Value::Null,
+        ]);
+        table
+    }
+
+    #[test]
+    fn test_csv() {
+        let csv = String::from_utf8(write_csv(&table()).unwrap()).unwrap();
+        assert_eq!(
+            csv,
+            "game_week_id,player_name,ict_index,is_captain,transfer_time\n\
+             1,\"Saka, B\",7.5,true,2024-08-23T18:00:00+00:00\n\
+             2,,0,false,\n"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let json: serde_json::Value =
+            serde_json::from_slice(&write_json(&table()).unwrap()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {
+                    "game_week_id": 1,
+                    "player_name": "Saka, B",
+                    "ict_index": 7.5,
+                    "is_captain": true,
+                    "transfer_time": "2024-08-23T18:00:00+00:00",
+                },
+                {
+                    "game_week_id": 2,
+                    "player_name": null,
+                    "ict_index": 0.0,
+                    "is_captain": false,
+                    "transfer_time": null,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parquet_round_trip() {
+        let bytes = write_parquet(&table()).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(bytes))
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(Result::unwrap).collect();
+        assert_eq!(batches.len(), 1);
+
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.num_columns(), 5);
+        let names = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(names.value(0), "Saka, B");
+        assert!(names.is_null(1));
+        assert_eq!(
+            batch.schema().field(4).data_type(),
+            &data_type(ColumnType::Timestamp)
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::BTreeMap;
+
+use fpl_common::types::{LeagueId, TeamId};
+use sqlx::PgPool;
+
+use crate::ServiceError;
+
+/// The team's hits per game week and in total. Each hit is a 4 point transfer cost.
+pub async fn get_team_hits(pool: &PgPool, team_id: TeamId) -> Result<Vec<String>, ServiceError> {
+    let team_hits = sqlx::query!(
+        "
+        SELECT game_week_id, event_transfers_cost
+        FROM team_game_weeks
+        WHERE event_transfers_cost > 0 and team_id = $1
+        ORDER BY game_week_id ASC;
+        ",
+        i32::from(team_id)
+    )
+    .fetch_all(pool)
+    .await?;
+
+    if team_hits.is_empty() {
+        return Ok(vec!["Has not taken a hit".to_string()]);
+    }
+
+    let mut result = vec!["**__Hits__**\n".to_string()];
+    let mut total_hits = 0;
+    result.extend(team_hits.into_iter().map(|row| {
+        let hits = row.event_transfers_cost / 4;
+        total_hits += hits;
+        format!("**GW{}** - {}", row.game_week_id, hits)
+    }));
+    result.push(format!(
+        "\n**Total:** {total_hits} (-{} points)",
+        total_hits * 4
+    ));
+
+    Ok(result)
+}
+
+/// Who in the league took hits, grouped by game week.
+pub async fn get_league_hits(
+    pool: &PgPool,
+    league_id: LeagueId,
+) -> Result<Vec<String>, ServiceError> {
+    let league_hits = sqlx::query!(
+        "
+        SELECT tgw.game_week_id, mls.player_name, mls.entry_name, tgw.event_transfers_cost FROM team_game_weeks tgw
+        JOIN mini_league_standings mls ON mls.team_id = tgw.team_id
+        WHERE tgw.event_transfers_cost > 0 AND mls.league_id = $1
+        ORDER BY mls.rank_sort;
+        ",
+        i32::from(league_id)
+    )
+    .fetch_all(pool)
+    .await?;
+
+    if league_hits.is_empty() {
+        return Ok(vec!["No one has taken a hit".to_string()]);
+    }
+
+    // Group by game week
+    type TeamRows = Vec<(String, String, i16)>;
+    let mut hits_game_week: BTreeMap<i16, TeamRows> = BTreeMap::new();
+    for row in league_hits {
+        hits_game_week.entry(row.game_week_id).or_default().push((
+            row.player_name,
+            row.entry_name,
+            row.event_transfers_cost,
+        ));
+    }
+
+    let mut result = vec!["**__Hits__**\n".to_string()];
+    result.extend(hits_game_week.into_iter().map(|(game_week, users)| {
+        let users_formatted = users
+            .into_iter()
+            .map(|(user_name, team_name, hits_cost)| {
+                format!("{user_name} ({team_name}) **{}**", hits_cost / 4)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("**GW{}** - {}", game_week, users_formatted)
+    }));
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../fixtures/seed.sql"))]
+    async fn test_team_hits(pool: PgPool) {
+        let rows = get_team_hits(&pool, TeamId::new(102)).await.unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                "**__Hits__**\n",
+                "**GW1** - 1",
+                "\n**Total:** 1 (-4 points)"
+            ]
+        );
+
+        let rows = get_team_hits(&pool, TeamId::new(101)).await.unwrap();
+        assert_eq!(rows, vec!["Has not taken a hit"]);
+    }
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../fixtures/seed.sql"))]
+    async fn test_league_hits(pool: PgPool) {
+        let rows = get_league_hits(&pool, LeagueId::new(500)).await.unwrap();
+        assert_eq!(
+            rows,
+            vec!["**__Hits__**\n", "**GW1** - Bob Jones (Bob United) **1**"]
+        );
+    }
+}
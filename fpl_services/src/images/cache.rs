@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime};
+
+use sha2::{Digest, Sha256};
+
+use super::render_png;
+
+/// How long a render is reused for, in memory and on disk.
+pub const DEFAULT_RENDER_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Team sheets are the biggest at a few hundred KB, so this keeps memory use in the tens of MB.
+pub const DEFAULT_MAX_CACHED_RENDERS: usize = 128;
+
+/// Makes temporary file names unique between threads rendering the same image.
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A rendered PNG, shared by every caller that asked for the same image.
+#[derive(Debug, Clone)]
+pub struct RenderedImage {
+    /// Hex SHA-256 of the SVG the PNG was rendered from
+    pub key: String,
+    /// Named after the key, so two renders only share a file if they'd write the same bytes
+    pub path: PathBuf,
+    pub png: Arc<Vec<u8>>,
+}
+
+impl RenderedImage {
+    pub fn file_name(&self) -> String {
+        format!("{}.png", self.key)
+    }
+}
+
+#[derive(Debug)]
+struct CachedRender {
+    image: RenderedImage,
+    rendered_at: Instant,
+}
+
+/// Content addressed cache of rasterised images. The key is a hash of the SVG, which is built
+/// from the render data and the renderer's layout, so any change to either is a new image,
+/// and building the SVG is cheap next to rasterising it with resvg.
+///
+/// Recent renders are served from memory. Every render is also written to `dir` so other
+/// processes and restarts can reuse it until `cleanup` removes it.
+#[derive(Debug)]
+pub struct RenderCache {
+    dir: PathBuf,
+    ttl: Duration,
+    max_renders: usize,
+    renders: Mutex<HashMap<String, CachedRender>>,
+}
+
+impl RenderCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl: DEFAULT_RENDER_TTL,
+            max_renders: DEFAULT_MAX_CACHED_RENDERS,
+            renders: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn with_max_renders(mut self, max_renders: usize) -> Self {
+        self.max_renders = max_renders;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn key(svg: &str) -> String {
+        hex::encode(Sha256::digest(svg.as_bytes()))
+    }
+
+    /// The PNG for `svg`, only rasterised if it isn't in memory or on disk already.
+    pub fn render(&self, svg: &str) -> io::Result<RenderedImage> {
+        let key = Self::key(svg);
+        if let Some(image) = self.get(&key) {
+            return Ok(image);
+        }
+
+        let path = self.dir.join(format!("{key}.png"));
+        let png = match self.read_fresh(&path) {
+            Some(png) => png,
+            None => {
+                let png = render_png(svg)?;
+                self.write(&path, &png)?;
+                png
+            }
+        };
+
+        let image = RenderedImage {
+            key,
+            path,
+            png: Arc::new(png),
+        };
+        self.insert(image.clone());
+        Ok(image)
+    }
+
+    /// Forgets expired renders and deletes image files older than the TTL from `dir`, returning
+    /// how many files were deleted. That includes anything else left in `dir`, like renders
+    /// from before there was a cache.
+    pub fn cleanup(&self) -> io::Result<usize> {
+        self.lock()
+            .retain(|_, render| render.rendered_at.elapsed() < self.ttl);
+
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e),
+        };
+
+        let mut removed = 0;
+        for entry in entries {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_file() && self.is_expired(&metadata) {
+                match fs::remove_file(entry.path()) {
+                    Ok(()) => removed += 1,
+                    // Removed by another instance in the meantime
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        Ok(removed)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, CachedRender>> {
+        // Entries are only ever inserted whole, so a panic elsewhere can't leave one half written
+        self.renders.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn get(&self, key: &str) -> Option<RenderedImage> {
+        let mut renders = self.lock();
+        match renders.get(key) {
+            Some(render) if render.rendered_at.elapsed() < self.ttl => Some(render.image.clone()),
+            Some(_) => {
+                renders.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, image: RenderedImage) {
+        let mut renders = self.lock();
+        if renders.len() >= self.max_renders && !renders.contains_key(&image.key) {
+            let oldest = renders
+                .iter()
+                .min_by_key(|(_, render)| render.rendered_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                renders.remove(&oldest);
+            }
+        }
+        renders.insert(
+            image.key.clone(),
+            CachedRender {
+                image,
+                rendered_at: Instant::now(),
+            },
+        );
+    }
+
+    fn is_expired(&self, metadata: &fs::Metadata) -> bool {
+        metadata
+            .modified()
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age >= self.ttl)
+    }
+
+    fn read_fresh(&self, path: &Path) -> Option<Vec<u8>> {
+        let metadata = fs::metadata(path).ok()?;
+        if self.is_expired(&metadata) {
+            return None;
+        }
+        fs::read(path).ok()
+    }
+
+    /// Writes to a temporary file first so a reader never sees half a PNG.
+    fn write(&self, path: &Path, png: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let temp_path = path.with_extension(format!(
+            "{}.{}.tmp",
+            std::process::id(),
+            TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&temp_path, png)?;
+        fs::rename(&temp_path, path).inspect_err(|_| {
+            let _ = fs::remove_file(&temp_path);
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn svg(colour: &str) -> String {
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="4"><rect width="4" height="4" fill="{colour}"/></svg>"#
+        )
+    }
+
+    fn cache(name: &str) -> RenderCache {
+        let dir =
+            std::env::temp_dir().join(format!("fpl_render_cache_{}_{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        RenderCache::new(dir)
+    }
+
+    #[test]
+    fn test_same_svg_is_rendered_once() {
+        let cache = cache("same");
+        let first = cache.render(&svg("red")).unwrap();
+        let second = cache.render(&svg("red")).unwrap();
+
+        assert_eq!(first.path, second.path);
+        assert!(Arc::ptr_eq(&first.png, &second.png));
+        assert_eq!(fs::read(&first.path).unwrap(), *first.png);
+
+        let other = cache.render(&svg("blue")).unwrap();
+        assert_ne!(first.key, other.key);
+        assert_ne!(first.path, other.path);
+        let _ = fs::remove_dir_all(cache.dir());
+    }
+
+    #[test]
+    fn test_reuses_file_from_another_instance() {
+        let cache = cache("shared");
+        let image = cache.render(&svg("green")).unwrap();
+
+        let other = RenderCache::new(cache.dir());
+        let reused = other.render(&svg("green")).unwrap();
+        assert_eq!(reused.path, image.path);
+        assert_eq!(reused.png, image.png);
+        let _ = fs::remove_dir_all(cache.dir());
+    }
+
+    #[test]
+    fn test_oldest_render_is_evicted() {
+        let cache = cache("evict").with_max_renders(1);
+        let red = cache.render(&svg("red")).unwrap();
+        cache.render(&svg("blue")).unwrap();
+
+        assert!(cache.get(&red.key).is_none());
+        assert_eq!(cache.lock().len(), 1);
+        let _ = fs::remove_dir_all(cache.dir());
+    }
+
+    #[test]
+    fn test_cleanup() {
+        let cache = cache("cleanup").with_ttl(Duration::ZERO);
+        let image = cache.render(&svg("red")).unwrap();
+        fs::write(cache.dir().join("table_1_2.png"), b"old").unwrap();
+
+        assert_eq!(cache.cleanup().unwrap(), 2);
+        assert!(!image.path.exists());
+        assert!(cache.lock().is_empty());
+
+        let missing = RenderCache::new(cache.dir().join("missing"));
+        assert_eq!(missing.cleanup().unwrap(), 0);
+        let _ = fs::remove_dir_all(cache.dir());
+    }
+}
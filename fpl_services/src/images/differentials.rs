@@ -1,15 +1,10 @@
-use resvg::{render, usvg};
 use std::collections::HashMap;
 use svg::Document;
-use tiny_skia::Pixmap;
-use usvg::{Options, Tree};
 
-use super::colours::{GREEN_COLOUR, WHITE_COLOUR};
 use super::{
-    calculate_player_card_xs, CenteredTextBox, CornerRounding, FontWeight, PlayerGameInfo,
-    PlayerInfo,
+    calculate_player_card_xs, save_png, CenteredTextBox, CornerRounding, FontWeight,
+    PlayerGameInfo, PlayerInfo, Theme,
 };
-use crate::images::constants::colours::PURPLE_COLOUR;
 
 #[derive(Debug, Clone)]
 pub struct DifferentialKey {
@@ -81,6 +76,7 @@ pub struct DifferentialsRenderer {
     pub player_row_height: u32,
     pub player_card_width: u32,
     pub internal_vertical_padding: u32,
+    pub theme: Theme,
 }
 
 impl Default for DifferentialsRenderer {
@@ -93,17 +89,28 @@ impl Default for DifferentialsRenderer {
             player_row_height: 250,
             player_card_width: 175,
             internal_vertical_padding: 20,
+            theme: Theme::default(),
         }
     }
 }
 
 impl DifferentialsRenderer {
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
     pub async fn render(&self, data: Differentials, path: &str) -> std::io::Result<()> {
+        save_png(&self.render_svg(data)?, path)
+    }
+
+    /// The SVG that `render` rasterises, for callers that can show it as is.
+    pub fn render_svg(&self, data: Differentials) -> std::io::Result<String> {
         let mut teams_vec: Vec<(String, Vec<PlayerInfo>)> =
             data.user_to_differentials.into_iter().collect();
 
         // sort by vec length so team boxes are similar heights
-        teams_vec.sort_by(|a, b| b.1.len().cmp(&a.1.len()));
+        teams_vec.sort_by_key(|team| std::cmp::Reverse(team.1.len()));
 
         let chunked_teams: Vec<_> = teams_vec.chunks(self.team_boxes_per_row as usize).collect();
 
@@ -115,6 +122,7 @@ impl DifferentialsRenderer {
             }
         }
 
+        let palette = &self.theme.palette;
         let mut document = Document::new()
             .set("viewBox", (0, 0, self.width, total_height))
             .set("width", self.width)
@@ -123,7 +131,7 @@ impl DifferentialsRenderer {
         let background = svg::node::element::Rectangle::new()
             .set("width", self.width)
             .set("height", total_height)
-            .set("fill", WHITE_COLOUR);
+            .set("fill", palette.background);
 
         document = document.add(background);
 
@@ -150,8 +158,9 @@ impl DifferentialsRenderer {
                     .text(team_name)
                     .dimensions(team_box_width as f64, self.team_box_title_height as f64)
                     .position(x_offset as f64, y_offset as f64)
-                    .background_color(PURPLE_COLOUR)
-                    .font_color(WHITE_COLOUR)
+                    .background_color(palette.primary)
+                    .font_color(palette.on_primary)
+                    .font_family(self.theme.font_family)
                     .font_weight(FontWeight::Black)
                     .corner_rounding(CornerRounding::None)
                     .inner_padding(0.95)
@@ -181,7 +190,7 @@ impl DifferentialsRenderer {
                     let player_y_pos = player_card_y_offset + self.internal_vertical_padding;
 
                     for (x_offset, player) in player_card_xs.iter().zip(players_chunk.iter()) {
-                        let player_card = player.clone().border_color(PURPLE_COLOUR).to_card_svg(
+                        let player_card = player.clone().theme(&self.theme).to_card_svg(
                             *x_offset,
                             player_y_pos,
                             self.player_card_width,
@@ -197,7 +206,7 @@ impl DifferentialsRenderer {
                 .set("y1", y_offset)
                 .set("x2", self.width / 2)
                 .set("y2", y_offset + self.team_box_title_height)
-                .set("stroke", GREEN_COLOUR)
+                .set("stroke", palette.accent)
                 .set("stroke-width", 2);
 
             document = document.add(vertical_divider);
@@ -205,23 +214,12 @@ impl DifferentialsRenderer {
             y_offset += max_team_box_y_offset as u32;
         }
 
-        // Convert SVG to PNG
-        let svg_string = document.to_string();
-        let mut opt: Options<'_> = Options::default();
-        opt.fontdb_mut().load_system_fonts();
-
-        let tree = Tree::from_str(&svg_string, &opt).unwrap();
-        let size = tree.size();
-        let mut pixmap = Pixmap::new(size.width() as u32, size.height() as u32).unwrap();
-        render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
-
-        std::fs::write(path, pixmap.encode_png().unwrap())
+        Ok(document.to_string())
     }
 
     fn calculate_team_box_height(&self, team_box: &(String, Vec<PlayerInfo>)) -> usize {
         let (_, players) = team_box;
-        let player_rows = (players.len() + self.columns_per_team_box as usize - 1)
-            / self.columns_per_team_box as usize;
+        let player_rows = players.len().div_ceil(self.columns_per_team_box as usize);
 
         self.team_box_title_height as usize + (player_rows * self.player_row_height as usize)
     }
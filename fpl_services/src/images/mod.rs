@@ -1,15 +1,21 @@
+pub mod cache;
 pub mod constants;
 pub mod differentials;
+pub mod points_digest;
 pub mod table;
 pub mod team;
+pub mod theme;
 pub mod transfers;
 pub mod unique;
 pub mod util;
 
+pub use cache::*;
 pub use constants::*;
 pub use differentials::*;
+pub use points_digest::*;
 pub use table::*;
 pub use team::*;
+pub use theme::*;
 pub use transfers::*;
 pub use unique::*;
 pub use util::*;
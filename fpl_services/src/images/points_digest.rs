@@ -0,0 +1,231 @@
+use svg::node::element::{Group, Image, Rectangle, Text};
+use svg::Document;
+
+use super::{save_png, Theme};
+
+const MAX_OWNERS_LENGTH: usize = 45;
+
+#[derive(Debug, Clone)]
+pub struct PointsDigestRow {
+    pub name: String,
+    pub code: i32,
+    pub old_points: i16,
+    pub new_points: i16,
+    pub owners: Vec<String>,
+}
+
+impl PointsDigestRow {
+    pub fn new(
+        name: String,
+        code: i32,
+        old_points: i16,
+        new_points: i16,
+        owners: Vec<String>,
+    ) -> Self {
+        Self {
+            name,
+            code,
+            old_points,
+            new_points,
+            owners,
+        }
+    }
+
+    fn delta_str(&self) -> String {
+        format!(
+            "{} → {} ({:+})",
+            self.old_points,
+            self.new_points,
+            self.new_points - self.old_points
+        )
+    }
+
+    fn owners_str(&self) -> String {
+        let owners = self.owners.join(", ");
+        if owners.chars().count() <= MAX_OWNERS_LENGTH {
+            return owners;
+        }
+
+        let truncated: String = owners.chars().take(MAX_OWNERS_LENGTH - 3).collect();
+        format!("{}...", truncated)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PointsDigestData {
+    pub title: String,
+    pub rows: Vec<PointsDigestRow>,
+}
+
+impl PointsDigestData {
+    pub fn new(title: String) -> Self {
+        Self {
+            title,
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn add_row(&mut self, row: PointsDigestRow) {
+        self.rows.push(row);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PointsDigestRenderer {
+    pub width: u32,
+    pub row_height: u32,
+    pub header_height: u32,
+    pub title_height: u32,
+    pub padding: u32,
+    pub theme: Theme,
+}
+
+impl Default for PointsDigestRenderer {
+    fn default() -> Self {
+        Self {
+            width: 1000,
+            row_height: 80,
+            header_height: 60,
+            title_height: 80,
+            padding: 20,
+            theme: Theme::default(),
+        }
+    }
+}
+
+impl PointsDigestRenderer {
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub async fn render(&self, data: PointsDigestData, path: &str) -> std::io::Result<()> {
+        save_png(&self.render_svg(data)?, path)
+    }
+
+    /// The SVG that `render` rasterises, for callers that can show it as is.
+    pub fn render_svg(&self, data: PointsDigestData) -> std::io::Result<String> {
+        let total_height =
+            self.title_height + self.header_height + (data.rows.len() as u32 * self.row_height);
+        let change_x = 400;
+        let owners_x = 600;
+
+        let palette = &self.theme.palette;
+        let font_family = self.theme.font_family;
+        let font_sizes = &self.theme.font_sizes;
+
+        let mut document = Document::new()
+            .set("viewBox", (0, 0, self.width, total_height))
+            .set("width", self.width)
+            .set("height", total_height);
+
+        // Add title background and text
+        let title_bg = Rectangle::new()
+            .set("x", 0)
+            .set("y", 0)
+            .set("width", self.width)
+            .set("height", self.title_height)
+            .set("fill", palette.primary);
+
+        let title_text = Text::new(&data.title)
+            .set("x", self.width / 2)
+            .set("y", self.title_height / 2 + 10) // +10 for vertical centering
+            .set("text-anchor", "middle")
+            .set("fill", palette.on_primary)
+            .set("font-family", font_family)
+            .set("font-weight", "900")
+            .set("font-size", font_sizes.title);
+
+        document = document.add(title_bg).add(title_text);
+
+        // Add header
+        let header_bg = Rectangle::new()
+            .set("x", 0)
+            .set("y", self.title_height)
+            .set("width", self.width)
+            .set("height", self.header_height)
+            .set("fill", palette.header_background);
+
+        let header_center = self.title_height + (self.header_height / 2);
+        let header_text = |text: &str, x: u32| {
+            Text::new(text)
+                .set("x", x)
+                .set("y", header_center)
+                .set("fill", palette.text)
+                .set("font-family", font_family)
+                .set("font-weight", "bold")
+                .set("font-size", font_sizes.header)
+                .set("dominant-baseline", "middle")
+                .set("alignment-baseline", "middle")
+        };
+
+        let header_group = Group::new()
+            .add(header_bg)
+            .add(header_text("Player", self.padding))
+            .add(header_text("Change", change_x))
+            .add(header_text("Owners", owners_x));
+
+        document = document.add(header_group);
+
+        // Add rows
+        for (index, row) in data.rows.iter().enumerate() {
+            let y_pos = self.title_height + self.header_height + (index as u32 * self.row_height);
+
+            let bg_color = if index % 2 == 0 {
+                palette.background
+            } else {
+                palette.alternate_background
+            };
+            let row_bg = Rectangle::new()
+                .set("x", 0)
+                .set("y", y_pos)
+                .set("width", self.width)
+                .set("height", self.row_height)
+                .set("fill", bg_color);
+
+            let bottom_border = svg::node::element::Line::new()
+                .set("x1", 0)
+                .set("y1", y_pos)
+                .set("x2", self.width)
+                .set("y2", y_pos)
+                .set("stroke", palette.border)
+                .set("stroke-width", 1);
+
+            let image_size = self.row_height - 10;
+            let player_image = Image::new()
+                .set("x", self.padding)
+                .set("y", y_pos + 5)
+                .set("width", image_size)
+                .set("height", image_size)
+                .set("href", fpl_common::paths::get_player_image_path(row.code))
+                .set("preserveAspectRatio", "xMidYMid meet");
+
+            let row_text = |text: String, x: u32, weight: &str| {
+                Text::new(text)
+                    .set("x", x)
+                    .set("y", y_pos + (self.row_height / 2))
+                    .set("fill", palette.text)
+                    .set("font-family", font_family)
+                    .set("font-size", font_sizes.body)
+                    .set("font-weight", weight)
+                    .set("dominant-baseline", "middle")
+            };
+
+            let row_group = Group::new()
+                .add(row_bg)
+                .add(bottom_border)
+                .add(player_image)
+                .add(row_text(
+                    row.name.clone(),
+                    self.padding + image_size + 10,
+                    "bold",
+                ))
+                .add(row_text(row.delta_str(), change_x, "bold"))
+                .add(row_text(row.owners_str(), owners_x, "normal"));
+
+            document = document.add(row_group);
+        }
+
+        Ok(document.to_string())
+    }
+}
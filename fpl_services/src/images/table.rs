@@ -1,13 +1,7 @@
-use resvg::{render, usvg};
 use svg::node::element::{Group, Rectangle, Text};
 use svg::Document;
-use tiny_skia::Pixmap;
-use usvg::{Options, Tree};
 
-use crate::images::constants::colours::PURPLE_COLOUR;
-use crate::images::constants::fonts::FPL_FONT_NAME;
-
-use super::colours::{GREEN_COLOUR, OFF_WHITE_COLOUR, WHITE_COLOUR};
+use super::{save_png, Theme};
 
 #[derive(Debug, Clone)]
 pub struct TableRow {
@@ -75,6 +69,7 @@ pub struct TableRenderer {
     pub header_height: u32,
     pub title_height: u32,
     pub padding: u32,
+    pub theme: Theme,
 }
 
 impl Default for TableRenderer {
@@ -85,15 +80,30 @@ impl Default for TableRenderer {
             header_height: 60,
             title_height: 80,
             padding: 20,
+            theme: Theme::default(),
         }
     }
 }
 
 impl TableRenderer {
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
     pub async fn render(&self, data: TableData, path: &str) -> std::io::Result<()> {
+        save_png(&self.render_svg(data)?, path)
+    }
+
+    /// The SVG that `render` rasterises, for callers that can show it as is.
+    pub fn render_svg(&self, data: TableData) -> std::io::Result<String> {
         let total_height =
             self.title_height + self.header_height + (data.rows.len() as u32 * self.row_height);
 
+        let palette = &self.theme.palette;
+        let font_family = self.theme.font_family;
+        let font_sizes = &self.theme.font_sizes;
+
         let mut document = Document::new()
             .set("viewBox", (0, 0, self.width, total_height))
             .set("width", self.width)
@@ -105,16 +115,16 @@ impl TableRenderer {
             .set("y", 0)
             .set("width", self.width)
             .set("height", self.title_height)
-            .set("fill", PURPLE_COLOUR);
+            .set("fill", palette.primary);
 
         let title_text = Text::new(&data.title)
             .set("x", self.width / 2)
             .set("y", self.title_height / 2 + 10) // +10 for vertical centering
             .set("text-anchor", "middle")
-            .set("fill", WHITE_COLOUR)
-            .set("font-family", FPL_FONT_NAME)
+            .set("fill", palette.on_primary)
+            .set("font-family", font_family)
             .set("font-weight", "900")
-            .set("font-size", "32");
+            .set("font-size", font_sizes.title);
 
         document = document.add(title_bg).add(title_text);
 
@@ -124,7 +134,7 @@ impl TableRenderer {
             .set("y", self.title_height)
             .set("width", self.width)
             .set("height", self.header_height)
-            .set("fill", "#F0F0F0");
+            .set("fill", palette.header_background);
 
         let header_center = self.title_height + (self.header_height / 2);
 
@@ -134,10 +144,10 @@ impl TableRenderer {
                 Text::new("Rank")
                     .set("x", self.padding)
                     .set("y", header_center)
-                    .set("fill", PURPLE_COLOUR)
-                    .set("font-family", FPL_FONT_NAME)
+                    .set("fill", palette.text)
+                    .set("font-family", font_family)
                     .set("font-weight", "bold")
-                    .set("font-size", "28")
+                    .set("font-size", font_sizes.header)
                     .set("dominant-baseline", "middle")
                     .set("alignment-baseline", "middle"),
             )
@@ -145,10 +155,10 @@ impl TableRenderer {
                 Text::new("Team and Manager")
                     .set("x", 125)
                     .set("y", header_center)
-                    .set("fill", PURPLE_COLOUR)
-                    .set("font-family", FPL_FONT_NAME)
+                    .set("fill", palette.text)
+                    .set("font-family", font_family)
                     .set("font-weight", "bold")
-                    .set("font-size", "28")
+                    .set("font-size", font_sizes.header)
                     .set("dominant-baseline", "middle")
                     .set("alignment-baseline", "middle"),
             )
@@ -156,10 +166,10 @@ impl TableRenderer {
                 Text::new("Confirmed Points")
                     .set("x", self.width - 500)
                     .set("y", header_center)
-                    .set("fill", PURPLE_COLOUR)
-                    .set("font-family", FPL_FONT_NAME)
+                    .set("fill", palette.text)
+                    .set("font-family", font_family)
                     .set("font-weight", "bold")
-                    .set("font-size", "28")
+                    .set("font-size", font_sizes.header)
                     .set("dominant-baseline", "middle")
                     .set("alignment-baseline", "middle"),
             )
@@ -167,10 +177,10 @@ impl TableRenderer {
                 Text::new("Live Points")
                     .set("x", self.width - 200)
                     .set("y", header_center)
-                    .set("fill", PURPLE_COLOUR)
-                    .set("font-family", FPL_FONT_NAME)
+                    .set("fill", palette.text)
+                    .set("font-family", font_family)
                     .set("font-weight", "bold")
-                    .set("font-size", "28")
+                    .set("font-size", font_sizes.header)
                     .set("dominant-baseline", "middle")
                     .set("alignment-baseline", "middle"),
             );
@@ -183,11 +193,15 @@ impl TableRenderer {
 
             // Row background
             let bg_color = if index % 2 == 0 {
-                WHITE_COLOUR
+                palette.background
+            } else {
+                palette.alternate_background
+            };
+            let (row_bg_colour, text_colour) = if row.caller {
+                (palette.accent, palette.on_accent)
             } else {
-                OFF_WHITE_COLOUR
+                (bg_color, palette.text)
             };
-            let row_bg_colour = if row.caller { GREEN_COLOUR } else { bg_color };
             let row_bg = Rectangle::new()
                 .set("x", 0)
                 .set("y", y_pos)
@@ -200,7 +214,7 @@ impl TableRenderer {
                 .set("y1", y_pos)
                 .set("x2", self.width)
                 .set("y2", y_pos)
-                .set("stroke", PURPLE_COLOUR)
+                .set("stroke", palette.border)
                 .set("stroke-width", 1);
 
             let row_group = Group::new()
@@ -210,62 +224,52 @@ impl TableRenderer {
                     Text::new((index + 1).to_string())
                         .set("x", self.padding)
                         .set("y", y_pos + 45)
-                        .set("fill", PURPLE_COLOUR)
-                        .set("font-family", FPL_FONT_NAME)
-                        .set("font-size", "24")
+                        .set("fill", text_colour)
+                        .set("font-family", font_family)
+                        .set("font-size", font_sizes.body)
                         .set("font-weight", "bold"),
                 )
                 .add(
                     Text::new("")
                         .set("x", 125)
                         .set("y", y_pos + (self.row_height / 2))
-                        .set("fill", PURPLE_COLOUR)
-                        .set("font-family", FPL_FONT_NAME)
+                        .set("fill", text_colour)
+                        .set("font-family", font_family)
                         .set("dominant-baseline", "middle")
                         .add(
                             svg::node::element::TSpan::new(&row.team_name)
                                 .set("x", 125)
                                 .set("dy", "-0.5em")
                                 .set("font-weight", "800")
-                                .set("font-size", "20"),
+                                .set("font-size", font_sizes.detail),
                         )
                         .add(
                             svg::node::element::TSpan::new(&row.name)
                                 .set("x", 125)
                                 .set("dy", "1.3em")
-                                .set("font-size", "20"),
+                                .set("font-size", font_sizes.detail),
                         ),
                 )
                 .add(
                     Text::new(row.confirmed_points.to_string())
                         .set("x", self.width - 500)
                         .set("y", y_pos + 45)
-                        .set("fill", PURPLE_COLOUR)
-                        .set("font-family", FPL_FONT_NAME)
-                        .set("font-size", "24"),
+                        .set("fill", text_colour)
+                        .set("font-family", font_family)
+                        .set("font-size", font_sizes.body),
                 )
                 .add(
                     Text::new(row.live_points.to_string())
                         .set("x", self.width - 200)
                         .set("y", y_pos + 45)
-                        .set("fill", PURPLE_COLOUR)
-                        .set("font-family", FPL_FONT_NAME)
-                        .set("font-size", "24"),
+                        .set("fill", text_colour)
+                        .set("font-family", font_family)
+                        .set("font-size", font_sizes.body),
                 );
 
             document = document.add(row_group);
         }
 
-        // Convert SVG to PNG
-        let svg_string = document.to_string();
-        let mut opt: Options<'_> = Options::default();
-        opt.fontdb_mut().load_system_fonts();
-
-        let tree = Tree::from_str(&svg_string, &opt).unwrap();
-        let size = tree.size();
-        let mut pixmap = Pixmap::new(size.width() as u32, size.height() as u32).unwrap();
-        render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
-
-        std::fs::write(path, pixmap.encode_png().unwrap())
+        Ok(document.to_string())
     }
 }
@@ -1,20 +1,14 @@
 use fpl_common::types::{Chip, GameWeekId};
-use resvg::{render, usvg};
 use sqlx::prelude::FromRow;
 use svg::node::element::Rectangle;
 use svg::Document;
 use thousands::Separable;
-use tiny_skia::Pixmap;
-use usvg::{Options, Tree};
 
 use crate::images::util::PlayerInfo;
 
 use super::{
-    calculate_player_card_xs,
-    colours::{
-        DARK_PITCH_GREEN_COLOUR, GREEN_COLOUR, PITCH_GREEN_COLOUR, PURPLE_COLOUR, WHITE_COLOUR,
-    },
-    CenteredTextBox, CornerRounding, FontWeight,
+    calculate_player_card_xs, save_png, CardImageStyle, CenteredTextBox, CornerRounding,
+    FontWeight, Theme,
 };
 
 #[derive(Debug, Clone, FromRow)]
@@ -62,6 +56,7 @@ pub struct TeamDataBuilder {
     assman: Option<PlayerInfo>,
     transfers: Vec<TransferInfo>,
     chip: Option<Chip>,
+    favourite_club_code: Option<i16>,
 }
 
 impl TeamDataBuilder {
@@ -134,6 +129,12 @@ impl TeamDataBuilder {
         self
     }
 
+    /// The `clubs.code` of the manager's favourite club, whose badge goes in the header.
+    pub fn favourite_club(mut self, club_code: i16) -> Self {
+        self.favourite_club_code = Some(club_code);
+        self
+    }
+
     pub fn build(self) -> Result<TeamData, &'static str> {
         let team_name = self.team_name.ok_or("Team Name Required")?;
         let gw_rank = self.gw_rank.ok_or("GW Rank Required")?;
@@ -156,6 +157,7 @@ impl TeamDataBuilder {
             assman: self.assman,
             transfers: self.transfers,
             chip: self.chip,
+            favourite_club_code: self.favourite_club_code,
         })
     }
 }
@@ -175,6 +177,7 @@ pub struct TeamData {
     pub bench: Vec<PlayerInfo>,
     pub transfers: Vec<TransferInfo>,
     pub chip: Option<Chip>,
+    pub favourite_club_code: Option<i16>,
 }
 
 impl TeamData {
@@ -211,9 +214,13 @@ pub struct TeamRenderer {
     pub chip_box_height: f64,
     pub side_box_height: f64,
     pub side_box_padding: f64,
+    pub badge_size: f64,
     pub transfer_row_image_width: f64,
     pub transfer_row_horizontal_padding: f64,
     pub transfer_row_vertical_padding: f64,
+    /// Draw players as their club's shirt on the pitch rather than photo cards
+    pub shirts: bool,
+    pub theme: Theme,
 }
 
 impl Default for TeamRenderer {
@@ -230,16 +237,35 @@ impl Default for TeamRenderer {
             chip_box_height: 25.0,
             side_box_height: 50.0,
             side_box_padding: 25.0,
+            badge_size: 60.0,
             transfer_row_height: 100,
             transfer_row_image_width: 100.0,
             transfer_row_horizontal_padding: 50.0,
             transfer_row_vertical_padding: 25.0,
+            shirts: false,
+            theme: Theme::default(),
         }
     }
 }
 
 impl TeamRenderer {
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn with_shirts(mut self, shirts: bool) -> Self {
+        self.shirts = shirts;
+        self
+    }
+
     pub async fn render(&self, data: TeamData, path: &str) -> std::io::Result<()> {
+        save_png(&self.render_svg(data)?, path)
+    }
+
+    /// The SVG that `render` rasterises, for callers that can show it as is.
+    pub fn render_svg(&self, data: TeamData) -> std::io::Result<String> {
+        let palette = &self.theme.palette;
         let header_height = self.header_height + self.header_vertical_padding;
         let players_height =
             (4 * self.player_card_height) + (5 * self.player_card_vertical_padding);
@@ -250,7 +276,7 @@ impl TeamRenderer {
         } else {
             0
         };
-        let transfer_rows = ((data.transfers.len() + 1) / 2) as u32;
+        let transfer_rows = data.transfers.len().div_ceil(2) as u32;
         let transfers_height = if !data.transfers.is_empty() {
             ((transfer_rows + 1) * self.transfer_row_height)
                 + (transfer_rows * self.transfer_row_vertical_padding as u32)
@@ -270,28 +296,28 @@ impl TeamRenderer {
             .set("y", 0)
             .set("width", "100%")
             .set("height", header_height)
-            .set("fill", WHITE_COLOUR);
+            .set("fill", palette.background);
 
         let players_background = Rectangle::new()
             .set("x", 0)
             .set("y", header_height)
             .set("width", "100%")
             .set("height", players_height)
-            .set("fill", PITCH_GREEN_COLOUR);
+            .set("fill", palette.pitch);
 
         let bench_background = Rectangle::new()
             .set("x", 0)
             .set("y", header_height + players_height)
             .set("width", "100%")
             .set("height", bench_height)
-            .set("fill", DARK_PITCH_GREEN_COLOUR);
+            .set("fill", palette.bench);
 
         let transfers_background = Rectangle::new()
             .set("x", 0)
             .set("y", players_height + header_height + bench_height)
             .set("width", "100%")
             .set("height", transfers_height)
-            .set("fill", WHITE_COLOUR);
+            .set("fill", palette.background);
 
         document = document
             .add(header_background)
@@ -304,16 +330,7 @@ impl TeamRenderer {
         if !data.transfers.is_empty() {
             document = self.add_transfers(&data, document)?;
         }
-        let svg_string = document.to_string();
-        let mut opt: Options<'_> = Options::default();
-        opt.fontdb_mut().load_system_fonts();
-
-        let tree = Tree::from_str(&svg_string, &opt).unwrap();
-        let size = tree.size();
-        let mut pixmap = Pixmap::new(size.width() as u32, size.height() as u32).unwrap();
-        render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
-
-        std::fs::write(path, pixmap.encode_png().unwrap())
+        Ok(document.to_string())
     }
 
     fn add_player_cards(
@@ -321,6 +338,10 @@ impl TeamRenderer {
         data: &TeamData,
         mut document: Document,
     ) -> Result<Document, std::io::Error> {
+        let image_style = match self.shirts {
+            true => CardImageStyle::Shirt,
+            false => CardImageStyle::Photo,
+        };
         let mut y_offset: u32 = self.header_height + (2 * self.header_vertical_padding);
         for (idx, row) in data.get_player_rows().iter().enumerate() {
             let xs: Vec<u32> =
@@ -336,12 +357,16 @@ impl TeamRenderer {
                 };
                 // TODO: Rethink this clone
                 let mut player_clone = player.clone();
-                let player_card = player_clone.card_opactiy(opacity).to_card_svg(
-                    x_offset,
-                    y_offset,
-                    self.player_card_width,
-                    self.player_card_height,
-                )?;
+                let player_card = player_clone
+                    .theme(&self.theme)
+                    .image_style(image_style)
+                    .card_opactiy(opacity)
+                    .to_card_svg(
+                        x_offset,
+                        y_offset,
+                        self.player_card_width,
+                        self.player_card_height,
+                    )?;
                 document = document.add(player_card);
             }
             y_offset += self.player_card_height + self.player_card_vertical_padding;
@@ -355,6 +380,7 @@ impl TeamRenderer {
         data: &TeamData,
         mut document: Document,
     ) -> Result<Document, std::io::Error> {
+        let palette = &self.theme.palette;
         let rounding = match data.chip {
             Some(_) => CornerRounding::Top,
             None => CornerRounding::All,
@@ -368,8 +394,9 @@ impl TeamRenderer {
                 (self.width as f64 - self.score_box_side_length) / 2.0,
                 (self.header_height as f64 - self.score_box_side_length) / 2.0,
             )
-            .background_color(PURPLE_COLOUR)
-            .font_color(GREEN_COLOUR)
+            .background_color(palette.primary)
+            .font_color(palette.accent)
+            .font_family(self.theme.font_family)
             .font_weight(FontWeight::Bold)
             .corner_rounding(rounding)
             .radius(self.score_box_radius)
@@ -386,8 +413,9 @@ impl TeamRenderer {
                     (self.width as f64 - self.score_box_side_length) / 2.0,
                     self.header_height as f64 - self.chip_box_height,
                 )
-                .background_color(GREEN_COLOUR)
-                .font_color(PURPLE_COLOUR)
+                .background_color(palette.accent)
+                .font_color(palette.on_accent)
+                .font_family(self.theme.font_family)
                 .font_weight(FontWeight::Regular)
                 .corner_rounding(CornerRounding::Bottom)
                 .radius(self.score_box_radius)
@@ -406,8 +434,9 @@ impl TeamRenderer {
             .text(&data.team_name)
             .dimensions(team_name_box_width, self.side_box_height)
             .position(self.side_box_padding, main_box_y)
-            .background_color(WHITE_COLOUR)
-            .font_color(PURPLE_COLOUR)
+            .background_color(palette.background)
+            .font_color(palette.text)
+            .font_family(self.theme.font_family)
             .font_weight(FontWeight::SemiBold)
             .corner_rounding(CornerRounding::All)
             .radius(self.score_box_radius)
@@ -416,6 +445,22 @@ impl TeamRenderer {
 
         document = document.add(team_name_bg).add(team_name_text);
 
+        // FAVOURITE CLUB BADGE, above the team name
+        if let Some(club_code) = data.favourite_club_code {
+            let badge = svg::node::element::Image::new()
+                .set(
+                    "x",
+                    self.side_box_padding + (team_name_box_width - self.badge_size) / 2.0,
+                )
+                .set("y", main_box_y - self.badge_size)
+                .set("width", self.badge_size)
+                .set("height", self.badge_size)
+                .set("href", fpl_common::paths::get_club_badge_path(club_code))
+                .set("preserveAspectRatio", "xMidYMid meet");
+
+            document = document.add(badge);
+        }
+
         // GWXY thing
         let (game_week_bg, game_week_text) = CenteredTextBox::new()
             .text(format!("GW{}", data.game_week))
@@ -424,8 +469,9 @@ impl TeamRenderer {
                 self.side_box_padding + (team_name_box_width * 0.375),
                 sub_box_y,
             )
-            .background_color(PURPLE_COLOUR)
-            .font_color(GREEN_COLOUR)
+            .background_color(palette.primary)
+            .font_color(palette.accent)
+            .font_family(self.theme.font_family)
             .font_weight(FontWeight::Bold)
             .corner_rounding(CornerRounding::All)
             .radius(self.score_box_radius)
@@ -449,8 +495,9 @@ impl TeamRenderer {
             .text(gw_rank_text)
             .dimensions(rank_box_width, self.side_box_height)
             .position(game_week_rank_x, main_box_y)
-            .background_color(WHITE_COLOUR)
-            .font_color(PURPLE_COLOUR)
+            .background_color(palette.background)
+            .font_color(palette.text)
+            .font_family(self.theme.font_family)
             .font_weight(FontWeight::SemiBold)
             .corner_rounding(CornerRounding::All)
             .radius(self.score_box_radius)
@@ -463,8 +510,9 @@ impl TeamRenderer {
             .text("GW Rank".to_string())
             .dimensions(rank_box_width / 2.0, self.side_box_height / 1.75)
             .position(game_week_rank_x + (rank_box_width * 0.25), sub_box_y)
-            .background_color(PURPLE_COLOUR)
-            .font_color(GREEN_COLOUR)
+            .background_color(palette.primary)
+            .font_color(palette.accent)
+            .font_family(self.theme.font_family)
             .font_weight(FontWeight::Bold)
             .corner_rounding(CornerRounding::All)
             .radius(self.score_box_radius)
@@ -481,8 +529,9 @@ impl TeamRenderer {
             .text(data.overall_rank.separate_with_commas())
             .dimensions(rank_box_width, self.side_box_height)
             .position(overall_rank_x, main_box_y)
-            .background_color(WHITE_COLOUR)
-            .font_color(PURPLE_COLOUR)
+            .background_color(palette.background)
+            .font_color(palette.text)
+            .font_family(self.theme.font_family)
             .font_weight(FontWeight::SemiBold)
             .corner_rounding(CornerRounding::All)
             .radius(self.score_box_radius)
@@ -495,8 +544,9 @@ impl TeamRenderer {
             .text("Rank".to_string())
             .dimensions(rank_box_width / 2.0, self.side_box_height / 1.75)
             .position(overall_rank_x + (rank_box_width * 0.25), sub_box_y)
-            .background_color(PURPLE_COLOUR)
-            .font_color(GREEN_COLOUR)
+            .background_color(palette.primary)
+            .font_color(palette.accent)
+            .font_family(self.theme.font_family)
             .font_weight(FontWeight::Bold)
             .corner_rounding(CornerRounding::All)
             .radius(self.score_box_radius)
@@ -515,6 +565,7 @@ impl TeamRenderer {
         data: &TeamData,
         mut document: Document,
     ) -> Result<Document, std::io::Error> {
+        let palette = &self.theme.palette;
         let mut y_offset = self.header_height as f64
             + (5.0 * self.player_card_height as f64)
             + (6.0 * self.player_card_vertical_padding as f64)
@@ -524,8 +575,9 @@ impl TeamRenderer {
             .text("Transfers")
             .dimensions(200.0, self.transfer_row_vertical_padding * 2.0)
             .position(400.0, y_offset)
-            .background_color(PURPLE_COLOUR)
-            .font_color(WHITE_COLOUR)
+            .background_color(palette.primary)
+            .font_color(palette.on_primary)
+            .font_family(self.theme.font_family)
             .font_weight(FontWeight::Bold)
             .corner_rounding(CornerRounding::Bottom)
             .radius(self.score_box_radius)
@@ -566,7 +618,7 @@ impl TeamRenderer {
                         y_offset + (self.transfer_row_height as f64 / 2.0)
                             - self.transfer_row_vertical_padding / 2.0,
                     )
-                    .set("stroke", PURPLE_COLOUR)
+                    .set("stroke", palette.border)
                     .set("stroke-width", 2);
 
                 document = document.add(separator_line);
@@ -605,8 +657,9 @@ impl TeamRenderer {
                     .text(transfer_text)
                     .dimensions(transfer_text_width, self.transfer_row_height as f64 * 0.65)
                     .position(row_x, row_y)
-                    .background_color(WHITE_COLOUR)
-                    .font_color(PURPLE_COLOUR)
+                    .background_color(palette.background)
+                    .font_color(palette.text)
+                    .font_family(self.theme.font_family)
                     .font_weight(FontWeight::Bold)
                     .inner_padding(0.95)
                     .build()?;
@@ -626,8 +679,9 @@ impl TeamRenderer {
                         row_x + (transfer_text_width * 0.15),
                         row_y + (self.transfer_row_height as f64 * 0.65),
                     )
-                    .background_color(PURPLE_COLOUR)
-                    .font_color(GREEN_COLOUR)
+                    .background_color(palette.primary)
+                    .font_color(palette.accent)
+                    .font_family(self.theme.font_family)
                     .font_weight(FontWeight::ExtraBold)
                     .corner_rounding(CornerRounding::All)
                     .build()?;
@@ -0,0 +1,211 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::colours::{
+    BLACK_COLOUR, DARK_PITCH_GREEN_COLOUR, GREEN_COLOUR, GREY_COLOUR, OFF_WHITE_COLOUR,
+    PITCH_GREEN_COLOUR, PURPLE_COLOUR, WHITE_COLOUR,
+};
+use super::fonts::FPL_FONT_NAME;
+
+/// The colours a renderer picks from, named by what they're used for rather than what they are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    /// Title bars, score boxes and active player statuses
+    pub primary: &'static str,
+    /// Text on `primary`
+    pub on_primary: &'static str,
+    /// The caller's table row, dividers and figures on `primary`
+    pub accent: &'static str,
+    /// Text on `accent`
+    pub on_accent: &'static str,
+    pub background: &'static str,
+    /// Every other row of a table
+    pub alternate_background: &'static str,
+    /// Column headings
+    pub header_background: &'static str,
+    /// Text on any of the backgrounds
+    pub text: &'static str,
+    /// Row separators and player card outlines
+    pub border: &'static str,
+    /// Players without a game
+    pub inactive: &'static str,
+    pub pitch: &'static str,
+    pub bench: &'static str,
+}
+
+/// Font sizes in pixels for the table style images. Text in boxes is sized to fit its box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FontSizes {
+    pub title: u32,
+    pub header: u32,
+    pub body: u32,
+    pub detail: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ThemeName {
+    #[default]
+    Light,
+    Dark,
+    HighContrast,
+}
+
+impl ThemeName {
+    pub const ALL: [ThemeName; 3] = [ThemeName::Light, ThemeName::Dark, ThemeName::HighContrast];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThemeName::Light => "light",
+            ThemeName::Dark => "dark",
+            ThemeName::HighContrast => "high_contrast",
+        }
+    }
+
+    pub fn pretty_name(&self) -> &'static str {
+        match self {
+            ThemeName::Light => "Light",
+            ThemeName::Dark => "Dark",
+            ThemeName::HighContrast => "High contrast",
+        }
+    }
+
+    pub fn theme(&self) -> Theme {
+        match self {
+            ThemeName::Light => Theme::light(),
+            ThemeName::Dark => Theme::dark(),
+            ThemeName::HighContrast => Theme::high_contrast(),
+        }
+    }
+}
+
+impl fmt::Display for ThemeName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ThemeName {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ThemeName::ALL
+            .into_iter()
+            .find(|name| name.as_str() == s)
+            .ok_or_else(|| format!("Unknown theme: {}", s))
+    }
+}
+
+/// Everything about how an image looks that isn't its layout. Every renderer has one, set with
+/// `with_theme`, and defaults to `Theme::light`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub name: ThemeName,
+    pub palette: Palette,
+    pub font_family: &'static str,
+    pub font_sizes: FontSizes,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::light()
+    }
+}
+
+impl Theme {
+    /// The FPL purple and green the images have always used.
+    pub fn light() -> Self {
+        Self {
+            name: ThemeName::Light,
+            palette: Palette {
+                primary: PURPLE_COLOUR,
+                on_primary: WHITE_COLOUR,
+                accent: GREEN_COLOUR,
+                on_accent: PURPLE_COLOUR,
+                background: WHITE_COLOUR,
+                alternate_background: OFF_WHITE_COLOUR,
+                header_background: OFF_WHITE_COLOUR,
+                text: PURPLE_COLOUR,
+                border: PURPLE_COLOUR,
+                inactive: GREY_COLOUR,
+                pitch: PITCH_GREEN_COLOUR,
+                bench: DARK_PITCH_GREEN_COLOUR,
+            },
+            font_family: FPL_FONT_NAME,
+            font_sizes: FontSizes {
+                title: 32,
+                header: 28,
+                body: 24,
+                detail: 20,
+            },
+        }
+    }
+
+    pub fn dark() -> Self {
+        Self {
+            name: ThemeName::Dark,
+            palette: Palette {
+                primary: PURPLE_COLOUR,
+                on_primary: WHITE_COLOUR,
+                accent: GREEN_COLOUR,
+                on_accent: PURPLE_COLOUR,
+                background: BLACK_COLOUR,
+                alternate_background: "#1e1e1e",
+                header_background: "#2a2a2a",
+                text: OFF_WHITE_COLOUR,
+                border: "#5b3a78",
+                inactive: "#5c5c5c",
+                pitch: "#3b6e1c",
+                bench: "#284a13",
+            },
+            ..Self::light()
+        }
+    }
+
+    /// Black on white with a yellow accent and slightly larger text, for readability over looks.
+    pub fn high_contrast() -> Self {
+        Self {
+            name: ThemeName::HighContrast,
+            palette: Palette {
+                primary: "#000000",
+                on_primary: WHITE_COLOUR,
+                accent: "#ffd800",
+                on_accent: "#000000",
+                background: WHITE_COLOUR,
+                alternate_background: "#e8e8e8",
+                header_background: "#d0d0d0",
+                text: "#000000",
+                border: "#000000",
+                inactive: "#595959",
+                pitch: "#2e6b12",
+                bench: "#1c420b",
+            },
+            font_sizes: FontSizes {
+                title: 36,
+                header: 30,
+                body: 26,
+                detail: 22,
+            },
+            ..Self::light()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_names_round_trip() {
+        for name in ThemeName::ALL {
+            assert_eq!(name.as_str().parse::<ThemeName>(), Ok(name));
+            assert_eq!(name.theme().name, name);
+        }
+        assert!("solarized".parse::<ThemeName>().is_err());
+    }
+
+    #[test]
+    fn test_light_is_default() {
+        assert_eq!(Theme::default(), Theme::light());
+        assert_eq!(Theme::default().palette.primary, PURPLE_COLOUR);
+    }
+}
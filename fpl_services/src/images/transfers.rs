@@ -1,12 +1,10 @@
-use resvg::{render, usvg};
 use std::collections::HashMap;
 use svg::Document;
-use tiny_skia::Pixmap;
-use usvg::{Options, Tree};
 
-use super::colours::{GREEN_COLOUR, WHITE_COLOUR};
-use super::{calculate_player_card_xs, CenteredTextBox, CornerRounding, FontWeight, PlayerInfo};
-use crate::images::constants::colours::PURPLE_COLOUR;
+use super::{
+    calculate_player_card_xs, save_png, CenteredTextBox, CornerRounding, FontWeight, PlayerInfo,
+    Theme,
+};
 
 #[derive(Debug, Clone)]
 pub struct TransfersKey {
@@ -65,6 +63,7 @@ pub struct TransfersRenderer {
     pub player_row_height: u32,
     pub player_card_width: u32,
     pub internal_vertical_padding: u32,
+    pub theme: Theme,
 }
 
 impl Default for TransfersRenderer {
@@ -76,17 +75,28 @@ impl Default for TransfersRenderer {
             player_row_height: 150,
             player_card_width: 100,
             internal_vertical_padding: 20,
+            theme: Theme::default(),
         }
     }
 }
 
 impl TransfersRenderer {
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
     pub async fn render(&self, data: Transfers, path: &str) -> std::io::Result<()> {
+        save_png(&self.render_svg(data)?, path)
+    }
+
+    /// The SVG that `render` rasterises, for callers that can show it as is.
+    pub fn render_svg(&self, data: Transfers) -> std::io::Result<String> {
         let mut transfers_vec: Vec<(String, Vec<(PlayerInfo, PlayerInfo)>)> =
             data.user_to_transfers.into_iter().collect();
 
         // sort by vec length so team boxes are similar heights
-        transfers_vec.sort_by(|a, b| b.1.len().cmp(&a.1.len()));
+        transfers_vec.sort_by_key(|transfers| std::cmp::Reverse(transfers.1.len()));
 
         let chunked_transfers: Vec<_> = transfers_vec
             .chunks(self.transfer_boxes_per_row as usize)
@@ -100,6 +110,7 @@ impl TransfersRenderer {
             }
         }
 
+        let palette = &self.theme.palette;
         let mut document = Document::new()
             .set("viewBox", (0, 0, self.width, total_height))
             .set("width", self.width)
@@ -108,7 +119,7 @@ impl TransfersRenderer {
         let background = svg::node::element::Rectangle::new()
             .set("width", self.width)
             .set("height", total_height)
-            .set("fill", WHITE_COLOUR);
+            .set("fill", palette.background);
 
         document = document.add(background);
 
@@ -140,8 +151,9 @@ impl TransfersRenderer {
                         self.transfer_box_title_height as f64,
                     )
                     .position(x_offset as f64, y_offset as f64)
-                    .background_color(PURPLE_COLOUR)
-                    .font_color(WHITE_COLOUR)
+                    .background_color(palette.primary)
+                    .font_color(palette.on_primary)
+                    .font_family(self.theme.font_family)
                     .font_weight(FontWeight::Black)
                     .corner_rounding(CornerRounding::None)
                     .inner_padding(0.95)
@@ -165,13 +177,12 @@ impl TransfersRenderer {
                     );
 
                     for (x_offset, player) in player_card_xs.iter().zip(players.iter()) {
-                        let player_card =
-                            (**player).clone().border_color(PURPLE_COLOUR).to_card_svg(
-                                *x_offset,
-                                player_card_y_pos,
-                                self.player_card_width,
-                                player_card_height,
-                            )?;
+                        let player_card = (**player).clone().theme(&self.theme).to_card_svg(
+                            *x_offset,
+                            player_card_y_pos,
+                            self.player_card_width,
+                            player_card_height,
+                        )?;
 
                         document = document.add(player_card);
                     }
@@ -184,8 +195,9 @@ impl TransfersRenderer {
                             text_x as f64,
                             (row_y + self.internal_vertical_padding) as f64,
                         )
-                        .font_color(PURPLE_COLOUR)
-                        .background_color(WHITE_COLOUR)
+                        .font_color(palette.text)
+                        .background_color(palette.background)
+                        .font_family(self.theme.font_family)
                         .font_weight(FontWeight::Bold)
                         .inner_padding(0.6)
                         .build()?;
@@ -202,7 +214,7 @@ impl TransfersRenderer {
                         .set("y1", y_offset)
                         .set("x2", divider_x)
                         .set("y2", y_offset + self.transfer_box_title_height)
-                        .set("stroke", GREEN_COLOUR)
+                        .set("stroke", palette.accent)
                         .set("stroke-width", 2);
 
                     document = document.add(vertical_divider);
@@ -212,17 +224,7 @@ impl TransfersRenderer {
             y_offset += max_transfer_box_y_offset;
         }
 
-        // Convert SVG to PNG
-        let svg_string = document.to_string();
-        let mut opt: Options<'_> = Options::default();
-        opt.fontdb_mut().load_system_fonts();
-
-        let tree: Tree = Tree::from_str(&svg_string, &opt).unwrap();
-        let size = tree.size();
-        let mut pixmap = Pixmap::new(size.width() as u32, size.height() as u32).unwrap();
-        render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
-
-        std::fs::write(path, pixmap.encode_png().unwrap())
+        Ok(document.to_string())
     }
 
     fn calculate_transfer_box_height(
@@ -1,12 +1,7 @@
-use resvg::{render, usvg};
 use svg::node::element::Rectangle;
 use svg::Document;
-use tiny_skia::Pixmap;
-use usvg::{Options, Tree};
 
-use super::colours::{GREY_COLOUR, OFF_WHITE_COLOUR, WHITE_COLOUR};
-use super::{calculate_player_card_xs, PlayerGameInfo, PlayerInfo};
-use crate::images::constants::colours::PURPLE_COLOUR;
+use super::{calculate_player_card_xs, save_png, PlayerGameInfo, PlayerInfo, Theme};
 
 #[derive(Debug, Clone)]
 pub struct UniquePlayers {
@@ -35,15 +30,10 @@ impl UniquePlayers {
     ) -> Self {
         let games = vec![PlayerGameInfo::FreeText(opponents)];
 
-        // Grey them out if they arent playing. Will be active_bg_colour as we are using free text
-        let bg_colour = match multiplier {
-            0 => GREY_COLOUR,
-            _ => PURPLE_COLOUR,
-        };
-
+        // Grey them out if they arent playing
         self.players.push(
             PlayerInfo::new(name, code, games, is_captain, is_vice_captain, true)
-                .status_active_bg_color(bg_colour),
+                .inactive(multiplier == 0),
         );
 
         self
@@ -57,6 +47,7 @@ pub struct UniqueRenderer {
     pub player_card_width: u32,
     pub players_per_row: usize,
     pub internal_vertical_padding: u32,
+    pub theme: Theme,
 }
 
 impl Default for UniqueRenderer {
@@ -67,15 +58,27 @@ impl Default for UniqueRenderer {
             player_card_width: 200,
             players_per_row: 3,
             internal_vertical_padding: 20,
+            theme: Theme::default(),
         }
     }
 }
 
 impl UniqueRenderer {
-    pub async fn render(&self, mut data: UniquePlayers, path: &str) -> std::io::Result<()> {
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub async fn render(&self, data: UniquePlayers, path: &str) -> std::io::Result<()> {
+        save_png(&self.render_svg(data)?, path)
+    }
+
+    /// The SVG that `render` rasterises, for callers that can show it as is.
+    pub fn render_svg(&self, mut data: UniquePlayers) -> std::io::Result<String> {
         let num_rows = (data.players.len() as f32 / self.players_per_row as f32).ceil() as u32;
         let total_height = num_rows * self.player_row_height;
         let player_card_height = self.player_row_height - (2 * self.internal_vertical_padding);
+        let palette = &self.theme.palette;
         let mut document = Document::new()
             .set("viewBox", (0, 0, self.width, total_height))
             .set("width", self.width)
@@ -86,9 +89,9 @@ impl UniqueRenderer {
             let y_pos = index as u32 * self.player_row_height;
             // Row background
             let bg_color = if index % 2 == 0 {
-                WHITE_COLOUR
+                palette.background
             } else {
-                OFF_WHITE_COLOUR
+                palette.alternate_background
             };
             let row_bg = Rectangle::new()
                 .set("x", 0)
@@ -102,7 +105,7 @@ impl UniqueRenderer {
                 .set("y1", y_pos - 1)
                 .set("x2", self.width)
                 .set("y2", y_pos - 1)
-                .set("stroke", PURPLE_COLOUR)
+                .set("stroke", palette.border)
                 .set("stroke-width", 2);
 
             document = document.add(row_bg).add(bottom_border);
@@ -116,7 +119,7 @@ impl UniqueRenderer {
             let player_y_pos = y_pos + self.internal_vertical_padding;
 
             for (x_offset, player) in player_card_xs.iter().zip(players_chunk.iter_mut()) {
-                let player_card = player.border_color(PURPLE_COLOUR).to_card_svg(
+                let player_card = player.theme(&self.theme).to_card_svg(
                     *x_offset,
                     player_y_pos,
                     self.player_card_width,
@@ -126,16 +129,6 @@ impl UniqueRenderer {
             }
         }
 
-        // Convert SVG to PNG
-        let svg_string = document.to_string();
-        let mut opt: Options<'_> = Options::default();
-        opt.fontdb_mut().load_system_fonts();
-
-        let tree = Tree::from_str(&svg_string, &opt).unwrap();
-        let size = tree.size();
-        let mut pixmap = Pixmap::new(size.width() as u32, size.height() as u32).unwrap();
-        render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
-
-        std::fs::write(path, pixmap.encode_png().unwrap())
+        Ok(document.to_string())
     }
 }
@@ -4,6 +4,10 @@ pub mod player_card;
 pub use centered_text_box::*;
 pub use player_card::*;
 
+use resvg::{render, usvg};
+use tiny_skia::Pixmap;
+use usvg::{Options, Tree};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum FontWeight {
     UltraThin,
@@ -49,3 +53,22 @@ pub fn calculate_player_card_xs(
         .map(|i| gap_width + i * (player_card_width + gap_width) + x_offset)
         .collect()
 }
+
+/// Rasterises an SVG using the system fonts into PNG bytes.
+pub fn render_png(svg: &str) -> std::io::Result<Vec<u8>> {
+    let mut opt: Options<'_> = Options::default();
+    opt.fontdb_mut().load_system_fonts();
+
+    let tree = Tree::from_str(svg, &opt).map_err(std::io::Error::other)?;
+    let size = tree.size();
+    let mut pixmap = Pixmap::new(size.width() as u32, size.height() as u32)
+        .ok_or_else(|| std::io::Error::other("SVG has no size"))?;
+    render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    pixmap.encode_png().map_err(std::io::Error::other)
+}
+
+/// Rasterises an SVG using the system fonts and writes it to `path` as a PNG.
+pub fn save_png(svg: &str, path: &str) -> std::io::Result<()> {
+    std::fs::write(path, render_png(svg)?)
+}
@@ -0,0 +1,345 @@
+use std::path::Path;
+
+use svg::node::element::path::Data;
+use svg::node::element::{Group, Rectangle};
+use svg::Node;
+
+use super::super::theme::Theme;
+use super::{CenteredTextBox, CornerRounding};
+
+#[derive(Debug, Clone)]
+pub enum GameStatus {
+    NotPlayed,
+    Played(i16),
+}
+
+#[derive(Debug, Clone)]
+pub enum PlayerGameInfo {
+    Status(GameStatus),
+    Fixture(String),
+    FreeText(String),
+}
+
+impl PlayerGameInfo {
+    pub fn pretty_str(&self) -> String {
+        match self {
+            PlayerGameInfo::Status(status) => match status {
+                GameStatus::NotPlayed => "-".to_string(),
+                GameStatus::Played(points) => format!("{} pts", points),
+            },
+            PlayerGameInfo::Fixture(fixture) => fixture.clone(),
+            PlayerGameInfo::FreeText(text) => text.clone(),
+        }
+    }
+}
+
+/// What a player card shows above the name. Either way it falls back through the player's
+/// photo and club shirt to whichever has been scraped, then to a placeholder shirt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CardImageStyle {
+    /// The player's photo on a card
+    #[default]
+    Photo,
+    /// The club's shirt with no card behind it, the classic FPL pitch view
+    Shirt,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlayerInfo {
+    pub name: String,
+    pub code: u32,
+    pub games: Vec<PlayerGameInfo>,
+    pub captain: bool,
+    pub vice_captain: bool,
+    pub has_fixture: bool,
+    /// Shown with the inactive status colour even when there's text to show, e.g. benched
+    pub inactive: bool,
+    /// `clubs.code` of the player's club, for their shirt
+    pub club_code: Option<u32>,
+    pub goalkeeper: bool,
+
+    // Style properties
+    border_color: String,
+    name_bg_color: String,
+    name_text_color: String,
+    status_active_bg_color: String,
+    status_inactive_bg_color: String,
+    status_text_color: String,
+    font_family: String,
+    image_style: CardImageStyle,
+    opacity: f64,
+}
+
+impl PlayerInfo {
+    pub fn new(
+        name: String,
+        code: u32,
+        games: Vec<PlayerGameInfo>,
+        captain: bool,
+        vice_captain: bool,
+        has_fixture: bool,
+    ) -> Self {
+        let mut player = Self {
+            name,
+            code,
+            games,
+            captain,
+            vice_captain,
+            has_fixture,
+            inactive: false,
+            club_code: None,
+            goalkeeper: false,
+            border_color: String::new(),
+            name_bg_color: String::new(),
+            name_text_color: String::new(),
+            status_active_bg_color: String::new(),
+            status_inactive_bg_color: String::new(),
+            status_text_color: String::new(),
+            font_family: String::new(),
+            image_style: CardImageStyle::default(),
+            opacity: 1.0,
+        };
+        player.theme(&Theme::default());
+        player
+    }
+
+    pub fn inactive(mut self, inactive: bool) -> Self {
+        self.inactive = inactive;
+        self
+    }
+
+    /// Lets the card fall back to the club's shirt, goalkeepers have their own.
+    pub fn club(mut self, club_code: u32, goalkeeper: bool) -> Self {
+        self.club_code = Some(club_code);
+        self.goalkeeper = goalkeeper;
+        self
+    }
+
+    /// Sets every style property from the theme, replacing any set individually.
+    pub fn theme(&mut self, theme: &Theme) -> &mut Self {
+        let palette = &theme.palette;
+        self.border_color = palette.border.to_string();
+        self.name_bg_color = palette.background.to_string();
+        self.name_text_color = palette.text.to_string();
+        self.status_active_bg_color = palette.primary.to_string();
+        self.status_inactive_bg_color = palette.inactive.to_string();
+        self.status_text_color = palette.on_primary.to_string();
+        self.font_family = theme.font_family.to_string();
+        self
+    }
+
+    // Style methods - these return &mut Self so they can be chained
+    pub fn border_color(&mut self, color: impl Into<String>) -> &mut Self {
+        self.border_color = color.into();
+        self
+    }
+
+    pub fn name_bg_color(&mut self, color: impl Into<String>) -> &mut Self {
+        self.name_bg_color = color.into();
+        self
+    }
+
+    pub fn name_text_color(&mut self, color: impl Into<String>) -> &mut Self {
+        self.name_text_color = color.into();
+        self
+    }
+
+    pub fn status_active_bg_color(mut self, color: impl Into<String>) -> Self {
+        self.status_active_bg_color = color.into();
+        self
+    }
+
+    pub fn status_inactive_bg_color(&mut self, color: impl Into<String>) -> &mut Self {
+        self.status_inactive_bg_color = color.into();
+        self
+    }
+
+    pub fn status_text_color(&mut self, color: impl Into<String>) -> &mut Self {
+        self.status_text_color = color.into();
+        self
+    }
+
+    pub fn image_style(&mut self, style: CardImageStyle) -> &mut Self {
+        self.image_style = style;
+        self
+    }
+
+    pub fn card_opactiy(&mut self, opacity: impl Into<f64>) -> &mut Self {
+        self.opacity = opacity.into();
+        self
+    }
+
+    pub fn to_card_svg(
+        &self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<Group, std::io::Error> {
+        let image_height = height * 2 / 3;
+        let text_row_height = (height - image_height) / 2;
+        let border_radius = 8;
+        let stroke_width: f64 = 2.0;
+
+        let mut group = Group::new();
+
+        if !self.has_fixture {
+            group = group.set("opacity", self.opacity);
+        }
+
+        // Semi-transparent border with more visible border
+        let background = Rectangle::new()
+            .set("x", x)
+            .set("y", y)
+            .set("width", width)
+            .set("height", height)
+            .set("rx", border_radius)
+            .set("ry", border_radius)
+            .set("fill", "rgba(27, 12, 12, 0.1)")
+            .set("stroke", self.border_color.as_str())
+            .set("stroke-width", stroke_width);
+
+        // Player image
+        let image: Box<dyn Node> = match self.image_path() {
+            Some(image_path) => Box::new(
+                svg::node::element::Image::new()
+                    .set("x", x + 5)
+                    .set("y", y + 5)
+                    .set("width", width - 10)
+                    .set("height", image_height)
+                    .set("href", image_path)
+                    .set("preserveAspectRatio", "xMidYMid meet"),
+            ),
+            None => Box::new(self.placeholder_svg(x + 5, y + 5, width - 10, image_height)),
+        };
+
+        // TOP ROW: Name
+        let name = match (self.captain, self.vice_captain) {
+            (true, _) => format!("{} (C)", self.name),
+            (_, true) => format!("{} (VC)", self.name),
+            (_, _) => self.name.clone(),
+        };
+
+        let name_y = y + image_height;
+        let (name_bg, name_text) = CenteredTextBox::new()
+            .text(&name)
+            .dimensions(width as f64 - stroke_width, text_row_height.into())
+            .position(x as f64 + (stroke_width / 2.0), name_y.into())
+            .background_color(&self.name_bg_color)
+            .font_color(&self.name_text_color)
+            .font_family(&self.font_family)
+            .build()?;
+
+        // BOTTOM ROW: Game Info (pts or opponent)
+        let status_text = self
+            .games
+            .iter()
+            .map(|game| game.pretty_str())
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        let status_bg_colour = match !self.inactive
+            && self.games.iter().any(|f| {
+                matches!(
+                    f,
+                    PlayerGameInfo::Status(GameStatus::Played(_)) | PlayerGameInfo::FreeText(_)
+                )
+            }) {
+            true => self.status_active_bg_color.clone(),
+            false => self.status_inactive_bg_color.clone(),
+        };
+
+        let status_y = name_y + text_row_height;
+        let (status_bg, status_text) = CenteredTextBox::new()
+            .text(&status_text)
+            .dimensions(width.into(), text_row_height.into())
+            .position(x.into(), status_y.into())
+            .background_color(&status_bg_colour)
+            .font_color(&self.status_text_color)
+            .font_family(&self.font_family)
+            .corner_rounding(CornerRounding::Bottom)
+            .radius(border_radius as f64)
+            .build()?;
+
+        // Assemble all elements in correct order, shirts stand on the pitch without a card
+        group = group.add(status_bg);
+        if self.image_style == CardImageStyle::Photo {
+            group = group.add(background);
+        }
+        Ok(group
+            .add(image)
+            .add(name_bg)
+            .add(name_text)
+            .add(status_text))
+    }
+
+    /// The first image in the fallback chain that's been scraped, `None` if neither has.
+    fn image_path(&self) -> Option<String> {
+        let photo = Some(fpl_common::paths::get_player_image_path(self.code));
+        let shirt = self
+            .club_code
+            .map(|club_code| fpl_common::paths::get_club_shirt_path(club_code, self.goalkeeper));
+        let chain = match self.image_style {
+            CardImageStyle::Photo => [photo, shirt],
+            CardImageStyle::Shirt => [shirt, photo],
+        };
+        chain
+            .into_iter()
+            .flatten()
+            .find(|path| Path::new(path).exists())
+    }
+
+    /// A plain shirt outline in the inactive colour, centred in the image's box.
+    fn placeholder_svg(&self, x: u32, y: u32, width: u32, height: u32) -> svg::node::element::Path {
+        let side = width.min(height) as f64;
+        let left = x as f64 + (width as f64 - side) / 2.0;
+        let top = y as f64 + (height as f64 - side) / 2.0;
+        let point = |px: f64, py: f64| (left + px * side, top + py * side);
+
+        let data = Data::new()
+            .move_to(point(0.30, 0.10))
+            .line_to(point(0.42, 0.16))
+            .line_to(point(0.58, 0.16))
+            .line_to(point(0.70, 0.10))
+            .line_to(point(0.95, 0.28))
+            .line_to(point(0.85, 0.45))
+            .line_to(point(0.75, 0.40))
+            .line_to(point(0.75, 0.95))
+            .line_to(point(0.25, 0.95))
+            .line_to(point(0.25, 0.40))
+            .line_to(point(0.15, 0.45))
+            .line_to(point(0.05, 0.28))
+            .close();
+
+        svg::node::element::Path::new()
+            .set("d", data)
+            .set("fill", self.status_inactive_bg_color.as_str())
+            .set("opacity", 0.6)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholder_without_images() {
+        let mut player = PlayerInfo::new(
+            "Raya".to_string(),
+            u32::MAX,
+            vec![PlayerGameInfo::Fixture("BOU (H)".to_string())],
+            false,
+            false,
+            true,
+        )
+        .club(u32::MAX, true);
+
+        assert_eq!(player.image_path(), None);
+        player.image_style(CardImageStyle::Shirt);
+        assert_eq!(player.image_path(), None);
+
+        let placeholder = player.placeholder_svg(0, 0, 140, 100).to_string();
+        assert!(placeholder.starts_with("<path"));
+        assert!(placeholder.contains(&player.status_inactive_bg_color));
+    }
+}
@@ -0,0 +1,163 @@
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+use fpl_db::models::{GuildMember, MiniLeagueStanding};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use tracing::debug;
+
+// Skim scores grow with the length of the names, so matches are scored as a percentage of the
+// longer name matched against itself. Anything below this is usually two unrelated names sharing
+// a few letters.
+const MIN_MATCH_PERCENT: i64 = 50;
+
+/// A server member paired with the league entry they're most likely to own.
+#[derive(Debug)]
+pub struct ImportMatch<'a> {
+    pub member: &'a GuildMember,
+    pub standing: &'a MiniLeagueStanding,
+}
+
+/// Pairs members with the league entry whose manager or team name is the closest match to their
+/// display name or username. Best matches are taken first so each member and team is used once.
+pub fn match_members_to_standings<'a>(
+    members: &'a [GuildMember],
+    standings: &'a [MiniLeagueStanding],
+) -> Vec<ImportMatch<'a>> {
+    let matcher = SkimMatcherV2::default();
+
+    let mut scored = Vec::new();
+    for (member_index, member) in members.iter().enumerate() {
+        let names = [
+            member.display_name.to_lowercase(),
+            member.username.to_lowercase(),
+        ];
+        for (standing_index, standing) in standings.iter().enumerate() {
+            let choices = [
+                standing.player_name.to_lowercase(),
+                standing.entry_name.to_lowercase(),
+            ];
+            let best = names
+                .iter()
+                .flat_map(|name| {
+                    choices
+                        .iter()
+                        .filter_map(|choice| match_percent(&matcher, choice, name))
+                })
+                .max();
+            if let Some(percent) = best.filter(|percent| *percent >= MIN_MATCH_PERCENT) {
+                scored.push((percent, member_index, standing_index));
+            }
+        }
+    }
+    scored.sort_by_key(|(percent, _, _)| Reverse(*percent));
+
+    let mut used_members = HashSet::new();
+    let mut used_standings = HashSet::new();
+    let mut matches = Vec::new();
+    for (percent, member_index, standing_index) in scored {
+        if used_members.contains(&member_index) || used_standings.contains(&standing_index) {
+            continue;
+        }
+        used_members.insert(member_index);
+        used_standings.insert(standing_index);
+
+        let (member, standing) = (&members[member_index], &standings[standing_index]);
+        debug!(
+            "Matched {} to {} ({}) with {}%",
+            member.display_name, standing.entry_name, standing.player_name, percent
+        );
+        matches.push(ImportMatch { member, standing });
+    }
+    matches
+}
+
+/// How well `name` matches `choice`, out of 100 for the two being the same.
+fn match_percent(matcher: &SkimMatcherV2, choice: &str, name: &str) -> Option<i64> {
+    let score = matcher.fuzzy_match(choice, name)?;
+    let best = matcher
+        .fuzzy_match(choice, choice)
+        .max(matcher.fuzzy_match(name, name))
+        .filter(|best| *best > 0)?;
+    Some(score * 100 / best)
+}
+
+#[cfg(test)]
+mod tests {
+    use fpl_common::types::{LeagueId, TeamId};
+
+    use super::*;
+
+    fn member(discord_id: i64, username: &str, display_name: &str) -> GuildMember {
+        GuildMember::new(
+            1,
+            discord_id,
+            username.to_string(),
+            display_name.to_string(),
+        )
+    }
+
+    fn standing(team_id: i32, player_name: &str, entry_name: &str) -> MiniLeagueStanding {
+        MiniLeagueStanding {
+            id: team_id,
+            event_total: 0,
+            player_name: player_name.to_string(),
+            rank: 1,
+            last_rank: 1,
+            rank_sort: 1,
+            total: 0,
+            team_id: TeamId::new(team_id),
+            entry_name: entry_name.to_string(),
+            has_player: true,
+            league_id: LeagueId::new(500),
+        }
+    }
+
+    fn pairs(matches: &[ImportMatch]) -> Vec<(i64, i32)> {
+        let mut pairs: Vec<_> = matches
+            .iter()
+            .map(|m| (m.member.discord_id, i32::from(m.standing.team_id)))
+            .collect();
+        pairs.sort();
+        pairs
+    }
+
+    #[test]
+    fn test_matches_display_names_and_usernames() {
+        let members = [
+            member(1001, "asmith", "Alice Smith"),
+            member(1002, "bobby_j", "B"),
+        ];
+        let standings = [
+            standing(101, "Alice Smith", "Smith's XI"),
+            standing(102, "Bob Jones", "bobby_j FC"),
+        ];
+
+        let matches = match_members_to_standings(&members, &standings);
+        assert_eq!(pairs(&matches), vec![(1001, 101), (1002, 102)]);
+    }
+
+    #[test]
+    fn test_best_match_takes_the_team() {
+        let members = [
+            member(1001, "alice", "Alice S"),
+            member(1002, "asmith", "Alice Smith"),
+        ];
+        let standings = [standing(101, "Alice Smith", "Team A")];
+
+        let matches = match_members_to_standings(&members, &standings);
+        assert_eq!(pairs(&matches), vec![(1002, 101)]);
+    }
+
+    #[test]
+    fn test_scattered_letters_dont_match() {
+        let members = [member(1001, "christopher", "Christopher")];
+        let standings = [standing(
+            101,
+            "Charlie Pemberton",
+            "Charlie's Top Hero Team",
+        )];
+
+        assert!(match_members_to_standings(&members, &standings).is_empty());
+    }
+}
@@ -0,0 +1,19 @@
+//! The data behind each command, free of any chat platform. Functions take a pool and plain
+//! ids and return render data or Markdown text rows, so the Discord bot, the web dashboard
+//! and anything else can share them.
+pub mod captains;
+pub mod chips;
+pub mod deadline;
+pub mod error;
+pub mod export;
+pub mod hits;
+pub mod images;
+pub mod league_import;
+pub mod notifications;
+pub mod table;
+pub mod team;
+pub mod theme;
+pub mod transfers;
+pub mod whohas;
+
+pub use error::ServiceError;
@@ -0,0 +1,204 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use fpl_db::events::{listen_for_changes, ChangeEvent, CHANGE_EVENTS_CHANNEL};
+use fpl_db::queries::game_week::get_current_game_week_id;
+use fpl_db::queries::notification_state::delete_old_notification_states;
+use sqlx::PgPool;
+use tracing::{debug, error, info, warn};
+
+const FALLBACK_POLL_INTERVAL: Duration = Duration::from_secs(10);
+// Even while listening, poll now and then in case an event was missed
+const RECONCILE_INTERVAL: Duration = Duration::from_secs(120);
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const REDRIVE_INTERVAL: Duration = Duration::from_secs(60);
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(3600);
+
+pub type HandlerError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Something a frontend notifies about. Every handler is given every event and ignores the ones
+/// it doesn't care about.
+#[async_trait]
+pub trait ChangeHandler: Send + Sync {
+    /// Used in logs
+    fn name(&self) -> &'static str;
+
+    async fn handle_event(&self, event: &ChangeEvent) -> Result<(), HandlerError>;
+
+    /// Catches up on anything missed while events weren't arriving. Event only notifications
+    /// have nothing to poll for.
+    async fn poll(&self) -> Result<(), HandlerError> {
+        Ok(())
+    }
+
+    /// Sends again whatever was claimed but never confirmed sent.
+    async fn redrive(&self) -> Result<(), HandlerError> {
+        Ok(())
+    }
+}
+
+/// Reacts to the `ChangeEvent`s the scraper publishes when it upserts players, fixtures and game week
+/// players. Polling only runs every `FALLBACK_POLL_INTERVAL` while the listener is disconnected.
+///
+/// Every `REDRIVE_INTERVAL` each handler re-sends whatever it claimed but never confirmed sent,
+/// and every `CLEANUP_INTERVAL` state from finished game weeks is dropped.
+pub struct ChangeListener {
+    pool: Arc<PgPool>,
+    handlers: Vec<Arc<dyn ChangeHandler>>,
+    connected: AtomicBool,
+    last_poll: Mutex<Option<Instant>>,
+    last_redrive: Mutex<Option<Instant>>,
+    last_cleanup: Mutex<Option<Instant>>,
+}
+
+impl ChangeListener {
+    pub fn new(pool: Arc<PgPool>, handlers: Vec<Arc<dyn ChangeHandler>>) -> Self {
+        Self {
+            pool,
+            handlers,
+            connected: AtomicBool::new(false),
+            last_poll: Mutex::new(None),
+            last_redrive: Mutex::new(None),
+            last_cleanup: Mutex::new(None),
+        }
+    }
+
+    pub fn start(self: Arc<Self>) {
+        info!("Starting change listener");
+
+        let listener = Arc::clone(&self);
+        tokio::spawn(async move { listener.listen().await });
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(FALLBACK_POLL_INTERVAL);
+
+            loop {
+                interval.tick().await;
+                if self.should_poll() {
+                    self.poll().await;
+                }
+                if is_due(&self.last_redrive, REDRIVE_INTERVAL) {
+                    self.redrive().await;
+                }
+                if is_due(&self.last_cleanup, CLEANUP_INTERVAL) {
+                    self.clean_up().await;
+                }
+            }
+        });
+    }
+
+    fn should_poll(&self) -> bool {
+        if !self.connected.load(Ordering::SeqCst) {
+            return true;
+        }
+
+        self.last_poll
+            .lock()
+            .unwrap()
+            .is_none_or(|last_poll| last_poll.elapsed() >= RECONCILE_INTERVAL)
+    }
+
+    async fn poll(&self) {
+        *self.last_poll.lock().unwrap() = Some(Instant::now());
+
+        for handler in &self.handlers {
+            if let Err(e) = handler.poll().await {
+                error!("Error when polling {} notifications: {}", handler.name(), e);
+            }
+        }
+    }
+
+    async fn redrive(&self) {
+        for handler in &self.handlers {
+            if let Err(e) = handler.redrive().await {
+                error!(
+                    "Error when re-sending {} notifications: {}",
+                    handler.name(),
+                    e
+                );
+            }
+        }
+    }
+
+    async fn clean_up(&self) {
+        let deleted = match get_current_game_week_id(&self.pool).await {
+            Ok(game_week_id) => {
+                delete_old_notification_states(&self.pool, game_week_id.into()).await
+            }
+            Err(e) => Err(e),
+        };
+        match deleted {
+            Ok(0) => {}
+            Ok(deleted) => info!("Deleted {} old notification states", deleted),
+            Err(e) => error!("Error when deleting old notification states: {}", e),
+        }
+    }
+
+    async fn listen(&self) {
+        loop {
+            let mut listener = match listen_for_changes(&self.pool).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    error!(
+                        "Failed to listen on {}, polling instead: {}",
+                        CHANGE_EVENTS_CHANNEL, e
+                    );
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                    continue;
+                }
+            };
+            info!("Listening for changes on {}", CHANGE_EVENTS_CHANNEL);
+            self.connected.store(true, Ordering::SeqCst);
+
+            loop {
+                match listener.try_recv().await {
+                    Ok(Some(notification)) => self.handle_payload(notification.payload()).await,
+                    // The connection dropped and sqlx reconnects on the next call, anything sent
+                    // in the meantime is lost so catch up by polling
+                    Ok(None) => {
+                        warn!("Lost connection to {}, catching up", CHANGE_EVENTS_CHANNEL);
+                        self.poll().await;
+                    }
+                    Err(e) => {
+                        error!("Error receiving on {}: {}", CHANGE_EVENTS_CHANNEL, e);
+                        break;
+                    }
+                }
+            }
+
+            self.connected.store(false, Ordering::SeqCst);
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    }
+
+    async fn handle_payload(&self, payload: &str) {
+        let event = match ChangeEvent::from_payload(payload) {
+            Ok(event) => event,
+            Err(e) => {
+                error!("Failed to parse change event {}: {}", payload, e);
+                return;
+            }
+        };
+        debug!("Received {:?}", event);
+
+        for handler in &self.handlers {
+            if let Err(e) = handler.handle_event(&event).await {
+                error!("Error handling {:?} for {}: {}", event, handler.name(), e);
+            }
+        }
+    }
+}
+
+/// Whether `every` has passed since `last`, resetting it if so.
+fn is_due(last: &Mutex<Option<Instant>>, every: Duration) -> bool {
+    let mut last = last.lock().unwrap();
+    if last.is_some_and(|last| last.elapsed() < every) {
+        return false;
+    }
+    *last = Some(Instant::now());
+    true
+}
@@ -0,0 +1,18 @@
+//! Which live changes to notify about, shared by every frontend. The scraper's `ChangeEvent`s
+//! are turned into claimed changes here, and each frontend only formats and sends them, then
+//! marks them sent.
+pub mod listener;
+pub mod points;
+pub mod scores;
+
+use std::time::Duration;
+
+pub use listener::*;
+pub use points::*;
+pub use scores::*;
+
+/// How long a claimed notification can go unconfirmed before `redrive` sends it again. Long
+/// enough for a frontend to work through a burst while rate limited.
+pub const REDRIVE_LEASE: Duration = Duration::from_secs(120);
+/// Sends a claimed notification gets before it's given up on, e.g. for a deleted channel
+pub const MAX_SEND_ATTEMPTS: i16 = 5;
@@ -0,0 +1,432 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use fpl_db::events::ChangeEvent;
+use fpl_db::models::{NotificationKind, NotificationTransition};
+use fpl_db::queries::game_week::get_current_game_week_id;
+use fpl_db::queries::notification_state::{
+    claim_notification, get_notification_state, get_notification_states,
+    reclaim_unsent_notifications, seed_notification_states,
+};
+use sqlx::PgPool;
+use tracing::{debug, info};
+
+use crate::ServiceError;
+
+use super::MAX_SEND_ATTEMPTS;
+
+/// Whose picks make a player worth notifying about, and how they're mentioned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointsAudience {
+    /// Registered Discord users, owners are Discord user ids
+    Discord,
+    /// Registered Matrix users, owners are Matrix user ids
+    Matrix,
+}
+
+struct OwnedPlayer {
+    player_id: i16,
+    web_name: String,
+    code: i32,
+    total_points: i16,
+    owners: Vec<String>,
+}
+
+/// A claimed points change for a player someone in the audience owns, waiting to be sent and
+/// then marked sent with its `transition`.
+#[derive(Debug, Clone)]
+pub struct PointsChange {
+    pub player_id: i16,
+    pub web_name: String,
+    pub code: i32,
+    pub old_points: i16,
+    pub new_points: i16,
+    pub owners: Vec<String>,
+    pub transition: NotificationTransition,
+}
+
+/// Works out which owned players' points a channel hasn't been told about and claims them,
+/// leaving how they're sent to the frontend.
+///
+/// - The last notified points for each player live in notification_state, keyed by channel and
+///   GW, so a restart or a GW change picks up where it left off
+/// - Players with nothing stored for the current GW (a new GW, channel or owner) are seeded
+///   quietly, anything else that's changed is claimed and only returned if the claim was won
+/// - `redrive` hands back claims that were never marked sent
+pub struct PointsChanges {
+    pool: Arc<PgPool>,
+    channel_id: i64,
+    audience: PointsAudience,
+}
+
+impl PointsChanges {
+    pub fn new(pool: Arc<PgPool>, channel_id: i64, audience: PointsAudience) -> Self {
+        Self {
+            pool,
+            channel_id,
+            audience,
+        }
+    }
+
+    fn transition(
+        &self,
+        game_week_id: i16,
+        player_id: i16,
+        previous_points: Option<i16>,
+        points: i16,
+    ) -> NotificationTransition {
+        NotificationTransition {
+            channel_id: self.channel_id,
+            kind: NotificationKind::PlayerPoints,
+            subject_id: player_id.into(),
+            game_week_id,
+            previous_value: previous_points.map(|points| points.to_string()),
+            value: points.to_string(),
+        }
+    }
+
+    /// Players the audience owns in the current game week, or just `player_id` if given.
+    async fn owned_players(
+        &self,
+        player_id: Option<i16>,
+    ) -> Result<Vec<OwnedPlayer>, ServiceError> {
+        let owned = match self.audience {
+            PointsAudience::Discord => sqlx::query!(
+                r#"
+                SELECT
+                    web_name as "web_name!",
+                    code as "code!",
+                    total_points as "total_points!",
+                    player_id as "player_id!",
+                    owners as "owners!"
+                FROM live_owners
+                WHERE $1::smallint IS NULL OR player_id = $1;
+                "#,
+                player_id
+            )
+            .fetch_all(&*self.pool)
+            .await?
+            .into_iter()
+            .map(|row| OwnedPlayer {
+                player_id: row.player_id,
+                web_name: row.web_name,
+                code: row.code,
+                total_points: row.total_points,
+                owners: row.owners.split(',').map(str::to_string).collect(),
+            })
+            .collect(),
+            PointsAudience::Matrix => sqlx::query!(
+                r#"
+                SELECT
+                    p.id as "player_id!",
+                    p.web_name as "web_name!",
+                    p.code as "code!",
+                    gwp.total_points as "total_points!",
+                    ARRAY_AGG(DISTINCT mu.matrix_id ORDER BY mu.matrix_id) as "owners!"
+                FROM players p
+                JOIN game_week_players gwp ON p.id = gwp.player_id
+                JOIN team_game_week_picks tgwp
+                    ON p.id = tgwp.player_id AND gwp.game_week_id = tgwp.game_week_id
+                JOIN matrix_users mu ON tgwp.team_id = mu.team_id
+                WHERE tgwp.game_week_id = (SELECT id FROM current_game_week)
+                    AND ($1::smallint IS NULL OR p.id = $1)
+                GROUP BY p.id, p.web_name, p.code, gwp.total_points;
+                "#,
+                player_id
+            )
+            .fetch_all(&*self.pool)
+            .await?
+            .into_iter()
+            .map(|row| OwnedPlayer {
+                player_id: row.player_id,
+                web_name: row.web_name,
+                code: row.code,
+                total_points: row.total_points,
+                owners: row.owners,
+            })
+            .collect(),
+        };
+        Ok(owned)
+    }
+
+    /// The change a `PlayerPointsUpdated` event brings, if the player is owned and it's one to
+    /// send.
+    pub async fn from_event(
+        &self,
+        event: &ChangeEvent,
+    ) -> Result<Option<PointsChange>, ServiceError> {
+        let ChangeEvent::PlayerPointsUpdated {
+            player_id,
+            game_week_id,
+            previous_points,
+            ..
+        } = *event
+        else {
+            return Ok(None);
+        };
+
+        let current_game_week = i16::from(get_current_game_week_id(&self.pool).await?);
+        if current_game_week != game_week_id {
+            debug!(
+                "Ignoring points change for player {} in GW{}",
+                player_id, game_week_id
+            );
+            return Ok(None);
+        }
+
+        let Some(player) = self.owned_players(Some(player_id)).await?.pop() else {
+            // Nobody owns them
+            return Ok(None);
+        };
+
+        // Claimed against what was last notified, the event's previous points only matter if
+        // nothing has been, and against the latest points in case another change has landed since
+        let stored_points = get_notification_state(
+            &self.pool,
+            self.channel_id,
+            NotificationKind::PlayerPoints,
+            player_id.into(),
+            current_game_week,
+        )
+        .await?
+        .and_then(|points| points.parse::<i16>().ok());
+        let previous_points = stored_points.unwrap_or(previous_points);
+        if previous_points == player.total_points {
+            debug!(
+                "Already notified {} points for player {}",
+                player.total_points, player_id
+            );
+            return Ok(None);
+        }
+
+        let transition = self.transition(
+            current_game_week,
+            player_id,
+            Some(previous_points),
+            player.total_points,
+        );
+        if !claim_notification(&self.pool, &transition).await? {
+            debug!("Already notified {}", transition.idempotency_key());
+            return Ok(None);
+        }
+
+        Ok(Some(PointsChange {
+            player_id,
+            web_name: player.web_name,
+            code: player.code,
+            old_points: previous_points,
+            new_points: player.total_points,
+            owners: player.owners,
+            transition,
+        }))
+    }
+
+    /// Every owned player's change since it was last notified.
+    pub async fn poll(&self) -> Result<Vec<PointsChange>, ServiceError> {
+        let current_game_week = i16::from(get_current_game_week_id(&self.pool).await?);
+        let stored_player_points = get_notification_states(
+            &self.pool,
+            self.channel_id,
+            NotificationKind::PlayerPoints,
+            current_game_week,
+        )
+        .await?;
+
+        info!(
+            "Polling live points. Game Week = [{}], ChannelID=[{}], Tracked Player Count = [{}]",
+            current_game_week,
+            self.channel_id,
+            stored_player_points.len()
+        );
+
+        let mut changes = Vec::new();
+        let mut new_players = Vec::new();
+        for player in self.owned_players(None).await? {
+            let existing_points = stored_player_points
+                .get(&i32::from(player.player_id))
+                .and_then(|points| points.parse::<i16>().ok());
+
+            match existing_points {
+                None => new_players.push(self.transition(
+                    current_game_week,
+                    player.player_id,
+                    None,
+                    player.total_points,
+                )),
+                Some(existing_points) if existing_points != player.total_points => {
+                    let transition = self.transition(
+                        current_game_week,
+                        player.player_id,
+                        Some(existing_points),
+                        player.total_points,
+                    );
+                    if claim_notification(&self.pool, &transition).await? {
+                        changes.push(PointsChange {
+                            player_id: player.player_id,
+                            web_name: player.web_name,
+                            code: player.code,
+                            old_points: existing_points,
+                            new_points: player.total_points,
+                            owners: player.owners,
+                            transition,
+                        });
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+
+        if !new_players.is_empty() {
+            info!(
+                "Seeding points for {} players in GW{} without notifying",
+                new_players.len(),
+                current_game_week
+            );
+            seed_notification_states(&self.pool, &new_players).await?;
+        }
+
+        Ok(changes)
+    }
+
+    /// Claimed changes that haven't been marked sent within `lease`, to send again.
+    pub async fn redrive(&self, lease: Duration) -> Result<Vec<PointsChange>, ServiceError> {
+        let unsent = reclaim_unsent_notifications(
+            &self.pool,
+            &[NotificationKind::PlayerPoints],
+            Some(self.channel_id),
+            lease.as_secs_f64(),
+            MAX_SEND_ATTEMPTS,
+        )
+        .await?;
+
+        let mut changes = Vec::new();
+        for state in unsent {
+            let transition = state.transition().map_err(ServiceError::Invalid)?;
+            let (Some(old_points), Ok(new_points)) = (
+                transition
+                    .previous_value
+                    .as_deref()
+                    .and_then(|points| points.parse::<i16>().ok()),
+                transition.value.parse::<i16>(),
+            ) else {
+                continue;
+            };
+            let player_id = i16::try_from(transition.subject_id)
+                .map_err(|_| ServiceError::Invalid(transition.idempotency_key()))?;
+
+            let (web_name, code, owners) = match self.owned_players(Some(player_id)).await?.pop() {
+                Some(player) => (player.web_name, player.code, player.owners),
+                // Owned when it was claimed, still worth sending without the mentions
+                None => {
+                    let Some(player) = sqlx::query!(
+                        "SELECT web_name, code FROM players WHERE id = $1",
+                        player_id
+                    )
+                    .fetch_optional(&*self.pool)
+                    .await?
+                    else {
+                        continue;
+                    };
+                    (player.web_name, player.code, Vec::new())
+                }
+            };
+
+            changes.push(PointsChange {
+                player_id,
+                web_name,
+                code,
+                old_points,
+                new_points,
+                owners,
+                transition,
+            });
+        }
+
+        Ok(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fpl_db::queries::notification_state::mark_notification_sent;
+
+    use super::*;
+
+    async fn set_points(pool: &PgPool, player_id: i16, total_points: i16) {
+        sqlx::query!(
+            "UPDATE game_week_players SET total_points = $1 WHERE player_id = $2 AND game_week_id = 2",
+            total_points,
+            player_id
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../../fixtures/seed.sql"))]
+    async fn test_poll_seeds_then_claims_changes(pool: PgPool) {
+        let points = PointsChanges::new(Arc::new(pool.clone()), 1, PointsAudience::Discord);
+        assert!(points.poll().await.unwrap().is_empty());
+
+        set_points(&pool, 8, 6).await;
+        let changes = points.poll().await.unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].player_id, 8);
+        assert_eq!((changes[0].old_points, changes[0].new_points), (2, 6));
+        let mut owners = changes[0].owners.clone();
+        owners.sort();
+        assert_eq!(owners, vec!["1001", "1002"]);
+
+        // Already claimed, nothing for another instance
+        assert!(points.poll().await.unwrap().is_empty());
+    }
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../../fixtures/seed.sql"))]
+    async fn test_matrix_audience_only_sees_matrix_owners(pool: PgPool) {
+        let points = PointsChanges::new(Arc::new(pool.clone()), -1, PointsAudience::Matrix);
+        assert!(points.poll().await.unwrap().is_empty());
+        set_points(&pool, 8, 6).await;
+        assert!(points.poll().await.unwrap().is_empty());
+
+        sqlx::query!(
+            "INSERT INTO matrix_users (matrix_id, team_id) VALUES ('@alice:localhost', 101)"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        // New owners are seeded first, then notified
+        assert!(points.poll().await.unwrap().is_empty());
+        set_points(&pool, 8, 7).await;
+        let changes = points.poll().await.unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].owners, vec!["@alice:localhost"]);
+    }
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../../fixtures/seed.sql"))]
+    async fn test_from_event_and_redrive(pool: PgPool) {
+        let points = PointsChanges::new(Arc::new(pool.clone()), 1, PointsAudience::Discord);
+        set_points(&pool, 8, 6).await;
+
+        let event = ChangeEvent::PlayerPointsUpdated {
+            player_id: 8,
+            game_week_id: 2,
+            previous_points: 2,
+            total_points: 6,
+        };
+        let change = points.from_event(&event).await.unwrap().unwrap();
+        assert_eq!((change.old_points, change.new_points), (2, 6));
+        assert!(points.from_event(&event).await.unwrap().is_none());
+
+        let unsent = points.redrive(Duration::ZERO).await.unwrap();
+        assert_eq!(unsent.len(), 1);
+        assert_eq!(
+            unsent[0].transition.idempotency_key(),
+            change.transition.idempotency_key()
+        );
+
+        mark_notification_sent(&pool, &unsent[0].transition)
+            .await
+            .unwrap();
+        assert!(points.redrive(Duration::ZERO).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,435 @@
+use std::collections::{hash_map::Entry, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use fpl_db::events::ChangeEvent;
+use fpl_db::models::{NotificationKind, NotificationTransition};
+use fpl_db::queries::notification_state::{
+    claim_notification, get_notification_state, get_notification_states,
+    reclaim_unsent_notifications, seed_notification_states,
+};
+use sqlx::PgPool;
+use tracing::{debug, info};
+
+use crate::ServiceError;
+
+use super::MAX_SEND_ATTEMPTS;
+
+struct LiveFixture {
+    id: i16,
+    game_week_id: i16,
+    minutes: i16,
+    home_team_score: i16,
+    away_team_score: i16,
+    home_team_name: String,
+    away_team_name: String,
+}
+
+/// A claimed kick off or score change, waiting to be sent and then marked sent with its
+/// `transition`.
+#[derive(Debug, Clone)]
+pub struct ScoreChange {
+    pub home_team: String,
+    pub away_team: String,
+    /// `None` for kick off
+    pub previous_score: Option<(i16, i16)>,
+    pub score: (i16, i16),
+    pub transition: NotificationTransition,
+}
+
+impl ScoreChange {
+    pub fn is_kick_off(&self) -> bool {
+        self.previous_score.is_none()
+    }
+
+    pub fn home_team_scored(&self) -> bool {
+        self.previous_score
+            .is_some_and(|(home, _)| home != self.score.0)
+    }
+
+    pub fn away_team_scored(&self) -> bool {
+        self.previous_score
+            .is_some_and(|(_, away)| away != self.score.1)
+    }
+}
+
+/// Works out which live fixture scores a channel hasn't been told about and claims them, leaving
+/// how they're sent to the frontend.
+///
+/// - The last notified score for each fixture lives in notification_state, keyed by channel, so a
+///   restart mid-match neither misses a kick off nor resends a goal
+/// - Live fixtures (started and not finished) are compared against the stored scores. Nothing
+///   stored means kick off, a different score means a score change
+/// - Every change is claimed first and only returned if the claim was won, so other instances
+///   don't send it too. The claim only wins if the stored score is still the previous one
+/// - `redrive` hands back claims that were never marked sent
+pub struct ScoreChanges {
+    pool: Arc<PgPool>,
+    channel_id: i64,
+}
+
+impl ScoreChanges {
+    pub fn new(pool: Arc<PgPool>, channel_id: i64) -> Self {
+        Self { pool, channel_id }
+    }
+
+    fn transition(
+        &self,
+        fixture: &LiveFixture,
+        previous_score: Option<(i16, i16)>,
+    ) -> NotificationTransition {
+        NotificationTransition {
+            channel_id: self.channel_id,
+            kind: NotificationKind::FixtureScore,
+            subject_id: fixture.id.into(),
+            game_week_id: fixture.game_week_id,
+            previous_value: previous_score.map(|(home, away)| score_value(home, away)),
+            value: score_value(fixture.home_team_score, fixture.away_team_score),
+        }
+    }
+
+    /// Claims the change from `previous_score` to the fixture's score, returning it if this
+    /// instance won the claim.
+    async fn claim(
+        &self,
+        fixture: LiveFixture,
+        previous_score: Option<(i16, i16)>,
+    ) -> Result<Option<ScoreChange>, ServiceError> {
+        let score = (fixture.home_team_score, fixture.away_team_score);
+        if previous_score == Some(score) {
+            return Ok(None);
+        }
+
+        let transition = self.transition(&fixture, previous_score);
+
+        // Can have a delay so only send kick off notifs if started is set to true in the first half.
+        // However sometimes FPL set finished = False way after the game is done, so this check
+        // ensures that notifications arent sent twice, one at the start and one hours later
+        if previous_score.is_none() && fixture.minutes >= 45 {
+            seed_notification_states(&self.pool, &[transition]).await?;
+            return Ok(None);
+        }
+
+        if !claim_notification(&self.pool, &transition).await? {
+            debug!("Already notified {}", transition.idempotency_key());
+            return Ok(None);
+        }
+
+        Ok(Some(ScoreChange {
+            home_team: fixture.home_team_name,
+            away_team: fixture.away_team_name,
+            previous_score,
+            score,
+            transition,
+        }))
+    }
+
+    /// The change a `FixtureUpdated` event brings, if it's one to send.
+    pub async fn from_event(
+        &self,
+        event: &ChangeEvent,
+    ) -> Result<Option<ScoreChange>, ServiceError> {
+        let ChangeEvent::FixtureUpdated {
+            fixture_id,
+            started,
+            finished,
+            was_started,
+            previous_home_team_score,
+            previous_away_team_score,
+            ..
+        } = *event
+        else {
+            return Ok(None);
+        };
+
+        if !started || finished {
+            return Ok(None);
+        }
+
+        let Some(fixture) = sqlx::query_as!(
+            LiveFixture,
+            r#"
+            SELECT
+                f.id as "id!",
+                f.game_week_id as "game_week_id!",
+                f.minutes as "minutes!",
+                COALESCE(f.home_team_score, 0::smallint) as "home_team_score!",
+                COALESCE(f.away_team_score, 0::smallint) as "away_team_score!",
+                home_club.name AS "home_team_name!",
+                away_club.name AS "away_team_name!"
+            FROM
+                fixtures f
+            JOIN
+                clubs home_club ON f.home_team_id = home_club.id
+            JOIN
+                clubs away_club ON f.away_team_id = away_club.id
+            WHERE f.id = $1 AND f.game_week_id IS NOT NULL;
+            "#,
+            fixture_id
+        )
+        .fetch_optional(&*self.pool)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        // What was last notified is what the claim is checked against, the event's previous
+        // score only matters if nothing has been
+        let stored_score = get_notification_state(
+            &self.pool,
+            self.channel_id,
+            NotificationKind::FixtureScore,
+            fixture.id.into(),
+            fixture.game_week_id,
+        )
+        .await?;
+        let previous_score = match stored_score {
+            Some(score) => parse_score_value(&score),
+            None => was_started.then(|| {
+                (
+                    previous_home_team_score.unwrap_or_default(),
+                    previous_away_team_score.unwrap_or_default(),
+                )
+            }),
+        };
+
+        self.claim(fixture, previous_score).await
+    }
+
+    /// Every live fixture's change since it was last notified.
+    pub async fn poll(&self) -> Result<Vec<ScoreChange>, ServiceError> {
+        let live_fixtures = sqlx::query_as!(
+            LiveFixture,
+            r#"
+            SELECT
+                f.id as "id!",
+                f.game_week_id as "game_week_id!",
+                f.minutes as "minutes!",
+                f.home_team_score as "home_team_score!",
+                f.away_team_score as "away_team_score!",
+                home_club.name AS "home_team_name!",
+                away_club.name AS "away_team_name!"
+            FROM
+                fixtures f
+            JOIN
+                clubs home_club ON f.home_team_id = home_club.id
+            JOIN
+                clubs away_club ON f.away_team_id = away_club.id
+            WHERE f.started = true AND f.finished = false AND f.game_week_id IS NOT NULL;
+            "#
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        info!(
+            "Polling live scores. ChannelID=[{}], Live Fixture Count = [{}]",
+            self.channel_id,
+            live_fixtures.len()
+        );
+
+        let mut stored_scores: HashMap<i16, HashMap<i32, String>> = HashMap::new();
+        for game_week_id in live_fixtures.iter().map(|f| f.game_week_id) {
+            if let Entry::Vacant(entry) = stored_scores.entry(game_week_id) {
+                entry.insert(
+                    get_notification_states(
+                        &self.pool,
+                        self.channel_id,
+                        NotificationKind::FixtureScore,
+                        game_week_id,
+                    )
+                    .await?,
+                );
+            }
+        }
+
+        let mut changes = Vec::new();
+        for fixture in live_fixtures {
+            let previous_score = stored_scores
+                .get(&fixture.game_week_id)
+                .and_then(|scores| scores.get(&i32::from(fixture.id)))
+                .and_then(|score| parse_score_value(score));
+
+            if let Some(change) = self.claim(fixture, previous_score).await? {
+                changes.push(change);
+            }
+        }
+
+        Ok(changes)
+    }
+
+    /// Claimed changes that haven't been marked sent within `lease`, to send again.
+    pub async fn redrive(&self, lease: Duration) -> Result<Vec<ScoreChange>, ServiceError> {
+        let unsent = reclaim_unsent_notifications(
+            &self.pool,
+            &[NotificationKind::FixtureScore],
+            Some(self.channel_id),
+            lease.as_secs_f64(),
+            MAX_SEND_ATTEMPTS,
+        )
+        .await?;
+
+        let mut changes = Vec::new();
+        for state in unsent {
+            let transition = state.transition().map_err(ServiceError::Invalid)?;
+            let Some(score) = parse_score_value(&transition.value) else {
+                continue;
+            };
+            let fixture_id = i16::try_from(transition.subject_id)
+                .map_err(|_| ServiceError::Invalid(transition.idempotency_key()))?;
+            let Some(fixture) = sqlx::query!(
+                r#"
+                SELECT
+                    home_club.name AS "home_team_name!",
+                    away_club.name AS "away_team_name!"
+                FROM
+                    fixtures f
+                JOIN
+                    clubs home_club ON f.home_team_id = home_club.id
+                JOIN
+                    clubs away_club ON f.away_team_id = away_club.id
+                WHERE f.id = $1;
+                "#,
+                fixture_id
+            )
+            .fetch_optional(&*self.pool)
+            .await?
+            else {
+                continue;
+            };
+
+            changes.push(ScoreChange {
+                home_team: fixture.home_team_name,
+                away_team: fixture.away_team_name,
+                previous_score: transition
+                    .previous_value
+                    .as_deref()
+                    .and_then(parse_score_value),
+                score,
+                transition,
+            });
+        }
+
+        Ok(changes)
+    }
+}
+
+fn score_value(home_team_score: i16, away_team_score: i16) -> String {
+    format!("{}-{}", home_team_score, away_team_score)
+}
+
+fn parse_score_value(value: &str) -> Option<(i16, i16)> {
+    let (home, away) = value.split_once('-')?;
+    Some((home.parse().ok()?, away.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use fpl_db::queries::notification_state::mark_notification_sent;
+
+    use super::*;
+
+    async fn kick_off(pool: &PgPool) {
+        sqlx::query!("UPDATE fixtures SET minutes = 10 WHERE id = 2")
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    async fn score(pool: &PgPool, home_team_score: i16, away_team_score: i16) {
+        sqlx::query!(
+            "UPDATE fixtures SET home_team_score = $1, away_team_score = $2 WHERE id = 2",
+            home_team_score,
+            away_team_score
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../../fixtures/seed.sql"))]
+    async fn test_poll_claims_kick_off_then_goals_once(pool: PgPool) {
+        kick_off(&pool).await;
+        let scores = ScoreChanges::new(Arc::new(pool.clone()), 1);
+
+        let changes = scores.poll().await.unwrap();
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].is_kick_off());
+        assert_eq!(changes[0].home_team, "Chelsea");
+        mark_notification_sent(&pool, &changes[0].transition)
+            .await
+            .unwrap();
+        assert!(scores.poll().await.unwrap().is_empty());
+
+        score(&pool, 0, 1).await;
+        let changes = scores.poll().await.unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].previous_score, Some((0, 0)));
+        assert!(!changes[0].home_team_scored());
+        assert!(changes[0].away_team_scored());
+
+        // Another instance, or the event for the same goal, loses the claim
+        let other = ScoreChanges::new(Arc::new(pool.clone()), 1);
+        assert!(other.poll().await.unwrap().is_empty());
+    }
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../../fixtures/seed.sql"))]
+    async fn test_late_kick_off_is_seeded(pool: PgPool) {
+        let scores = ScoreChanges::new(Arc::new(pool.clone()), 1);
+        assert!(scores.poll().await.unwrap().is_empty());
+
+        score(&pool, 1, 0).await;
+        let changes = scores.poll().await.unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].previous_score, Some((0, 0)));
+    }
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../../fixtures/seed.sql"))]
+    async fn test_from_event_uses_the_stored_score(pool: PgPool) {
+        kick_off(&pool).await;
+        let scores = ScoreChanges::new(Arc::new(pool.clone()), 1);
+        let kicked_off = scores.poll().await.unwrap();
+        mark_notification_sent(&pool, &kicked_off[0].transition)
+            .await
+            .unwrap();
+
+        score(&pool, 2, 0).await;
+        // The event only saw the second goal, the channel was last told 0-0
+        let event = ChangeEvent::FixtureUpdated {
+            fixture_id: 2,
+            started: true,
+            finished: false,
+            minutes: 60,
+            home_team_score: Some(2),
+            away_team_score: Some(0),
+            was_started: true,
+            previous_home_team_score: Some(1),
+            previous_away_team_score: Some(0),
+        };
+        let change = scores.from_event(&event).await.unwrap().unwrap();
+        assert_eq!(change.previous_score, Some((0, 0)));
+        assert_eq!(change.score, (2, 0));
+    }
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../../fixtures/seed.sql"))]
+    async fn test_redrive_returns_unsent_claims(pool: PgPool) {
+        kick_off(&pool).await;
+        let scores = ScoreChanges::new(Arc::new(pool.clone()), 1);
+        assert_eq!(scores.poll().await.unwrap().len(), 1);
+
+        let changes = scores.redrive(Duration::ZERO).await.unwrap();
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].is_kick_off());
+        assert_eq!(changes[0].away_team, "Arsenal");
+
+        mark_notification_sent(&pool, &changes[0].transition)
+            .await
+            .unwrap();
+        assert!(scores.redrive(Duration::ZERO).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_score_value_round_trips() {
+        assert_eq!(parse_score_value(&score_value(2, 1)), Some((2, 1)));
+        assert_eq!(parse_score_value("nonsense"), None);
+    }
+}
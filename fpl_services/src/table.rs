@@ -0,0 +1,151 @@
+use std::cmp::Reverse;
+use std::str::FromStr;
+
+use fpl_common::types::LeagueId;
+use sqlx::{FromRow, PgPool};
+
+use crate::images::TableData;
+use crate::ServiceError;
+
+#[derive(FromRow)]
+pub struct LivePoints {
+    pub player_first_name: String,
+    pub player_last_name: String,
+    pub name: String,
+    pub discord_id: Option<i64>,
+    pub week_points: i16,
+    pub calculated_week_points: i64,
+    pub overall_points: i16,
+    pub calculated_overall_points: i64,
+}
+
+/// Which points a league table is ranked by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableView {
+    Overall,
+    CurrentGameWeek,
+}
+
+impl TableView {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TableView::Overall => "Overall",
+            TableView::CurrentGameWeek => "Current Gameweek",
+        }
+    }
+}
+
+impl FromStr for TableView {
+    type Err = ServiceError;
+
+    // The labels are what the /table autocomplete offers and what live tables are stored with
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Overall" => Ok(TableView::Overall),
+            "Current Gameweek" => Ok(TableView::CurrentGameWeek),
+            _ => Err(ServiceError::Invalid(format!("Unknown table view {s}"))),
+        }
+    }
+}
+
+/// Sorts the live points and builds the table, highlighting `caller` if they're in it.
+pub fn build_table_data(
+    mut live_points: Vec<LivePoints>,
+    view: TableView,
+    caller: Option<i64>,
+) -> TableData {
+    live_points.sort_by_key(|lp| {
+        Reverse(match view {
+            TableView::Overall => lp.calculated_overall_points,
+            TableView::CurrentGameWeek => lp.calculated_week_points,
+        })
+    });
+
+    let title = format!("{} League Standings", view.label());
+    let mut data: TableData = TableData::new(title);
+    for lp in live_points {
+        let is_caller = lp.discord_id.is_some() && lp.discord_id == caller;
+        let (points, live_points) = match view {
+            TableView::Overall => (lp.overall_points, lp.calculated_overall_points),
+            TableView::CurrentGameWeek => (lp.week_points, lp.calculated_week_points),
+        };
+        data.add_row(
+            lp.name,
+            format!("{} {}", lp.player_first_name, lp.player_last_name),
+            points as u16,
+            live_points as u16,
+            is_caller,
+        );
+    }
+
+    data
+}
+
+pub async fn get_points(
+    pool: &PgPool,
+    league_id: LeagueId,
+) -> Result<Vec<LivePoints>, ServiceError> {
+    Ok(sqlx::query_as!(
+        LivePoints,
+        r#"
+        SELECT
+            player_first_name as "player_first_name!",
+            player_last_name as "player_last_name!",
+            name as "name!",
+            discord_id,
+            week_points as "week_points!",
+            calculated_week_points as "calculated_week_points!",
+            overall_points as "overall_points!",
+            calculated_overall_points as "calculated_overall_points!"
+        FROM live_points
+        WHERE team_id IN (
+            SELECT team_id
+            FROM mini_league_standings
+            WHERE league_id = $1
+        )
+        "#,
+        i32::from(league_id)
+    )
+    .fetch_all(pool)
+    .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../fixtures/seed.sql"))]
+    async fn test_overall_table_ranks_by_live_points(pool: PgPool) {
+        let live_points = get_points(&pool, LeagueId::new(500)).await.unwrap();
+        let data = build_table_data(live_points, TableView::Overall, Some(1002));
+
+        assert_eq!(data.title, "Overall League Standings");
+        let rows: Vec<_> = data
+            .rows
+            .iter()
+            .map(|row| {
+                (
+                    row.name.as_str(),
+                    row.confirmed_points,
+                    row.live_points,
+                    row.caller,
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("Alice FC", 150, 143, false),
+                ("Bob United", 140, 138, true)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_table_view_round_trips_its_label() {
+        for view in [TableView::Overall, TableView::CurrentGameWeek] {
+            assert_eq!(TableView::from_str(view.label()).unwrap(), view);
+        }
+        assert!(TableView::from_str("week").is_err());
+    }
+}
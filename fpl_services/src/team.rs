@@ -0,0 +1,358 @@
+use std::{collections::HashMap, str::FromStr};
+
+use fpl_common::types::{Chip, GameWeekId, PlayerPosition};
+use fpl_db::queries::game_week::get_current_game_week;
+use sqlx::PgPool;
+
+use crate::images::{
+    GameStatus, PlayerGameInfo, PlayerInfo, TeamData, TeamDataBuilder, TransferInfo,
+};
+use crate::ServiceError;
+
+/// Builds a team sheet for a game week that's already passed its deadline, with live points
+/// if it's the current one.
+pub async fn get_team_data(
+    pool: &PgPool,
+    team_id: i32,
+    game_week_id: i16,
+) -> Result<TeamData, ServiceError> {
+    let mut data = TeamData::builder();
+    data = get_basic_team_data(pool, team_id, game_week_id, data).await?;
+    data = get_player_data(pool, team_id, game_week_id, data).await?;
+    data = get_transfers_data(pool, team_id, game_week_id, data).await?;
+    data.build()
+        .map_err(|e| ServiceError::Invalid(e.to_string()))
+}
+
+#[derive(sqlx::FromRow)]
+struct TeamQueryResult {
+    team_name: String,
+    gw_rank: Option<i32>,
+    overall_rank: i32,
+    chip: Option<String>,
+    points: Option<i16>,
+    favourite_club_code: Option<i16>,
+}
+
+async fn get_basic_team_data(
+    pool: &PgPool,
+    team_id: i32,
+    game_week: i16,
+    mut team_data: TeamDataBuilder,
+) -> Result<TeamDataBuilder, ServiceError> {
+    let current_gw = get_current_game_week(pool).await?.id;
+    let is_current = i16::from(current_gw) == game_week;
+
+    let result = if is_current {
+        sqlx::query_as!(
+            TeamQueryResult,
+            r#"
+            SELECT t.name AS team_name, 
+                   tgw.rank AS gw_rank, 
+                   tgw.overall_rank AS overall_rank, 
+                   tgw.active_chip AS chip, 
+                   lp.calculated_week_points::smallint AS points,
+                   c.code AS "favourite_club_code?"
+            FROM team_game_weeks tgw
+            JOIN teams t ON t.id = tgw.team_id
+            JOIN live_points lp ON lp.team_id = t.id
+            LEFT JOIN clubs c ON c.id = t.favourite_team
+            WHERE tgw.game_week_id = $1
+            AND t.id = $2
+            LIMIT 1;
+            "#,
+            game_week,
+            team_id
+        )
+        .fetch_one(pool)
+        .await?
+    } else {
+        sqlx::query_as!(
+            TeamQueryResult,
+            r#"
+            SELECT t.name AS team_name, 
+                   tgw.rank AS gw_rank, 
+                   tgw.overall_rank AS overall_rank, 
+                   tgw.active_chip AS chip, 
+                   tgw.points AS points,
+                   c.code AS "favourite_club_code?"
+            FROM team_game_weeks tgw
+            JOIN teams t ON t.id = tgw.team_id
+            LEFT JOIN clubs c ON c.id = t.favourite_team
+            WHERE tgw.game_week_id = $1
+            AND t.id = $2
+            LIMIT 1;
+            "#,
+            game_week,
+            team_id
+        )
+        .fetch_one(pool)
+        .await?
+    };
+
+    team_data = team_data
+        .points(result.points.unwrap_or_default() as i64)
+        .team_name(result.team_name)
+        .gw_rank(result.gw_rank.unwrap_or_default() as i64)
+        .overall_rank(result.overall_rank.into())
+        .game_week(GameWeekId::new(game_week).map_err(|e| ServiceError::Invalid(e.to_string()))?);
+
+    if let Some(club_code) = result.favourite_club_code {
+        team_data = team_data.favourite_club(club_code);
+    }
+
+    if let Some(chip_str) = result.chip {
+        if let Ok(chip) = Chip::from_str(chip_str.as_str()) {
+            team_data = team_data.add_chip(chip);
+        }
+    }
+
+    Ok(team_data)
+}
+
+async fn get_player_data(
+    pool: &PgPool,
+    team_id: i32,
+    game_week: i16,
+    mut team_data: TeamDataBuilder,
+) -> Result<TeamDataBuilder, ServiceError> {
+    let mut results = sqlx::query!(
+        r#"
+        WITH combined_player_fixtures AS (
+            SELECT
+                ph.player_id,
+                ph.fixture_id,
+                ph.was_home AS is_home,
+                f.game_week_id
+            FROM player_history ph
+            JOIN fixtures f ON ph.fixture_id = f.id AND f.started = true
+            UNION ALL
+            SELECT
+                pf.player_id,
+                pf.fixture_id,
+                pf.is_home,
+                f.game_week_id
+            FROM player_fixtures pf
+            JOIN fixtures f ON pf.fixture_id = f.id AND f.started = false
+        )
+        SELECT
+            p.web_name as name,
+            p.code as code,
+            p.team_code as club_code,
+            CASE
+                WHEN bwc.bonus = 0 AND bwc.bps > 0 THEN gwp.total_points + bwc.calculated_bonus
+                ELSE gwp.total_points
+            END as "points!",
+            tgwp.is_captain as captain,
+            tgwp.is_vice_captain as vice_captain,
+            tgwp.multiplier as multiplier,
+            tgwp.position as "position!",
+            tgwp.element_type as player_position,
+            COALESCE(f.started, false) as "started!",
+            gwp.minutes as minutes,
+            tgwp.player_id as player_id,
+            coalesce(c.short_name, 'N/A') as "short_name!",
+            COALESCE(cpf.is_home, false) as "is_home!",
+            CASE WHEN cpf.player_id IS NULL THEN false ELSE true END as "has_fixture!"
+        FROM team_game_week_picks tgwp
+        JOIN game_week_players gwp ON tgwp.player_id = gwp.player_id AND gwp.game_week_id = tgwp.game_week_id
+        JOIN players p ON gwp.player_id = p.id
+        LEFT JOIN combined_player_fixtures cpf ON cpf.player_id = tgwp.player_id AND cpf.game_week_id = tgwp.game_week_id
+        LEFT JOIN fixtures f ON f.id = cpf.fixture_id
+        LEFT JOIN clubs c ON c.id = CASE WHEN cpf.is_home THEN f.home_team_id ELSE f.away_team_id END
+        LEFT JOIN bonus_with_calculated bwc ON f.id = bwc.fixture_id AND p.id = bwc.player_id
+        WHERE tgwp.team_id = $1 AND tgwp.game_week_id = $2;
+        "#,
+        team_id,
+        game_week
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut player_games: HashMap<i16, Vec<PlayerGameInfo>> = HashMap::new();
+
+    for result in &results {
+        let player_game_info = if !result.has_fixture {
+            PlayerGameInfo::FreeText("-".to_string())
+        } else if result.started {
+            if result.minutes == 0 && result.position != 16 {
+                PlayerGameInfo::Status(GameStatus::NotPlayed)
+            } else {
+                let heuristic_multiplier = if result.position <= 15 && result.position >= 12 {
+                    1
+                } else {
+                    result.multiplier
+                };
+                PlayerGameInfo::Status(GameStatus::Played(
+                    result.points as i16 * heuristic_multiplier,
+                ))
+            }
+        } else {
+            let home_or_away = if result.is_home { "H" } else { "A" };
+            PlayerGameInfo::Fixture(format!("{} ({})", result.short_name, home_or_away))
+        };
+
+        player_games
+            .entry(result.player_id)
+            .or_default()
+            .push(player_game_info);
+    }
+
+    // POST PROCESS: If all of their games have been played, combine into 1 entry
+    for games in player_games.values_mut() {
+        if games.len() > 1 {
+            // Check if all entries are GameStatus::Played
+            let all_played = games
+                .iter()
+                .all(|g| matches!(g, PlayerGameInfo::Status(GameStatus::Played(_))));
+
+            if all_played {
+                let points = match games.first() {
+                    Some(PlayerGameInfo::Status(GameStatus::Played(p))) => *p,
+                    _ => continue,
+                };
+
+                games.clear();
+                games.push(PlayerGameInfo::Status(GameStatus::Played(points)));
+            }
+        }
+    }
+    results.sort_by_key(|r| r.player_id);
+    results.dedup_by_key(|r| r.player_id);
+
+    let mut bench_players = Vec::new();
+
+    for result in results {
+        let game_info = player_games
+            .get(&result.player_id)
+            .expect("Player should exist in games map");
+
+        let position = PlayerPosition::from_str(result.player_position.as_str());
+        let goalkeeper = matches!(position, Ok(PlayerPosition::Goalkeeper));
+        let player_info = PlayerInfo::new(
+            result.name,
+            result.code as u32,
+            game_info.clone(),
+            result.captain,
+            result.vice_captain,
+            result.has_fixture,
+        )
+        .club(result.club_code as u32, goalkeeper);
+
+        match result.position {
+            // Playing team
+            1..=11 => match position {
+                Ok(position) => match position {
+                    PlayerPosition::Goalkeeper => {
+                        team_data = team_data.goalkeeper(player_info);
+                    }
+                    PlayerPosition::Defender => {
+                        team_data = team_data.add_defender(player_info);
+                    }
+                    PlayerPosition::Midfielder => {
+                        team_data = team_data.add_midfielder(player_info);
+                    }
+                    PlayerPosition::Attacker => {
+                        team_data = team_data.add_forward(player_info);
+                    }
+                    PlayerPosition::Manager => {
+                        team_data = team_data.add_manager(player_info);
+                    }
+                },
+                Err(e) => return Err(ServiceError::Invalid(e.to_string())),
+            },
+            // Bench - collect for later
+            12..=15 => {
+                bench_players.push((result.position, player_info));
+            }
+            16 => {
+                team_data = team_data.add_manager(player_info);
+            }
+            _ => {
+                return Err(ServiceError::Invalid(
+                    "Position > 16 on team game week pick!".to_string(),
+                ));
+            }
+        }
+    }
+
+    // Sort bench players by position and add them in order
+    bench_players.sort_by_key(|(pos, _)| *pos);
+    for (_, player_info) in bench_players {
+        team_data = team_data.add_bench_player(player_info);
+    }
+
+    Ok(team_data)
+}
+
+/// Adds the team's transfers for the game week to a team sheet that's being built.
+pub async fn get_transfers_data(
+    pool: &PgPool,
+    team_id: i32,
+    game_week: i16,
+    mut team_data: TeamDataBuilder,
+) -> Result<TeamDataBuilder, ServiceError> {
+    let transfers: Vec<TransferInfo> = sqlx::query_as!(
+        TransferInfo,
+        r#"
+    SELECT 
+        player_in.web_name as "player_in_name!",
+        player_in.code as "player_in_code!",
+        (t.player_in_cost::float8 / 10) as "player_in_cost!",
+        player_out.web_name as "player_out_name!",
+        player_out.code as "player_out_code!",
+        (t.player_out_cost::float8 / 10) as "player_out_cost!"
+    FROM 
+        transfers t
+        LEFT JOIN players player_in ON t.player_in_id = player_in.id
+        LEFT JOIN players player_out ON t.player_out_id = player_out.id
+        WHERE t.game_week_id = $1 and t.team_id = $2;
+    "#,
+        game_week,
+        team_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for transfer in transfers {
+        team_data = team_data.add_transfer(transfer);
+    }
+
+    Ok(team_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(players: &[PlayerInfo]) -> Vec<&str> {
+        players.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../fixtures/seed.sql"))]
+    async fn test_team_sheet_lines_up_picks(pool: PgPool) {
+        let data = get_team_data(&pool, 101, 2).await.unwrap();
+
+        assert_eq!(data.team_name, "Alice FC");
+        assert_eq!(data.chip, Some(Chip::TripleCaptain));
+        assert_eq!(data.goalkeeper.name, "Player1");
+        assert_eq!(data.defenders.len(), 4);
+        assert_eq!(data.midfielders.len(), 4);
+        assert_eq!(names(&data.forwards), vec!["Player13", "Player14"]);
+        assert!(data.forwards[0].captain);
+        // Bench keeps pick order, with the new signing last
+        assert_eq!(
+            names(&data.bench),
+            vec!["Player2", "Player7", "Player12", "Player16"]
+        );
+        assert_eq!(data.transfers.len(), 1);
+        assert_eq!(data.transfers[0].player_in_name, "Player16");
+        assert_eq!(data.transfers[0].player_out_name, "Player15");
+    }
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../fixtures/seed.sql"))]
+    async fn test_team_sheet_for_missing_game_week_is_not_found(pool: PgPool) {
+        let result = get_team_data(&pool, 101, 3).await;
+        assert!(matches!(result, Err(ServiceError::NotFound(_))));
+    }
+}
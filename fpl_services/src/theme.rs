@@ -0,0 +1,59 @@
+use fpl_db::queries::theme::get_theme_preference;
+use sqlx::PgPool;
+
+use crate::images::{Theme, ThemeName};
+use crate::ServiceError;
+
+/// The theme to render with for a user in a server. A user's own choice wins over the
+/// server's, and anything unset or no longer known is the default light theme.
+pub async fn get_theme(
+    pool: &PgPool,
+    discord_id: Option<i64>,
+    guild_id: Option<i64>,
+) -> Result<Theme, ServiceError> {
+    let theme = get_theme_preference(pool, discord_id, guild_id)
+        .await?
+        .and_then(|name| name.parse::<ThemeName>().ok())
+        .unwrap_or_default();
+    Ok(theme.theme())
+}
+
+#[cfg(test)]
+mod tests {
+    use fpl_db::queries::theme::{delete_user_theme, upsert_guild_theme, upsert_user_theme};
+
+    use super::*;
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../fixtures/seed.sql"))]
+    async fn test_user_theme_overrides_guild_theme(pool: PgPool) {
+        let theme = get_theme(&pool, Some(1), Some(10)).await.unwrap();
+        assert_eq!(theme.name, ThemeName::Light);
+
+        upsert_guild_theme(&pool, 10, ThemeName::HighContrast.as_str())
+            .await
+            .unwrap();
+        let theme = get_theme(&pool, Some(1), Some(10)).await.unwrap();
+        assert_eq!(theme.name, ThemeName::HighContrast);
+
+        upsert_user_theme(&pool, 1, ThemeName::Dark.as_str())
+            .await
+            .unwrap();
+        let theme = get_theme(&pool, Some(1), Some(10)).await.unwrap();
+        assert_eq!(theme.name, ThemeName::Dark);
+        let theme = get_theme(&pool, Some(2), Some(10)).await.unwrap();
+        assert_eq!(theme.name, ThemeName::HighContrast);
+        let theme = get_theme(&pool, Some(1), None).await.unwrap();
+        assert_eq!(theme.name, ThemeName::Dark);
+
+        assert!(delete_user_theme(&pool, 1).await.unwrap());
+        let theme = get_theme(&pool, Some(1), Some(10)).await.unwrap();
+        assert_eq!(theme.name, ThemeName::HighContrast);
+    }
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../fixtures/seed.sql"))]
+    async fn test_unknown_theme_is_light(pool: PgPool) {
+        upsert_user_theme(&pool, 1, "sepia").await.unwrap();
+        let theme = get_theme(&pool, Some(1), None).await.unwrap();
+        assert_eq!(theme, Theme::light());
+    }
+}
@@ -0,0 +1,119 @@
+use fpl_common::types::PlayerPosition;
+use sqlx::PgPool;
+
+use crate::images::{PlayerGameInfo, PlayerInfo, Transfers, TransfersKey};
+use crate::ServiceError;
+
+/// Each team's transfers in a game week, with how the players in and out have done.
+pub async fn get_transfers(
+    pool: &PgPool,
+    team_ids: &[i32],
+    game_week_id: i16,
+) -> Result<Transfers, ServiceError> {
+    let records = sqlx::query!(
+        r#"
+        SELECT 
+            teams.player_first_name as "user_first_name",
+            teams.player_last_name as "user_last_name",
+            teams.name,
+            player_in.web_name as "player_in_name!",
+            player_in.code as "player_in_code!",
+            player_in.team_code as "player_in_club_code!",
+            player_in.element_type as "player_in_element_type!",
+            po_in.opponents as "player_in_opponents",
+            gwp_in.total_points as "player_in_points",
+            gwp_in.minutes as "player_in_minutes",
+            player_out.web_name as "player_out_name!",
+            player_out.code as "player_out_code!",
+            player_out.team_code as "player_out_club_code!",
+            player_out.element_type as "player_out_element_type!",
+            po_out.opponents as "player_out_opponents",
+            gwp_out.total_points as "player_out_points",
+            gwp_out.minutes as "player_out_minutes"
+        FROM 
+            transfers t
+            join teams on teams.id = t.team_id 
+            LEFT JOIN players player_in ON t.player_in_id = player_in.id
+            LEFT JOIN players player_out ON t.player_out_id = player_out.id
+            left join player_opponents po_in on po_in.player_id = player_in.id and po_in.game_week_id = $1
+            left join player_opponents po_out on po_out.player_id = player_out.id and po_out.game_week_id = $1
+            LEFT JOIN game_week_players gwp_in  ON player_in.id = gwp_in.player_id and gwp_in.game_week_id  = $1
+   	        LEFT JOIN game_week_players gwp_out  ON player_out.id = gwp_out.player_id and gwp_out.game_week_id  = $1
+            WHERE t.game_week_id = $1 and t.team_id = ANY($2);
+        "#,
+        game_week_id,
+        team_ids
+    ).fetch_all(pool).await?;
+
+    let mut transfers = Transfers::new();
+
+    for row in records {
+        let key = TransfersKey {
+            team_name: row.name,
+            user_first_name: row.user_first_name,
+            user_last_name: row.user_last_name,
+        };
+
+        let player_in_text = match row.player_in_minutes {
+            0 => row.player_in_opponents.unwrap_or("N/A".to_string()),
+            _ => row.player_in_points.to_string(),
+        };
+
+        // Grey them out if they arent playing
+        let transfer_in = PlayerInfo::new(
+            row.player_in_name,
+            row.player_in_code as u32,
+            vec![PlayerGameInfo::FreeText(player_in_text)],
+            false,
+            false,
+            true,
+        )
+        .inactive(row.player_in_minutes == 0)
+        .club(
+            row.player_in_club_code as u32,
+            row.player_in_element_type == PlayerPosition::Goalkeeper.to_i16(),
+        );
+
+        let player_out_text = match row.player_out_minutes {
+            0 => row.player_out_opponents.unwrap_or("N/A".to_string()),
+            _ => row.player_out_points.to_string(),
+        };
+
+        // Grey them out if they arent playing
+        let transfer_out = PlayerInfo::new(
+            row.player_out_name,
+            row.player_out_code as u32,
+            vec![PlayerGameInfo::FreeText(player_out_text)],
+            false,
+            false,
+            true,
+        )
+        .inactive(row.player_out_minutes == 0)
+        .club(
+            row.player_out_club_code as u32,
+            row.player_out_element_type == PlayerPosition::Goalkeeper.to_i16(),
+        );
+
+        transfers = transfers.add_transfer(key, transfer_out, transfer_in);
+    }
+
+    Ok(transfers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../fixtures/seed.sql"))]
+    async fn test_transfers_grouped_by_manager(pool: PgPool) {
+        let transfers = get_transfers(&pool, &[101, 102], 2).await.unwrap();
+
+        assert_eq!(transfers.user_to_transfers.len(), 1);
+        let pairs = &transfers.user_to_transfers["Alice Smith (Alice FC)"];
+        let names: Vec<_> = pairs
+            .iter()
+            .map(|(a, b)| (a.name.as_str(), b.name.as_str()))
+            .collect();
+        assert_eq!(names, vec![("Player15", "Player16")]);
+    }
+}
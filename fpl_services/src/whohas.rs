@@ -0,0 +1,206 @@
+use std::collections::{BTreeMap, HashMap};
+
+use fpl_common::types::{ClubId, GameWeekId, LeagueId, PlayerId};
+use sqlx::PgPool;
+
+use crate::ServiceError;
+
+/// Who in the league owns the player in the game week, split into captained, vice captained
+/// and regular picks.
+pub async fn get_whohas_player(
+    pool: &PgPool,
+    league_id: LeagueId,
+    game_week_id: GameWeekId,
+    player_id: PlayerId,
+) -> Result<Vec<String>, ServiceError> {
+    let whohas = sqlx::query!(
+        r#"
+        SELECT
+            mls.player_name,
+            mls.entry_name,
+            tgwp.is_captain,
+            tgwp.is_vice_captain,
+            tgwp.multiplier
+        FROM team_game_week_picks tgwp
+        JOIN mini_league_standings mls ON tgwp.team_id = mls.team_id
+        WHERE tgwp.game_week_id = $1
+        AND tgwp.player_id = $2
+        AND mls.league_id = $3
+        ORDER BY mls.rank_sort
+        "#,
+        i16::from(game_week_id),
+        i16::from(player_id),
+        i32::from(league_id)
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let player_name = sqlx::query!(
+        "SELECT web_name FROM players WHERE id = $1",
+        i16::from(player_id)
+    )
+    .fetch_one(pool)
+    .await?
+    .web_name;
+
+    if whohas.is_empty() {
+        return Ok(vec![format!(
+            "No one has {player_name} in **GW{game_week_id}**."
+        )]);
+    }
+
+    // Group by (is_captain, is_vice_captian) ->
+    // - (true, _) = captained
+    // - (_, true) = VC
+    // - (_, _) = other
+    type CaptainsBools = (bool, bool);
+    type WhoHasRows = Vec<(String, String, i16)>;
+    let mut grouped: HashMap<CaptainsBools, WhoHasRows> = HashMap::new();
+    for row in whohas {
+        grouped
+            .entry((row.is_captain, row.is_vice_captain))
+            .or_default()
+            .push((row.player_name, row.entry_name, row.multiplier));
+    }
+
+    let mut result = vec![format!(
+        "**__{player_name} owners in GW{game_week_id}__**\n"
+    )];
+
+    let group_order = [
+        ((true, false), "**__Captained__**"),
+        ((false, true), "**__Vice Captained__**"),
+        ((false, false), "**__Regular__**"),
+    ];
+
+    for (key, label) in group_order.iter() {
+        if let Some(group) = grouped.get(key) {
+            result.push(label.to_string());
+            for (player_name, team_name, multiplier) in group {
+                let status = match multiplier {
+                    0 => "(Benched)",
+                    3 => "(Triple Captain)",
+                    _ => "",
+                };
+                result.push(format!("- **{}** ({}) {}", player_name, team_name, status));
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Which of the club's players are owned in the league in the game week, and by whom.
+pub async fn get_whohas_club(
+    pool: &PgPool,
+    league_id: LeagueId,
+    game_week_id: GameWeekId,
+    club_id: ClubId,
+) -> Result<Vec<String>, ServiceError> {
+    let whohas = sqlx::query!(
+        r#"
+        SELECT
+            mls.player_name,
+            mls.entry_name,
+            p.web_name
+        FROM team_game_week_picks tgwp
+        JOIN mini_league_standings mls ON tgwp.team_id = mls.team_id
+        JOIN players p ON tgwp.player_id = p.id
+        WHERE tgwp.game_week_id = $1
+        AND p.team = $2
+        AND mls.league_id = $3
+        ORDER BY web_name ASC, mls.rank_sort
+        "#,
+        i16::from(game_week_id),
+        i16::from(club_id),
+        i32::from(league_id)
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let club_name = sqlx::query!("SELECT name FROM clubs WHERE id = $1", i16::from(club_id))
+        .fetch_one(pool)
+        .await?
+        .name;
+
+    if whohas.is_empty() {
+        return Ok(vec![format!(
+            "No one has anyone from {club_name} in **GW{game_week_id}**."
+        )]);
+    }
+
+    let mut player_users = BTreeMap::<String, Vec<(String, String)>>::new();
+    for row in whohas {
+        player_users
+            .entry(row.web_name)
+            .or_default()
+            .push((row.player_name, row.entry_name));
+    }
+
+    let mut result = vec![format!("**__{club_name} assets in GW{game_week_id}__**\n")];
+    result.extend(player_users.into_iter().map(|(player, users)| {
+        let users_formatted = users
+            .into_iter()
+            .map(|(user_name, team_name)| format!("{user_name} ({team_name})"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("- **{}:** {}", player, users_formatted)
+    }));
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../fixtures/seed.sql"))]
+    async fn test_whohas_player_groups_by_armband(pool: PgPool) {
+        let game_week_id = GameWeekId::new(2).unwrap();
+        let league_id = LeagueId::new(500);
+
+        let rows = get_whohas_player(&pool, league_id, game_week_id, PlayerId::new(8))
+            .await
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                "**__Player8 owners in GW2__**\n",
+                "**__Vice Captained__**",
+                "- **Alice Smith** (Alice FC) ",
+                "- **Bob Jones** (Bob United) ",
+            ]
+        );
+
+        let rows = get_whohas_player(&pool, league_id, game_week_id, PlayerId::new(15))
+            .await
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                "**__Player15 owners in GW2__**\n",
+                "**__Regular__**",
+                "- **Bob Jones** (Bob United) (Benched)",
+            ]
+        );
+    }
+
+    #[sqlx::test(migrations = "../fpl_db/migrations", fixtures("../fixtures/seed.sql"))]
+    async fn test_whohas_club(pool: PgPool) {
+        let rows = get_whohas_club(
+            &pool,
+            LeagueId::new(500),
+            GameWeekId::new(2).unwrap(),
+            ClubId::try_from(2).unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(rows[0], "**__Chelsea assets in GW2__**\n");
+        assert_eq!(
+            rows[1],
+            "- **Player10:** Alice Smith (Alice FC), Bob Jones (Bob United)"
+        );
+        // Only Alice brought in Player16
+        assert!(rows.contains(&"- **Player16:** Alice Smith (Alice FC)".to_string()));
+    }
+}
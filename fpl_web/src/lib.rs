@@ -0,0 +1,34 @@
+pub mod error;
+pub mod pages;
+
+use std::sync::Arc;
+
+use axum::routing::get;
+use axum::Router;
+use fpl_common::paths::{get_club_image_dir, get_player_image_dir};
+use sqlx::PgPool;
+use tower_http::services::ServeDir;
+
+/// Where the player photos referenced by the rendered SVGs are served from.
+pub const PLAYER_IMAGES_ROUTE: &str = "/images/players";
+/// Where the club shirts and badges in the rendered SVGs are served from.
+pub const CLUB_IMAGES_ROUTE: &str = "/images/clubs";
+
+#[derive(Clone)]
+pub struct AppState {
+    pub pool: Arc<PgPool>,
+}
+
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/", get(pages::index))
+        .route("/leagues/{league_id}", get(pages::league))
+        .route(
+            "/leagues/{league_id}/transfers",
+            get(pages::league_transfers),
+        )
+        .route("/teams/{team_id}", get(pages::team))
+        .nest_service(PLAYER_IMAGES_ROUTE, ServeDir::new(get_player_image_dir()))
+        .nest_service(CLUB_IMAGES_ROUTE, ServeDir::new(get_club_image_dir()))
+        .with_state(state)
+}
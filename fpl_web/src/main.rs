@@ -0,0 +1,39 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use fpl_web::{router, AppState};
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use tracing::info;
+
+const DEFAULT_BIND_ADDRESS: &str = "127.0.0.1:8081";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .init();
+
+    dotenv::from_filename("../.env").ok();
+    let database_url =
+        std::env::var("DATABASE_URL").expect("DATABASE_URL must be set in .env file");
+    let bind_address =
+        std::env::var("WEB_BIND_ADDRESS").unwrap_or_else(|_| DEFAULT_BIND_ADDRESS.to_string());
+
+    let options = PgConnectOptions::from_str(&database_url)?.application_name("fpl_web");
+    let pool = PgPoolOptions::new()
+        .max_connections(10)
+        .acquire_timeout(Duration::from_secs(5))
+        .connect_with(options)
+        .await?;
+
+    let app = router(AppState {
+        pool: Arc::new(pool),
+    });
+
+    let listener = tokio::net::TcpListener::bind(&bind_address).await?;
+    info!("Dashboard listening on {}", bind_address);
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
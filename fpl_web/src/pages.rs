@@ -0,0 +1,268 @@
+use askama::Template;
+use axum::extract::{Path, Query, State};
+use axum::response::Html;
+use fpl_common::paths::{get_club_image_dir, get_player_image_dir};
+use fpl_common::types::{GameWeekId, LeagueId};
+use fpl_db::models::{MiniLeague, MiniLeagueStanding};
+use fpl_db::queries::game_week::get_current_game_week_id;
+use fpl_db::queries::live_table::get_game_week_fixture_progress;
+use fpl_db::queries::mini_league::{
+    get_mini_league, get_mini_league_standings, get_mini_leagues, get_team_ids_from_league_id,
+};
+use fpl_services::images::{TableRenderer, TeamRenderer, TransfersRenderer};
+use fpl_services::table::{build_table_data, get_points, TableView};
+use fpl_services::team::get_team_data;
+use fpl_services::transfers::get_transfers;
+use serde::Deserialize;
+use sqlx::PgPool;
+
+use crate::error::WebError;
+use crate::{AppState, CLUB_IMAGES_ROUTE, PLAYER_IMAGES_ROUTE};
+
+// Anything bigger than this is a public league nobody wants a team sheet link for
+const MAX_LISTED_MANAGERS: i64 = 200;
+
+#[derive(Deserialize)]
+pub struct LeagueQuery {
+    /// `week` for the current game week's table, anything else is overall
+    view: Option<String>,
+}
+
+impl LeagueQuery {
+    fn is_week(&self) -> bool {
+        self.view.as_deref() == Some("week")
+    }
+
+    fn table_view(&self) -> TableView {
+        match self.is_week() {
+            true => TableView::CurrentGameWeek,
+            false => TableView::Overall,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct GameWeekQuery {
+    game_week: Option<i16>,
+}
+
+#[derive(Template)]
+#[template(path = "index.html")]
+struct IndexTemplate {
+    live: bool,
+    leagues: Vec<MiniLeague>,
+}
+
+#[derive(Template)]
+#[template(path = "league.html")]
+struct LeagueTemplate {
+    live: bool,
+    league: MiniLeague,
+    is_week: bool,
+    game_week_id: GameWeekId,
+    table: String,
+    managers: Vec<MiniLeagueStanding>,
+}
+
+#[derive(Template)]
+#[template(path = "team.html")]
+struct TeamTemplate {
+    live: bool,
+    team_id: i32,
+    team_name: String,
+    game_week_id: GameWeekId,
+    previous_game_week: Option<i16>,
+    next_game_week: Option<i16>,
+    team: String,
+}
+
+#[derive(Template)]
+#[template(path = "transfers.html")]
+struct TransfersTemplate {
+    live: bool,
+    league: MiniLeague,
+    game_week_id: GameWeekId,
+    previous_game_week: Option<i16>,
+    next_game_week: Option<i16>,
+    transfers: Option<String>,
+}
+
+/// The renderers point `<image>`s at player photos and club shirts and badges on disk, swap those
+/// for the routes that serve them.
+pub fn for_browser(svg: String) -> String {
+    svg.replace(
+        &format!("{}/", get_player_image_dir()),
+        &format!("{PLAYER_IMAGES_ROUTE}/"),
+    )
+    .replace(
+        &format!("{}/", get_club_image_dir()),
+        &format!("{CLUB_IMAGES_ROUTE}/"),
+    )
+}
+
+/// Pages showing the current game week refresh themselves while any of its fixtures are in
+/// play.
+async fn is_live(pool: &PgPool, game_week_id: GameWeekId) -> Result<bool, WebError> {
+    if game_week_id != get_current_game_week_id(pool).await? {
+        return Ok(false);
+    }
+    let (live, _) = get_game_week_fixture_progress(pool, game_week_id).await?;
+    Ok(live > 0)
+}
+
+async fn find_league(pool: &PgPool, league_id: i32) -> Result<MiniLeague, WebError> {
+    get_mini_league(pool, LeagueId::new(league_id))
+        .await?
+        .ok_or_else(|| WebError::NotFound(format!("League {league_id} isn't tracked")))
+}
+
+/// The requested game week if it's valid and has started, otherwise the current one, along
+/// with the neighbouring game weeks that can be navigated to.
+async fn resolve_game_week(
+    pool: &PgPool,
+    game_week: Option<i16>,
+) -> Result<(GameWeekId, Option<i16>, Option<i16>), WebError> {
+    let current = get_current_game_week_id(pool).await?;
+    let game_week_id = match game_week.map(GameWeekId::new) {
+        None => current,
+        Some(Ok(game_week_id)) if game_week_id <= current => game_week_id,
+        Some(_) => {
+            return Err(WebError::NotFound(format!(
+                "Gameweek {} isn't available yet",
+                game_week.unwrap_or_default()
+            )))
+        }
+    };
+
+    let game_week = i16::from(game_week_id);
+    let previous = (game_week > GameWeekId::MIN).then_some(game_week - 1);
+    let next = (game_week_id < current).then_some(game_week + 1);
+    Ok((game_week_id, previous, next))
+}
+
+pub async fn index(State(state): State<AppState>) -> Result<Html<String>, WebError> {
+    let leagues = get_mini_leagues(&state.pool).await?;
+    let live = is_live(&state.pool, get_current_game_week_id(&state.pool).await?).await?;
+    Ok(Html(IndexTemplate { live, leagues }.render()?))
+}
+
+pub async fn league(
+    State(state): State<AppState>,
+    Path(league_id): Path<i32>,
+    Query(query): Query<LeagueQuery>,
+) -> Result<Html<String>, WebError> {
+    let league = find_league(&state.pool, league_id).await?;
+    let game_week_id = get_current_game_week_id(&state.pool).await?;
+
+    let live_points = get_points(&state.pool, league.id).await?;
+    let data = build_table_data(live_points, query.table_view(), None);
+    let table = for_browser(TableRenderer::default().render_svg(data)?);
+    let managers =
+        get_mini_league_standings(&state.pool, league.id, MAX_LISTED_MANAGERS, 0).await?;
+
+    Ok(Html(
+        LeagueTemplate {
+            live: is_live(&state.pool, game_week_id).await?,
+            league,
+            is_week: query.is_week(),
+            game_week_id,
+            table,
+            managers,
+        }
+        .render()?,
+    ))
+}
+
+pub async fn league_transfers(
+    State(state): State<AppState>,
+    Path(league_id): Path<i32>,
+    Query(query): Query<GameWeekQuery>,
+) -> Result<Html<String>, WebError> {
+    let league = find_league(&state.pool, league_id).await?;
+    let (game_week_id, previous_game_week, next_game_week) =
+        resolve_game_week(&state.pool, query.game_week).await?;
+
+    let team_ids = get_team_ids_from_league_id(&state.pool, league.id).await?;
+    let data = get_transfers(&state.pool, &team_ids, i16::from(game_week_id)).await?;
+    let transfers = match data.user_to_transfers.is_empty() {
+        true => None,
+        false => Some(for_browser(TransfersRenderer::default().render_svg(data)?)),
+    };
+
+    Ok(Html(
+        TransfersTemplate {
+            live: is_live(&state.pool, game_week_id).await?,
+            league,
+            game_week_id,
+            previous_game_week,
+            next_game_week,
+            transfers,
+        }
+        .render()?,
+    ))
+}
+
+pub async fn team(
+    State(state): State<AppState>,
+    Path(team_id): Path<i32>,
+    Query(query): Query<GameWeekQuery>,
+) -> Result<Html<String>, WebError> {
+    let (game_week_id, previous_game_week, next_game_week) =
+        resolve_game_week(&state.pool, query.game_week).await?;
+
+    let data = get_team_data(&state.pool, team_id, i16::from(game_week_id)).await?;
+    let team_name = data.team_name.clone();
+    let team = for_browser(TeamRenderer::default().render_svg(data)?);
+
+    Ok(Html(
+        TeamTemplate {
+            live: is_live(&state.pool, game_week_id).await?,
+            team_id,
+            team_name,
+            game_week_id,
+            previous_game_week,
+            next_game_week,
+            team,
+        }
+        .render()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use fpl_common::paths::{get_club_badge_path, get_club_shirt_path, get_player_image_path};
+
+    use super::*;
+
+    #[test]
+    fn test_for_browser_serves_player_images() {
+        let svg = format!(r#"<image href="{}"/>"#, get_player_image_path(12345));
+        assert_eq!(
+            for_browser(svg),
+            r#"<image href="/images/players/12345.png"/>"#
+        );
+    }
+
+    #[test]
+    fn test_for_browser_serves_club_images() {
+        let svg = format!(
+            r#"<image href="{}"/><image href="{}"/>"#,
+            get_club_shirt_path(3, true),
+            get_club_badge_path(3)
+        );
+        assert_eq!(
+            for_browser(svg),
+            r#"<image href="/images/clubs/shirts/3_gk.png"/><image href="/images/clubs/badges/3.png"/>"#
+        );
+    }
+
+    #[test]
+    fn test_league_view_defaults_to_overall() {
+        let query = |view: Option<&str>| LeagueQuery {
+            view: view.map(str::to_string),
+        };
+        assert_eq!(query(None).table_view(), TableView::Overall);
+        assert_eq!(query(Some("nonsense")).table_view(), TableView::Overall);
+        assert_eq!(query(Some("week")).table_view(), TableView::CurrentGameWeek);
+    }
+}
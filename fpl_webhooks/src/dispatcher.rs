@@ -0,0 +1,500 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use fpl_api::retry::RetryPolicy;
+use fpl_db::events::{listen_for_changes, ChangeEvent, CHANGE_EVENTS_CHANNEL};
+use fpl_db::models::{DeliveryStatus, DueWebhookDelivery};
+use fpl_db::queries::webhook::{
+    claim_due_webhook_deliveries, get_subscribed_webhooks, insert_webhook_delivery,
+    record_webhook_delivery_attempt,
+};
+use futures::StreamExt;
+use reqwest::{redirect, StatusCode, Url};
+use sqlx::PgPool;
+use tokio::sync::Notify;
+use tracing::{debug, error, info, warn};
+
+use crate::error::WebhookError;
+use crate::payload::WebhookPayload;
+use crate::signing::{sign, DELIVERY_HEADER, EVENT_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+use crate::target::{check_target, PublicResolver};
+
+/// Attempts before a delivery is given up on and marked failed.
+pub const MAX_ATTEMPTS: i16 = 8;
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(30);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(3600);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+// Comfortably longer than a request can take, so a delivery isn't claimed twice while in flight
+const DELIVERY_LEASE: Duration = Duration::from_secs(60);
+const DELIVERY_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const DELIVERY_BATCH_SIZE: i64 = 50;
+const CONCURRENT_DELIVERIES: usize = 8;
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// How one attempt at a delivery went.
+#[derive(Debug, PartialEq, Eq)]
+pub enum AttemptOutcome {
+    Delivered(StatusCode),
+    /// Worth trying again, a 5xx, 408, 429 or the request not getting through at all
+    Retry {
+        status: Option<StatusCode>,
+        error: String,
+    },
+    /// Any other 4xx, sending it again won't change the answer
+    Rejected {
+        status: StatusCode,
+        error: String,
+    },
+    /// The URL resolves somewhere webhooks aren't allowed to go, nothing was sent
+    Blocked(String),
+}
+
+impl AttemptOutcome {
+    /// Only the status is kept, never the response body. The delivery log is shown to whoever
+    /// owns the webhook, and a body would let them read whatever the URL points at.
+    fn from_status(status: StatusCode) -> Self {
+        let error = status.to_string();
+
+        if status.is_success() {
+            Self::Delivered(status)
+        } else if status.is_server_error()
+            || status == StatusCode::REQUEST_TIMEOUT
+            || status == StatusCode::TOO_MANY_REQUESTS
+        {
+            Self::Retry {
+                status: Some(status),
+                error,
+            }
+        } else {
+            Self::Rejected { status, error }
+        }
+    }
+}
+
+async fn check_delivery_target(url: &str) -> Result<(), AttemptOutcome> {
+    let url = Url::parse(url).map_err(|e| AttemptOutcome::Blocked(e.to_string()))?;
+    check_target(&url).await.map_err(|e| match e {
+        WebhookError::Unresolvable(error) => AttemptOutcome::Retry {
+            status: None,
+            error,
+        },
+        e => AttemptOutcome::Blocked(e.to_string()),
+    })
+}
+
+/// Turns `ChangeEvent`s into queued deliveries for every subscribed webhook and posts them.
+/// Deliveries live in `webhook_deliveries`, so anything still pending when the process stops is
+/// picked up on the next start, and several instances can run without double sending.
+///
+/// Unlike the Discord notifications there's no polling fallback, events published while the
+/// listener is reconnecting are missed.
+///
+/// Webhook URLs come from anyone who can manage a server or holds an API key, so deliveries only
+/// go to public addresses and redirects aren't followed.
+pub struct WebhookDispatcher {
+    pool: Arc<PgPool>,
+    http: reqwest::Client,
+    retry_policy: RetryPolicy,
+    wake: Notify,
+    allow_private_targets: bool,
+}
+
+impl WebhookDispatcher {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self::build(pool, false)
+    }
+
+    fn build(pool: Arc<PgPool>, allow_private_targets: bool) -> Self {
+        let mut http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .redirect(redirect::Policy::none())
+            .user_agent(concat!("fpl-webhooks/", env!("CARGO_PKG_VERSION")));
+        if !allow_private_targets {
+            http = http.dns_resolver(Arc::new(PublicResolver));
+        }
+
+        Self {
+            pool,
+            http: http.build().expect("Static reqwest config is valid"),
+            retry_policy: RetryPolicy::new(
+                MAX_ATTEMPTS as usize,
+                RETRY_BASE_DELAY,
+                RETRY_MAX_DELAY,
+            ),
+            wake: Notify::new(),
+            allow_private_targets,
+        }
+    }
+
+    pub async fn start(self: Arc<Self>) -> Result<(), sqlx::Error> {
+        info!("Starting webhook deliveries");
+
+        let listener = Arc::clone(&self);
+        tokio::spawn(async move { listener.listen().await });
+
+        tokio::spawn(async move {
+            loop {
+                match self.deliver_due().await {
+                    // A full batch probably means there's more waiting
+                    Ok(delivered) if delivered as i64 >= DELIVERY_BATCH_SIZE => continue,
+                    Ok(_) => {}
+                    Err(e) => error!("Error delivering webhooks: {}", e),
+                }
+                tokio::select! {
+                    _ = tokio::time::sleep(DELIVERY_POLL_INTERVAL) => {}
+                    _ = self.wake.notified() => {}
+                }
+            }
+        });
+        Ok(())
+    }
+
+    async fn listen(&self) {
+        loop {
+            let mut listener = match listen_for_changes(&self.pool).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    error!("Failed to listen on {}: {}", CHANGE_EVENTS_CHANNEL, e);
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                    continue;
+                }
+            };
+            info!("Listening for changes on {}", CHANGE_EVENTS_CHANNEL);
+
+            loop {
+                match listener.try_recv().await {
+                    Ok(Some(notification)) => self.handle_payload(notification.payload()).await,
+                    Ok(None) => warn!(
+                        "Lost connection to {}, events until it's back are missed",
+                        CHANGE_EVENTS_CHANNEL
+                    ),
+                    Err(e) => {
+                        error!("Error receiving on {}: {}", CHANGE_EVENTS_CHANNEL, e);
+                        break;
+                    }
+                }
+            }
+
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    }
+
+    async fn handle_payload(&self, payload: &str) {
+        let event = match ChangeEvent::from_payload(payload) {
+            Ok(event) => event,
+            Err(e) => {
+                error!("Failed to parse change event {}: {}", payload, e);
+                return;
+            }
+        };
+
+        match self.handle_event(&event).await {
+            Ok(0) => {}
+            Ok(_) => self.wake.notify_one(),
+            Err(e) => error!("Error queueing webhooks for {:?}: {}", event, e),
+        }
+    }
+
+    /// Queues a delivery of the event for each subscribed webhook, returning how many were new.
+    pub async fn handle_event(&self, event: &ChangeEvent) -> Result<usize, sqlx::Error> {
+        let mut queued = 0;
+        let created_at = Utc::now();
+
+        for payload in WebhookPayload::from_change_event(event) {
+            let body = payload.body(created_at);
+            for webhook in get_subscribed_webhooks(&self.pool, payload.event).await? {
+                if insert_webhook_delivery(
+                    &self.pool,
+                    webhook.id,
+                    payload.event,
+                    &payload.idempotency_key,
+                    &body,
+                )
+                .await?
+                {
+                    queued += 1;
+                }
+            }
+            debug!("Queued {} for webhooks", payload.idempotency_key);
+        }
+        Ok(queued)
+    }
+
+    /// Sends one batch of due deliveries, returning how many were attempted.
+    pub async fn deliver_due(&self) -> Result<usize, sqlx::Error> {
+        let deliveries = claim_due_webhook_deliveries(
+            &self.pool,
+            DELIVERY_BATCH_SIZE,
+            DELIVERY_LEASE.as_secs_f64(),
+        )
+        .await?;
+        let attempted = deliveries.len();
+
+        futures::stream::iter(deliveries)
+            .for_each_concurrent(CONCURRENT_DELIVERIES, |delivery| async move {
+                let outcome = self.attempt(&delivery).await;
+                if let Err(e) = self.record(&delivery, outcome).await {
+                    error!("Error recording webhook delivery {}: {}", delivery.id, e);
+                }
+            })
+            .await;
+
+        Ok(attempted)
+    }
+
+    /// Posts the stored body, signed with a fresh timestamp.
+    pub async fn attempt(&self, delivery: &DueWebhookDelivery) -> AttemptOutcome {
+        // Checked on every attempt, the URL may predate the check or its DNS may have changed
+        if !self.allow_private_targets {
+            if let Err(outcome) = check_delivery_target(&delivery.url).await {
+                return outcome;
+            }
+        }
+
+        let timestamp = Utc::now().timestamp();
+        let response = self
+            .http
+            .post(&delivery.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, &delivery.event)
+            .header(DELIVERY_HEADER, &delivery.idempotency_key)
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(
+                SIGNATURE_HEADER,
+                sign(&delivery.secret, timestamp, &delivery.payload),
+            )
+            .body(delivery.payload.clone())
+            .send()
+            .await;
+
+        match response {
+            Ok(response) => AttemptOutcome::from_status(response.status()),
+            Err(e) => AttemptOutcome::Retry {
+                status: None,
+                error: e.to_string(),
+            },
+        }
+    }
+
+    async fn record(
+        &self,
+        delivery: &DueWebhookDelivery,
+        outcome: AttemptOutcome,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        let (status, status_code, error, next_attempt_at) = match outcome {
+            AttemptOutcome::Delivered(status) => {
+                debug!("Delivered webhook {} to {}", delivery.id, delivery.url);
+                (DeliveryStatus::Delivered, Some(status), None, now)
+            }
+            AttemptOutcome::Retry { status, error } if delivery.attempts < MAX_ATTEMPTS => {
+                let delay = self.retry_policy.backoff(delivery.attempts as usize);
+                warn!(
+                    "Webhook delivery {} to {} failed (attempt {}), retrying in {:?}: {}",
+                    delivery.id, delivery.url, delivery.attempts, delay, error
+                );
+                let next_attempt_at =
+                    now + chrono::Duration::from_std(delay).unwrap_or(chrono::Duration::zero());
+                (
+                    DeliveryStatus::Pending,
+                    status,
+                    Some(error),
+                    next_attempt_at,
+                )
+            }
+            AttemptOutcome::Retry { status, error } => {
+                warn!(
+                    "Giving up on webhook delivery {} to {} after {} attempts: {}",
+                    delivery.id, delivery.url, delivery.attempts, error
+                );
+                (DeliveryStatus::Failed, status, Some(error), now)
+            }
+            AttemptOutcome::Rejected { status, error } => {
+                warn!(
+                    "Webhook delivery {} rejected by {} with {}",
+                    delivery.id, delivery.url, status
+                );
+                (DeliveryStatus::Failed, Some(status), Some(error), now)
+            }
+            AttemptOutcome::Blocked(error) => {
+                warn!(
+                    "Webhook delivery {} to {} blocked: {}",
+                    delivery.id, delivery.url, error
+                );
+                (DeliveryStatus::Failed, None, Some(error), now)
+            }
+        };
+
+        record_webhook_delivery_attempt(
+            &self.pool,
+            delivery.id,
+            status,
+            status_code.map(|status| status.as_u16() as i16),
+            error.as_deref(),
+            next_attempt_at,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+    use axum::Router;
+    use sqlx::postgres::PgPoolOptions;
+
+    use super::*;
+    use crate::signing::verify;
+
+    fn dispatcher() -> WebhookDispatcher {
+        // Never connects, `attempt` doesn't touch the database
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/fpl")
+            .unwrap();
+        // The receivers below listen on loopback
+        WebhookDispatcher::build(Arc::new(pool), true)
+    }
+
+    fn delivery(url: String) -> DueWebhookDelivery {
+        DueWebhookDelivery {
+            id: 1,
+            webhook_id: 1,
+            url,
+            secret: "secret".to_string(),
+            event: "game_week_finished".to_string(),
+            idempotency_key: "game_week_finished:7".to_string(),
+            payload: r#"{"id":"game_week_finished:7"}"#.to_string(),
+            attempts: 1,
+        }
+    }
+
+    /// Answers every post with `status`, keeping what it was sent.
+    async fn receiver(
+        status: StatusCode,
+        received: Arc<Mutex<Vec<(HeaderMap, String)>>>,
+    ) -> String {
+        let app = Router::new().route(
+            "/hook",
+            post(move |headers: HeaderMap, body: String| async move {
+                received.lock().unwrap().push((headers, body));
+                status
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{address}/hook")
+    }
+
+    #[tokio::test]
+    async fn test_attempt_sends_signed_payload() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let url = receiver(StatusCode::NO_CONTENT, Arc::clone(&received)).await;
+
+        let outcome = dispatcher().attempt(&delivery(url)).await;
+        assert_eq!(outcome, AttemptOutcome::Delivered(StatusCode::NO_CONTENT));
+
+        let received = received.lock().unwrap();
+        let (headers, body) = &received[0];
+        assert_eq!(body, r#"{"id":"game_week_finished:7"}"#);
+        assert_eq!(headers[EVENT_HEADER], "game_week_finished");
+        assert_eq!(headers[DELIVERY_HEADER], "game_week_finished:7");
+        let timestamp: i64 = headers[TIMESTAMP_HEADER].to_str().unwrap().parse().unwrap();
+        let signature = headers[SIGNATURE_HEADER].to_str().unwrap();
+        assert!(verify("secret", timestamp, body, signature));
+    }
+
+    #[tokio::test]
+    async fn test_attempt_outcomes() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let url = receiver(StatusCode::SERVICE_UNAVAILABLE, Arc::clone(&received)).await;
+        assert!(matches!(
+            dispatcher().attempt(&delivery(url)).await,
+            AttemptOutcome::Retry {
+                status: Some(StatusCode::SERVICE_UNAVAILABLE),
+                ..
+            }
+        ));
+
+        let url = receiver(StatusCode::GONE, Arc::clone(&received)).await;
+        assert!(matches!(
+            dispatcher().attempt(&delivery(url)).await,
+            AttemptOutcome::Rejected {
+                status: StatusCode::GONE,
+                ..
+            }
+        ));
+
+        // Nothing listening
+        let outcome = dispatcher()
+            .attempt(&delivery("http://127.0.0.1:9/hook".to_string()))
+            .await;
+        assert!(matches!(
+            outcome,
+            AttemptOutcome::Retry { status: None, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_private_targets_are_blocked() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let url = receiver(StatusCode::NO_CONTENT, Arc::clone(&received)).await;
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/fpl")
+            .unwrap();
+
+        let outcome = WebhookDispatcher::new(Arc::new(pool))
+            .attempt(&delivery(url))
+            .await;
+        assert!(matches!(outcome, AttemptOutcome::Blocked(_)));
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_redirects_are_not_followed() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let target = receiver(StatusCode::NO_CONTENT, Arc::clone(&received)).await;
+        let app = Router::new().route(
+            "/hook",
+            post(move || {
+                let target = target.clone();
+                async move { axum::response::Redirect::temporary(&target) }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let outcome = dispatcher()
+            .attempt(&delivery(format!("http://{address}/hook")))
+            .await;
+        assert!(matches!(
+            outcome,
+            AttemptOutcome::Rejected {
+                status: StatusCode::TEMPORARY_REDIRECT,
+                ..
+            }
+        ));
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rate_limits_are_retried() {
+        assert!(matches!(
+            AttemptOutcome::from_status(StatusCode::TOO_MANY_REQUESTS),
+            AttemptOutcome::Retry { .. }
+        ));
+        assert_eq!(
+            AttemptOutcome::from_status(StatusCode::BAD_REQUEST),
+            AttemptOutcome::Rejected {
+                status: StatusCode::BAD_REQUEST,
+                error: "400 Bad Request".to_string()
+            }
+        );
+    }
+}
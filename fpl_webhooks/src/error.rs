@@ -0,0 +1,17 @@
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookError {
+    #[error("Invalid webhook URL: {0}")]
+    InvalidUrl(String),
+    #[error("Webhooks can't be sent to private or local addresses ({0})")]
+    PrivateAddress(String),
+    #[error("Couldn't resolve {0}")]
+    Unresolvable(String),
+    #[error("{0}")]
+    InvalidEvent(String),
+    #[error("Pick at least one event")]
+    NoEvents,
+    #[error("Already at the limit of {0} webhooks, remove one first")]
+    TooManyWebhooks(i64),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
@@ -0,0 +1,9 @@
+pub mod dispatcher;
+pub mod error;
+pub mod management;
+pub mod payload;
+pub mod signing;
+pub mod target;
+
+pub use dispatcher::WebhookDispatcher;
+pub use error::WebhookError;
@@ -0,0 +1,31 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use fpl_webhooks::WebhookDispatcher;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .init();
+
+    dotenv::from_filename("../.env").ok();
+    let database_url =
+        std::env::var("DATABASE_URL").expect("DATABASE_URL must be set in .env file");
+
+    let options = PgConnectOptions::from_str(&database_url)?.application_name("fpl_webhooks");
+    let pool = Arc::new(
+        PgPoolOptions::new()
+            .max_connections(5)
+            .acquire_timeout(Duration::from_secs(5))
+            .connect_with(options)
+            .await?,
+    );
+
+    Arc::new(WebhookDispatcher::new(pool)).start().await?;
+
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
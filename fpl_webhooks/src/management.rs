@@ -0,0 +1,117 @@
+use fpl_db::models::{Webhook, WebhookEvent, WebhookOwner, MAX_WEBHOOKS_PER_OWNER};
+use fpl_db::queries::webhook::{count_webhooks, insert_webhook};
+use reqwest::Url;
+use sqlx::PgPool;
+
+use crate::error::WebhookError;
+use crate::signing::generate_secret;
+use crate::target::{check_host, check_target};
+
+/// Only absolute http(s) URLs with a public host, anything else can't or mustn't be posted to.
+pub fn validate_url(url: &str) -> Result<Url, WebhookError> {
+    let parsed = Url::parse(url.trim()).map_err(|e| WebhookError::InvalidUrl(e.to_string()))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(WebhookError::InvalidUrl(format!(
+            "{} isn't http or https",
+            parsed.scheme()
+        )));
+    }
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err(WebhookError::InvalidUrl("missing host".to_string()));
+    }
+    check_host(&parsed)?;
+    Ok(parsed)
+}
+
+/// Parses a comma separated list of event names, `all` or nothing at all subscribes to everything.
+pub fn parse_events(events: Option<&str>) -> Result<Vec<WebhookEvent>, WebhookError> {
+    let events = events.map(str::trim).unwrap_or_default();
+    if events.is_empty() || events == "all" {
+        return Ok(WebhookEvent::ALL.to_vec());
+    }
+
+    let mut parsed = Vec::new();
+    for name in events
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        let event: WebhookEvent = name.parse().map_err(WebhookError::InvalidEvent)?;
+        if !parsed.contains(&event) {
+            parsed.push(event);
+        }
+    }
+
+    if parsed.is_empty() {
+        return Err(WebhookError::NoEvents);
+    }
+    Ok(parsed)
+}
+
+/// Registers a webhook with a fresh signing secret. Checked here rather than in each frontend
+/// so Discord and the REST API apply the same rules.
+pub async fn create_webhook(
+    pool: &PgPool,
+    owner: &WebhookOwner,
+    url: &str,
+    events: &[WebhookEvent],
+) -> Result<Webhook, WebhookError> {
+    let url = validate_url(url)?;
+    check_target(&url).await?;
+    if events.is_empty() {
+        return Err(WebhookError::NoEvents);
+    }
+    if count_webhooks(pool, owner).await? >= MAX_WEBHOOKS_PER_OWNER {
+        return Err(WebhookError::TooManyWebhooks(MAX_WEBHOOKS_PER_OWNER));
+    }
+
+    Ok(insert_webhook(pool, owner, url.as_str(), &generate_secret(), events).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_url() {
+        assert!(validate_url("https://example.com/hooks/fpl").is_ok());
+        assert!(validate_url(" http://hooks.example.com:8000 ").is_ok());
+        assert!(matches!(
+            validate_url("http://localhost:8000"),
+            Err(WebhookError::PrivateAddress(_))
+        ));
+        assert!(matches!(
+            validate_url("http://169.254.169.254/latest/meta-data"),
+            Err(WebhookError::PrivateAddress(_))
+        ));
+        assert!(matches!(
+            validate_url("ftp://example.com"),
+            Err(WebhookError::InvalidUrl(_))
+        ));
+        assert!(matches!(
+            validate_url("example.com/hook"),
+            Err(WebhookError::InvalidUrl(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_events() {
+        assert_eq!(parse_events(None).unwrap(), WebhookEvent::ALL.to_vec());
+        assert_eq!(
+            parse_events(Some("all")).unwrap(),
+            WebhookEvent::ALL.to_vec()
+        );
+        assert_eq!(
+            parse_events(Some("fixture_score, deadline_passed,fixture_score")).unwrap(),
+            vec![WebhookEvent::FixtureScore, WebhookEvent::DeadlinePassed]
+        );
+        assert!(matches!(
+            parse_events(Some("goal")),
+            Err(WebhookError::InvalidEvent(_))
+        ));
+        assert!(matches!(
+            parse_events(Some(",")),
+            Err(WebhookError::NoEvents)
+        ));
+    }
+}
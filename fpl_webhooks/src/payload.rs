@@ -0,0 +1,217 @@
+use chrono::{DateTime, Utc};
+use fpl_db::events::ChangeEvent;
+use fpl_db::models::WebhookEvent;
+use serde_json::{json, Value};
+
+/// What a `ChangeEvent` looks like to webhook subscribers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookPayload {
+    pub event: WebhookEvent,
+    /// The same for every instance that sees the change, deliveries are deduplicated on it
+    pub idempotency_key: String,
+    pub data: Value,
+}
+
+impl WebhookPayload {
+    fn new(event: WebhookEvent, idempotency_key: String, data: Value) -> Self {
+        Self {
+            event,
+            idempotency_key,
+            data,
+        }
+    }
+
+    /// The webhook events a change maps to, usually zero or one. Kick offs and score changes
+    /// are the same transitions `ScoreNotifications` posts, points the same as `PointsNotifications`.
+    pub fn from_change_event(event: &ChangeEvent) -> Vec<Self> {
+        match event {
+            ChangeEvent::FixtureUpdated {
+                fixture_id,
+                started,
+                finished,
+                minutes,
+                home_team_score,
+                away_team_score,
+                was_started,
+                previous_home_team_score,
+                previous_away_team_score,
+            } => {
+                if *started && !*was_started {
+                    vec![Self::new(
+                        WebhookEvent::FixtureKickoff,
+                        format!("fixture_kickoff:{fixture_id}"),
+                        json!({
+                            "fixture_id": fixture_id,
+                            "home_team_score": home_team_score.unwrap_or_default(),
+                            "away_team_score": away_team_score.unwrap_or_default(),
+                        }),
+                    )]
+                } else if *started
+                    && (home_team_score != previous_home_team_score
+                        || away_team_score != previous_away_team_score)
+                {
+                    let (home, away) = (
+                        home_team_score.unwrap_or_default(),
+                        away_team_score.unwrap_or_default(),
+                    );
+                    let (previous_home, previous_away) = (
+                        previous_home_team_score.unwrap_or_default(),
+                        previous_away_team_score.unwrap_or_default(),
+                    );
+                    vec![Self::new(
+                        WebhookEvent::FixtureScore,
+                        format!(
+                            "fixture_score:{fixture_id}:{previous_home}-{previous_away}:{home}-{away}"
+                        ),
+                        json!({
+                            "fixture_id": fixture_id,
+                            "minutes": minutes,
+                            "finished": finished,
+                            "home_team_score": home,
+                            "away_team_score": away,
+                            "previous_home_team_score": previous_home,
+                            "previous_away_team_score": previous_away,
+                        }),
+                    )]
+                } else {
+                    Vec::new()
+                }
+            }
+            ChangeEvent::PlayerPointsUpdated {
+                player_id,
+                game_week_id,
+                previous_points,
+                total_points,
+            } => vec![Self::new(
+                WebhookEvent::PlayerPoints,
+                format!(
+                    "player_points:{player_id}:{game_week_id}:{previous_points}:{total_points}"
+                ),
+                json!({
+                    "player_id": player_id,
+                    "game_week_id": game_week_id,
+                    "previous_points": previous_points,
+                    "total_points": total_points,
+                }),
+            )],
+            ChangeEvent::DeadlinePassed {
+                game_week_id,
+                deadline_time,
+            } => vec![Self::new(
+                WebhookEvent::DeadlinePassed,
+                format!("deadline_passed:{game_week_id}"),
+                json!({
+                    "game_week_id": game_week_id,
+                    "deadline_time": deadline_time,
+                }),
+            )],
+            ChangeEvent::GameWeekFinished { game_week_id } => vec![Self::new(
+                WebhookEvent::GameWeekFinished,
+                format!("game_week_finished:{game_week_id}"),
+                json!({ "game_week_id": game_week_id }),
+            )],
+            ChangeEvent::PlayerMatchStatsUpdated { .. }
+            | ChangeEvent::PlayerPriceChanged { .. }
+            | ChangeEvent::PlayerStatusChanged { .. }
+            | ChangeEvent::FixtureRescheduled { .. }
+            | ChangeEvent::CupMatchDecided { .. } => Vec::new(),
+        }
+    }
+
+    /// The JSON body that's signed and posted, `id` is the idempotency key so receivers can
+    /// drop repeats.
+    pub fn body(&self, created_at: DateTime<Utc>) -> String {
+        json!({
+            "id": self.idempotency_key,
+            "event": self.event.as_str(),
+            "created_at": created_at,
+            "data": self.data,
+        })
+        .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_updated(
+        was_started: bool,
+        started: bool,
+        previous: (Option<i16>, Option<i16>),
+        score: (Option<i16>, Option<i16>),
+    ) -> ChangeEvent {
+        ChangeEvent::FixtureUpdated {
+            fixture_id: 10,
+            started,
+            finished: false,
+            minutes: 34,
+            home_team_score: score.0,
+            away_team_score: score.1,
+            was_started,
+            previous_home_team_score: previous.0,
+            previous_away_team_score: previous.1,
+        }
+    }
+
+    #[test]
+    fn test_kickoff() {
+        let payloads = WebhookPayload::from_change_event(&fixture_updated(
+            false,
+            true,
+            (None, None),
+            (Some(0), Some(0)),
+        ));
+        assert_eq!(payloads.len(), 1);
+        assert_eq!(payloads[0].event, WebhookEvent::FixtureKickoff);
+        assert_eq!(payloads[0].idempotency_key, "fixture_kickoff:10");
+    }
+
+    #[test]
+    fn test_score_change() {
+        let payloads = WebhookPayload::from_change_event(&fixture_updated(
+            true,
+            true,
+            (Some(0), Some(0)),
+            (Some(1), Some(0)),
+        ));
+        assert_eq!(payloads.len(), 1);
+        assert_eq!(payloads[0].event, WebhookEvent::FixtureScore);
+        assert_eq!(payloads[0].idempotency_key, "fixture_score:10:0-0:1-0");
+        assert_eq!(payloads[0].data["home_team_score"], 1);
+        assert_eq!(payloads[0].data["previous_home_team_score"], 0);
+    }
+
+    #[test]
+    fn test_no_payload_without_a_kickoff_or_score_change() {
+        // e.g. a fixture finishing without a late goal
+        let event = fixture_updated(true, true, (Some(2), Some(1)), (Some(2), Some(1)));
+        assert!(WebhookPayload::from_change_event(&event).is_empty());
+
+        let event = ChangeEvent::PlayerPriceChanged {
+            player_id: 1,
+            previous_cost: 50,
+            now_cost: 51,
+        };
+        assert!(WebhookPayload::from_change_event(&event).is_empty());
+    }
+
+    #[test]
+    fn test_body() {
+        let payloads =
+            WebhookPayload::from_change_event(&ChangeEvent::GameWeekFinished { game_week_id: 7 });
+        let created_at = DateTime::parse_from_rfc3339("2025-05-24T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let body: Value = serde_json::from_str(&payloads[0].body(created_at)).unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "id": "game_week_finished:7",
+                "event": "game_week_finished",
+                "created_at": "2025-05-24T10:00:00Z",
+                "data": { "game_week_id": 7 },
+            })
+        );
+    }
+}
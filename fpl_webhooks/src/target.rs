@@ -0,0 +1,154 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::Url;
+
+use crate::error::WebhookError;
+
+/// Whether posting to `ip` could only reach something on the public internet. Anything loopback,
+/// private, link-local (which includes cloud metadata endpoints) or otherwise not routable is
+/// refused, so a webhook can't be pointed back at the host or its network.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ipv4(ip),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // 0.0.0.0/8 "this network"
+        || first == 0
+        // 100.64.0.0/10 carrier-grade NAT
+        || (first == 100 && (second & 0b1100_0000) == 64)
+        // 198.18.0.0/15 benchmarking
+        || (first == 198 && (second & 0b1111_1110) == 18)
+        // 240.0.0.0/4 reserved
+        || first >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // fc00::/7 unique local
+        || (first & 0xfe00) == 0xfc00
+        // fe80::/10 link-local
+        || (first & 0xffc0) == 0xfe80
+        // 2001:db8::/32 documentation
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+/// Checks what can be told from the URL alone: literal addresses and `localhost`.
+pub fn check_host(url: &Url) -> Result<(), WebhookError> {
+    let host = url.host_str().unwrap_or_default();
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        if !is_public_ip(ip) {
+            return Err(WebhookError::PrivateAddress(ip.to_string()));
+        }
+    } else {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        if host == "localhost" || host.ends_with(".localhost") {
+            return Err(WebhookError::PrivateAddress(host));
+        }
+    }
+    Ok(())
+}
+
+/// Resolves the URL's host and refuses it if any of its addresses isn't public.
+pub async fn check_target(url: &Url) -> Result<(), WebhookError> {
+    check_host(url)?;
+
+    let host = url.host_str().unwrap_or_default();
+    let port = url.port_or_known_default().unwrap_or(443);
+    let addresses = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| WebhookError::Unresolvable(format!("{host}: {e}")))?;
+
+    for address in addresses {
+        if !is_public_ip(address.ip()) {
+            return Err(WebhookError::PrivateAddress(address.ip().to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// DNS resolver for the delivery client that drops non-public addresses, so a host that passed
+/// `check_target` can't be re-pointed at a private one before the connection is made.
+#[derive(Debug, Default)]
+pub struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|address| is_public_ip(address.ip()))
+                .collect();
+            if addresses.is_empty() {
+                return Err(WebhookError::PrivateAddress(name.as_str().to_string()).into());
+            }
+            let addresses: Addrs = Box::new(addresses.into_iter());
+            Ok(addresses)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_public_ip() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "0.0.0.0",
+            "100.64.0.1",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::ffff:169.254.169.254",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{ip} should be refused");
+        }
+        for ip in ["1.1.1.1", "93.184.216.34", "2606:4700:4700::1111"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{ip} should be allowed");
+        }
+    }
+
+    #[test]
+    fn test_check_host() {
+        for url in [
+            "http://127.0.0.1:8000/hook",
+            "http://[::1]/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://localhost:8000",
+            "http://api.localhost./hook",
+        ] {
+            assert!(matches!(
+                check_host(&Url::parse(url).unwrap()),
+                Err(WebhookError::PrivateAddress(_))
+            ));
+        }
+        assert!(check_host(&Url::parse("https://example.com/hook").unwrap()).is_ok());
+    }
+}